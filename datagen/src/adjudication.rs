@@ -0,0 +1,150 @@
+use games::{gamerules::GameResult, general::side::Side};
+
+/// When to stop a game before its natural end. A rule whose plies are 0 is off.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Adjudication {
+    /// A side scored at least `win_score` for `win_plies` plies in a row wins
+    pub win_score: i32,
+    pub win_plies: usize,
+    /// Scores within `draw_score` of zero for `draw_plies` plies in a row draw
+    pub draw_score: i32,
+    pub draw_plies: usize,
+    /// A game this many plies long is a draw
+    pub max_plies: Option<usize>,
+}
+
+impl Default for Adjudication {
+    fn default() -> Self {
+        Self {
+            win_score: 1_000,
+            win_plies: 0,
+            draw_score: 10,
+            draw_plies: 0,
+            max_plies: None,
+        }
+    }
+}
+
+/// Follows a game's scores to see when `Adjudication` calls it.
+pub struct Adjudicator {
+    rules: Adjudication,
+    /// Plies the first player's been winning for, or the second player's as a
+    /// negative number
+    win_streak: i64,
+    draw_streak: usize,
+    plies: usize,
+}
+
+impl Adjudicator {
+    #[must_use]
+    pub fn new(rules: Adjudication) -> Self {
+        Self {
+            rules,
+            win_streak: 0,
+            draw_streak: 0,
+            plies: 0,
+        }
+    }
+
+    /// A ply's been played, from a position with `score` from the first
+    /// player's view. Searchless plies, such as random openings, pass `None`.
+    #[must_use]
+    pub fn update(&mut self, score: Option<i32>) -> Option<GameResult> {
+        self.plies += 1;
+
+        match score {
+            Some(score) if score >= self.rules.win_score => {
+                self.win_streak = self.win_streak.max(0) + 1;
+            }
+            Some(score) if score <= -self.rules.win_score => {
+                self.win_streak = self.win_streak.min(0) - 1;
+            }
+            _ => self.win_streak = 0,
+        }
+
+        match score {
+            Some(score) if score.abs() <= self.rules.draw_score => self.draw_streak += 1,
+            _ => self.draw_streak = 0,
+        }
+
+        let wins = self.rules.win_plies > 0;
+        let draws = self.rules.draw_plies > 0;
+        if wins && self.win_streak >= self.rules.win_plies as i64 {
+            Some(GameResult::Win(Side::Player1))
+        } else if wins && -self.win_streak >= self.rules.win_plies as i64 {
+            Some(GameResult::Win(Side::Player2))
+        } else if draws && self.draw_streak >= self.rules.draw_plies {
+            Some(GameResult::Draw)
+        } else if self.rules.max_plies.is_some_and(|max| self.plies >= max) {
+            Some(GameResult::Draw)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(rules: Adjudication, scores: &[Option<i32>]) -> Option<(usize, GameResult)> {
+        let mut adjudicator = Adjudicator::new(rules);
+        scores
+            .iter()
+            .enumerate()
+            .find_map(|(ply, score)| adjudicator.update(*score).map(|result| (ply, result)))
+    }
+
+    #[test]
+    fn off() {
+        let scores = [Some(5_000); 100];
+        assert_eq!(run(Adjudication::default(), &scores), None);
+    }
+
+    #[test]
+    fn wins() {
+        let rules = Adjudication {
+            win_score: 500,
+            win_plies: 3,
+            ..Default::default()
+        };
+        let first = [
+            Some(600),
+            Some(700),
+            Some(400),
+            Some(500),
+            Some(900),
+            Some(800),
+        ];
+        assert_eq!(
+            run(rules, &first),
+            Some((5, GameResult::Win(Side::Player1)))
+        );
+        let second = [Some(-600), Some(600), Some(-500), Some(-700), Some(-900)];
+        assert_eq!(
+            run(rules, &second),
+            Some((4, GameResult::Win(Side::Player2)))
+        );
+        let swinging = [Some(600), Some(-600), Some(600), Some(-600)];
+        assert_eq!(run(rules, &swinging), None);
+    }
+
+    #[test]
+    fn draws() {
+        let rules = Adjudication {
+            draw_score: 5,
+            draw_plies: 4,
+            ..Default::default()
+        };
+        let scores = [Some(0), Some(5), Some(-5), Some(20), Some(1), None, Some(0)];
+        assert_eq!(run(rules, &scores), None);
+        let scores = [Some(20), Some(1), Some(-1), Some(0), Some(3)];
+        assert_eq!(run(rules, &scores), Some((4, GameResult::Draw)));
+
+        let rules = Adjudication {
+            max_plies: Some(3),
+            ..Default::default()
+        };
+        assert_eq!(run(rules, &[None; 5]), Some((2, GameResult::Draw)));
+    }
+}
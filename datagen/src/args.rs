@@ -1,13 +1,46 @@
-use games::Games;
+use crate::adjudication::Adjudication;
+use faeries::args::Game;
+use faeries::search::NAMES;
 use std::{env, fmt::Display, num::ParseIntError};
 
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Format {
+    /// `fen | score | result` to a line
+    Text,
+    /// The packed records `packed::Entry` describes
+    Binary,
+}
+
 pub struct Args {
+    /// `convert` turns a positions file from one format to the other
+    pub convert: bool,
+    /// `rescore` searches a positions file's positions again, keeping their
+    /// results
+    pub rescore: bool,
     pub fens_path: String,
+    pub records_path: Option<String>,
+    /// Every position played, with its score and the result of its game, for
+    /// tuners and trainers
+    pub positions_path: Option<String>,
+    /// How positions are written, whether played or converted, and how
+    /// they're read when rescored
+    pub format: Format,
+    pub input_path: Option<String>,
+    pub output_path: Option<String>,
+    /// Saved after every game, for `--resume` to carry on from
+    pub checkpoint_path: Option<String>,
+    pub resume: bool,
+    /// The search positions are played and rescored with, one of the names
+    /// `faeries::search::NAMES` holds
+    pub search: String,
     pub nodes: u64,
     pub threads: usize,
     pub num_games: usize,
+    /// Plies played at random from each opening before searching
+    pub random_plies: usize,
+    pub adjudication: Adjudication,
     pub verbose: bool,
-    pub game: Option<games::Games>,
+    pub game: Option<Game>,
 }
 
 #[derive(Debug)]
@@ -25,10 +58,22 @@ impl Display for ArgsError {
 impl Default for Args {
     fn default() -> Self {
         Self {
+            convert: false,
+            rescore: false,
             fens_path: Default::default(),
+            records_path: None,
+            positions_path: None,
+            format: Format::Text,
+            input_path: None,
+            output_path: None,
+            checkpoint_path: None,
+            resume: false,
+            search: "primary".to_string(),
             nodes: 5_000,
             threads: 1,
             num_games: 1_000,
+            random_plies: 0,
+            adjudication: Adjudication::default(),
             verbose: false,
             game: None,
         }
@@ -64,6 +109,8 @@ pub fn parse_string(line: &str) -> Result<Args, ArgsError> {
 pub fn parse(words: &[&str]) -> Result<Args, ArgsError> {
     let mut parsed = Args::default();
     let mut iter = words.iter().peekable();
+    parsed.convert = iter.next_if_eq(&&"convert").is_some();
+    parsed.rescore = !parsed.convert && iter.next_if_eq(&&"rescore").is_some();
 
     while let Some(word) = iter.next() {
         match (word, iter.peek()) {
@@ -74,17 +121,73 @@ pub fn parse(words: &[&str]) -> Result<Args, ArgsError> {
                     msg: "Missing fen path".to_string(),
                 });
             }
+            // Records
+            (&"--records", Some(_)) => parsed.records_path = Some(iter.next().unwrap().to_string()),
+            (&"--records", _) => {
+                return Err(ArgsError {
+                    msg: "Missing records path".to_string(),
+                });
+            }
+            // Positions
+            (&"--positions", Some(_)) => {
+                parsed.positions_path = Some(iter.next().unwrap().to_string())
+            }
+            (&"--positions", _) => {
+                return Err(ArgsError {
+                    msg: "Missing positions path".to_string(),
+                });
+            }
+            // Format
+            (&"--format", Some(&&"text")) => {
+                parsed.format = Format::Text;
+                iter.next();
+            }
+            (&"--format", Some(&&"binary")) => {
+                parsed.format = Format::Binary;
+                iter.next();
+            }
+            (&"--format", _) => {
+                return Err(ArgsError {
+                    msg: "Format must be 'text' or 'binary'".to_string(),
+                });
+            }
+            // Input
+            (&"--input", Some(_)) => parsed.input_path = Some(iter.next().unwrap().to_string()),
+            (&"--input", _) => {
+                return Err(ArgsError {
+                    msg: "Missing input path".to_string(),
+                });
+            }
+            // Output
+            (&"--output", Some(_)) => parsed.output_path = Some(iter.next().unwrap().to_string()),
+            (&"--output", _) => {
+                return Err(ArgsError {
+                    msg: "Missing output path".to_string(),
+                });
+            }
+            // Checkpoint
+            (&"--checkpoint", Some(_)) => {
+                parsed.checkpoint_path = Some(iter.next().unwrap().to_string())
+            }
+            (&"--checkpoint", _) => {
+                return Err(ArgsError {
+                    msg: "Missing checkpoint path".to_string(),
+                });
+            }
             // Game
             (&"--game", Some(name)) => {
                 parsed.game = match name {
-                    &&"ataxx" => Some(Games::Ataxx),
-                    &&"chess" => Some(Games::Chess),
-                    &&"connect4" => Some(Games::Connect4),
-                    &&"droptaxx" => Some(Games::Droptaxx),
-                    &&"gomoku" => Some(Games::Gomoku),
-                    &&"isolation" => Some(Games::Isolation),
-                    &&"pijersi" => Some(Games::Pijersi),
-                    &&"tak" => Some(Games::Tak),
+                    &&"ataxx" => Some(Game::Ataxx),
+                    &&"checkers" => Some(Game::Checkers),
+                    &&"chess" => Some(Game::Chess),
+                    &&"connect4" => Some(Game::Connect4),
+                    &&"droptaxx" => Some(Game::Droptaxx),
+                    &&"gomoku" => Some(Game::Gomoku),
+                    &&"isolation" => Some(Game::Isolation),
+                    &&"othello" => Some(Game::Othello),
+                    &&"pijersi" => Some(Game::Pijersi),
+                    &&"shogi" => Some(Game::Shogi),
+                    &&"tak" => Some(Game::Tak),
                     _ => {
                         return Err(ArgsError {
                             msg: "Unrecognised game name".to_string(),
@@ -94,6 +197,21 @@ pub fn parse(words: &[&str]) -> Result<Args, ArgsError> {
                 iter.next();
             }
 
+            // Search
+            (&"--search", Some(name)) => {
+                if !NAMES.contains(*name) {
+                    return Err(ArgsError {
+                        msg: format!("Unrecognised search name: '{}'", name),
+                    });
+                }
+                parsed.search = name.to_string();
+                iter.next();
+            }
+            (&"--search", _) => {
+                return Err(ArgsError {
+                    msg: "Missing search name".to_string(),
+                });
+            }
             // Nodes
             (&"--nodes", Some(_)) => parsed.nodes = iter.next().unwrap().parse::<u64>()?,
             (&"--nodes", _) => {
@@ -115,7 +233,42 @@ pub fn parse(words: &[&str]) -> Result<Args, ArgsError> {
                     msg: "Missing thread count".to_string(),
                 });
             }
+            // Random plies
+            (&"--random-plies", Some(_)) => {
+                parsed.random_plies = iter.next().unwrap().parse::<usize>()?
+            }
+            (&"--random-plies", _) => {
+                return Err(ArgsError {
+                    msg: "Missing number of random plies".to_string(),
+                });
+            }
+            // Adjudication
+            (&"--win-score", Some(_)) => {
+                parsed.adjudication.win_score = iter.next().unwrap().parse::<i32>()?
+            }
+            (&"--win-plies", Some(_)) => {
+                parsed.adjudication.win_plies = iter.next().unwrap().parse::<usize>()?
+            }
+            (&"--draw-score", Some(_)) => {
+                parsed.adjudication.draw_score = iter.next().unwrap().parse::<i32>()?
+            }
+            (&"--draw-plies", Some(_)) => {
+                parsed.adjudication.draw_plies = iter.next().unwrap().parse::<usize>()?
+            }
+            (&"--max-plies", Some(_)) => {
+                parsed.adjudication.max_plies = Some(iter.next().unwrap().parse::<usize>()?)
+            }
+            (&"--win-score", _)
+            | (&"--win-plies", _)
+            | (&"--draw-score", _)
+            | (&"--draw-plies", _)
+            | (&"--max-plies", _) => {
+                return Err(ArgsError {
+                    msg: format!("Missing value for {}", word),
+                });
+            }
             // Flags
+            (&"--resume", _) => parsed.resume = true,
             (&"--verbose", _) => parsed.verbose = true,
             // Other
             _ => {
@@ -130,11 +283,35 @@ pub fn parse(words: &[&str]) -> Result<Args, ArgsError> {
         Err(ArgsError {
             msg: "nodes must be >= 1".to_string(),
         })
+    } else if parsed.adjudication.win_score < 1 {
+        Err(ArgsError {
+            msg: "win score must be >= 1".to_string(),
+        })
+    } else if parsed.adjudication.draw_score < 0 {
+        Err(ArgsError {
+            msg: "draw score must be >= 0".to_string(),
+        })
+    } else if parsed.adjudication.max_plies == Some(0) {
+        Err(ArgsError {
+            msg: "max plies must be >= 1".to_string(),
+        })
+    } else if parsed.resume && parsed.checkpoint_path.is_none() {
+        Err(ArgsError {
+            msg: "resuming needs a checkpoint path".to_string(),
+        })
     } else if parsed.threads < 1 {
         Err(ArgsError {
             msg: "threads must be >= 1".to_string(),
         })
-    } else if parsed.fens_path.is_empty() {
+    } else if parsed.convert && (parsed.input_path.is_none() || parsed.output_path.is_none()) {
+        Err(ArgsError {
+            msg: "convert needs an input and an output path".to_string(),
+        })
+    } else if parsed.rescore && (parsed.input_path.is_none() || parsed.output_path.is_none()) {
+        Err(ArgsError {
+            msg: "rescore needs an input and an output path".to_string(),
+        })
+    } else if !parsed.convert && !parsed.rescore && parsed.fens_path.is_empty() {
         Err(ArgsError {
             msg: "fen path must not be empty".to_string(),
         })
@@ -161,10 +338,63 @@ mod tests {
         let args =
             parse_string("--fens path --game ataxx --nodes 1000 --games 5000 --threads 2").unwrap();
         assert_eq!(args.fens_path, "path");
-        assert_eq!(args.game, Some(Games::Ataxx));
+        assert_eq!(args.game, Some(Game::Ataxx));
         assert_eq!(args.nodes, 1000);
         assert_eq!(args.num_games, 5000);
         assert_eq!(args.threads, 2);
+        assert!(!args.convert);
+    }
+
+    #[test]
+    fn openings_and_adjudication() {
+        let args = parse_string(
+            "--fens path --game chess --random-plies 8 --win-score 800 --win-plies 6 --draw-score 5 --draw-plies 12 --max-plies 300",
+        )
+        .unwrap();
+        assert_eq!(args.random_plies, 8);
+        assert_eq!(
+            args.adjudication,
+            Adjudication {
+                win_score: 800,
+                win_plies: 6,
+                draw_score: 5,
+                draw_plies: 12,
+                max_plies: Some(300),
+            }
+        );
+    }
+
+    #[test]
+    fn convert() {
+        let args =
+            parse_string("convert --game chess --input data.bin --output data.txt --format text")
+                .unwrap();
+        assert!(args.convert);
+        assert_eq!(args.game, Some(Game::Chess));
+        assert_eq!(args.input_path.as_deref(), Some("data.bin"));
+        assert_eq!(args.output_path.as_deref(), Some("data.txt"));
+        assert_eq!(args.format, Format::Text);
+    }
+
+    #[test]
+    fn rescore() {
+        let args = parse_string(
+            "rescore --game ataxx --input old.bin --output new.bin --format binary --nodes 50000 --threads 8",
+        )
+        .unwrap();
+        assert!(args.rescore);
+        assert!(!args.convert);
+        assert_eq!(args.input_path.as_deref(), Some("old.bin"));
+        assert_eq!(args.output_path.as_deref(), Some("new.bin"));
+        assert_eq!(args.format, Format::Binary);
+        assert_eq!((args.nodes, args.threads), (50_000, 8));
+        assert_eq!(args.search, "primary");
+
+        let args = parse_string(
+            "rescore --game connect4 --input old.txt --output new.txt --search alphabeta",
+        )
+        .unwrap();
+        assert_eq!(args.search, "alphabeta");
     }
 
     #[test]
@@ -172,12 +402,28 @@ mod tests {
         let tests = [
             "--fens path --game ataxx",
             "--fens path --game chess",
+            "--fens path --game checkers",
             "--fens path --game ataxx --verbose",
             "--fens path --game ataxx --nodes 1",
             "--fens path --game ataxx --nodes 100000",
             "--fens path --game ataxx --threads 1",
             "--fens path --game ataxx --threads 100000",
             "--fens path --game ataxx --nodes 1000 --threads 2",
+            "--fens path --game ataxx --records games.pgn",
+            "--fens path --game ataxx --positions positions.txt",
+            "--fens path --game ataxx --positions positions.bin --format binary",
+            "convert --game ataxx --input positions.txt --output positions.bin --format binary",
+            "convert --game ataxx --input positions.bin --output positions.txt",
+            "rescore --game ataxx --input positions.txt --output rescored.txt",
+            "rescore --game ataxx --input positions.txt --output rescored.txt --search minimax",
+            "--fens path --game ataxx --search flatmc",
+            "--fens path --game ataxx --random-plies 0",
+            "--fens path --game ataxx --random-plies 8",
+            "--fens path --game ataxx --win-plies 4",
+            "--fens path --game ataxx --draw-score 0 --draw-plies 10",
+            "--fens path --game ataxx --max-plies 400",
+            "--fens path --game ataxx --checkpoint run.txt",
+            "--fens path --game ataxx --checkpoint run.txt --resume",
         ];
 
         for line in tests {
@@ -198,6 +444,32 @@ mod tests {
             "--game ataxx",
             "--nodes",
             "--nodes 0",
+            "--fens path --game ataxx --records",
+            "--fens path --game ataxx --positions",
+            "--fens path --game ataxx --format",
+            "--fens path --game ataxx --format json",
+            "--fens path --game ataxx --input",
+            "--fens path --game ataxx --output",
+            "convert",
+            "convert --game ataxx",
+            "convert --game ataxx --input positions.bin",
+            "convert --input positions.bin --output positions.txt",
+            "--game ataxx convert --input positions.bin --output positions.txt",
+            "rescore --game ataxx --input positions.txt",
+            "rescore --input positions.txt --output rescored.txt",
+            "convert rescore --game ataxx --input positions.txt --output rescored.txt",
+            "rescore --game ataxx --input positions.txt --output rescored.txt --search",
+            "rescore --game ataxx --input positions.txt --output rescored.txt --search deep",
+            "--fens path --game ataxx --random-plies",
+            "--fens path --game ataxx --random-plies -1",
+            "--fens path --game ataxx --win-score",
+            "--fens path --game ataxx --win-score 0",
+            "--fens path --game ataxx --win-plies x",
+            "--fens path --game ataxx --draw-score -1",
+            "--fens path --game ataxx --draw-plies",
+            "--fens path --game ataxx --max-plies 0",
+            "--fens path --game ataxx --checkpoint",
+            "--fens path --game ataxx --resume",
             "--fens --game ataxx",
             "--fens path --game",
             "--fens path --game ataxx test",
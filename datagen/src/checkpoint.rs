@@ -0,0 +1,131 @@
+use std::fmt;
+use std::fs::{read_to_string, rename, write};
+use std::io::Error;
+
+/// Where a run had got to, saved after every finished game so that `--resume`
+/// can carry on from there.
+///
+/// Each thread plays every `threads`th game starting from its own id, one at a
+/// time, so the games a thread has finished are the ones in its sequence before
+/// its next game. Output past the lengths here belongs to games that didn't
+/// finish being written, and is cut off when resuming.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Checkpoint {
+    pub games: usize,
+    pub records_len: u64,
+    pub positions_len: u64,
+    /// Each thread's next game, and its generator's state ready for it
+    pub threads: Vec<(usize, u64)>,
+}
+
+impl Checkpoint {
+    /// The start of a run.
+    #[must_use]
+    pub fn new(threads: usize) -> Self {
+        Self {
+            games: 0,
+            records_len: 0,
+            positions_len: 0,
+            threads: (0..threads)
+                .map(|i| (i, 0xe50076937a9e5b1c ^ ((i as u64 + 1) << 20)))
+                .collect(),
+        }
+    }
+
+    /// The text `Display` writes.
+    #[must_use]
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut checkpoint = Self::new(0);
+
+        for line in text.lines() {
+            let words = line.split_ascii_whitespace().collect::<Vec<&str>>();
+            match words.as_slice() {
+                ["games", n] => checkpoint.games = n.parse().ok()?,
+                ["records", n] => checkpoint.records_len = n.parse().ok()?,
+                ["positions", n] => checkpoint.positions_len = n.parse().ok()?,
+                ["thread", id, next, state] => {
+                    if id.parse::<usize>().ok()? != checkpoint.threads.len() {
+                        return None;
+                    }
+                    checkpoint
+                        .threads
+                        .push((next.parse().ok()?, state.parse().ok()?));
+                }
+                [] => {}
+                _ => return None,
+            }
+        }
+
+        Some(checkpoint)
+    }
+
+    pub fn load(path: &str) -> Result<Self, Error> {
+        Self::parse(&read_to_string(path)?)
+            .ok_or_else(|| Error::other(format!("Bad checkpoint in {}", path)))
+    }
+
+    /// Written beside the old one and moved over it, so that a checkpoint is
+    /// never left half written.
+    pub fn save(&self, path: &str) -> Result<(), Error> {
+        let temp = format!("{}.tmp", path);
+        write(&temp, self.to_string())?;
+        rename(&temp, path)
+    }
+}
+
+impl fmt::Display for Checkpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "games {}", self.games)?;
+        writeln!(f, "records {}", self.records_len)?;
+        writeln!(f, "positions {}", self.positions_len)?;
+        for (id, (next, state)) in self.threads.iter().enumerate() {
+            writeln!(f, "thread {} {} {}", id, next, state)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let mut checkpoint = Checkpoint::new(3);
+        assert_eq!(
+            Checkpoint::parse(&checkpoint.to_string()),
+            Some(checkpoint.clone())
+        );
+
+        checkpoint.games = 7;
+        checkpoint.records_len = 12_345;
+        checkpoint.positions_len = 678_910;
+        checkpoint.threads[1] = (10, 42);
+        assert_eq!(Checkpoint::parse(&checkpoint.to_string()), Some(checkpoint));
+    }
+
+    #[test]
+    fn fresh() {
+        let checkpoint = Checkpoint::new(4);
+        assert_eq!(checkpoint.games, 0);
+        let next = checkpoint.threads.iter().map(|(next, _)| *next);
+        assert_eq!(next.collect::<Vec<usize>>(), vec![0, 1, 2, 3]);
+        assert!(checkpoint.threads.iter().all(|(_, state)| *state != 0));
+    }
+
+    #[test]
+    fn errors() {
+        let tests = [
+            "games x",
+            "games 1 2",
+            "thread 1 0 5",
+            "thread 0 0 5\nthread 0 1 6",
+            "thread 0 0",
+            "unknown 1",
+        ];
+
+        for text in tests {
+            assert_eq!(Checkpoint::parse(text), None, "{}", text);
+        }
+    }
+}
@@ -1,4 +1,5 @@
-use games::gamerules::{GameResult, GameRules};
+use faeries::record::Record;
+use games::gamerules::GameRules;
 
 pub enum Event<G: GameRules> {
     ThreadStart {
@@ -15,7 +16,12 @@ pub enum Event<G: GameRules> {
     GameFinish {
         thread_id: usize,
         id: usize,
-        result: Option<GameResult>,
+        record: Record,
+        /// Every position a move was played from, with its score from the
+        /// first player's view
+        positions: Vec<(String, i32)>,
+        /// The thread's generator, ready for its next game
+        prng: u64,
     },
     Move {
         thread_id: usize,
@@ -1,3 +1,5 @@
+use faeries::symmetry::{Symmetric, canonical_fen};
+use std::collections::HashSet;
 use std::fs::read_to_string;
 
 #[must_use]
@@ -12,3 +14,13 @@ pub fn get_fens(path: &str) -> std::io::Result<Vec<String>> {
 
     Ok(result)
 }
+
+/// Drop every fen that's the same as an earlier one up to symmetry
+#[must_use]
+pub fn unique_fens<G: Symmetric>(fens: &[String]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    fens.iter()
+        .filter(|fen| seen.insert(canonical_fen::<G>(fen).0))
+        .cloned()
+        .collect()
+}
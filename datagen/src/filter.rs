@@ -0,0 +1,96 @@
+use faeries::checkers::position::{CheckersMove, CheckersPosition};
+use faeries::chess::san::is_check;
+use faeries::connect4::position::Connect4Position;
+use faeries::droptaxx::position::DroptaxxPosition;
+use faeries::gomoku::position::GomokuPosition;
+use faeries::othello::position::OthelloPosition;
+use faeries::shogi::position::ShogiPosition;
+use games::{
+    ataxx::AtaxxPosition, chess::ChessPosition, gamerules::GameRules, isolation::IsolationPosition,
+    pijersi::PijersiPosition, tak::TakPosition,
+};
+
+/// What makes a position a poor one to train on, where the game has it. The
+/// search's score for such a position depends on what happens next rather
+/// than on the position itself.
+pub trait Filterable: GameRules {
+    /// Whether the side to move is in check.
+    #[must_use]
+    fn in_check(&self) -> bool {
+        false
+    }
+
+    /// Whether `mv` takes anything off the board.
+    #[must_use]
+    fn is_capture(&self, _mv: &Self::MoveType) -> bool {
+        false
+    }
+}
+
+/// The pieces on the board in a FEN written a letter to a piece.
+#[must_use]
+fn pieces(fen: &str) -> usize {
+    fen.split_ascii_whitespace()
+        .next()
+        .unwrap_or("")
+        .chars()
+        .filter(char::is_ascii_alphabetic)
+        .count()
+}
+
+/// A capture leaves fewer pieces than there were.
+#[must_use]
+fn takes<G: GameRules>(pos: &G, mv: &G::MoveType) -> bool {
+    let mut after = pos.clone();
+    after.makemove(mv);
+    pieces(&after.get_fen()) < pieces(&pos.get_fen())
+}
+
+impl Filterable for ChessPosition {
+    fn in_check(&self) -> bool {
+        is_check(self)
+    }
+
+    fn is_capture(&self, mv: &Self::MoveType) -> bool {
+        takes(self, mv)
+    }
+}
+
+impl Filterable for CheckersPosition {
+    fn is_capture(&self, mv: &CheckersMove) -> bool {
+        mv.captured != 0
+    }
+}
+
+impl Filterable for PijersiPosition {
+    fn is_capture(&self, mv: &Self::MoveType) -> bool {
+        takes(self, mv)
+    }
+}
+
+/// A captured piece goes to the hand, off the board.
+impl Filterable for ShogiPosition {
+    fn in_check(&self) -> bool {
+        self.is_check()
+    }
+
+    fn is_capture(&self, mv: &Self::MoveType) -> bool {
+        takes(self, mv)
+    }
+}
+
+/// Nothing in these games takes pieces off the board, and there's no check.
+impl Filterable for AtaxxPosition<7, 7> {}
+
+impl Filterable for DroptaxxPosition {}
+
+impl Filterable for Connect4Position {}
+
+impl Filterable for GomokuPosition {}
+
+impl Filterable for IsolationPosition {}
+
+/// Discs are turned over, never taken off.
+impl Filterable for OthelloPosition {}
+
+impl Filterable for TakPosition<6> {}
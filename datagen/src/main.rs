@@ -1,138 +1,464 @@
+mod adjudication;
 mod args;
+mod checkpoint;
 mod events;
 mod fens;
+mod filter;
+mod packed;
 mod play;
+mod progress;
+mod search;
 
-use crate::args::Args;
-use fens::get_fens;
+use crate::args::{Args, Format};
+use checkpoint::Checkpoint;
+use events::Event;
+use faeries::args::Game;
+use faeries::checkers::position::CheckersPosition;
+use faeries::connect4::position::Connect4Position;
+use faeries::droptaxx::position::DroptaxxPosition;
+use faeries::gomoku::position::GomokuPosition;
+use faeries::othello::position::OthelloPosition;
+use faeries::prng::XorshiftGenerator;
+use faeries::record::Recordable;
+use faeries::shogi::position::ShogiPosition;
+use faeries::symmetry::Symmetric;
+use fens::{get_fens, unique_fens};
+use filter::Filterable;
 use games::{
-    Games,
-    ataxx::AtaxxPosition,
-    chess::ChessPosition,
-    connect4::Connect4Position,
-    droptaxx::DroptaxxPosition,
-    gamerules::GameRules,
-    gomoku::GomokuPosition,
-    isolation::IsolationPosition,
-    pijersi::PijersiPosition,
-    tak::TakPosition,
+    ataxx::AtaxxPosition, chess::ChessPosition, general::side::Side, isolation::IsolationPosition,
+    pijersi::PijersiPosition, tak::TakPosition,
 };
-use protocols::ugi::UGI;
-use std::io::Error;
+use packed::{Entry, Packable, Wdl};
+use play::{get_move, play};
+use progress::Progress;
+use protocols::GoSettings;
+use search::Searchable;
+use std::fs::{File, OpenOptions, read, read_to_string};
+use std::io::{BufWriter, Error, Seek, SeekFrom, Write};
+use std::sync::mpsc;
+use std::thread;
 
-#[must_use]
-fn move_string<G: GameRules + UGI>(_pos: &G, _mv: &G::MoveType) -> String {
-    "0000".to_string()
+fn generate<G>(args: Args, fens: &[String]) -> Result<(), Error>
+where
+    G: Symmetric + Recordable + Searchable + Packable + Filterable + Send + 'static,
+    G::MoveType: Send,
+{
+    let fens = unique_fens::<G>(fens);
+    if args.verbose {
+        println!("unique fens: {}", fens.len());
+    }
+    if fens.is_empty() {
+        return Err(Error::other("No opening fens to play from"));
+    }
+
+    if args.positions_path.is_some()
+        && args.format == Format::Binary
+        && G::pack(&G::startpos().get_fen()).is_none()
+    {
+        return Err(Error::other(format!(
+            "{:?} has no binary layout",
+            args.game.unwrap()
+        )));
+    }
+
+    let mut checkpoint = if args.resume {
+        let path = args.checkpoint_path.as_ref().unwrap();
+        let checkpoint = Checkpoint::load(path)?;
+        if checkpoint.threads.len() != args.threads {
+            return Err(Error::other(format!(
+                "{} was saved with {} threads, not {}",
+                path,
+                checkpoint.threads.len(),
+                args.threads
+            )));
+        }
+        checkpoint
+    } else {
+        Checkpoint::new(args.threads)
+    };
+
+    let mut records = match &args.records_path {
+        Some(path) => Some(open_output(path, args.resume, checkpoint.records_len)?),
+        None => None,
+    };
+
+    let mut positions_file = match &args.positions_path {
+        Some(path) => Some(open_output(path, args.resume, checkpoint.positions_len)?),
+        None => None,
+    };
+
+    let mut workers = vec![];
+    let (tx, rx) = mpsc::channel();
+    let num_fens = fens.len();
+
+    // Create workers
+    for i in 0..args.threads {
+        let tx: mpsc::Sender<Event<G>> = tx.clone();
+        let fens = fens.clone();
+        let (verbose, nodes, threads, num_games) =
+            (args.verbose, args.nodes, args.threads, args.num_games);
+        let (adjudication, random_plies) = (args.adjudication, args.random_plies);
+        let search = args.search.clone();
+        let (mut game_number, state) = checkpoint.threads[i];
+
+        workers.push(thread::spawn(move || {
+            if verbose {
+                tx.send(Event::ThreadStart { thread_id: i }).unwrap();
+            }
+
+            let mut prng = XorshiftGenerator::new(state);
+
+            while game_number < num_games {
+                let fen_idx = game_number % num_fens;
+                play(
+                    i,
+                    &fens[fen_idx],
+                    game_number,
+                    &search,
+                    &GoSettings::from_nodes(nodes),
+                    adjudication,
+                    random_plies,
+                    &mut prng,
+                    &tx,
+                );
+                game_number += threads;
+            }
+
+            if verbose {
+                tx.send(Event::ThreadFinish { thread_id: i }).unwrap();
+            }
+        }));
+    }
+    drop(tx);
+
+    let mut progress = Progress::default();
+
+    for event in &rx {
+        match event {
+            Event::ThreadStart { thread_id } => println!("Start thread {}", thread_id),
+            Event::ThreadFinish { thread_id } => println!("Finish thread {}", thread_id),
+            Event::GameStart { thread_id, id, fen } => {
+                if args.verbose {
+                    println!("<Thread:{}> Start game {} fen {}", thread_id, id, fen)
+                }
+            }
+            Event::GameFinish {
+                thread_id,
+                id,
+                mut record,
+                positions,
+                prng,
+            } => {
+                if args.verbose {
+                    println!(
+                        "<Thread:{}> Finish game {} result {}",
+                        thread_id,
+                        id,
+                        record.result_string()
+                    );
+                }
+
+                // Each file gets the whole game in one write, so an
+                // interrupted run leaves at most one partial game to cut off
+                if let Some(file) = &mut records {
+                    record.set_tag("Round", &(id + 1).to_string());
+                    let text = format!("{}\n", record);
+                    file.write_all(text.as_bytes())?;
+                    checkpoint.records_len += text.len() as u64;
+                }
+
+                let wdl = Wdl::from_result(record.result);
+                let mut written = 0;
+                if let (Some(file), Some(wdl)) = (&mut positions_file, wdl) {
+                    let mut bytes = vec![];
+                    for (fen, score) in &positions {
+                        let entry = Entry::new(fen, *score, wdl);
+                        match args.format {
+                            Format::Text => writeln!(bytes, "{}", entry)?,
+                            Format::Binary => match entry.to_bytes::<G>() {
+                                Some(packed) => bytes.extend(packed),
+                                None => return Err(Error::other(format!("Can't pack {}", fen))),
+                            },
+                        }
+                    }
+                    file.write_all(&bytes)?;
+                    checkpoint.positions_len += bytes.len() as u64;
+                    written = positions.len();
+                }
+
+                checkpoint.games += 1;
+                checkpoint.threads[thread_id] = (id + args.threads, prng);
+                if let Some(path) = &args.checkpoint_path {
+                    checkpoint.save(path)?;
+                }
+
+                progress.finish_game(wdl, written);
+                if progress.due() {
+                    println!("{}", progress);
+                }
+
+                if checkpoint.games >= args.num_games {
+                    break;
+                }
+            }
+            Event::Move { .. } => {}
+        }
+    }
+
+    // Wait
+    for worker in workers {
+        let _ = worker.join();
+    }
+
+    println!("{}", progress);
+
+    Ok(())
+}
+
+/// An output file, new or cut back to where a checkpoint had it when resuming.
+fn open_output(path: &str, resume: bool, len: u64) -> Result<File, Error> {
+    if !resume {
+        return File::create(path);
+    }
+
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)?;
+    file.set_len(len)?;
+    file.seek(SeekFrom::End(0))?;
+    Ok(file)
+}
+
+/// The positions in a file written in `format`.
+fn read_entries<G: Packable>(path: &str, format: Format) -> Result<Vec<Entry>, Error> {
+    let mut entries = vec![];
+
+    match format {
+        Format::Binary => {
+            let bytes = read(path)?;
+            let mut rest = bytes.as_slice();
+            while !rest.is_empty() {
+                let Some(entry) = Entry::read::<G>(&mut rest) else {
+                    return Err(Error::other(format!(
+                        "Bad record after {} positions",
+                        entries.len()
+                    )));
+                };
+                entries.push(entry);
+            }
+        }
+        Format::Text => {
+            for (idx, line) in read_to_string(path)?.lines().enumerate() {
+                if line.trim().is_empty() || line.starts_with('#') {
+                    continue;
+                }
+                let Some(entry) = Entry::parse(line) else {
+                    return Err(Error::other(format!("Bad position on line {}", idx + 1)));
+                };
+                entries.push(entry);
+            }
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Write positions to a file in `format`.
+fn write_entries<G: Packable>(path: &str, format: Format, entries: &[Entry]) -> Result<(), Error> {
+    let mut output = BufWriter::new(File::create(path)?);
+
+    for entry in entries {
+        match format {
+            Format::Text => writeln!(output, "{}", entry)?,
+            Format::Binary => match entry.to_bytes::<G>() {
+                Some(bytes) => output.write_all(&bytes)?,
+                None => return Err(Error::other(format!("Can't pack {}", entry.fen))),
+            },
+        }
+    }
+
+    output.flush()
 }
 
-fn generate<G: GameRules>(_args: Args, _fens: &Vec<String>) -> Result<(), Error> {
-    // let mut workers = vec![];
-    // let (tx, rx) = mpsc::channel();
-    // let num_fens = fens.len();
-
-    // // Create workers
-    // for i in 0..args.threads {
-    //     let ntx: mpsc::Sender<Event<AtaxxPosition>> = tx.clone();
-    //     // let ntx: mpsc::Sender<Event<G>> = tx.clone();
-    //     let gg = fens.clone();
-
-    //     workers.push(thread::spawn(move || {
-    //         if args.verbose {
-    //             tx.send(Event::ThreadStart { thread_id: i }).unwrap();
-    //         }
-
-    //         let mut game_number = i;
-
-    //         while game_number < args.num_games {
-    //             let fen_idx = game_number % num_fens;
-    //             play(
-    //                 i,
-    //                 &gg[fen_idx],
-    //                 game_number,
-    //                 &GoSettings::from_nodes(args.nodes),
-    //                 &ntx,
-    //             );
-    //             game_number += args.threads;
-    //         }
-
-    //         if args.verbose {
-    //             tx.send(Event::ThreadFinish { thread_id: i }).unwrap();
-    //         }
-    //     }));
-    // }
-
-    // let mut games_completed = 0;
-
-    // for event in &rx {
-    //     match event {
-    //         Event::ThreadStart { thread_id } => {
-    //             if args.verbose {
-    //                 println!("Start thread {}", thread_id)
-    //             }
-    //         }
-    //         Event::ThreadFinish { thread_id } => {
-    //             if args.verbose {
-    //                 println!("Finish thread {}", thread_id)
-    //             }
-    //         }
-    //         Event::GameStart { thread_id, id, fen } => {
-    //             println!("<Thread:{}> Start game {} fen {}", thread_id, id, fen)
-    //         }
-    //         Event::GameFinish {
-    //             thread_id,
-    //             id,
-    //             result,
-    //         } => {
-    //             games_completed += 1;
-    //             println!(
-    //                 "<Thread:{}> Finish game {} result {}",
-    //                 thread_id,
-    //                 id,
-    //                 match result {
-    //                     Some(GameResult::Win(Side::Player1)) => "1-0",
-    //                     Some(GameResult::Win(Side::Player2)) => "0-1",
-    //                     Some(GameResult::Draw) => "1/2-1/2",
-    //                     None => "None",
-    //                 }
-    //             );
-    //             if games_completed >= args.num_games {
-    //                 break;
-    //             }
-    //         }
-    //         _ => {}
-    //     }
-    // }
-
-    // // Wait
-    // for worker in workers {
-    //     let _ = worker.join();
-    // }
+/// Turn a positions file into the other format, `args.format` being the one
+/// wanted.
+fn convert<G: Packable>(args: &Args) -> Result<(), Error> {
+    let from = match args.format {
+        Format::Text => Format::Binary,
+        Format::Binary => Format::Text,
+    };
+    let entries = read_entries::<G>(args.input_path.as_ref().unwrap(), from)?;
+    write_entries::<G>(args.output_path.as_ref().unwrap(), args.format, &entries)?;
+
+    if args.verbose {
+        println!("converted: {}", entries.len());
+    }
+
+    Ok(())
+}
+
+/// Search a positions file's positions again with the search named
+/// `args.search` and `args.nodes`, keeping the results of the games they came
+/// from. Threads take every `args.threads`th position with the generators a
+/// fresh run starts them with, and positions the search has no move for keep
+/// their old scores.
+fn rescore<G: Packable + Searchable>(args: &Args) -> Result<(), Error> {
+    let mut entries = read_entries::<G>(args.input_path.as_ref().unwrap(), args.format)?;
+    let settings = GoSettings::from_nodes(args.nodes);
+    let mut scores = vec![None; entries.len()];
+    let (tx, rx) = mpsc::channel();
+
+    let seeds = Checkpoint::new(args.threads).threads;
+
+    thread::scope(|scope| {
+        for (i, (_, state)) in seeds.into_iter().enumerate() {
+            let tx = tx.clone();
+            let (entries, settings) = (&entries, &settings);
+
+            scope.spawn(move || {
+                let mut prng = XorshiftGenerator::new(state);
+                for (idx, entry) in entries.iter().enumerate().skip(i).step_by(args.threads) {
+                    let pos = G::from_fen(&entry.fen);
+                    let score = if pos.is_gameover() {
+                        None
+                    } else {
+                        get_move(&pos, &args.search, settings, &mut prng).map(
+                            |(_, score)| match pos.get_turn() {
+                                Side::Player1 => score,
+                                Side::Player2 => -score,
+                            },
+                        )
+                    };
+                    tx.send((idx, score)).unwrap();
+                }
+            });
+        }
+        drop(tx);
+
+        for (done, (idx, score)) in rx.iter().enumerate() {
+            scores[idx] = score;
+            if args.verbose && (done + 1) % 10_000 == 0 {
+                println!("searched: {}/{}", done + 1, entries.len());
+            }
+        }
+    });
+
+    let mut rescored = 0;
+    for (entry, score) in entries.iter_mut().zip(scores) {
+        if let Some(score) = score {
+            *entry = Entry::new(&entry.fen, score, entry.wdl);
+            rescored += 1;
+        }
+    }
+
+    write_entries::<G>(args.output_path.as_ref().unwrap(), args.format, &entries)?;
+
+    if args.verbose {
+        println!("rescored: {}/{}", rescored, entries.len());
+    }
 
     Ok(())
 }
 
 fn main() -> Result<(), Error> {
     let args = args::parse_commandline()?;
+
+    if args.convert {
+        return match args.game.unwrap() {
+            Game::Ataxx => convert::<AtaxxPosition<7, 7>>(&args),
+            Game::Checkers => convert::<CheckersPosition>(&args),
+            Game::Chess => convert::<ChessPosition>(&args),
+            Game::Connect4 => convert::<Connect4Position>(&args),
+            Game::Droptaxx => convert::<DroptaxxPosition>(&args),
+            Game::Gomoku => convert::<GomokuPosition>(&args),
+            Game::Isolation => convert::<IsolationPosition>(&args),
+            Game::Othello => convert::<OthelloPosition>(&args),
+            Game::Pijersi => convert::<PijersiPosition>(&args),
+            Game::Tak => convert::<TakPosition<6>>(&args),
+            game => Err(Error::other(format!("{:?} has no binary layout", game))),
+        };
+    }
+
+    if args.rescore {
+        return match args.game.unwrap() {
+            Game::Ataxx => rescore::<AtaxxPosition<7, 7>>(&args),
+            Game::Checkers => rescore::<CheckersPosition>(&args),
+            Game::Chess => rescore::<ChessPosition>(&args),
+            Game::Connect4 => rescore::<Connect4Position>(&args),
+            Game::Droptaxx => rescore::<DroptaxxPosition>(&args),
+            Game::Gomoku => rescore::<GomokuPosition>(&args),
+            Game::Isolation => rescore::<IsolationPosition>(&args),
+            Game::Othello => rescore::<OthelloPosition>(&args),
+            Game::Pijersi => rescore::<PijersiPosition>(&args),
+            Game::Shogi => rescore::<ShogiPosition>(&args),
+            Game::Tak => rescore::<TakPosition<6>>(&args),
+            game => Err(Error::other(format!("{:?} can't be searched", game))),
+        };
+    }
+
     let fens = get_fens(&args.fens_path)?;
 
     if args.verbose {
         println!("Verbose enabled");
-        println!("game:  {}", args.game.unwrap());
+        println!("game:  {:?}", args.game.unwrap());
         println!("fens:  {}", fens.len());
         println!("nodes: {}", args.nodes);
     }
 
-    let _success = match args.game {
-        Some(Games::Ataxx) => generate::<AtaxxPosition<7, 7>>(args, &fens),
-        Some(Games::Chess) => generate::<ChessPosition>(args, &fens),
-        Some(Games::Connect4) => generate::<Connect4Position>(args, &fens),
-        Some(Games::Droptaxx) => generate::<DroptaxxPosition>(args, &fens),
-        Some(Games::Gomoku) => generate::<GomokuPosition>(args, &fens),
-        Some(Games::Isolation) => generate::<IsolationPosition>(args, &fens),
-        Some(Games::Pijersi) => generate::<PijersiPosition>(args, &fens),
-        Some(Games::Tak) => generate::<TakPosition<6>>(args, &fens),
-        None => todo!(),
-    };
+    match args.game {
+        Some(Game::Ataxx) => generate::<AtaxxPosition<7, 7>>(args, &fens),
+        Some(Game::Checkers) => generate::<CheckersPosition>(args, &fens),
+        Some(Game::Chess) => generate::<ChessPosition>(args, &fens),
+        Some(Game::Connect4) => generate::<Connect4Position>(args, &fens),
+        Some(Game::Droptaxx) => generate::<DroptaxxPosition>(args, &fens),
+        Some(Game::Gomoku) => generate::<GomokuPosition>(args, &fens),
+        Some(Game::Isolation) => generate::<IsolationPosition>(args, &fens),
+        Some(Game::Othello) => generate::<OthelloPosition>(args, &fens),
+        Some(Game::Pijersi) => generate::<PijersiPosition>(args, &fens),
+        Some(Game::Shogi) => generate::<ShogiPosition>(args, &fens),
+        Some(Game::Tak) => generate::<TakPosition<6>>(args, &fens),
+        Some(Game::Baduk) => Err(Error::other("Baduk can't be searched")),
+        None => Err(Error::other("game must be specified")),
+    }
+}
 
-    Ok(())
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rescore_keeps_results() {
+        let dir = std::env::temp_dir();
+        let input = dir.join(format!("datagen-rescore-{}-in.txt", std::process::id()));
+        let output = dir.join(format!("datagen-rescore-{}-out.txt", std::process::id()));
+        let entries = [
+            // Either side to move wins on the spot, and the last game's over
+            Entry::new("7/7/7/7/7/xxx1ooo x", 0, Wdl::Loss),
+            Entry::new("7/7/7/7/7/xxx1ooo o", 0, Wdl::Win),
+            Entry::new("7/7/7/7/o6/xxxxooo o", 17, Wdl::Draw),
+        ];
+        write_entries::<Connect4Position>(input.to_str().unwrap(), Format::Text, &entries).unwrap();
+
+        let args = args::parse_string(&format!(
+            "rescore --game connect4 --input {} --output {} --search minimax --nodes 2000 --threads 2",
+            input.display(),
+            output.display()
+        ))
+        .unwrap();
+        rescore::<Connect4Position>(&args).unwrap();
+        let rescored = read_entries::<Connect4Position>(output.to_str().unwrap(), Format::Text);
+        let _ = std::fs::remove_file(&input);
+        let _ = std::fs::remove_file(&output);
+        let rescored = rescored.unwrap();
+
+        assert_eq!(rescored.len(), entries.len());
+        for (before, after) in entries.iter().zip(&rescored) {
+            assert_eq!((&before.fen, before.wdl), (&after.fen, after.wdl));
+        }
+        assert_eq!(rescored[0].score, i16::MAX);
+        assert_eq!(rescored[1].score, -i16::MAX);
+        assert_eq!(rescored[2].score, 17);
+    }
 }
@@ -0,0 +1,880 @@
+use faeries::checkers::position::CheckersPosition;
+use faeries::connect4::position::Connect4Position;
+use faeries::droptaxx::position::DroptaxxPosition;
+use faeries::gomoku::position::GomokuPosition;
+use faeries::othello::position::OthelloPosition;
+use faeries::shogi::position::ShogiPosition;
+use faeries::symmetry::{Cells, board_fen, cells, dimensions};
+use games::{
+    ataxx::AtaxxPosition,
+    chess::ChessPosition,
+    gamerules::{GameResult, GameRules},
+    general::side::Side,
+    isolation::IsolationPosition,
+    pijersi::PijersiPosition,
+    tak::TakPosition,
+};
+use std::fmt;
+
+/// What a record keeps of a position.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Board {
+    pub width: u8,
+    pub height: u8,
+    /// A code for every square from the bottom left along the ranks, with 0
+    /// for an empty square. Codes only go up to 15. Games with stacks keep the
+    /// board more than once, one layer above another.
+    pub squares: Vec<u8>,
+    pub first_to_move: bool,
+    pub fullmove: u16,
+}
+
+/// How a game went, from the first player's view.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Wdl {
+    Loss,
+    Draw,
+    Win,
+}
+
+impl Wdl {
+    /// `None` for a game that hasn't finished.
+    #[must_use]
+    pub fn from_result(result: Option<GameResult>) -> Option<Wdl> {
+        match result? {
+            GameResult::Win(Side::Player1) => Some(Wdl::Win),
+            GameResult::Win(Side::Player2) => Some(Wdl::Loss),
+            GameResult::Draw => Some(Wdl::Draw),
+        }
+    }
+
+    #[must_use]
+    fn from_byte(byte: u8) -> Option<Wdl> {
+        match byte {
+            0 => Some(Wdl::Loss),
+            1 => Some(Wdl::Draw),
+            2 => Some(Wdl::Win),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    fn from_word(word: &str) -> Option<Wdl> {
+        match word {
+            "0.0" | "0" | "0-1" => Some(Wdl::Loss),
+            "0.5" | "1/2-1/2" => Some(Wdl::Draw),
+            "1.0" | "1" | "1-0" => Some(Wdl::Win),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Wdl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Wdl::Loss => write!(f, "0.0"),
+            Wdl::Draw => write!(f, "0.5"),
+            Wdl::Win => write!(f, "1.0"),
+        }
+    }
+}
+
+/// A game whose positions can be packed into records. Each game's layout
+/// says what its square codes mean and what of its FEN isn't kept.
+pub trait Packable: GameRules {
+    /// `None` for a FEN that can't be read, or a game without a layout.
+    #[must_use]
+    fn pack(_fen: &str) -> Option<Board> {
+        None
+    }
+
+    /// The FEN again, with whatever the record didn't keep at its start.
+    #[must_use]
+    fn unpack(_board: &Board) -> Option<String> {
+        None
+    }
+}
+
+/// A position, the search's score for it and the result of its game, with
+/// the score and result from the first player's view.
+///
+/// As text that's `fen | score | result`, with the result as `1.0`, `0.5` or
+/// `0.0`. A packed record is, with numbers little endian:
+///
+/// | Bytes              | Contents                                           |
+/// |--------------------|----------------------------------------------------|
+/// | 1                  | Width                                              |
+/// | 1                  | Height                                             |
+/// | (width*height+7)/8 | Occupancy, square `i` at bit `i % 8` of byte `i / 8` |
+/// | (occupied+1)/2     | A 4 bit code per occupied square, low nibble first |
+/// | 1                  | Side to move, 0 for the first player               |
+/// | 2                  | Score as an `i16`                                  |
+/// | 1                  | Result, 0 for a loss, 1 a draw and 2 a win         |
+/// | 2                  | Fullmove number as a `u16`                         |
+#[derive(Clone, PartialEq, Debug)]
+pub struct Entry {
+    pub fen: String,
+    pub score: i16,
+    pub wdl: Wdl,
+}
+
+impl Entry {
+    /// Scores too big for the record, such as mates, are cut down to fit.
+    #[must_use]
+    pub fn new(fen: &str, score: i32, wdl: Wdl) -> Self {
+        Entry {
+            fen: fen.to_owned(),
+            score: score.clamp(-i32::from(i16::MAX), i32::from(i16::MAX)) as i16,
+            wdl,
+        }
+    }
+
+    /// The text form, as the entry's `Display` writes it.
+    #[must_use]
+    pub fn parse(line: &str) -> Option<Entry> {
+        let mut fields = line.split('|').map(str::trim);
+        let fen = fields.next().filter(|fen| !fen.is_empty())?;
+        let score = fields.next()?.parse::<i16>().ok()?;
+        let wdl = Wdl::from_word(fields.next()?)?;
+        if fields.next().is_some() {
+            return None;
+        }
+
+        Some(Entry {
+            fen: fen.to_owned(),
+            score,
+            wdl,
+        })
+    }
+
+    #[must_use]
+    pub fn to_bytes<G: Packable>(&self) -> Option<Vec<u8>> {
+        let board = G::pack(&self.fen)?;
+        let mut bytes = vec![board.width, board.height];
+
+        let mut occupancy = vec![0; board.squares.len().div_ceil(8)];
+        let mut codes = vec![];
+        for (sq, code) in board.squares.iter().enumerate() {
+            if *code != 0 {
+                occupancy[sq / 8] |= 1 << (sq % 8);
+                codes.push(*code);
+            }
+        }
+        bytes.extend(occupancy);
+        bytes.extend(
+            codes
+                .chunks(2)
+                .map(|pair| pair[0] | pair.get(1).map_or(0, |high| high << 4)),
+        );
+
+        bytes.push(if board.first_to_move { 0 } else { 1 });
+        bytes.extend(self.score.to_le_bytes());
+        bytes.push(self.wdl as u8);
+        bytes.extend(board.fullmove.to_le_bytes());
+        Some(bytes)
+    }
+
+    /// Read the record at the start of `bytes`, and move `bytes` on past it.
+    #[must_use]
+    pub fn read<G: Packable>(bytes: &mut &[u8]) -> Option<Entry> {
+        let mut take = |n: usize| {
+            let (taken, rest) = bytes.split_at_checked(n)?;
+            *bytes = rest;
+            Some(taken)
+        };
+
+        let size = take(2)?;
+        let (width, height) = (size[0], size[1]);
+        let num_squares = usize::from(width) * usize::from(height);
+
+        let occupancy = take(num_squares.div_ceil(8))?;
+        let occupied = (0..num_squares)
+            .filter(|sq| occupancy[sq / 8] & (1 << (sq % 8)) != 0)
+            .collect::<Vec<usize>>();
+
+        let codes = take(occupied.len().div_ceil(2))?
+            .iter()
+            .flat_map(|byte| [byte & 0xf, byte >> 4]);
+        let mut squares = vec![0; num_squares];
+        for (sq, code) in occupied.iter().zip(codes) {
+            squares[*sq] = code;
+        }
+
+        let rest = take(6)?;
+        let board = Board {
+            width,
+            height,
+            squares,
+            first_to_move: rest[0] == 0,
+            fullmove: u16::from_le_bytes([rest[4], rest[5]]),
+        };
+
+        Some(Entry {
+            fen: G::unpack(&board)?,
+            score: i16::from_le_bytes([rest[1], rest[2]]),
+            wdl: Wdl::from_byte(rest[3])?,
+        })
+    }
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} | {} | {}", self.fen, self.score, self.wdl)
+    }
+}
+
+/// The board of a FEN written a character to a square, with each piece's
+/// code being its place in `pieces` counting from 1.
+#[must_use]
+fn pack_grid(fen: &str, pieces: &str, first_to_move: bool, fullmove: u16) -> Option<Board> {
+    let (width, height) = dimensions(fen, Cells::Chars)?;
+    let squares = cells(fen, Cells::Chars)?
+        .iter()
+        .map(|cell| match cell.as_str() {
+            "" => Some(0),
+            piece => pieces.find(piece).map(|idx| idx as u8 + 1),
+        })
+        .collect::<Option<Vec<u8>>>()?;
+
+    Some(Board {
+        width: u8::try_from(width).ok()?,
+        height: u8::try_from(height).ok()?,
+        squares,
+        first_to_move,
+        fullmove,
+    })
+}
+
+#[must_use]
+fn unpack_grid(board: &Board, pieces: &str) -> Option<String> {
+    let cells = board
+        .squares
+        .iter()
+        .map(|code| match code {
+            0 => Some(String::new()),
+            code => pieces.chars().nth(usize::from(*code) - 1).map(String::from),
+        })
+        .collect::<Option<Vec<String>>>()?;
+
+    if board.width == 0 || cells.len() != usize::from(board.width) * usize::from(board.height) {
+        return None;
+    }
+    Some(board_fen(&cells, i32::from(board.width), Cells::Chars))
+}
+
+/// Whether a FEN's side to move is the first player, written as `sides[0]`.
+#[must_use]
+fn first_to_move(word: Option<&str>, sides: [&str; 2]) -> Option<bool> {
+    match word? {
+        word if word == sides[0] => Some(true),
+        word if word == sides[1] => Some(false),
+        _ => None,
+    }
+}
+
+/// A missing counter counts as the start of the game.
+#[must_use]
+fn counter(word: Option<&str>) -> Option<u16> {
+    word.map_or(Some(1), |word| word.parse::<u16>().ok())
+}
+
+/// For FENs without move counters, every move having put a piece down.
+#[must_use]
+fn stones_fullmove(fen: &str) -> u16 {
+    let stones = fen
+        .split_ascii_whitespace()
+        .next()
+        .unwrap_or("")
+        .chars()
+        .filter(|c| c.is_ascii_alphabetic())
+        .count();
+    (stones / 2 + 1) as u16
+}
+
+/// Codes 1 to 3 are `x`, `o` and blockers. The halfmove clock isn't kept.
+impl Packable for AtaxxPosition<7, 7> {
+    fn pack(fen: &str) -> Option<Board> {
+        let mut words = fen.split_ascii_whitespace().skip(1);
+        let first = first_to_move(words.next(), ["x", "o"])?;
+        let fullmove = counter(words.nth(1))?;
+        pack_grid(fen, "xo-", first, fullmove)
+    }
+
+    fn unpack(board: &Board) -> Option<String> {
+        Some(format!(
+            "{} {} 0 {}",
+            unpack_grid(board, "xo-")?,
+            if board.first_to_move { "x" } else { "o" },
+            board.fullmove
+        ))
+    }
+}
+
+/// Codes 1 to 3 are `x`, `o` and blockers.
+impl Packable for DroptaxxPosition {
+    fn pack(fen: &str) -> Option<Board> {
+        let mut words = fen.split_ascii_whitespace().skip(1);
+        let first = first_to_move(words.next(), ["x", "o"])?;
+        let fullmove = counter(words.next())?;
+        pack_grid(fen, "xo-", first, fullmove)
+    }
+
+    fn unpack(board: &Board) -> Option<String> {
+        Some(format!(
+            "{} {} {}",
+            unpack_grid(board, "xo-")?,
+            if board.first_to_move { "x" } else { "o" },
+            board.fullmove
+        ))
+    }
+}
+
+/// Codes 1 and 2 are `x` and `o`. The FEN has no move counters, so the
+/// fullmove number comes from the discs played.
+impl Packable for Connect4Position {
+    fn pack(fen: &str) -> Option<Board> {
+        let first = first_to_move(fen.split_ascii_whitespace().nth(1), ["x", "o"])?;
+        pack_grid(fen, "xo", first, stones_fullmove(fen))
+    }
+
+    fn unpack(board: &Board) -> Option<String> {
+        Some(format!(
+            "{} {}",
+            unpack_grid(board, "xo")?,
+            if board.first_to_move { "x" } else { "o" }
+        ))
+    }
+}
+
+/// Codes 1 and 2 are `x` and `o`. The FEN has no move counters, so the
+/// fullmove number comes from the stones played.
+impl Packable for GomokuPosition {
+    fn pack(fen: &str) -> Option<Board> {
+        let first = first_to_move(fen.split_ascii_whitespace().nth(1), ["x", "o"])?;
+        pack_grid(fen, "xo", first, stones_fullmove(fen))
+    }
+
+    fn unpack(board: &Board) -> Option<String> {
+        Some(format!(
+            "{} {}",
+            unpack_grid(board, "xo")?,
+            if board.first_to_move { "x" } else { "o" }
+        ))
+    }
+}
+
+/// Codes 1 and 2 are `x` and `o`. The FEN has no move counters, so the
+/// fullmove number comes from the discs played after the first four.
+impl Packable for OthelloPosition {
+    fn pack(fen: &str) -> Option<Board> {
+        let first = first_to_move(fen.split_ascii_whitespace().nth(1), ["x", "o"])?;
+        let fullmove = stones_fullmove(fen).saturating_sub(2).max(1);
+        pack_grid(fen, "xo", first, fullmove)
+    }
+
+    fn unpack(board: &Board) -> Option<String> {
+        Some(format!(
+            "{} {}",
+            unpack_grid(board, "xo")?,
+            if board.first_to_move { "x" } else { "o" }
+        ))
+    }
+}
+
+/// What the chess codes go up to before the ones that say more than the piece.
+const CHESS_PIECES: &str = "PNBRQKpnbrqk";
+/// A rook that can still castle, white and black.
+const CASTLING_ROOKS: [u8; 2] = [13, 14];
+/// A pawn that's just moved two squares and can be taken en passant.
+const EN_PASSANT_PAWN: u8 = 15;
+/// Each castling right, and the square of the rook it belongs to.
+const CASTLING: [(char, usize); 4] = [('K', 7), ('Q', 0), ('k', 63), ('q', 56)];
+
+/// Codes 1 to 12 are `PNBRQKpnbrqk`. Rooks that can still castle are 13 for
+/// white and 14 for black, and a pawn that can be taken en passant is 15, so
+/// that the castling rights and en passant square needn't be kept. The halfmove
+/// clock isn't kept.
+impl Packable for ChessPosition {
+    fn pack(fen: &str) -> Option<Board> {
+        let words = fen.split_ascii_whitespace().collect::<Vec<&str>>();
+        let first = first_to_move(words.get(1).copied(), ["w", "b"])?;
+        let fullmove = counter(words.get(5).copied())?;
+        let mut board = pack_grid(fen, CHESS_PIECES, first, fullmove)?;
+        if board.squares.len() != 64 {
+            return None;
+        }
+
+        let castling = words.get(2).copied().unwrap_or("-");
+        for (right, sq) in CASTLING {
+            let (rook, castling_rook) = if right.is_ascii_uppercase() {
+                (4, CASTLING_ROOKS[0])
+            } else {
+                (10, CASTLING_ROOKS[1])
+            };
+            if castling.contains(right) && board.squares[sq] == rook {
+                board.squares[sq] = castling_rook;
+            }
+        }
+
+        // The pawn is in front of the square it can be taken on
+        if let Some(&[file, rank]) = words.get(3).map(|word| word.as_bytes()) {
+            let (x, y) = (
+                usize::from(file.wrapping_sub(b'a')),
+                rank.wrapping_sub(b'1'),
+            );
+            let pawn = match (x, y) {
+                (0..8, 2) => Some((3 * 8 + x, 1)),
+                (0..8, 5) => Some((4 * 8 + x, 7)),
+                _ => None,
+            };
+            if let Some((sq, _)) = pawn.filter(|(sq, piece)| board.squares[*sq] == *piece) {
+                board.squares[sq] = EN_PASSANT_PAWN;
+            }
+        }
+
+        Some(board)
+    }
+
+    fn unpack(board: &Board) -> Option<String> {
+        if board.squares.len() != 64 {
+            return None;
+        }
+        let mut plain = board.clone();
+
+        let mut castling = String::new();
+        for (right, sq) in CASTLING {
+            let (rook, castling_rook) = if right.is_ascii_uppercase() {
+                (4, CASTLING_ROOKS[0])
+            } else {
+                (10, CASTLING_ROOKS[1])
+            };
+            if plain.squares[sq] == castling_rook {
+                plain.squares[sq] = rook;
+                castling.push(right);
+            }
+        }
+        if castling.is_empty() {
+            castling.push('-');
+        }
+
+        // The side that isn't to move made the double step
+        let mut en_passant = "-".to_owned();
+        if let Some(sq) = plain
+            .squares
+            .iter()
+            .position(|code| *code == EN_PASSANT_PAWN)
+        {
+            if !(8..56).contains(&sq) {
+                return None;
+            }
+            let (pawn, target) = if board.first_to_move {
+                (7, sq + 8)
+            } else {
+                (1, sq - 8)
+            };
+            plain.squares[sq] = pawn;
+            en_passant = format!("{}{}", (b'a' + (target % 8) as u8) as char, target / 8 + 1);
+        }
+
+        Some(format!(
+            "{} {} {} {} 0 {}",
+            unpack_grid(&plain, CHESS_PIECES)?,
+            if board.first_to_move { "w" } else { "b" },
+            castling,
+            en_passant,
+            board.fullmove
+        ))
+    }
+}
+
+/// The 32 playable squares in their usual numbering make a single row. Codes 1
+/// to 4 are black men, white men, black kings and white kings. The halfmove
+/// clock isn't kept.
+///
+/// The FEN is read here rather than by `CheckersPosition`, which passes over
+/// what it can't read, so that squares off the board or taken twice aren't
+/// packed as something else.
+impl Packable for CheckersPosition {
+    fn pack(fen: &str) -> Option<Board> {
+        let mut words = fen.split_ascii_whitespace();
+        let mut sections = words.next()?.trim_end_matches('.').split(':');
+        let first = first_to_move(sections.next(), ["B", "W"])?;
+        let fullmove = counter(words.nth(1))?;
+
+        let mut squares = vec![0; 32];
+        for section in sections {
+            let (men, list) = match section.split_at_checked(1)? {
+                ("B", list) => (1, list),
+                ("W", list) => (2, list),
+                _ => return None,
+            };
+            for token in list.split(',').filter(|token| !token.is_empty()) {
+                let (king, token) = match token.strip_prefix('K') {
+                    Some(rest) => (2, rest),
+                    None => (0, token),
+                };
+                let (from, to) = token.split_once('-').unwrap_or((token, token));
+                let (from, to) = (from.parse::<usize>().ok()?, to.parse::<usize>().ok()?);
+                if from < 1 || to > 32 {
+                    return None;
+                }
+                for sq in from..=to {
+                    if squares[sq - 1] != 0 {
+                        return None;
+                    }
+                    squares[sq - 1] = men + king;
+                }
+            }
+        }
+
+        Some(Board {
+            width: 32,
+            height: 1,
+            squares,
+            first_to_move: first,
+            fullmove,
+        })
+    }
+
+    fn unpack(board: &Board) -> Option<String> {
+        if board.squares.len() != 32 || board.squares.iter().any(|code| *code > 4) {
+            return None;
+        }
+
+        let list = |men: u8, kings: u8| {
+            board
+                .squares
+                .iter()
+                .enumerate()
+                .filter_map(|(sq, code)| match code {
+                    code if *code == men => Some(format!("{}", sq + 1)),
+                    code if *code == kings => Some(format!("K{}", sq + 1)),
+                    _ => None,
+                })
+                .collect::<Vec<String>>()
+                .join(",")
+        };
+
+        Some(format!(
+            "{}:W{}:B{} 0 {}",
+            if board.first_to_move { "B" } else { "W" },
+            list(2, 4),
+            list(1, 3),
+            board.fullmove
+        ))
+    }
+}
+
+/// Codes 1 to 3 are `x`, `o` and the squares taken away.
+impl Packable for IsolationPosition {
+    fn pack(fen: &str) -> Option<Board> {
+        let mut words = fen.split_ascii_whitespace().skip(1);
+        let first = first_to_move(words.next(), ["x", "o"])?;
+        let fullmove = counter(words.next())?;
+        pack_grid(fen, "xo-", first, fullmove)
+    }
+
+    fn unpack(board: &Board) -> Option<String> {
+        Some(format!(
+            "{} {} {}",
+            unpack_grid(board, "xo-")?,
+            if board.first_to_move { "x" } else { "o" },
+            board.fullmove
+        ))
+    }
+}
+
+/// Stacks as layers of a board, the bottom of every stack in the first. A stack
+/// shorter than the tallest is empty in the layers above it.
+#[must_use]
+fn stack_layers(stacks: &[Vec<u8>], layers: usize) -> Vec<u8> {
+    (0..layers)
+        .flat_map(|layer| {
+            stacks
+                .iter()
+                .map(move |stack| stack.get(layer).copied().unwrap_or(0))
+        })
+        .collect()
+}
+
+/// The stacks back from layers of `area` squares, with `None` for a stack that
+/// has a gap in it.
+#[must_use]
+fn unstack_layers(squares: &[u8], area: usize) -> Option<Vec<Vec<u8>>> {
+    if area == 0 || !squares.len().is_multiple_of(area) {
+        return None;
+    }
+
+    let mut stacks = vec![vec![]; area];
+    for (sq, code) in squares.iter().enumerate() {
+        let stack = &mut stacks[sq % area];
+        match code {
+            0 => {}
+            _ if stack.len() != sq / area => return None,
+            code => stack.push(*code),
+        }
+    }
+    Some(stacks)
+}
+
+const PIJERSI_PIECES: &str = "RPSWrpsw";
+/// The number of cells in each row of a Pijersi board, from the top.
+const PIJERSI_ROWS: [usize; 7] = [6, 7, 6, 7, 6, 7, 6];
+
+/// Codes 1 to 8 are `RPSWrpsw`. The board is seven rows of seven, with the last
+/// cell of the short rows always empty, and is kept twice: the bottom piece of
+/// every cell, then above that the piece on top of it. The halfmove clock isn't
+/// kept.
+impl Packable for PijersiPosition {
+    fn pack(fen: &str) -> Option<Board> {
+        let mut words = fen.split_ascii_whitespace();
+        let rows = words.next()?.split('/').collect::<Vec<&str>>();
+        let first = first_to_move(words.next(), ["w", "b"])?;
+        let fullmove = counter(words.nth(1))?;
+        if rows.len() != PIJERSI_ROWS.len() {
+            return None;
+        }
+
+        let code = |piece: char| PIJERSI_PIECES.find(piece).map(|idx| idx as u8 + 1);
+        let mut stacks = vec![];
+        for (row, len) in rows.iter().zip(PIJERSI_ROWS).rev() {
+            let mut cells = vec![];
+            let mut chars = row.chars();
+            while let Some(c) = chars.next() {
+                if let Some(n) = c.to_digit(10) {
+                    cells.extend(std::iter::repeat_n(vec![], n as usize));
+                    continue;
+                }
+                // A piece, then `-` or the piece on top of it
+                let mut stack = vec![code(c)?];
+                match chars.next()? {
+                    '-' => {}
+                    top => stack.push(code(top)?),
+                }
+                cells.push(stack);
+            }
+
+            if cells.len() != len {
+                return None;
+            }
+            cells.resize(7, vec![]);
+            stacks.extend(cells);
+        }
+
+        Some(Board {
+            width: 7,
+            height: 14,
+            squares: stack_layers(&stacks, 2),
+            first_to_move: first,
+            fullmove,
+        })
+    }
+
+    fn unpack(board: &Board) -> Option<String> {
+        if (board.width, board.height) != (7, 14) {
+            return None;
+        }
+        let stacks = unstack_layers(&board.squares, 49)?;
+
+        let mut rows = vec![];
+        for (cells, len) in stacks.chunks(7).zip(PIJERSI_ROWS.iter().rev()) {
+            if cells[*len..].iter().any(|stack| !stack.is_empty()) {
+                return None;
+            }
+            let cells = cells[..*len]
+                .iter()
+                .map(|stack| {
+                    let mut cell = stack
+                        .iter()
+                        .map(|code| PIJERSI_PIECES.chars().nth(usize::from(*code) - 1))
+                        .collect::<Option<String>>()?;
+                    if stack.len() == 1 {
+                        cell.push('-');
+                    }
+                    Some(cell)
+                })
+                .collect::<Option<Vec<String>>>()?;
+            rows.push(board_fen(&cells, *len as i32, Cells::Chars));
+        }
+        rows.reverse();
+
+        Some(format!(
+            "{} {} 0 {}",
+            rows.join("/"),
+            if board.first_to_move { "w" } else { "b" },
+            board.fullmove
+        ))
+    }
+}
+
+/// Each player's flat stones, standing stones and capstones.
+const TAK_STONES: [&str; 6] = ["1", "2", "1S", "2S", "1C", "2C"];
+
+/// Codes 1 to 6 are `1`, `2`, `1S`, `2S`, `1C` and `2C`, with only the top of a
+/// stack ever standing or a capstone. The board is kept once for every stone in
+/// the tallest stack, the bottom stones first, so a board is at most 42 stones
+/// high. The stones each player has left come from those on the board.
+impl Packable for TakPosition<6> {
+    fn pack(fen: &str) -> Option<Board> {
+        let mut words = fen.split_ascii_whitespace().skip(1);
+        let first = first_to_move(words.next(), ["1", "2"])?;
+        let fullmove = counter(words.next())?;
+        if dimensions(fen, Cells::Commas)? != (6, 6) {
+            return None;
+        }
+
+        let code = |stone: &str| {
+            TAK_STONES
+                .iter()
+                .position(|known| *known == stone)
+                .map(|idx| idx as u8 + 1)
+        };
+        let stacks = cells(fen, Cells::Commas)?
+            .iter()
+            .map(|cell| {
+                if cell.is_empty() {
+                    return Some(vec![]);
+                }
+                let top = cell.len() - if cell.ends_with(['S', 'C']) { 2 } else { 1 };
+                let mut stack = cell
+                    .get(..top)?
+                    .chars()
+                    .map(|c| code(&c.to_string()).filter(|code| *code <= 2))
+                    .collect::<Option<Vec<u8>>>()?;
+                stack.push(code(cell.get(top..)?)?);
+                Some(stack)
+            })
+            .collect::<Option<Vec<Vec<u8>>>>()?;
+
+        let layers = stacks.iter().map(Vec::len).max()?.max(1);
+        Some(Board {
+            width: 6,
+            height: u8::try_from(6 * layers).ok()?,
+            squares: stack_layers(&stacks, layers),
+            first_to_move: first,
+            fullmove,
+        })
+    }
+
+    fn unpack(board: &Board) -> Option<String> {
+        if board.width != 6 {
+            return None;
+        }
+
+        let stone = |code: u8| TAK_STONES.get(usize::from(code).checked_sub(1)?).copied();
+        let cells = unstack_layers(&board.squares, 36)?
+            .iter()
+            .map(|stack| match stack.split_last() {
+                None => Some(String::new()),
+                Some((top, below)) => {
+                    let mut cell = below
+                        .iter()
+                        .map(|code| stone(*code).filter(|_| *code <= 2))
+                        .collect::<Option<String>>()?;
+                    cell.push_str(stone(*top)?);
+                    Some(cell)
+                }
+            })
+            .collect::<Option<Vec<String>>>()?;
+
+        Some(format!(
+            "{} {} {}",
+            board_fen(&cells, 6, Cells::Commas),
+            if board.first_to_move { "1" } else { "2" },
+            board.fullmove
+        ))
+    }
+}
+
+/// No layout: there are more kinds of piece than codes, and nowhere to keep the
+/// pieces in hand.
+impl Packable for ShogiPosition {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip<G: Packable>(fen: &str) {
+        let entry = Entry::new(fen, -35, Wdl::Draw);
+        let bytes = entry.to_bytes::<G>().unwrap();
+        let mut rest = bytes.as_slice();
+        assert_eq!(Entry::read::<G>(&mut rest), Some(entry.clone()), "{}", fen);
+        assert!(rest.is_empty(), "{}", fen);
+        assert_eq!(Entry::parse(&entry.to_string()), Some(entry), "{}", fen);
+    }
+
+    /// A capstone on a stack of flats, `stones` high in all.
+    fn tall_tak(stones: usize) -> String {
+        format!("2,x5/x6/x2,{}C,x3/x6/x6/x6 2 40", "1".repeat(stones))
+    }
+
+    #[test]
+    fn round_trips() {
+        round_trip::<AtaxxPosition<7, 7>>("x5o/7/7/7/7/7/o5x x 0 1");
+        round_trip::<AtaxxPosition<7, 7>>("x5o/7/2-1-2/7/2-1-2/7/o5x o 0 12");
+        round_trip::<DroptaxxPosition>("7/1xo4/2xx3/3oo2/4x2/2o4/7 x 5");
+        round_trip::<DroptaxxPosition>("5/1x-2/2o2/1-x2/5 o 3");
+        round_trip::<Connect4Position>("7/7/7/3o3/3xo2/2xxo2 x");
+        round_trip::<Connect4Position>("xxoo/ooxx/xxoo/ooxx o");
+        round_trip::<GomokuPosition>("19/19/19/19/19/19/19/19/19/9x9/19/19/19/19/19/19/19/19/19 o");
+        round_trip::<OthelloPosition>("8/8/8/3xo3/3ox3/8/8/8 x");
+        round_trip::<OthelloPosition>("8/8/8/2oxxx2/xxoxx3/2o5/1x1o4/8 x");
+        round_trip::<ChessPosition>("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        round_trip::<ChessPosition>("rnbqkbnr/pppp1ppp/8/8/3Pp3/8/PPP1PPPP/RNBQKBNR b Kq d3 0 3");
+        round_trip::<ChessPosition>("rnbqkbnr/ppp1pppp/8/2Pp4/8/8/PP1PPPPP/RNBQKBNR w - d6 0 3");
+        round_trip::<CheckersPosition>("W:WK10,K15,18,24,27,28:B12,16,20,K22,K25,K29 0 7");
+        round_trip::<IsolationPosition>("x6/1-5/7/3-3/7/5o1/7 o 7");
+        round_trip::<PijersiPosition>("6/R-p-5/p-5/7/6/7/6 w 0 1");
+        round_trip::<PijersiPosition>("rpw-4/7/RR5/7/6/1S-5/5SW b 0 9");
+        round_trip::<TakPosition<6>>("x6/x6/x6/x6/x6/x6 1 1");
+        round_trip::<TakPosition<6>>("x2,2,x3/x,1,1C,2,x2/x,2,21,1,x2/x2,2,x3/x6/x6 2 6");
+        round_trip::<TakPosition<6>>(&tall_tak(42));
+    }
+
+    #[test]
+    fn layout() {
+        let entry = Entry::new("7/7/7/7/7/7/xxo4 o 0 300", 100_000, Wdl::Win);
+        let bytes = entry.to_bytes::<AtaxxPosition<7, 7>>().unwrap();
+        let mut expected = vec![7, 7];
+        expected.extend([0b0000_0111, 0, 0, 0, 0, 0, 0]);
+        expected.extend([0x11, 0x02]);
+        expected.extend([1, 0xff, 0x7f, 2, 0x2c, 0x01]);
+        assert_eq!(bytes, expected);
+    }
+
+    #[test]
+    fn not_records() {
+        let bytes = Entry::new("x5o/7/7/7/7/7/o5x x 0 1", 0, Wdl::Loss)
+            .to_bytes::<AtaxxPosition<7, 7>>()
+            .unwrap();
+        for len in 0..bytes.len() {
+            assert!(Entry::read::<AtaxxPosition<7, 7>>(&mut &bytes[..len]).is_none());
+        }
+
+        assert!(Entry::parse("x5o/7/7/7/7/7/o5x x 0 1 | 0").is_none());
+        assert!(Entry::parse("x5o/7/7/7/7/7/o5x x 0 1 | x | 1.0").is_none());
+        assert!(Entry::parse("x5o/7/7/7/7/7/o5x x 0 1 | 0 | 2.0").is_none());
+        assert!(
+            Entry::new("x5q/7/7/7/7/7/o5x x 0 1", 0, Wdl::Win)
+                .to_bytes::<AtaxxPosition<7, 7>>()
+                .is_none()
+        );
+        assert!(
+            Entry::new("x5/6 x", 0, Wdl::Win)
+                .to_bytes::<TakPosition<6>>()
+                .is_none()
+        );
+        assert!(TakPosition::<6>::pack(&tall_tak(43)).is_none());
+        assert!(PijersiPosition::pack("6/R-6/s-s-5/7/6/7/6 w 0 1").is_none());
+        for fen in [
+            "X:W18:B1",
+            "B:W33:B1",
+            "B:W0:B1",
+            "B:W5:B1-5",
+            "B:Wx:B1",
+            "B:R18:B1",
+        ] {
+            assert!(CheckersPosition::pack(fen).is_none(), "{}", fen);
+        }
+    }
+}
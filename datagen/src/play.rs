@@ -1,30 +1,69 @@
+use crate::adjudication::{Adjudication, Adjudicator};
 use crate::events::Event;
-use games::gamerules::GameRules;
+use crate::filter::Filterable;
+use crate::search::Searchable;
+use faeries::bound::Bound;
+use faeries::prng::XorshiftGenerator;
+use faeries::record::{Record, Recordable};
+use faeries::search::think;
+use faeries::searchstats::SearchStats;
+use games::{gamerules::GameRules, general::side::Side};
 use protocols::GoSettings;
+use std::cell::Cell;
 use std::sync::mpsc;
 
-// fn info_handler<G: GameRules>(
-//     _: &G,
-//     _: Option<i32>,
-//     _: Option<i32>,
-//     _: Option<i32>,
-//     _: Option<i32>,
-//     _: Option<u64>,
-//     _: Option<u128>,
-//     _: Option<i32>,
-//     _: &Vec<G::MoveType>,
-// ) {
-// }
-
-fn get_move<G: GameRules>(pos: &G, _settings: &GoSettings) -> Option<G::MoveType> {
-    Some(pos.legal_moves()[0])
+/// The move to play, and the score the search named `search` gave the
+/// position.
+pub fn get_move<G: Searchable>(
+    pos: &G,
+    search: &str,
+    settings: &GoSettings,
+    prng: &mut XorshiftGenerator,
+) -> Option<(G::MoveType, i32)> {
+    let score = Cell::new(0);
+    let info_handler = |_: &G,
+                        _: Option<i32>,
+                        _: Option<i32>,
+                        searched: Option<i32>,
+                        bound: Option<Bound>,
+                        _: Option<i32>,
+                        _: Option<u64>,
+                        _: Option<u128>,
+                        _: Option<i32>,
+                        _: &Vec<G::MoveType>,
+                        _: Option<&SearchStats>| {
+        // Only finished iterations are exact
+        if let (Some(searched), None) = (searched, bound) {
+            score.set(searched);
+        }
+    };
+
+    let mv = think(
+        search,
+        pos,
+        settings,
+        &info_handler,
+        &G::evaluate,
+        prng,
+        &mut SearchStats::default(),
+        |stats| pos.search(settings, &info_handler, stats),
+    )?;
+    Some((mv, score.get()))
 }
 
-pub fn play<G: GameRules>(
+/// Play a game from `fen`, the first `random_plies` of it at random with
+/// moves `prng` picks and the rest with the search named `search`, and keep
+/// the searched positions that are worth training on.
+#[allow(clippy::too_many_arguments)]
+pub fn play<G: Recordable + Searchable + Filterable>(
     thread_id: usize,
     fen: &str,
     id: usize,
+    search: &str,
     settings: &GoSettings,
+    adjudication: Adjudication,
+    random_plies: usize,
+    prng: &mut XorshiftGenerator,
     tx: &mpsc::Sender<Event<G>>,
 ) {
     tx.send(Event::GameStart {
@@ -35,22 +74,65 @@ pub fn play<G: GameRules>(
     .unwrap();
 
     let mut pos = G::from_fen(fen);
+    let mut record = Record::new(&pos);
+    record.set_players("Faeries", "Faeries");
+    let mut positions = vec![];
+    let mut adjudicator = Adjudicator::new(adjudication);
+    let mut adjudicated = None;
+    let mut after_capture = false;
 
-    while !pos.is_gameover() {
-        let res = get_move(&pos, settings);
+    for _ in 0..random_plies {
+        let moves = pos.legal_moves();
+        if pos.is_gameover() || moves.is_empty() {
+            break;
+        }
+        let mv = moves[(prng.next() % moves.len() as u64) as usize];
+        tx.send(Event::Move { thread_id, id, mv }).unwrap();
+        record.push(&pos, &mv);
+        after_capture = pos.is_capture(&mv);
+        pos.makemove(&mv);
+        adjudicated = adjudicator.update(None);
+        if adjudicated.is_some() {
+            break;
+        }
+    }
+
+    while adjudicated.is_none() && !pos.is_gameover() {
+        let res = get_move(&pos, search, settings, prng);
 
-        if let Some(mv) = res {
+        if let Some((mv, score)) = res {
             tx.send(Event::Move { thread_id, id, mv }).unwrap();
+            record.push(&pos, &mv);
+            let score = match pos.get_turn() {
+                Side::Player1 => score,
+                Side::Player2 => -score,
+            };
+            // Positions a move's played from are never over
+            if !after_capture && !pos.in_check() {
+                positions.push((pos.get_fen(), score));
+            }
+            after_capture = pos.is_capture(&mv);
             pos.makemove(&mv);
+            adjudicated = adjudicator.update(Some(score));
         } else {
             panic!("No move returned");
         }
     }
 
+    record.result = match adjudicated {
+        Some(result) => {
+            record.set_tag("Termination", "adjudication");
+            Some(result)
+        }
+        None => pos.get_result(),
+    };
+
     tx.send(Event::GameFinish {
         thread_id,
         id,
-        result: pos.get_result(),
+        record,
+        positions,
+        prng: prng.state(),
     })
     .unwrap();
 }
@@ -0,0 +1,73 @@
+use crate::packed::Wdl;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// How often progress gets shown.
+pub const INTERVAL: Duration = Duration::from_secs(10);
+
+/// What this run's finished games have added up to. Results are from the
+/// first player's view.
+pub struct Progress {
+    start: Instant,
+    shown: Instant,
+    games: usize,
+    positions: usize,
+    wins: usize,
+    draws: usize,
+    losses: usize,
+}
+
+impl Default for Progress {
+    fn default() -> Self {
+        Self {
+            start: Instant::now(),
+            shown: Instant::now(),
+            games: 0,
+            positions: 0,
+            wins: 0,
+            draws: 0,
+            losses: 0,
+        }
+    }
+}
+
+impl Progress {
+    pub fn finish_game(&mut self, wdl: Option<Wdl>, positions: usize) {
+        self.games += 1;
+        self.positions += positions;
+        match wdl {
+            Some(Wdl::Win) => self.wins += 1,
+            Some(Wdl::Draw) => self.draws += 1,
+            Some(Wdl::Loss) => self.losses += 1,
+            None => {}
+        }
+    }
+
+    /// Whether it's been `INTERVAL` since progress was last shown, counting
+    /// this as showing it if so.
+    #[must_use]
+    pub fn due(&mut self) -> bool {
+        if self.shown.elapsed() < INTERVAL {
+            return false;
+        }
+        self.shown = Instant::now();
+        true
+    }
+}
+
+impl fmt::Display for Progress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let seconds = self.start.elapsed().as_secs_f64();
+        let rate = if seconds > 0.0 {
+            self.games as f64 / seconds
+        } else {
+            0.0
+        };
+
+        write!(
+            f,
+            "games {} ({:.2}/s) positions {} w/d/l {}/{}/{}",
+            self.games, rate, self.positions, self.wins, self.draws, self.losses
+        )
+    }
+}
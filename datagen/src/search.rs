@@ -0,0 +1,198 @@
+use faeries::checkers::position::CheckersPosition;
+use faeries::connect4::position::Connect4Position;
+use faeries::droptaxx::position::DroptaxxPosition;
+use faeries::gomoku::position::GomokuPosition;
+use faeries::othello::position::OthelloPosition;
+use faeries::searchstats::SearchStats;
+use faeries::shogi::position::ShogiPosition;
+use faeries::testsuite::InfoHandler;
+use faeries::{
+    ataxx, checkers, chess, connect4, droptaxx, gomoku, isolation, othello, pijersi, shogi, tak,
+};
+use games::{
+    ataxx::AtaxxPosition, chess::ChessPosition, gamerules::GameRules, isolation::IsolationPosition,
+    pijersi::PijersiPosition, tak::TakPosition,
+};
+use protocols::GoSettings;
+
+/// A game that can be played with the engine's own search, or with the generic
+/// ones `faeries::search::think` knows.
+pub trait Searchable: GameRules + Clone {
+    /// The game's own search, leaving what it counted in `stats`.
+    #[must_use]
+    fn search(
+        &self,
+        settings: &GoSettings,
+        info_handler: &InfoHandler<Self, Self::MoveType>,
+        stats: &mut SearchStats,
+    ) -> Option<Self::MoveType>;
+
+    /// What the generic searches score positions with.
+    #[must_use]
+    fn evaluate(&self) -> i32;
+}
+
+impl Searchable for AtaxxPosition<7, 7> {
+    fn search(
+        &self,
+        settings: &GoSettings,
+        info_handler: &InfoHandler<Self, Self::MoveType>,
+        stats: &mut SearchStats,
+    ) -> Option<Self::MoveType> {
+        ataxx::root::primary_with_stats(self.clone(), settings, &info_handler, stats)
+    }
+
+    fn evaluate(&self) -> i32 {
+        ataxx::eval::eval(self)
+    }
+}
+
+impl Searchable for CheckersPosition {
+    fn search(
+        &self,
+        settings: &GoSettings,
+        info_handler: &InfoHandler<Self, Self::MoveType>,
+        stats: &mut SearchStats,
+    ) -> Option<Self::MoveType> {
+        checkers::root::primary_with_stats(self.clone(), settings, &info_handler, stats)
+    }
+
+    fn evaluate(&self) -> i32 {
+        checkers::eval::eval(self)
+    }
+}
+
+impl Searchable for ChessPosition {
+    fn search(
+        &self,
+        settings: &GoSettings,
+        info_handler: &InfoHandler<Self, Self::MoveType>,
+        stats: &mut SearchStats,
+    ) -> Option<Self::MoveType> {
+        chess::root::primary_with_stats(self.clone(), settings, &info_handler, stats)
+    }
+
+    fn evaluate(&self) -> i32 {
+        chess::eval::eval(self)
+    }
+}
+
+impl Searchable for Connect4Position {
+    fn search(
+        &self,
+        settings: &GoSettings,
+        info_handler: &InfoHandler<Self, Self::MoveType>,
+        stats: &mut SearchStats,
+    ) -> Option<Self::MoveType> {
+        connect4::root::primary_with_stats(self.clone(), settings, &info_handler, stats)
+    }
+
+    fn evaluate(&self) -> i32 {
+        connect4::eval::eval(self)
+    }
+}
+
+impl Searchable for DroptaxxPosition {
+    fn search(
+        &self,
+        settings: &GoSettings,
+        info_handler: &InfoHandler<Self, Self::MoveType>,
+        stats: &mut SearchStats,
+    ) -> Option<Self::MoveType> {
+        droptaxx::root::primary_with_stats(self.clone(), settings, &info_handler, stats)
+    }
+
+    fn evaluate(&self) -> i32 {
+        droptaxx::eval::eval(self)
+    }
+}
+
+impl Searchable for GomokuPosition {
+    fn search(
+        &self,
+        settings: &GoSettings,
+        info_handler: &InfoHandler<Self, Self::MoveType>,
+        stats: &mut SearchStats,
+    ) -> Option<Self::MoveType> {
+        gomoku::root::primary_with_stats(self.clone(), settings, &info_handler, stats)
+    }
+
+    fn evaluate(&self) -> i32 {
+        gomoku::eval::eval(self)
+    }
+}
+
+impl Searchable for IsolationPosition {
+    fn search(
+        &self,
+        settings: &GoSettings,
+        info_handler: &InfoHandler<Self, Self::MoveType>,
+        stats: &mut SearchStats,
+    ) -> Option<Self::MoveType> {
+        isolation::root::primary_with_stats(self.clone(), settings, &info_handler, stats)
+    }
+
+    fn evaluate(&self) -> i32 {
+        isolation::eval::eval(self)
+    }
+}
+
+impl Searchable for OthelloPosition {
+    fn search(
+        &self,
+        settings: &GoSettings,
+        info_handler: &InfoHandler<Self, Self::MoveType>,
+        stats: &mut SearchStats,
+    ) -> Option<Self::MoveType> {
+        othello::root::primary_with_stats(self.clone(), settings, &info_handler, stats)
+    }
+
+    fn evaluate(&self) -> i32 {
+        othello::eval::eval(self)
+    }
+}
+
+impl Searchable for PijersiPosition {
+    fn search(
+        &self,
+        settings: &GoSettings,
+        info_handler: &InfoHandler<Self, Self::MoveType>,
+        stats: &mut SearchStats,
+    ) -> Option<Self::MoveType> {
+        pijersi::root::primary_with_stats(self.clone(), settings, &info_handler, stats)
+    }
+
+    fn evaluate(&self) -> i32 {
+        pijersi::eval::eval(self)
+    }
+}
+
+impl Searchable for ShogiPosition {
+    fn search(
+        &self,
+        settings: &GoSettings,
+        info_handler: &InfoHandler<Self, Self::MoveType>,
+        stats: &mut SearchStats,
+    ) -> Option<Self::MoveType> {
+        shogi::root::primary_with_stats(self.clone(), settings, &info_handler, stats)
+    }
+
+    fn evaluate(&self) -> i32 {
+        shogi::eval::eval(self)
+    }
+}
+
+impl Searchable for TakPosition<6> {
+    fn search(
+        &self,
+        settings: &GoSettings,
+        info_handler: &InfoHandler<Self, Self::MoveType>,
+        stats: &mut SearchStats,
+    ) -> Option<Self::MoveType> {
+        tak::root::primary_with_stats(self.clone(), settings, &info_handler, stats)
+    }
+
+    fn evaluate(&self) -> i32 {
+        tak::eval::eval(self)
+    }
+}
@@ -1,10 +1,67 @@
 use games::{Games, parse_game_name};
 use std::env;
 
+/// Every game faeries plays: those from the games crate, and those it has to
+/// itself.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Game {
+    Ataxx,
+    Baduk,
+    Checkers,
+    Chess,
+    Connect4,
+    Droptaxx,
+    Gomoku,
+    Isolation,
+    Othello,
+    Pijersi,
+    Shogi,
+    Tak,
+}
+
+impl From<Games> for Game {
+    fn from(game: Games) -> Self {
+        match game {
+            Games::Ataxx => Game::Ataxx,
+            Games::Chess => Game::Chess,
+            Games::Connect4 => Game::Connect4,
+            Games::Droptaxx => Game::Droptaxx,
+            Games::Gomoku => Game::Gomoku,
+            Games::Isolation => Game::Isolation,
+            Games::Pijersi => Game::Pijersi,
+            Games::Tak => Game::Tak,
+        }
+    }
+}
+
+#[must_use]
+pub fn parse_game(name: &str) -> Option<Game> {
+    match name {
+        "baduk" | "go" => Some(Game::Baduk),
+        "checkers" | "draughts" => Some(Game::Checkers),
+        "othello" | "reversi" => Some(Game::Othello),
+        "shogi" => Some(Game::Shogi),
+        _ => parse_game_name(name).map(Game::from),
+    }
+}
+
 #[derive(Default)]
 pub struct Args {
-    pub game: Option<Games>,
+    pub game: Option<Game>,
     pub depth: i32,
+    pub nodes: u64,
+    pub movetime: i32,
+    /// Search the bench positions and quit, rather than wait for a protocol
+    pub bench: bool,
+    /// Run the EPD positions in `file` and quit
+    pub testsuite: bool,
+    pub file: Option<String>,
+    /// Write search info, best moves, perft, options and errors as JSON lines
+    pub json: bool,
+    /// Answer analysis requests over HTTP on localhost, for any game
+    pub serve: bool,
+    /// The port to serve on, or any that's free if 0
+    pub port: u16,
 }
 
 #[must_use]
@@ -17,18 +74,30 @@ pub fn parse_args() -> std::io::Result<Args> {
         // Pairs
         if i + 1 < args.len() {
             match (args[i].as_str(), args[i + 1].as_str()) {
-                ("--game", value) => parsed.game = parse_game_name(value),
+                ("--game", value) => parsed.game = parse_game(value),
                 ("--depth", value) => parsed.depth = value.parse::<i32>().unwrap(),
+                ("--nodes", value) => parsed.nodes = value.parse::<u64>().unwrap(),
+                ("--movetime", value) => parsed.movetime = value.parse::<i32>().unwrap(),
+                ("--file", value) => parsed.file = Some(value.to_owned()),
+                ("--port", value) => parsed.port = value.parse::<u16>().unwrap(),
                 _ => {}
             }
         }
 
         // Singles
+        match args[i].as_str() {
+            "bench" => parsed.bench = true,
+            "testsuite" => parsed.testsuite = true,
+            "--json" => parsed.json = true,
+            "serve" => parsed.serve = true,
+            _ => {}
+        }
 
         i += 1;
     }
 
-    if parsed.game.is_none() {
+    // The server's told the game with every request
+    if parsed.game.is_none() && !parsed.serve {
         return Err(std::io::Error::new(
             std::io::ErrorKind::Other,
             "Invalid 'game' parameter",
@@ -1,23 +1,37 @@
+use crate::tune::{Tunable, difference, dot};
 use games::ataxx::AtaxxPosition;
+use games::gamerules::GameRules;
 use games::general::bitboard::Bitboard;
+use games::general::side::Side;
+
+pub const NAMES: [&str; 1] = ["material"];
+pub const WEIGHTS: [i32; 1] = [100];
 
 #[must_use]
-fn eval_side(us: Bitboard<7, 7>, _them: Bitboard<7, 7>, _blockers: Bitboard<7, 7>) -> i32 {
+fn features_side<const W: usize, const H: usize>(us: Bitboard<W, H>) -> [i32; 1] {
     // Material count
-    100 * us.count()
+    [us.count()]
 }
 
 #[must_use]
-fn eval_us(pos: &AtaxxPosition<7, 7>) -> i32 {
-    eval_side(pos.get_us(), pos.get_them(), pos.get_blockers())
+pub fn features<const W: usize, const H: usize>(pos: &AtaxxPosition<W, H>) -> [i32; 1] {
+    difference(features_side(pos.get_us()), features_side(pos.get_them()))
 }
 
 #[must_use]
-fn eval_them(pos: &AtaxxPosition<7, 7>) -> i32 {
-    eval_side(pos.get_them(), pos.get_us(), pos.get_blockers())
+pub fn eval<const W: usize, const H: usize>(pos: &AtaxxPosition<W, H>) -> i32 {
+    dot(&WEIGHTS, &features(pos))
 }
 
-#[must_use]
-pub fn eval(pos: &AtaxxPosition<7, 7>) -> i32 {
-    eval_us(pos) - eval_them(pos)
+impl<const W: usize, const H: usize> Tunable for AtaxxPosition<W, H> {
+    const NAMES: &'static [&'static str] = &NAMES;
+    const WEIGHTS: &'static [i32] = &WEIGHTS;
+
+    fn features(&self) -> Vec<i32> {
+        features(self).to_vec()
+    }
+
+    fn first_to_move(&self) -> bool {
+        self.get_turn() == Side::Player1
+    }
 }
@@ -0,0 +1,290 @@
+use super::eval::eval;
+use super::search::MATE_SCORE;
+use games::ataxx::{AtaxxMove, AtaxxPosition};
+use games::gamerules::GameRules;
+use games::general::side::Side;
+use games::general::square::Square;
+use std::io;
+use std::path::Path;
+
+/// The network is only for the usual 7x7 board.
+pub const SQUARES: usize = 49;
+/// Our discs, their discs and the blockers, a square of each.
+pub const INPUTS: usize = 3 * SQUARES;
+pub const HIDDEN: usize = 64;
+/// The feature layer is quantised by this much, and clipped to it.
+pub const QA: i32 = 255;
+/// The output layer is quantised by this much.
+pub const QB: i32 = 64;
+/// What an output of 1.0 is worth in centidiscs.
+pub const SCALE: i32 = 400;
+/// The furthest an evaluation goes either way, well short of the mate scores.
+pub const MAX_EVAL: i32 = MATE_SCORE / 2;
+
+/// How many values a network file holds.
+pub const FILE_VALUES: usize = INPUTS * HIDDEN + HIDDEN + 2 * HIDDEN + 1;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Cell {
+    Black,
+    White,
+    Blocker,
+}
+
+/// Where a cell's feature is for black's view and for white's.
+#[must_use]
+fn feature(cell: Cell, sq: usize) -> (usize, usize) {
+    match cell {
+        Cell::Black => (sq, SQUARES + sq),
+        Cell::White => (SQUARES + sq, sq),
+        Cell::Blocker => (2 * SQUARES + sq, 2 * SQUARES + sq),
+    }
+}
+
+/// What's on every square, counting from a1 along the ranks. Boards up to 8x8
+/// fit.
+#[must_use]
+fn cells<const W: usize, const H: usize>(pos: &AtaxxPosition<W, H>) -> [Option<Cell>; 64] {
+    let mut cells = [None; 64];
+    for y in 0..H {
+        for x in 0..W {
+            let sq = Square::<W, H>::from_coords(x as i32, y as i32);
+            cells[y * W + x] = if pos.get_black().is_square_set(sq) {
+                Some(Cell::Black)
+            } else if pos.get_white().is_square_set(sq) {
+                Some(Cell::White)
+            } else if pos.get_blockers().is_square_set(sq) {
+                Some(Cell::Blocker)
+            } else {
+                None
+            };
+        }
+    }
+    cells
+}
+
+/// The inputs that are on, for black's view and for white's.
+#[must_use]
+pub fn features(pos: &AtaxxPosition<7, 7>) -> (Vec<usize>, Vec<usize>) {
+    cells(pos)
+        .iter()
+        .take(SQUARES)
+        .enumerate()
+        .filter_map(|(sq, cell)| cell.map(|cell| feature(cell, sq)))
+        .unzip()
+}
+
+/// A quantised network: the inputs to a hidden layer for each side, both of
+/// which go to a single output, the side to move's first.
+///
+/// A file holds the feature weights input by input, the feature biases, the
+/// output weights and the output bias, as little endian `i16`s.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Network {
+    pub feature_weights: Vec<[i16; HIDDEN]>,
+    pub feature_bias: [i16; HIDDEN],
+    pub output_weights: [i16; 2 * HIDDEN],
+    pub output_bias: i16,
+}
+
+impl Network {
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> io::Result<Self> {
+        if bytes.len() != 2 * FILE_VALUES {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "Expected {} bytes of network, not {}",
+                    2 * FILE_VALUES,
+                    bytes.len()
+                ),
+            ));
+        }
+
+        let mut values = bytes
+            .chunks_exact(2)
+            .map(|pair| i16::from_le_bytes([pair[0], pair[1]]));
+        let mut next = || values.next().unwrap();
+
+        let feature_weights = (0..INPUTS)
+            .map(|_| std::array::from_fn(|_| next()))
+            .collect();
+        let feature_bias = std::array::from_fn(|_| next());
+        let output_weights = std::array::from_fn(|_| next());
+        let output_bias = next();
+
+        Ok(Network {
+            feature_weights,
+            feature_bias,
+            output_weights,
+            output_bias,
+        })
+    }
+
+    #[must_use]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.feature_weights
+            .iter()
+            .flatten()
+            .chain(&self.feature_bias)
+            .chain(&self.output_weights)
+            .chain(std::iter::once(&self.output_bias))
+            .flat_map(|value| value.to_le_bytes())
+            .collect()
+    }
+
+    /// Only for 7x7 boards, though it takes any size so the search needn't know.
+    #[must_use]
+    pub fn accumulate<const W: usize, const H: usize>(
+        &self,
+        pos: &AtaxxPosition<W, H>,
+    ) -> Accumulator {
+        debug_assert_eq!(W * H, SQUARES);
+        let bias = self.feature_bias.map(i32::from);
+        let mut acc = Accumulator {
+            black: bias,
+            white: bias,
+        };
+        for (sq, cell) in cells(pos).iter().take(SQUARES).enumerate() {
+            if let Some(cell) = cell {
+                acc.add(self, *cell, sq);
+            }
+        }
+        acc
+    }
+
+    /// The score for the side to move, in centidiscs.
+    ///
+    /// Large enough weights overflow an `i32` on the way, so it's worked out in
+    /// `i64`s.
+    #[must_use]
+    pub fn evaluate(&self, acc: &Accumulator, turn: Side) -> i32 {
+        let (us, them) = match turn {
+            Side::Player1 => (&acc.black, &acc.white),
+            Side::Player2 => (&acc.white, &acc.black),
+        };
+
+        let mut sum = 0;
+        for (value, weight) in us.iter().chain(them).zip(&self.output_weights) {
+            sum += i64::from((*value).clamp(0, QA)) * i64::from(*weight);
+        }
+
+        let score = (sum + i64::from(self.output_bias) * i64::from(QA)) * i64::from(SCALE)
+            / i64::from(QA * QB);
+        score.clamp(i64::from(-MAX_EVAL), i64::from(MAX_EVAL)) as i32
+    }
+}
+
+/// The hidden layer before it's clipped, for each side's view. Moves only
+/// change a few squares, so it's kept up to date rather than worked out again.
+///
+/// Sums of `i16` weights can go past what an `i16` holds, so they're `i32`s.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Accumulator {
+    pub black: [i32; HIDDEN],
+    pub white: [i32; HIDDEN],
+}
+
+impl Accumulator {
+    fn add(&mut self, net: &Network, cell: Cell, sq: usize) {
+        let (black, white) = feature(cell, sq);
+        for i in 0..HIDDEN {
+            self.black[i] += i32::from(net.feature_weights[black][i]);
+            self.white[i] += i32::from(net.feature_weights[white][i]);
+        }
+    }
+
+    fn sub(&mut self, net: &Network, cell: Cell, sq: usize) {
+        let (black, white) = feature(cell, sq);
+        for i in 0..HIDDEN {
+            self.black[i] -= i32::from(net.feature_weights[black][i]);
+            self.white[i] -= i32::from(net.feature_weights[white][i]);
+        }
+    }
+}
+
+/// Makes and unmakes moves for the search, so that the network's accumulators
+/// follow along. Without a network, or on a board it isn't for, the
+/// handcrafted evaluation gets used instead.
+pub struct Evaluator<'a> {
+    network: Option<&'a Network>,
+    stack: Vec<Accumulator>,
+}
+
+impl<'a> Evaluator<'a> {
+    #[must_use]
+    pub fn new<const W: usize, const H: usize>(
+        network: Option<&'a Network>,
+        pos: &AtaxxPosition<W, H>,
+    ) -> Self {
+        let network = network.filter(|_| W * H == SQUARES);
+        let stack = match network {
+            Some(net) => vec![net.accumulate(pos)],
+            None => vec![],
+        };
+        Evaluator { network, stack }
+    }
+
+    /// Only the squares a move changes get updated: the one moved from, the one
+    /// moved to and the discs turned over, all of which change side or empty.
+    /// Blockers never move.
+    pub fn makemove<const W: usize, const H: usize>(
+        &mut self,
+        pos: &mut AtaxxPosition<W, H>,
+        mv: &AtaxxMove<W, H>,
+    ) {
+        let Some(net) = self.network else {
+            pos.makemove(mv);
+            return;
+        };
+
+        let (black, white) = (pos.get_black(), pos.get_white());
+        pos.makemove(mv);
+        let changed = (black ^ pos.get_black()) | (white ^ pos.get_white());
+
+        let mut acc = self.stack.last().unwrap().clone();
+        for y in 0..H {
+            for x in 0..W {
+                let sq = Square::<W, H>::from_coords(x as i32, y as i32);
+                if !changed.is_square_set(sq) {
+                    continue;
+                }
+
+                let idx = y * W + x;
+                if black.is_square_set(sq) {
+                    acc.sub(net, Cell::Black, idx);
+                } else if white.is_square_set(sq) {
+                    acc.sub(net, Cell::White, idx);
+                }
+                if pos.get_black().is_square_set(sq) {
+                    acc.add(net, Cell::Black, idx);
+                } else if pos.get_white().is_square_set(sq) {
+                    acc.add(net, Cell::White, idx);
+                }
+            }
+        }
+        self.stack.push(acc);
+    }
+
+    pub fn undomove<const W: usize, const H: usize>(
+        &mut self,
+        pos: &mut AtaxxPosition<W, H>,
+        mv: &AtaxxMove<W, H>,
+    ) {
+        pos.undomove(mv);
+        if self.network.is_some() {
+            self.stack.pop();
+        }
+    }
+
+    #[must_use]
+    pub fn eval<const W: usize, const H: usize>(&self, pos: &AtaxxPosition<W, H>) -> i32 {
+        match (self.network, self.stack.last()) {
+            (Some(net), Some(acc)) => net.evaluate(acc, pos.get_turn()),
+            _ => eval(pos),
+        }
+    }
+}
@@ -1,10 +1,23 @@
+use super::nnue::{Network, SQUARES};
+use super::root::primary_with_network;
+use crate::bound::Bound;
 use crate::prng;
-use games::{ataxx::AtaxxPosition, gamerules::GameRules};
+use crate::search;
+use crate::searchstats::SearchStats;
+use games::{
+    ataxx::{AtaxxMove, AtaxxPosition},
+    gamerules::GameRules,
+};
+use protocols::GoSettings;
 use protocols::ugi::options::{Check, Combo};
 
 pub struct AtaxxState {
     pub pos: AtaxxPosition<7, 7>,
     pub prng: prng::XorshiftGenerator,
+    pub startfen: String,
+    pub history: Vec<Option<AtaxxMove<7, 7>>>,
+    /// What the last search counted
+    pub stats: SearchStats,
     // Options
     pub debug: Check,
     pub search: Combo,
@@ -15,6 +28,9 @@ impl Default for AtaxxState {
         Self {
             pos: AtaxxPosition::startpos(),
             prng: prng::XorshiftGenerator::new(0xe50076937a9e5b1c),
+            startfen: "startpos".to_owned(),
+            history: vec![],
+            stats: SearchStats::default(),
             // Options
             debug: Check {
                 name: "debug".to_owned(),
@@ -34,3 +50,77 @@ impl Default for AtaxxState {
         }
     }
 }
+
+impl AtaxxState {
+    #[must_use]
+    pub fn think(
+        &mut self,
+        settings: &GoSettings,
+        info_handler: &impl Fn(
+            &AtaxxPosition<7, 7>,
+            Option<i32>,
+            Option<i32>,
+            Option<i32>,
+            Option<Bound>,
+            Option<i32>,
+            Option<u64>,
+            Option<u128>,
+            Option<i32>,
+            &Vec<AtaxxMove<7, 7>>,
+            Option<&SearchStats>,
+        ),
+    ) -> Option<AtaxxMove<7, 7>> {
+        think(
+            &self.pos,
+            &self.search.value,
+            &mut self.prng,
+            &mut self.stats,
+            None,
+            settings,
+            info_handler,
+        )
+    }
+}
+
+/// Run the named search on a board of any size, evaluating with `network`
+/// where it fits the board.
+#[must_use]
+pub fn think<const W: usize, const H: usize>(
+    pos: &AtaxxPosition<W, H>,
+    search: &str,
+    prng: &mut prng::XorshiftGenerator,
+    stats: &mut SearchStats,
+    network: Option<&Network>,
+    settings: &GoSettings,
+    info_handler: &impl Fn(
+        &AtaxxPosition<W, H>,
+        Option<i32>,
+        Option<i32>,
+        Option<i32>,
+        Option<Bound>,
+        Option<i32>,
+        Option<u64>,
+        Option<u128>,
+        Option<i32>,
+        &Vec<AtaxxMove<W, H>>,
+        Option<&SearchStats>,
+    ),
+) -> Option<AtaxxMove<W, H>> {
+    let eval = |pos: &AtaxxPosition<W, H>| -> i32 {
+        match network {
+            Some(net) if W * H == SQUARES => net.evaluate(&net.accumulate(pos), pos.get_turn()),
+            _ => super::eval::eval(pos),
+        }
+    };
+
+    search::think(
+        search,
+        pos,
+        settings,
+        info_handler,
+        &eval,
+        prng,
+        stats,
+        |stats| primary_with_network(pos.clone(), settings, info_handler, stats, network),
+    )
+}
@@ -1,53 +1,50 @@
-use super::root::primary;
-use super::state::AtaxxState;
+use super::nnue::Network;
+use super::root::bench;
+use super::state::{AtaxxState, think};
+use crate::bound::Bound;
 use crate::colour::Colour;
-use crate::search::alphabeta::alphabeta;
-use crate::search::flatmc::flatmc;
-use crate::search::minimax::minimax;
-use crate::search::random::random;
+use crate::prng;
+use crate::searchstats::SearchStats;
 use games::ataxx::AtaxxMove;
 use games::gamerules::GameRules;
 use games::general::side::Side;
 use games::general::square::Square;
 use games::perft;
 use games::{ataxx::AtaxxPosition, gamerules::GameResult};
+use protocols::json::ToJson;
+use protocols::output::{self, Bestmove, Info, Message, StringOption};
+use protocols::ugi::options::{Check, Combo, Spin};
 use protocols::ugi::{GoSettings, UGI, UGIGameResult};
 
-pub fn info_handler(
-    _: &AtaxxPosition<7, 7>,
+pub fn info_handler<const W: usize, const H: usize>(
+    _: &AtaxxPosition<W, H>,
     depth: Option<i32>,
     seldepth: Option<i32>,
     score: Option<i32>,
+    bound: Option<Bound>,
     mate: Option<i32>,
     nodes: Option<u64>,
     elapsed: Option<u128>,
     hashfull: Option<i32>,
-    pv: &Vec<AtaxxMove<7, 7>>,
+    pv: &Vec<AtaxxMove<W, H>>,
+    stats: Option<&SearchStats>,
 ) {
-    print!("info");
-    depth.inspect(|d| print!(" depth {}", d));
-    seldepth.inspect(|d| print!(" seldepth {}", d));
-    score.inspect(|s| print!(" score cp {}", s));
-    mate.inspect(|d| print!(" score mate {}", d));
-    nodes.inspect(|n| print!(" nodes {}", n));
-    elapsed.inspect(|ms| print!(" time {}", ms));
-    if let (Some(t), Some(n)) = (elapsed, nodes) {
-        if t > 0 {
-            print!(" nps {}", (n as u128 * 1000) / t);
-        }
-    }
-    hashfull.inspect(|hashfull| print!(" hashfull {}", hashfull));
-    if !pv.is_empty() {
-        print!(" pv");
-        for mv in pv {
-            print!(" {}", as_ugi(mv));
-        }
-    }
-    println!();
+    output::emit(&Info {
+        depth,
+        seldepth,
+        score,
+        bound: bound.map(|b| b.to_string()),
+        mate,
+        nodes,
+        elapsed,
+        hashfull,
+        pv: pv.iter().map(as_ugi).collect(),
+        stats: stats.map(ToJson::to_json),
+    });
 }
 
 #[must_use]
-pub fn as_ugi(mv: &AtaxxMove<7, 7>) -> String {
+pub fn as_ugi<const W: usize, const H: usize>(mv: &AtaxxMove<W, H>) -> String {
     match mv {
         AtaxxMove::Pass => "0000".to_owned(),
         AtaxxMove::Single(to) => format!("{}", to).to_owned(),
@@ -55,7 +52,99 @@ pub fn as_ugi(mv: &AtaxxMove<7, 7>) -> String {
     }
 }
 
-impl UGI for AtaxxState {
+pub enum AtaxxPositions {
+    Size5(AtaxxPosition<5, 5>),
+    Size6(AtaxxPosition<6, 6>),
+    Size7(AtaxxPosition<7, 7>),
+    Size8(AtaxxPosition<8, 8>),
+}
+
+pub struct UGIAtaxxState {
+    pub pos: AtaxxPositions,
+    pub prng: prng::XorshiftGenerator,
+    /// What the last search counted
+    pub stats: SearchStats,
+    /// Loaded from `evalfile`, if it could be
+    pub network: Option<Network>,
+    // Options
+    pub debug: Check,
+    pub size: Spin<i32>,
+    pub search: Combo,
+    pub evalfile: String,
+}
+
+impl Default for UGIAtaxxState {
+    fn default() -> Self {
+        Self {
+            pos: AtaxxPositions::Size7(AtaxxPosition::startpos()),
+            prng: prng::XorshiftGenerator::new(0xe50076937a9e5b1c),
+            stats: SearchStats::default(),
+            network: None,
+            // Options
+            debug: Check {
+                name: "debug".to_owned(),
+                value: cfg!(debug_assertions),
+            },
+            size: Spin {
+                name: "size".to_owned(),
+                min: 5,
+                max: 8,
+                value: 7,
+            },
+            search: Combo {
+                name: "search".to_owned(),
+                value: "primary".to_owned(),
+                options: vec![
+                    "primary".to_owned(),
+                    "random".to_owned(),
+                    "minimax".to_owned(),
+                    "alphabeta".to_owned(),
+                    "flatmc".to_owned(),
+                ],
+            },
+            evalfile: String::new(),
+        }
+    }
+}
+
+fn makemove<const W: usize, const H: usize>(pos: &mut AtaxxPosition<W, H>, movestr: &str) {
+    let mv = pos
+        .legal_moves()
+        .iter()
+        .find(|mv| as_ugi(*mv) == movestr)
+        .copied();
+
+    if let Some(found) = mv {
+        pos.makemove(&found);
+    }
+}
+
+#[must_use]
+fn go<const W: usize, const H: usize>(
+    pos: &AtaxxPosition<W, H>,
+    search: &str,
+    prng: &mut prng::XorshiftGenerator,
+    stats: &mut SearchStats,
+    network: Option<&Network>,
+    settings: &protocols::GoSettings,
+) -> String {
+    match think(pos, search, prng, stats, network, settings, &info_handler) {
+        Some(mv) => as_ugi(&mv),
+        None => "0000".to_owned(),
+    }
+}
+
+fn movelist<const W: usize, const H: usize>(pos: &AtaxxPosition<W, H>) {
+    for mv in pos.legal_moves() {
+        println!("{}", as_ugi(&mv));
+    }
+}
+
+fn split_info<const W: usize, const H: usize>(mv: AtaxxMove<W, H>, nodes: u64) {
+    output::split(as_ugi(&mv), nodes);
+}
+
+impl UGI for UGIAtaxxState {
     fn init(&mut self) {
         self.uginewgame();
     }
@@ -71,7 +160,7 @@ impl UGI for AtaxxState {
     }
 
     fn uginewgame(&mut self) {
-        self.pos = AtaxxPosition::startpos();
+        self.position("startpos");
     }
 
     fn isready(&mut self) {
@@ -79,43 +168,42 @@ impl UGI for AtaxxState {
     }
 
     fn position(&mut self, fen: &str) {
-        self.pos.set_fen(fen);
+        match &mut self.pos {
+            AtaxxPositions::Size5(pos) => pos.set_fen(fen),
+            AtaxxPositions::Size6(pos) => pos.set_fen(fen),
+            AtaxxPositions::Size7(pos) => pos.set_fen(fen),
+            AtaxxPositions::Size8(pos) => pos.set_fen(fen),
+        }
     }
 
     fn moves(&mut self, movestr: &str) {
-        let mv = self
-            .pos
-            .legal_moves()
-            .iter()
-            .find(|mv| as_ugi(mv) == movestr)
-            .copied();
-
-        if let Some(found) = mv {
-            self.pos.makemove(&found);
+        match &mut self.pos {
+            AtaxxPositions::Size5(pos) => makemove(pos, movestr),
+            AtaxxPositions::Size6(pos) => makemove(pos, movestr),
+            AtaxxPositions::Size7(pos) => makemove(pos, movestr),
+            AtaxxPositions::Size8(pos) => makemove(pos, movestr),
         }
     }
 
     fn go(&mut self, settings: &GoSettings) {
-        let settings = &protocols::GoSettings::from(settings);
-        let eval = |pos: &AtaxxPosition<7, 7>| -> i32 {
-            100 * pos.get_us().count() - 100 * pos.get_them().count()
+        let settings = &protocols::GoSettings {
+            debug: self.debug.value,
+            ..protocols::GoSettings::from(settings)
         };
-
-        let bestmove = match self.search.value.as_str() {
-            "primary" => primary(self.pos.clone(), settings, &info_handler),
-            "random" => random(&self.pos, || self.prng.next()),
-            "minimax" => minimax(self.pos.clone(), settings, &info_handler, &eval),
-            "alphabeta" => alphabeta(self.pos.clone(), settings, &info_handler, &eval),
-            "flatmc" => flatmc(self.pos.clone(), settings, &info_handler, || {
-                self.prng.next()
-            }),
-            _ => panic!("Unknown search type"),
+        let search = &self.search.value;
+        let prng = &mut self.prng;
+        let stats = &mut self.stats;
+        let network = self.network.as_ref();
+        let bestmove = match &self.pos {
+            AtaxxPositions::Size5(pos) => go(pos, search, prng, stats, network, settings),
+            AtaxxPositions::Size6(pos) => go(pos, search, prng, stats, network, settings),
+            AtaxxPositions::Size7(pos) => go(pos, search, prng, stats, network, settings),
+            AtaxxPositions::Size8(pos) => go(pos, search, prng, stats, network, settings),
         };
-        if let Some(mv) = bestmove {
-            println!("bestmove {}", as_ugi(&mv));
-        } else {
-            println!("bestmove 0000");
-        }
+        output::emit(&Bestmove {
+            mv: bestmove,
+            stats: self.stats.to_json(),
+        });
     }
 
     fn stop(&mut self) {}
@@ -123,23 +211,45 @@ impl UGI for AtaxxState {
     fn unrecognised_command(
         &mut self,
         word: &str,
-        _args: &mut std::iter::Peekable<std::str::SplitAsciiWhitespace>,
+        args: &mut std::iter::Peekable<std::str::SplitAsciiWhitespace>,
     ) {
         match word {
-            "print" => self.print(),
-            "pprint" => self.pprint(),
+            "print" => match &self.pos {
+                AtaxxPositions::Size5(pos) => print(pos),
+                AtaxxPositions::Size6(pos) => print(pos),
+                AtaxxPositions::Size7(pos) => print(pos),
+                AtaxxPositions::Size8(pos) => print(pos),
+            },
+            "pprint" => match &self.pos {
+                AtaxxPositions::Size5(pos) => pprint(pos),
+                AtaxxPositions::Size6(pos) => pprint(pos),
+                AtaxxPositions::Size7(pos) => pprint(pos),
+                AtaxxPositions::Size8(pos) => pprint(pos),
+            },
+            "bench" => {
+                let depth = args.next().and_then(|word| word.parse::<i32>().ok());
+                println!("{}", bench(depth));
+            }
+            "stats" => output::emit(&Message(self.stats.to_string())),
             _ => {}
         }
     }
 
     fn print_options(&self) {
-        println!("{}", self.debug);
-        println!("{}", self.search);
+        output::emit(&self.debug);
+        output::emit(&self.size);
+        output::emit(&self.search);
+        output::emit(&StringOption {
+            name: "EvalFile".to_owned(),
+        });
     }
 
     fn movelist(&self) {
-        for mv in self.pos.legal_moves() {
-            println!("{}", as_ugi(&mv));
+        match &self.pos {
+            AtaxxPositions::Size5(pos) => movelist(pos),
+            AtaxxPositions::Size6(pos) => movelist(pos),
+            AtaxxPositions::Size7(pos) => movelist(pos),
+            AtaxxPositions::Size8(pos) => movelist(pos),
         }
     }
 
@@ -147,7 +257,35 @@ impl UGI for AtaxxState {
         match (name, value) {
             ("debug", "true") => self.debug.value = true,
             ("debug", "false") => self.debug.value = false,
+            ("size", _) => {
+                let Ok(size) = value.parse::<i32>() else {
+                    return;
+                };
+                self.pos = match size {
+                    5 => AtaxxPositions::Size5(AtaxxPosition::startpos()),
+                    6 => AtaxxPositions::Size6(AtaxxPosition::startpos()),
+                    7 => AtaxxPositions::Size7(AtaxxPosition::startpos()),
+                    8 => AtaxxPositions::Size8(AtaxxPosition::startpos()),
+                    _ => return,
+                };
+                self.size.value = size;
+            }
             ("search", _) => self.search.value = value.to_owned(),
+            // Anything that doesn't load leaves the handcrafted evaluation in charge
+            ("EvalFile", _) => {
+                self.evalfile = value.to_owned();
+                self.network = match value {
+                    "" | "<empty>" => None,
+                    path => match Network::load(path) {
+                        Ok(network) => Some(network),
+                        Err(e) => {
+                            let message = format!("Couldn't load {}: {}", path, e);
+                            output::emit(&output::Error(message));
+                            None
+                        }
+                    },
+                };
+            }
             (_, _) => {}
         }
     }
@@ -157,11 +295,24 @@ impl UGI for AtaxxState {
     }
 
     fn query_p1turn(&self) -> bool {
-        self.pos.get_turn() == Side::Player1
+        let turn = match &self.pos {
+            AtaxxPositions::Size5(pos) => pos.get_turn(),
+            AtaxxPositions::Size6(pos) => pos.get_turn(),
+            AtaxxPositions::Size7(pos) => pos.get_turn(),
+            AtaxxPositions::Size8(pos) => pos.get_turn(),
+        };
+        turn == Side::Player1
     }
 
     fn query_result(&self) -> Option<UGIGameResult> {
-        match self.pos.get_result() {
+        let result = match &self.pos {
+            AtaxxPositions::Size5(pos) => pos.get_result(),
+            AtaxxPositions::Size6(pos) => pos.get_result(),
+            AtaxxPositions::Size7(pos) => pos.get_result(),
+            AtaxxPositions::Size8(pos) => pos.get_result(),
+        };
+
+        match result {
             Some(GameResult::Win(Side::Player1)) => Some(UGIGameResult::P1Win),
             Some(GameResult::Win(Side::Player2)) => Some(UGIGameResult::P2Win),
             Some(GameResult::Draw) => Some(UGIGameResult::Draw),
@@ -170,93 +321,81 @@ impl UGI for AtaxxState {
     }
 
     fn perft(&mut self, settings: &GoSettings) {
-        let info_handler = |depth: i32, elapsed: f32, nodes: u64| {
-            print!("info");
-            print!(" depth {}", depth);
-            print!(" nodes {}", nodes);
-            print!(" time {}", (elapsed * 1000.0) as u64);
-            if elapsed > 0.0 {
-                let nps = nodes as f32 / elapsed;
-                print!(" nps {}", nps as u64);
-            }
-            println!();
-        };
-        let final_handler = |nodes: u64| {
-            println!("nodes {}", nodes);
+        let depth = settings.depth.unwrap();
+        match &mut self.pos {
+            AtaxxPositions::Size5(pos) => perft::perft(pos, depth, &output::perft, &output::nodes),
+            AtaxxPositions::Size6(pos) => perft::perft(pos, depth, &output::perft, &output::nodes),
+            AtaxxPositions::Size7(pos) => perft::perft(pos, depth, &output::perft, &output::nodes),
+            AtaxxPositions::Size8(pos) => perft::perft(pos, depth, &output::perft, &output::nodes),
         };
-        perft::perft(
-            &mut self.pos,
-            settings.depth.unwrap(),
-            &info_handler,
-            &final_handler,
-        );
     }
 
     fn split(&mut self, settings: &GoSettings) {
-        let info_handler = |mv: AtaxxMove<7, 7>, nodes: u64| println!("{} {}", as_ugi(&mv), nodes);
-        let final_handler = |nodes: u64| {
-            println!("nodes {}", nodes);
+        let depth = settings.depth.unwrap();
+        match &mut self.pos {
+            AtaxxPositions::Size5(pos) => perft::split(pos, depth, &split_info, &output::nodes),
+            AtaxxPositions::Size6(pos) => perft::split(pos, depth, &split_info, &output::nodes),
+            AtaxxPositions::Size7(pos) => perft::split(pos, depth, &split_info, &output::nodes),
+            AtaxxPositions::Size8(pos) => perft::split(pos, depth, &split_info, &output::nodes),
         };
-        perft::split(
-            &mut self.pos,
-            settings.depth.unwrap(),
-            &info_handler,
-            &final_handler,
-        );
     }
 }
 
-impl AtaxxState {
-    fn print(&self) {
-        print!("{}", self.pos);
-        println!("FEN: {}", self.pos.get_fen());
-    }
+fn print<const W: usize, const H: usize>(pos: &AtaxxPosition<W, H>) {
+    print!("{}", pos);
+    println!("FEN: {}", pos.get_fen());
+}
+
+pub fn pprint<const W: usize, const H: usize>(pos: &AtaxxPosition<W, H>) {
+    let printer = |piece: &str, x: i32, y: i32| {
+        let sq = Square::<W, H>::from_coords(x, y);
+        let is_light = y % 2 == x % 2;
 
-    fn pprint(&self) {
-        let printer = |piece: &str, x: i32, y: i32| {
-            let sq = Square::<7, 7>::from_coords(x, y);
-            let is_light = y % 2 == x % 2;
-
-            let background = if self.pos.get_blockers().is_square_set(sq) {
-                Colour::BlackBackground
-            } else if is_light {
-                Colour::MagentaBackground
-            } else {
-                Colour::BlueBackground
-            };
-
-            let foreground = if self.pos.get_black().is_square_set(sq) {
-                Colour::Black
-            } else {
-                Colour::WhiteBright
-            };
-
-            if self.pos.get_both().is_square_set(sq) {
-                print!("{}{}{}{}", background, foreground, piece, Colour::Clear);
-            } else {
-                print!("{}   {}", background, Colour::Clear);
-            };
+        let background = if pos.get_blockers().is_square_set(sq) {
+            Colour::BlackBackground
+        } else if is_light {
+            Colour::MagentaBackground
+        } else {
+            Colour::BlueBackground
         };
 
-        for y in (0..7).rev() {
-            for x in 0..7 {
-                printer("◢█◣", x, y);
-            }
-            println!("");
-            for x in 0..7 {
-                printer("███", x, y);
-            }
-            println!("");
-            for x in 0..7 {
-                printer("◥█◤", x, y);
-            }
-            println!("");
-        }
+        let foreground = if pos.get_black().is_square_set(sq) {
+            Colour::Black
+        } else {
+            Colour::WhiteBright
+        };
 
-        if self.pos.get_turn() == Side::Player1 {
-            println!("Turn: {}●{}", Colour::Black, Colour::Clear);
+        if pos.get_both().is_square_set(sq) {
+            print!("{}{}{}{}", background, foreground, piece, Colour::Clear);
         } else {
-            println!("Turn: {}●{}", Colour::WhiteBright, Colour::Clear);
+            print!("{}   {}", background, Colour::Clear);
+        };
+    };
+
+    for y in (0..H as i32).rev() {
+        for x in 0..W as i32 {
+            printer("◢█◣", x, y);
+        }
+        println!("");
+        for x in 0..W as i32 {
+            printer("███", x, y);
         }
+        println!("");
+        for x in 0..W as i32 {
+            printer("◥█◤", x, y);
+        }
+        println!("");
+    }
+
+    if pos.get_turn() == Side::Player1 {
+        println!("Turn: {}●{}", Colour::Black, Colour::Clear);
+    } else {
+        println!("Turn: {}●{}", Colour::WhiteBright, Colour::Clear);
+    }
+}
+
+impl AtaxxState {
+    pub fn pprint(&self) {
+        pprint(&self.pos);
     }
 }
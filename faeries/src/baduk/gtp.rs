@@ -0,0 +1,202 @@
+use super::position::{BadukMove, BadukPosition, Stone};
+use super::search::dead_stones;
+use super::state::BadukState;
+use crate::gtp::{MAX_SIZE, budget, clock, parse_vertex, vertex_name};
+use crate::prng::XorshiftGenerator;
+use protocols::GoSettings;
+use protocols::gtp::{GTP, GTPColour};
+
+/// Random games to play out when deciding which stones are dead.
+const STATUS_PLAYOUTS: u64 = 200;
+
+impl From<GTPColour> for Stone {
+    fn from(colour: GTPColour) -> Self {
+        match colour {
+            GTPColour::Black => Stone::Black,
+            GTPColour::White => Stone::White,
+        }
+    }
+}
+
+#[must_use]
+fn as_gtp(pos: &BadukPosition, mv: &BadukMove) -> String {
+    match mv {
+        BadukMove::Place(idx) => {
+            let (x, y) = pos.coords(*idx);
+            vertex_name(x, y)
+        }
+        BadukMove::Pass => "pass".to_owned(),
+    }
+}
+
+impl BadukState {
+    /// Start a new game, keeping the settings that aren't part of one.
+    fn reset(&mut self, size: usize) {
+        self.pos = BadukPosition::new(size, self.pos.get_komi());
+        self.pos.set_suicide(self.suicide);
+        self.history.clear();
+    }
+
+    /// The stones to take off before scoring. The playouts have a generator of their
+    /// own so that asking twice gives the same answer.
+    #[must_use]
+    fn dead_stones(&self) -> Vec<usize> {
+        let mut prng = XorshiftGenerator::new(0x5eed_dead_57a7_e500);
+        dead_stones(&self.pos, STATUS_PLAYOUTS, || prng.next())
+    }
+
+    #[must_use]
+    fn settings(&self, side: Stone) -> GoSettings {
+        if let Some(playouts) = self.playouts {
+            return GoSettings::from_nodes(playouts);
+        }
+
+        match self.time_left[side as usize] {
+            Some(time_left) => GoSettings::from_movetime(budget(time_left, self.byoyomi)),
+            None => GoSettings::default(),
+        }
+    }
+}
+
+impl GTP for BadukState {
+    fn name(&self) -> String {
+        "Faeries".to_owned()
+    }
+
+    fn version(&self) -> String {
+        env!("CARGO_PKG_VERSION").to_owned()
+    }
+
+    fn boardsize(&mut self, size: i32) -> Result<(), String> {
+        if !(2..=MAX_SIZE as i32).contains(&size) {
+            return Err("unacceptable size".to_owned());
+        }
+
+        self.reset(size as usize);
+        Ok(())
+    }
+
+    fn clear_board(&mut self) {
+        self.reset(self.pos.get_size());
+    }
+
+    fn komi(&mut self, komi: f32) {
+        self.pos.set_komi(komi);
+    }
+
+    fn play(&mut self, colour: GTPColour, vertex: &str) -> Result<(), String> {
+        let mv = if vertex.eq_ignore_ascii_case("pass") {
+            BadukMove::Pass
+        } else {
+            let size = self.pos.get_size();
+            let (x, y) = parse_vertex(vertex, size, size).ok_or("illegal move")?;
+            BadukMove::Place(self.pos.index(x, y))
+        };
+
+        if !self.pos.is_legal_for(colour.into(), &mv) {
+            return Err("illegal move".to_owned());
+        }
+
+        self.pos.makemove_for(colour.into(), &mv);
+        self.history.push(mv);
+        Ok(())
+    }
+
+    fn genmove(&mut self, colour: GTPColour) -> String {
+        let side = Stone::from(colour);
+        let settings = self.settings(side);
+
+        // The search plays for the side to move
+        let turn = self.pos.get_turn();
+        self.pos.set_turn(side);
+        let mv = self.think(&settings);
+        self.pos.set_turn(turn);
+
+        self.pos.makemove_for(side, &mv);
+        self.history.push(mv);
+        as_gtp(&self.pos, &mv)
+    }
+
+    fn undo(&mut self) -> Result<(), String> {
+        let mv = self.history.pop().ok_or("cannot undo")?;
+        self.pos.undomove(&mv);
+        Ok(())
+    }
+
+    fn showboard(&self) -> String {
+        format!("\n{}", self.pos)
+    }
+
+    fn final_score(&self) -> String {
+        let mut pos = self.pos.clone();
+        pos.remove(&self.dead_stones());
+        let score = pos.score();
+        if score > 0.0 {
+            format!("B+{}", score)
+        } else if score < 0.0 {
+            format!("W+{}", -score)
+        } else {
+            "0".to_owned()
+        }
+    }
+
+    /// Stones are dead if random play from here takes them off the board. There's
+    /// no telling seki apart, so those stones are alive.
+    fn final_status_list(&self, status: &str) -> Result<String, String> {
+        let dead = self.dead_stones();
+        let size = self.pos.get_size();
+        let stones = (0..size * size).filter(|idx| self.pos.get(*idx).is_some());
+        let list = |stones: Vec<usize>| {
+            stones
+                .iter()
+                .map(|idx| as_gtp(&self.pos, &BadukMove::Place(*idx)))
+                .collect::<Vec<String>>()
+                .join(" ")
+        };
+
+        match status {
+            "alive" => Ok(list(stones.filter(|idx| !dead.contains(idx)).collect())),
+            "dead" => Ok(list(dead)),
+            "seki" => Ok(String::new()),
+            _ => Err("invalid status".to_owned()),
+        }
+    }
+
+    fn time_settings(&mut self, main: i32, byoyomi: i32, stones: i32) {
+        let clock = clock(main, byoyomi, stones);
+        self.time_left = [clock.map(|(main, _)| (main, 0)); 2];
+        self.byoyomi = clock.and_then(|(_, byoyomi)| byoyomi);
+    }
+
+    fn time_left(&mut self, colour: GTPColour, time: i32, stones: i32) {
+        self.time_left[Stone::from(colour) as usize] = Some((time, stones));
+    }
+
+    fn extra_commands(&self) -> Vec<String> {
+        vec!["faeries-playouts".to_owned(), "faeries-suicide".to_owned()]
+    }
+
+    fn custom(&mut self, command: &str, args: &[&str]) -> Result<String, String> {
+        match command {
+            // Playouts per move, or 0 to go back to thinking by the clock
+            "faeries-playouts" => {
+                let playouts = args
+                    .first()
+                    .and_then(|n| n.parse::<u64>().ok())
+                    .ok_or("syntax error")?;
+                self.playouts = (playouts > 0).then_some(playouts);
+                Ok(String::new())
+            }
+            "faeries-suicide" => {
+                self.suicide = match args.first().map(|w| w.to_ascii_lowercase()).as_deref() {
+                    Some("true") | Some("on") => true,
+                    Some("false") | Some("off") => false,
+                    _ => return Err("syntax error".to_owned()),
+                };
+                self.pos.set_suicide(self.suicide);
+                Ok(String::new())
+            }
+            _ => Err("unknown command".to_owned()),
+        }
+    }
+}
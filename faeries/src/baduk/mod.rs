@@ -0,0 +1,4 @@
+pub mod gtp;
+pub mod position;
+pub mod search;
+pub mod state;
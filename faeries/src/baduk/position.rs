@@ -0,0 +1,401 @@
+use crate::gtp::vertex_name;
+use std::fmt;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Stone {
+    Black,
+    White,
+}
+
+impl Stone {
+    #[must_use]
+    pub fn opponent(self) -> Self {
+        match self {
+            Stone::Black => Stone::White,
+            Stone::White => Stone::Black,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BadukMove {
+    Place(usize),
+    Pass,
+}
+
+/// What a move changed, so that it can be taken back.
+#[derive(Clone, Debug)]
+struct Undo {
+    turn: Stone,
+    captured: Vec<(usize, Stone)>,
+    ko: Option<usize>,
+    passes: i32,
+}
+
+/// A game of Go on a square board, with area scoring.
+///
+/// Any stone can be played for either side, since handicap stones and GTP
+/// controllers don't always alternate. Suicide is illegal unless it's switched on,
+/// and the ko rule is simple ko: a single stone can't be taken straight back.
+#[derive(Clone, Debug)]
+pub struct BadukPosition {
+    size: usize,
+    board: Vec<Option<Stone>>,
+    turn: Stone,
+    ko: Option<usize>,
+    passes: i32,
+    komi: f32,
+    suicide: bool,
+    undo: Vec<Undo>,
+}
+
+impl BadukPosition {
+    #[must_use]
+    pub fn new(size: usize, komi: f32) -> Self {
+        Self {
+            size,
+            board: vec![None; size * size],
+            turn: Stone::Black,
+            ko: None,
+            passes: 0,
+            komi,
+            suicide: false,
+            undo: vec![],
+        }
+    }
+
+    #[must_use]
+    pub fn startpos() -> Self {
+        Self::new(19, 7.5)
+    }
+
+    #[must_use]
+    pub fn get_size(&self) -> usize {
+        self.size
+    }
+
+    #[must_use]
+    pub fn get_turn(&self) -> Stone {
+        self.turn
+    }
+
+    pub fn set_turn(&mut self, side: Stone) {
+        self.turn = side;
+    }
+
+    #[must_use]
+    pub fn get_komi(&self) -> f32 {
+        self.komi
+    }
+
+    pub fn set_komi(&mut self, komi: f32) {
+        self.komi = komi;
+    }
+
+    pub fn set_suicide(&mut self, allowed: bool) {
+        self.suicide = allowed;
+    }
+
+    #[must_use]
+    pub fn get(&self, idx: usize) -> Option<Stone> {
+        self.board[idx]
+    }
+
+    #[must_use]
+    pub fn index(&self, x: usize, y: usize) -> usize {
+        y * self.size + x
+    }
+
+    #[must_use]
+    pub fn coords(&self, idx: usize) -> (usize, usize) {
+        (idx % self.size, idx / self.size)
+    }
+
+    /// The number of moves that can be taken back.
+    #[must_use]
+    pub fn get_ply(&self) -> usize {
+        self.undo.len()
+    }
+
+    pub fn neighbours(&self, idx: usize) -> impl Iterator<Item = usize> + use<> {
+        let size = self.size;
+        let (x, y) = (idx % size, idx / size);
+        [
+            (x > 0).then(|| idx - 1),
+            (x + 1 < size).then(|| idx + 1),
+            (y > 0).then(|| idx - size),
+            (y + 1 < size).then(|| idx + size),
+        ]
+        .into_iter()
+        .flatten()
+    }
+
+    /// The stones connected to a point, and how many liberties they have between them.
+    #[must_use]
+    pub fn group(&self, idx: usize) -> (Vec<usize>, usize) {
+        let Some(colour) = self.board[idx] else {
+            return (vec![], 0);
+        };
+
+        let mut seen = vec![false; self.board.len()];
+        let mut stones = vec![idx];
+        let mut liberties = 0;
+        let mut i = 0;
+        seen[idx] = true;
+
+        while i < stones.len() {
+            for n in self.neighbours(stones[i]) {
+                if seen[n] {
+                    continue;
+                }
+                match self.board[n] {
+                    Some(stone) if stone == colour => {
+                        seen[n] = true;
+                        stones.push(n);
+                    }
+                    Some(_) => {}
+                    None => {
+                        seen[n] = true;
+                        liberties += 1;
+                    }
+                }
+            }
+            i += 1;
+        }
+
+        (stones, liberties)
+    }
+
+    #[must_use]
+    fn has_liberties(&self, idx: usize, more_than: usize) -> bool {
+        self.group(idx).1 > more_than
+    }
+
+    /// Whether a side could play a move, whoever's turn it is.
+    #[must_use]
+    pub fn is_legal_for(&self, side: Stone, mv: &BadukMove) -> bool {
+        let idx = match mv {
+            BadukMove::Pass => return true,
+            BadukMove::Place(idx) => *idx,
+        };
+
+        if idx >= self.board.len() || self.board[idx].is_some() {
+            return false;
+        }
+
+        if side == self.turn && self.ko == Some(idx) {
+            return false;
+        }
+
+        if self.suicide {
+            return true;
+        }
+
+        // A stone with room to breathe, next to friends who have room besides this
+        // point, or taking the last liberty of an enemy group isn't suicide
+        self.neighbours(idx).any(|n| match self.board[n] {
+            None => true,
+            Some(stone) if stone == side => self.has_liberties(n, 1),
+            Some(_) => !self.has_liberties(n, 1),
+        })
+    }
+
+    #[must_use]
+    pub fn is_legal(&self, mv: &BadukMove) -> bool {
+        self.is_legal_for(self.turn, mv)
+    }
+
+    #[must_use]
+    pub fn legal_moves(&self) -> Vec<BadukMove> {
+        let mut moves = (0..self.board.len())
+            .map(BadukMove::Place)
+            .filter(|mv| self.is_legal(mv))
+            .collect::<Vec<BadukMove>>();
+        moves.push(BadukMove::Pass);
+        moves
+    }
+
+    pub fn makemove(&mut self, mv: &BadukMove) {
+        self.makemove_for(self.turn, mv);
+    }
+
+    /// Play a move for a side, whoever's turn it is. The other side is to move after.
+    pub fn makemove_for(&mut self, side: Stone, mv: &BadukMove) {
+        debug_assert!(self.is_legal_for(side, mv));
+
+        let mut undo = Undo {
+            turn: self.turn,
+            captured: vec![],
+            ko: self.ko,
+            passes: self.passes,
+        };
+
+        match mv {
+            BadukMove::Pass => {
+                self.passes += 1;
+                self.ko = None;
+            }
+            BadukMove::Place(idx) => {
+                let idx = *idx;
+                self.board[idx] = Some(side);
+                self.passes = 0;
+
+                for n in self.neighbours(idx) {
+                    if self.board[n] == Some(side.opponent()) && !self.has_liberties(n, 0) {
+                        self.remove_group(n, &mut undo.captured);
+                    }
+                }
+
+                let (own, liberties) = self.group(idx);
+
+                // Taking a single stone with a single stone that's left in atari is a
+                // ko, and the stone can't be taken back straight away
+                self.ko = if undo.captured.len() == 1 && own.len() == 1 && liberties == 1 {
+                    Some(undo.captured[0].0)
+                } else {
+                    None
+                };
+
+                if liberties == 0 {
+                    self.remove_group(idx, &mut undo.captured);
+                }
+            }
+        }
+
+        self.turn = side.opponent();
+        self.undo.push(undo);
+    }
+
+    fn remove_group(&mut self, idx: usize, captured: &mut Vec<(usize, Stone)>) {
+        let Some(colour) = self.board[idx] else {
+            return;
+        };
+
+        for stone in self.group(idx).0 {
+            self.board[stone] = None;
+            captured.push((stone, colour));
+        }
+    }
+
+    pub fn undomove(&mut self, mv: &BadukMove) {
+        let undo = self.undo.pop().expect("No move to undo");
+
+        for (idx, stone) in undo.captured {
+            self.board[idx] = Some(stone);
+        }
+        if let BadukMove::Place(idx) = mv {
+            self.board[*idx] = None;
+        }
+
+        self.turn = undo.turn;
+        self.ko = undo.ko;
+        self.passes = undo.passes;
+    }
+
+    /// Take stones off without it being a move, as dead stones are at the end of
+    /// the game. This can't be undone.
+    pub fn remove(&mut self, stones: &[usize]) {
+        for idx in stones {
+            self.board[*idx] = None;
+        }
+    }
+
+    /// The game is over when both sides pass in a row.
+    #[must_use]
+    pub fn is_gameover(&self) -> bool {
+        self.passes >= 2
+    }
+
+    /// Whether the last move was a pass.
+    #[must_use]
+    pub fn is_passed(&self) -> bool {
+        self.passes > 0
+    }
+
+    /// Points for each side: their stones, plus the empty areas only they border.
+    /// Every stone on the board counts as alive, so dead ones have to be removed
+    /// first.
+    #[must_use]
+    pub fn area(&self) -> (usize, usize) {
+        let mut black = 0;
+        let mut white = 0;
+        let mut seen = vec![false; self.board.len()];
+
+        for idx in 0..self.board.len() {
+            match self.board[idx] {
+                Some(Stone::Black) => black += 1,
+                Some(Stone::White) => white += 1,
+                None if !seen[idx] => {
+                    let mut region = vec![idx];
+                    let (mut touches_black, mut touches_white) = (false, false);
+                    let mut i = 0;
+                    seen[idx] = true;
+
+                    while i < region.len() {
+                        for n in self.neighbours(region[i]) {
+                            match self.board[n] {
+                                Some(Stone::Black) => touches_black = true,
+                                Some(Stone::White) => touches_white = true,
+                                None if !seen[n] => {
+                                    seen[n] = true;
+                                    region.push(n);
+                                }
+                                None => {}
+                            }
+                        }
+                        i += 1;
+                    }
+
+                    match (touches_black, touches_white) {
+                        (true, false) => black += region.len(),
+                        (false, true) => white += region.len(),
+                        _ => {}
+                    }
+                }
+                None => {}
+            }
+        }
+
+        (black, white)
+    }
+
+    /// Black's area less White's and komi. Positive is good for Black.
+    #[must_use]
+    pub fn score(&self) -> f32 {
+        let (black, white) = self.area();
+        black as f32 - white as f32 - self.komi
+    }
+}
+
+impl fmt::Display for BadukPosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let header = (0..self.size)
+            .map(|x| vertex_name(x, 0)[..1].to_owned())
+            .collect::<Vec<String>>()
+            .join(" ");
+
+        writeln!(f, "   {}", header)?;
+        for y in (0..self.size).rev() {
+            write!(f, "{:>2}", y + 1)?;
+            for x in 0..self.size {
+                let c = match self.board[self.index(x, y)] {
+                    Some(Stone::Black) => 'X',
+                    Some(Stone::White) => 'O',
+                    None => '.',
+                };
+                write!(f, " {}", c)?;
+            }
+            writeln!(f, " {}", y + 1)?;
+        }
+        writeln!(f, "   {}", header)?;
+        write!(
+            f,
+            "{} to move, komi {}",
+            match self.turn {
+                Stone::Black => "Black",
+                Stone::White => "White",
+            },
+            self.komi
+        )
+    }
+}
@@ -0,0 +1,174 @@
+use super::position::{BadukMove, BadukPosition, Stone};
+use protocols::GoSettings;
+use std::time::Instant;
+
+/// Playouts to spend on a move when there's no other limit.
+const DEFAULT_PLAYOUTS: u64 = 2_000;
+
+/// A point surrounded by one side's stones that the other side can't easily take.
+/// Filling these is how random players kill their own groups.
+#[must_use]
+pub fn is_eye(pos: &BadukPosition, idx: usize, side: Stone) -> bool {
+    if pos.get(idx).is_some() || pos.neighbours(idx).any(|n| pos.get(n) != Some(side)) {
+        return false;
+    }
+
+    let size = pos.get_size() as i32;
+    let (x, y) = pos.coords(idx);
+    let (x, y) = (x as i32, y as i32);
+    let mut diagonals = 0;
+    let mut enemies = 0;
+
+    for (dx, dy) in [(-1, -1), (-1, 1), (1, -1), (1, 1)] {
+        let (nx, ny) = (x + dx, y + dy);
+        if nx < 0 || ny < 0 || nx >= size || ny >= size {
+            continue;
+        }
+        diagonals += 1;
+        if pos.get(pos.index(nx as usize, ny as usize)) == Some(side.opponent()) {
+            enemies += 1;
+        }
+    }
+
+    // On the edge a single enemy diagonal is enough to make the eye false
+    if diagonals < 4 {
+        enemies == 0
+    } else {
+        enemies < 2
+    }
+}
+
+/// A random legal move that doesn't fill one of the mover's own eyes, or a pass if
+/// there isn't one.
+#[must_use]
+fn random_move(pos: &BadukPosition, rand: &mut impl FnMut() -> u64) -> BadukMove {
+    let side = pos.get_turn();
+    let mut empty = (0..pos.get_size() * pos.get_size())
+        .filter(|idx| pos.get(*idx).is_none())
+        .collect::<Vec<usize>>();
+
+    while !empty.is_empty() {
+        let idx = empty.swap_remove(rand() as usize % empty.len());
+        let mv = BadukMove::Place(idx);
+        if pos.is_legal(&mv) && !is_eye(pos, idx, side) {
+            return mv;
+        }
+    }
+
+    BadukMove::Pass
+}
+
+/// Play randomly to the end.
+#[must_use]
+fn play_out(mut pos: BadukPosition, rand: &mut impl FnMut() -> u64) -> BadukPosition {
+    // Mutual eye filling aside, random games end well before this
+    let max_moves = 3 * pos.get_size() * pos.get_size();
+    let mut moves = 0;
+
+    while !pos.is_gameover() && moves < max_moves {
+        let mv = random_move(&pos, rand);
+        pos.makemove(&mv);
+        moves += 1;
+    }
+
+    pos
+}
+
+/// Play randomly to the end and score the result for Black.
+#[must_use]
+fn playout(pos: BadukPosition, rand: &mut impl FnMut() -> u64) -> f32 {
+    play_out(pos, rand).score()
+}
+
+/// The stones that are lost in most random games played out from here. Random
+/// players don't fill their own eyes, so groups that are alive stay on the board
+/// and those that aren't get taken.
+#[must_use]
+pub fn dead_stones(
+    pos: &BadukPosition,
+    playouts: u64,
+    mut rand: impl FnMut() -> u64,
+) -> Vec<usize> {
+    let points = pos.get_size() * pos.get_size();
+    let mut lost = vec![0u64; points];
+
+    for _ in 0..playouts {
+        let end = play_out(pos.clone(), &mut rand);
+        for (idx, count) in lost.iter_mut().enumerate() {
+            if pos.get(idx).is_some() && end.get(idx) != pos.get(idx) {
+                *count += 1;
+            }
+        }
+    }
+
+    (0..points)
+        .filter(|idx| 2 * lost[*idx] > playouts)
+        .collect()
+}
+
+/// Pick a move by playing random games from each candidate and keeping the one that
+/// wins most often. `nodes` counts playouts.
+#[must_use]
+pub fn flatmc(
+    pos: &BadukPosition,
+    settings: &GoSettings,
+    mut rand: impl FnMut() -> u64,
+) -> BadukMove {
+    let side = pos.get_turn();
+    let sign = match side {
+        Stone::Black => 1.0,
+        Stone::White => -1.0,
+    };
+
+    // The other side passed and the board as it stands is a win, so finish the game
+    if pos.is_passed() && sign * pos.score() > 0.0 {
+        return BadukMove::Pass;
+    }
+
+    let candidates = pos
+        .legal_moves()
+        .into_iter()
+        .filter(|mv| match mv {
+            BadukMove::Place(idx) => !is_eye(pos, *idx, side),
+            BadukMove::Pass => false,
+        })
+        .collect::<Vec<BadukMove>>();
+
+    if candidates.is_empty() {
+        return BadukMove::Pass;
+    }
+
+    let playouts = match (settings.nodes, settings.movetime) {
+        (Some(nodes), _) => nodes,
+        (None, Some(_)) => u64::MAX,
+        (None, None) => DEFAULT_PLAYOUTS,
+    };
+    let start = Instant::now();
+    let mut wins = vec![0u64; candidates.len()];
+    let mut games = vec![0u64; candidates.len()];
+
+    for n in 0..playouts {
+        if settings
+            .movetime
+            .is_some_and(|t| start.elapsed().as_millis() >= t as u128)
+        {
+            break;
+        }
+
+        let idx = n as usize % candidates.len();
+        let mut child = pos.clone();
+        child.makemove(&candidates[idx]);
+
+        if sign * playout(child, &mut rand) > 0.0 {
+            wins[idx] += 1;
+        }
+        games[idx] += 1;
+    }
+
+    let rate = |idx: usize| wins[idx] as f32 / games[idx].max(1) as f32;
+    let best = (0..candidates.len())
+        .max_by(|a, b| rate(*a).total_cmp(&rate(*b)))
+        .unwrap();
+
+    candidates[best]
+}
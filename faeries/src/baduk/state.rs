@@ -0,0 +1,39 @@
+use super::position::{BadukMove, BadukPosition};
+use super::search::flatmc;
+use crate::prng;
+use protocols::GoSettings;
+
+pub struct BadukState {
+    pub pos: BadukPosition,
+    pub prng: prng::XorshiftGenerator,
+    pub history: Vec<BadukMove>,
+    /// Seconds and byo-yomi stones left for Black and White
+    pub time_left: [Option<(i32, i32)>; 2],
+    /// Seconds and stones in each byo-yomi period
+    pub byoyomi: Option<(i32, i32)>,
+    // Options
+    pub playouts: Option<u64>,
+    pub suicide: bool,
+}
+
+impl Default for BadukState {
+    fn default() -> Self {
+        Self {
+            pos: BadukPosition::startpos(),
+            prng: prng::XorshiftGenerator::new(0xe50076937a9e5b1c),
+            history: vec![],
+            time_left: [None; 2],
+            byoyomi: None,
+            // Options
+            playouts: None,
+            suicide: false,
+        }
+    }
+}
+
+impl BadukState {
+    #[must_use]
+    pub fn think(&mut self, settings: &GoSettings) -> BadukMove {
+        flatmc(&self.pos, settings, || self.prng.next())
+    }
+}
@@ -0,0 +1,70 @@
+use crate::bound::Bound;
+use crate::searchstats::SearchStats;
+use protocols::GoSettings;
+use std::{fmt, time::Instant};
+
+/// How many nodes a bench searched, and how long it took in milliseconds.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Bench {
+    pub nodes: u64,
+    pub elapsed: u128,
+}
+
+impl Bench {
+    #[must_use]
+    pub fn nps(&self) -> u64 {
+        if self.elapsed == 0 {
+            return 0;
+        }
+        (self.nodes as u128 * 1000 / self.elapsed) as u64
+    }
+}
+
+impl fmt::Display for Bench {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} nodes {} nps", self.nodes, self.nps())
+    }
+}
+
+/// Search every position to `depth`, each from scratch, and add up the nodes.
+/// `search` sets up the position from its FEN and searches it.
+///
+/// Nothing but the search decides the count, so it's the same on any machine
+/// and only changes when the search does.
+#[must_use]
+pub fn run(
+    fens: &[&str],
+    depth: i32,
+    search: impl Fn(&str, &GoSettings, &mut SearchStats),
+) -> Bench {
+    let settings = GoSettings::from_depth(depth);
+    let mut stats = SearchStats::default();
+    let mut nodes = 0;
+    let start = Instant::now();
+
+    for fen in fens {
+        search(fen, &settings, &mut stats);
+        nodes += stats.nodes;
+    }
+
+    Bench {
+        nodes,
+        elapsed: start.elapsed().as_millis(),
+    }
+}
+
+/// An info handler for searches nobody's watching.
+pub fn quiet<P, M>(
+    _: &P,
+    _: Option<i32>,
+    _: Option<i32>,
+    _: Option<i32>,
+    _: Option<Bound>,
+    _: Option<i32>,
+    _: Option<u64>,
+    _: Option<u128>,
+    _: Option<i32>,
+    _: &Vec<M>,
+    _: Option<&SearchStats>,
+) {
+}
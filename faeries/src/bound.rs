@@ -0,0 +1,20 @@
+use std::fmt;
+
+/// Which side of a score the real one is on, when the search was only asked
+/// whether it was inside a window and it wasn't.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Bound {
+    /// The score is at least this much
+    Lower,
+    /// The score is at most this much
+    Upper,
+}
+
+impl fmt::Display for Bound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Bound::Lower => write!(f, "lowerbound"),
+            Bound::Upper => write!(f, "upperbound"),
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use super::position::{BLACK_KING_ROW, CheckersPosition, WHITE_KING_ROW};
+use crate::tune::{Tunable, difference, dot};
+use games::{gamerules::GameRules, general::side::Side};
+
+pub const NAMES: [&str; 3] = ["man", "king", "back_rank"];
+pub const WEIGHTS: [i32; 3] = [100, 150, 10];
+
+#[must_use]
+fn features_side(pieces: u32, kings: u32, back_rank: u32) -> [i32; 3] {
+    let men = pieces & !kings;
+
+    // Material, and men left at home to keep the other side from crowning
+    [
+        men.count_ones() as i32,
+        (pieces & kings).count_ones() as i32,
+        (men & back_rank).count_ones() as i32,
+    ]
+}
+
+#[must_use]
+pub fn features(pos: &CheckersPosition) -> [i32; 3] {
+    let kings = pos.get_kings();
+    let black = features_side(pos.get_black(), kings, WHITE_KING_ROW);
+    let white = features_side(pos.get_white(), kings, BLACK_KING_ROW);
+
+    match pos.get_turn() {
+        Side::Player1 => difference(black, white),
+        Side::Player2 => difference(white, black),
+    }
+}
+
+#[must_use]
+pub fn eval(pos: &CheckersPosition) -> i32 {
+    dot(&WEIGHTS, &features(pos))
+}
+
+impl Tunable for CheckersPosition {
+    const NAMES: &'static [&'static str] = &NAMES;
+    const WEIGHTS: &'static [i32] = &WEIGHTS;
+
+    fn features(&self) -> Vec<i32> {
+        features(self).to_vec()
+    }
+
+    fn first_to_move(&self) -> bool {
+        self.get_turn() == Side::Player1
+    }
+}
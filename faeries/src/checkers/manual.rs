@@ -0,0 +1,171 @@
+use super::position::{CheckersMove, CheckersPosition};
+use super::{
+    state::CheckersState,
+    ugi::{as_pdn, parse_move},
+};
+use crate::bound::Bound;
+use crate::record::Record;
+use crate::searchstats::SearchStats;
+use games::{
+    gamerules::{GameResult, GameRules},
+    general::side::Side,
+};
+use protocols::json::ToJson;
+use protocols::output::{self, Info};
+use protocols::{
+    GoSettings,
+    manual::{Manual, ManualGameResult},
+};
+
+pub fn info_handler(
+    pos: &CheckersPosition,
+    depth: Option<i32>,
+    seldepth: Option<i32>,
+    score: Option<i32>,
+    bound: Option<Bound>,
+    mate: Option<i32>,
+    nodes: Option<u64>,
+    elapsed: Option<u128>,
+    hashfull: Option<i32>,
+    pv: &Vec<CheckersMove>,
+    stats: Option<&SearchStats>,
+) {
+    let mut pos = pos.clone();
+    let mut moves = vec![];
+    for mv in pv {
+        moves.push(as_pdn(&pos, mv));
+        pos.makemove(mv);
+    }
+    output::emit(&Info {
+        depth,
+        seldepth,
+        score,
+        bound: bound.map(|b| b.to_string()),
+        mate,
+        nodes,
+        elapsed,
+        hashfull,
+        pv: moves,
+        stats: stats.map(ToJson::to_json),
+    });
+}
+
+pub fn empty_handler(
+    _: &CheckersPosition,
+    _: Option<i32>,
+    _: Option<i32>,
+    _: Option<i32>,
+    _: Option<Bound>,
+    _: Option<i32>,
+    _: Option<u64>,
+    _: Option<u128>,
+    _: Option<i32>,
+    _: &Vec<CheckersMove>,
+    _: Option<&SearchStats>,
+) {
+}
+
+impl CheckersState {
+    fn replay(&mut self) {
+        self.pos.set_fen(&self.startfen);
+        for mv in &self.history {
+            match mv {
+                Some(mv) => self.pos.makemove(mv),
+                None => self.pos.makenull(),
+            }
+        }
+    }
+}
+
+impl Manual for CheckersState {
+    fn print(&self) {
+        println!("{}", self.pos);
+    }
+
+    fn pprint(&self) {
+        CheckersState::pprint(self);
+    }
+
+    fn is_gameover(&self) -> bool {
+        self.pos.is_gameover()
+    }
+
+    fn is_p1turn(&self) -> bool {
+        self.pos.get_turn() == Side::Player1
+    }
+
+    fn makemove(&mut self, movestr: &str) -> bool {
+        if let Some(mv) = parse_move(&self.pos, movestr) {
+            self.pos.makemove(&mv);
+            self.history.push(Some(mv));
+            true
+        } else {
+            false
+        }
+    }
+
+    fn makenull(&mut self) {
+        self.pos.makenull();
+        self.history.push(None);
+    }
+
+    fn undo(&mut self) -> bool {
+        match self.history.pop() {
+            Some(Some(mv)) => self.pos.undomove(&mv),
+            // Null moves can only be taken back by playing the game again
+            Some(None) => self.replay(),
+            None => return false,
+        }
+        true
+    }
+
+    fn newgame(&mut self) {
+        self.set_fen("startpos");
+    }
+
+    fn set_fen(&mut self, fen: &str) {
+        self.pos.set_fen(fen);
+        self.startfen = fen.to_owned();
+        self.history.clear();
+    }
+
+    fn set_search(&mut self, name: &str) -> bool {
+        if self.search.options.iter().any(|option| option == name) {
+            self.search.value = name.to_owned();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Captures are written out in full, which takes the position each was played in.
+    fn history(&self) -> Vec<String> {
+        Record::replay::<CheckersPosition>(&self.startfen, &self.history).moves
+    }
+
+    fn play(&mut self, settings: &GoSettings) {
+        if let Some(mv) = self.think(settings, &empty_handler) {
+            self.pos.makemove(&mv);
+            self.history.push(Some(mv));
+        }
+    }
+
+    fn hint(&mut self, settings: &GoSettings) {
+        let _ = self.think(settings, &info_handler);
+    }
+
+    fn record(&self, p1: &str, p2: &str) -> String {
+        let mut record = Record::replay::<CheckersPosition>(&self.startfen, &self.history);
+        record.set_players(p1, p2);
+        record.to_string()
+    }
+
+    fn get_result(&self) -> Option<ManualGameResult> {
+        match self.pos.get_result() {
+            Some(GameResult::Win(Side::Player1)) => Some(ManualGameResult::P1Win),
+            Some(GameResult::Win(Side::Player2)) => Some(ManualGameResult::P2Win),
+            Some(GameResult::Draw) => Some(ManualGameResult::Draw),
+            None => None,
+        }
+    }
+}
@@ -0,0 +1,7 @@
+pub mod eval;
+pub mod manual;
+pub mod position;
+pub mod root;
+pub mod search;
+pub mod state;
+pub mod ugi;
@@ -0,0 +1,529 @@
+use games::{
+    gamerules::{GameResult, GameRules},
+    general::side::Side,
+};
+use std::fmt;
+
+pub const STARTPOS_FEN: &str =
+    "B:W21,22,23,24,25,26,27,28,29,30,31,32:B1,2,3,4,5,6,7,8,9,10,11,12 0 1";
+
+/// Moves without a capture or a man moving before the game is drawn: 40 each.
+pub const DRAW_PLIES: i32 = 80;
+
+/// Where Black's men are crowned, and White's back rank
+pub const BLACK_KING_ROW: u32 = 0xf000_0000;
+/// Where White's men are crowned, and Black's back rank
+pub const WHITE_KING_ROW: u32 = 0x0000_000f;
+
+const NONE: u8 = 32;
+
+/// The square one step away in each direction: up left, up right, down left and
+/// down right, with "up" towards Black's side of the board.
+const STEPS: [[u8; 4]; 32] = {
+    let mut steps = [[NONE; 4]; 32];
+    let dirs = [(-1, 1), (1, 1), (-1, -1), (1, -1)];
+    let mut sq = 0;
+    while sq < 32 {
+        let (x, y) = coords(sq as u8);
+        let mut dir = 0;
+        while dir < 4 {
+            let (nx, ny) = (x + dirs[dir].0, y + dirs[dir].1);
+            if nx >= 0 && nx < 8 && ny >= 0 && ny < 8 {
+                steps[sq][dir] = ((7 - ny) * 4 + nx / 2) as u8;
+            }
+            dir += 1;
+        }
+        sq += 1;
+    }
+    steps
+};
+
+/// The file and rank of a playable square, counting from the bottom left. Squares
+/// are numbered from 0 here, and from 1 in PDN, starting at the top left.
+#[must_use]
+pub const fn coords(sq: u8) -> (i32, i32) {
+    let row = sq as i32 / 4;
+    let x = 2 * (sq as i32 % 4) + if row % 2 == 0 { 1 } else { 0 };
+    (x, 7 - row)
+}
+
+/// A step or a sequence of jumps. The pieces a capture takes are enough to tell
+/// apart two sequences between the same squares.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct CheckersMove {
+    pub from: u8,
+    pub to: u8,
+    pub captured: u32,
+}
+
+impl fmt::Display for CheckersMove {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sep = if self.captured == 0 { '-' } else { 'x' };
+        write!(f, "{}{}{}", self.from + 1, sep, self.to + 1)
+    }
+}
+
+/// What a move changed, so that it can be taken back.
+#[derive(Clone, Debug)]
+struct Undo {
+    halfmoves: i32,
+    was_king: bool,
+    captured_kings: u32,
+}
+
+/// English draughts: men move and take forwards only and are crowned on the far
+/// row, kings move and take both ways one square at a time. Taking is compulsory,
+/// any sequence of jumps can be chosen, and a man that's crowned mid-sequence
+/// stops there. Black moves first.
+#[derive(Clone, Debug)]
+pub struct CheckersPosition {
+    black: u32,
+    white: u32,
+    kings: u32,
+    turn: Side,
+    halfmoves: i32,
+    fullmoves: i32,
+    undo: Vec<Undo>,
+}
+
+impl CheckersPosition {
+    #[must_use]
+    pub fn get_black(&self) -> u32 {
+        self.black
+    }
+
+    #[must_use]
+    pub fn get_white(&self) -> u32 {
+        self.white
+    }
+
+    #[must_use]
+    pub fn get_kings(&self) -> u32 {
+        self.kings
+    }
+
+    #[must_use]
+    pub fn get_us(&self) -> u32 {
+        match self.turn {
+            Side::Player1 => self.black,
+            Side::Player2 => self.white,
+        }
+    }
+
+    #[must_use]
+    pub fn get_them(&self) -> u32 {
+        match self.turn {
+            Side::Player1 => self.white,
+            Side::Player2 => self.black,
+        }
+    }
+
+    #[must_use]
+    pub fn get_halfmoves(&self) -> i32 {
+        self.halfmoves
+    }
+
+    /// The directions a piece on `sq` can move in.
+    #[must_use]
+    fn directions(&self, sq: u8) -> &'static [usize] {
+        if self.kings & (1 << sq) != 0 {
+            &[0, 1, 2, 3]
+        } else if self.black & (1 << sq) != 0 {
+            &[2, 3]
+        } else {
+            &[0, 1]
+        }
+    }
+
+    #[must_use]
+    fn is_crowning(&self, sq: u8, to: u8) -> bool {
+        if self.kings & (1 << sq) != 0 {
+            return false;
+        }
+        let row = if self.black & (1 << sq) != 0 {
+            BLACK_KING_ROW
+        } else {
+            WHITE_KING_ROW
+        };
+        row & (1 << to) != 0
+    }
+
+    /// Every way of carrying on a capture by the piece that started on `from` and
+    /// has reached `sq`. Pieces already jumped stay on the board until the move is
+    /// over, so they can't be jumped twice or landed on.
+    fn add_jumps(
+        &self,
+        from: u8,
+        sq: u8,
+        captured: u32,
+        empty: u32,
+        moves: &mut Vec<CheckersMove>,
+    ) {
+        let them = self.get_them();
+        let mut extended = false;
+
+        for &dir in self.directions(from) {
+            let mid = STEPS[sq as usize][dir];
+            if mid == NONE || them & !captured & (1 << mid) == 0 {
+                continue;
+            }
+            let land = STEPS[mid as usize][dir];
+            if land == NONE || empty & (1 << land) == 0 {
+                continue;
+            }
+
+            extended = true;
+            let captured = captured | (1 << mid);
+            if self.is_crowning(from, land) {
+                moves.push(CheckersMove {
+                    from,
+                    to: land,
+                    captured,
+                });
+            } else {
+                self.add_jumps(from, land, captured, empty, moves);
+            }
+        }
+
+        if !extended && captured != 0 {
+            let mv = CheckersMove {
+                from,
+                to: sq,
+                captured,
+            };
+            // A king can go round the same pieces either way
+            if !moves.contains(&mv) {
+                moves.push(mv);
+            }
+        }
+    }
+
+    #[must_use]
+    fn captures(&self) -> Vec<CheckersMove> {
+        let mut moves = vec![];
+        let mut us = self.get_us();
+
+        while us != 0 {
+            let from = us.trailing_zeros() as u8;
+            let empty = !(self.black | self.white) | (1 << from);
+            self.add_jumps(from, from, 0, empty, &mut moves);
+            us &= us - 1;
+        }
+
+        moves
+    }
+
+    #[must_use]
+    fn steps(&self) -> Vec<CheckersMove> {
+        let mut moves = vec![];
+        let empty = !(self.black | self.white);
+        let mut us = self.get_us();
+
+        while us != 0 {
+            let from = us.trailing_zeros() as u8;
+            for &dir in self.directions(from) {
+                let to = STEPS[from as usize][dir];
+                if to != NONE && empty & (1 << to) != 0 {
+                    moves.push(CheckersMove {
+                        from,
+                        to,
+                        captured: 0,
+                    });
+                }
+            }
+            us &= us - 1;
+        }
+
+        moves
+    }
+
+    /// The squares a capture lands on, from the first jump to the last. Moves that
+    /// aren't captures give just the destination.
+    #[must_use]
+    pub fn jump_path(&self, mv: &CheckersMove) -> Vec<u8> {
+        if mv.captured == 0 {
+            return vec![mv.to];
+        }
+
+        let empty = !(self.black | self.white) | (1 << mv.from);
+        let mut path = vec![];
+        self.find_path(mv, mv.from, mv.captured, empty, &mut path);
+        path
+    }
+
+    fn find_path(
+        &self,
+        mv: &CheckersMove,
+        sq: u8,
+        left: u32,
+        empty: u32,
+        path: &mut Vec<u8>,
+    ) -> bool {
+        if left == 0 {
+            return sq == mv.to;
+        }
+
+        for &dir in self.directions(mv.from) {
+            let mid = STEPS[sq as usize][dir];
+            if mid == NONE || left & (1 << mid) == 0 {
+                continue;
+            }
+            let land = STEPS[mid as usize][dir];
+            if land == NONE || empty & (1 << land) == 0 {
+                continue;
+            }
+
+            path.push(land);
+            if self.find_path(mv, land, left & !(1 << mid), empty, path) {
+                return true;
+            }
+            path.pop();
+        }
+
+        false
+    }
+}
+
+impl GameRules for CheckersPosition {
+    type MoveType = CheckersMove;
+
+    fn startpos() -> Self {
+        Self::from_fen(STARTPOS_FEN)
+    }
+
+    fn from_fen(fen: &str) -> Self {
+        let mut pos = Self {
+            black: 0,
+            white: 0,
+            kings: 0,
+            turn: Side::Player1,
+            halfmoves: 0,
+            fullmoves: 1,
+            undo: vec![],
+        };
+        pos.set_fen(fen);
+        pos
+    }
+
+    /// PDN style: the side to move, then each side's pieces by square number with
+    /// `K` in front of kings, as in `W:W18,K26:B1-4`. The move counters can follow.
+    fn set_fen(&mut self, fen: &str) {
+        let fen = if fen == "startpos" { STARTPOS_FEN } else { fen };
+        let mut parts = fen.split_ascii_whitespace();
+        let board = parts.next().unwrap_or("").trim_end_matches('.');
+
+        self.black = 0;
+        self.white = 0;
+        self.kings = 0;
+        self.turn = Side::Player1;
+        self.undo.clear();
+
+        for (idx, section) in board.split(':').enumerate() {
+            let mut chars = section.chars();
+            let colour = chars.next().map(|c| c.to_ascii_uppercase());
+
+            if idx == 0 {
+                if colour == Some('W') {
+                    self.turn = Side::Player2;
+                }
+                continue;
+            }
+
+            let pieces = match colour {
+                Some('B') => &mut self.black,
+                Some('W') => &mut self.white,
+                _ => continue,
+            };
+
+            for token in chars.as_str().split(',') {
+                let (king, token) = match token.strip_prefix(['K', 'k']) {
+                    Some(rest) => (true, rest),
+                    None => (false, token),
+                };
+                let (first, last) = token.split_once('-').unwrap_or((token, token));
+                let (Ok(first), Ok(last)) = (first.parse::<u32>(), last.parse::<u32>()) else {
+                    continue;
+                };
+
+                for n in first.max(1)..=last.min(32) {
+                    *pieces |= 1 << (n - 1);
+                    if king {
+                        self.kings |= 1 << (n - 1);
+                    }
+                }
+            }
+        }
+
+        self.halfmoves = parts.next().and_then(|n| n.parse().ok()).unwrap_or(0);
+        self.fullmoves = parts.next().and_then(|n| n.parse().ok()).unwrap_or(1);
+    }
+
+    fn get_fen(&self) -> String {
+        let list = |pieces: u32| {
+            (0..32)
+                .filter(|sq| pieces & (1 << sq) != 0)
+                .map(|sq| {
+                    let prefix = if self.kings & (1 << sq) != 0 { "K" } else { "" };
+                    format!("{}{}", prefix, sq + 1)
+                })
+                .collect::<Vec<String>>()
+                .join(",")
+        };
+
+        format!(
+            "{}:W{}:B{} {} {}",
+            match self.turn {
+                Side::Player1 => 'B',
+                Side::Player2 => 'W',
+            },
+            list(self.white),
+            list(self.black),
+            self.halfmoves,
+            self.fullmoves
+        )
+    }
+
+    fn get_turn(&self) -> Side {
+        self.turn
+    }
+
+    fn legal_moves(&self) -> Vec<CheckersMove> {
+        if self.halfmoves >= DRAW_PLIES {
+            return vec![];
+        }
+
+        let captures = self.captures();
+        if captures.is_empty() {
+            self.steps()
+        } else {
+            captures
+        }
+    }
+
+    fn makemove(&mut self, mv: &CheckersMove) {
+        let from = 1 << mv.from;
+        let to = 1 << mv.to;
+        let was_king = self.kings & from != 0;
+        let crowned = self.is_crowning(mv.from, mv.to);
+
+        self.undo.push(Undo {
+            halfmoves: self.halfmoves,
+            was_king,
+            captured_kings: self.kings & mv.captured,
+        });
+
+        let (us, them) = match self.turn {
+            Side::Player1 => (&mut self.black, &mut self.white),
+            Side::Player2 => (&mut self.white, &mut self.black),
+        };
+        // A king can finish a capture where it started
+        *us = (*us & !from) | to;
+        *them &= !mv.captured;
+        self.kings &= !(mv.captured | from);
+        if was_king || crowned {
+            self.kings |= to;
+        }
+
+        self.halfmoves = if was_king && mv.captured == 0 {
+            self.halfmoves + 1
+        } else {
+            0
+        };
+        if self.turn == Side::Player2 {
+            self.fullmoves += 1;
+        }
+        self.turn = match self.turn {
+            Side::Player1 => Side::Player2,
+            Side::Player2 => Side::Player1,
+        };
+    }
+
+    fn undomove(&mut self, mv: &CheckersMove) {
+        let undo = self.undo.pop().expect("No move to undo");
+        let from = 1 << mv.from;
+        let to = 1 << mv.to;
+
+        self.turn = match self.turn {
+            Side::Player1 => Side::Player2,
+            Side::Player2 => Side::Player1,
+        };
+        if self.turn == Side::Player2 {
+            self.fullmoves -= 1;
+        }
+
+        let (us, them) = match self.turn {
+            Side::Player1 => (&mut self.black, &mut self.white),
+            Side::Player2 => (&mut self.white, &mut self.black),
+        };
+        *us = (*us & !to) | from;
+        *them |= mv.captured;
+        self.kings &= !to;
+        if undo.was_king {
+            self.kings |= from;
+        }
+        self.kings |= undo.captured_kings;
+        self.halfmoves = undo.halfmoves;
+    }
+
+    fn makenull(&mut self) {
+        if self.turn == Side::Player2 {
+            self.fullmoves += 1;
+        }
+        self.turn = match self.turn {
+            Side::Player1 => Side::Player2,
+            Side::Player2 => Side::Player1,
+        };
+    }
+
+    fn is_gameover(&self) -> bool {
+        self.legal_moves().is_empty()
+    }
+
+    /// A side that can't move has lost, unless the game was drawn first.
+    fn get_result(&self) -> Option<GameResult> {
+        if self.halfmoves >= DRAW_PLIES {
+            Some(GameResult::Draw)
+        } else if self.legal_moves().is_empty() {
+            match self.turn {
+                Side::Player1 => Some(GameResult::Win(Side::Player2)),
+                Side::Player2 => Some(GameResult::Win(Side::Player1)),
+            }
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for CheckersPosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for y in (0..8).rev() {
+            write!(f, "{} ", y + 1)?;
+            for x in 0..8 {
+                if (x + y) % 2 == 1 {
+                    write!(f, " ")?;
+                    continue;
+                }
+                let sq = (7 - y) * 4 + x / 2;
+                let bb = 1 << sq;
+                let c = match (self.black & bb != 0, self.white & bb != 0) {
+                    (true, _) if self.kings & bb != 0 => 'B',
+                    (true, _) => 'b',
+                    (_, true) if self.kings & bb != 0 => 'W',
+                    (_, true) => 'w',
+                    _ => '.',
+                };
+                write!(f, "{}", c)?;
+            }
+            writeln!(f)?;
+        }
+        writeln!(f, "  abcdefgh")?;
+        write!(
+            f,
+            "Turn: {}",
+            match self.turn {
+                Side::Player1 => 'b',
+                Side::Player2 => 'w',
+            }
+        )
+    }
+}
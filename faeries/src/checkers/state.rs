@@ -0,0 +1,82 @@
+use super::eval::eval;
+use super::position::{CheckersMove, CheckersPosition};
+use super::root::primary_with_stats;
+use crate::bound::Bound;
+use crate::prng;
+use crate::search;
+use crate::searchstats::SearchStats;
+use games::gamerules::GameRules;
+use protocols::GoSettings;
+use protocols::ugi::options::{Check, Combo};
+
+pub struct CheckersState {
+    pub pos: CheckersPosition,
+    pub prng: prng::XorshiftGenerator,
+    pub startfen: String,
+    pub history: Vec<Option<CheckersMove>>,
+    /// What the last search counted
+    pub stats: SearchStats,
+    // Options
+    pub debug: Check,
+    pub search: Combo,
+}
+
+impl Default for CheckersState {
+    fn default() -> Self {
+        Self {
+            pos: CheckersPosition::startpos(),
+            prng: prng::XorshiftGenerator::new(0xe50076937a9e5b1c),
+            startfen: "startpos".to_owned(),
+            history: vec![],
+            stats: SearchStats::default(),
+            // Options
+            debug: Check {
+                name: "debug".to_owned(),
+                value: cfg!(debug_assertions),
+            },
+            search: Combo {
+                name: "search".to_owned(),
+                value: "primary".to_owned(),
+                options: vec![
+                    "primary".to_owned(),
+                    "random".to_owned(),
+                    "minimax".to_owned(),
+                    "alphabeta".to_owned(),
+                    "flatmc".to_owned(),
+                ],
+            },
+        }
+    }
+}
+
+impl CheckersState {
+    #[must_use]
+    pub fn think(
+        &mut self,
+        settings: &GoSettings,
+        info_handler: &impl Fn(
+            &CheckersPosition,
+            Option<i32>,
+            Option<i32>,
+            Option<i32>,
+            Option<Bound>,
+            Option<i32>,
+            Option<u64>,
+            Option<u128>,
+            Option<i32>,
+            &Vec<CheckersMove>,
+            Option<&SearchStats>,
+        ),
+    ) -> Option<CheckersMove> {
+        search::think(
+            &self.search.value,
+            &self.pos,
+            settings,
+            info_handler,
+            &eval,
+            &mut self.prng,
+            &mut self.stats,
+            |stats| primary_with_stats(self.pos.clone(), settings, info_handler, stats),
+        )
+    }
+}
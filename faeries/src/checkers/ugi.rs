@@ -0,0 +1,263 @@
+use super::position::{CheckersMove, CheckersPosition, coords};
+use super::root::bench;
+use super::state::CheckersState;
+use crate::bound::Bound;
+use crate::colour::Colour;
+use crate::searchstats::SearchStats;
+use games::gamerules::{GameResult, GameRules};
+use games::general::side::Side;
+use games::perft;
+use protocols::json::ToJson;
+use protocols::output::{self, Bestmove, Info, Message};
+use protocols::ugi::{GoSettings, UGI, UGIGameResult};
+
+pub fn info_handler(
+    pos: &CheckersPosition,
+    depth: Option<i32>,
+    seldepth: Option<i32>,
+    score: Option<i32>,
+    bound: Option<Bound>,
+    mate: Option<i32>,
+    nodes: Option<u64>,
+    elapsed: Option<u128>,
+    hashfull: Option<i32>,
+    pv: &Vec<CheckersMove>,
+    stats: Option<&SearchStats>,
+) {
+    // Jumps are written out square by square, which needs the position
+    let mut pos = pos.clone();
+    let mut moves = vec![];
+    for mv in pv {
+        moves.push(as_pdn(&pos, mv));
+        pos.makemove(mv);
+    }
+    output::emit(&Info {
+        depth,
+        seldepth,
+        score,
+        bound: bound.map(|b| b.to_string()),
+        mate,
+        nodes,
+        elapsed,
+        hashfull,
+        pv: moves,
+        stats: stats.map(ToJson::to_json),
+    });
+}
+
+/// A move in PDN: `11-15` for a step, and every square landed on for a capture,
+/// as in `15x24x31`. Squares are numbered 1 to 32.
+#[must_use]
+pub fn as_pdn(pos: &CheckersPosition, mv: &CheckersMove) -> String {
+    if mv.captured == 0 {
+        return mv.to_string();
+    }
+
+    let mut movestr = (mv.from + 1).to_string();
+    for sq in pos.jump_path(mv) {
+        movestr += &format!("x{}", sq + 1);
+    }
+    movestr
+}
+
+/// Find a legal move from its PDN. A capture can be given by its first and last
+/// squares alone if that's enough to tell which one it is.
+#[must_use]
+pub fn parse_move(pos: &CheckersPosition, movestr: &str) -> Option<CheckersMove> {
+    let moves = pos.legal_moves();
+    if let Some(mv) = moves.iter().find(|mv| as_pdn(pos, mv) == movestr) {
+        return Some(*mv);
+    }
+
+    let short = moves
+        .iter()
+        .filter(|mv| mv.captured != 0 && mv.to_string() == movestr)
+        .collect::<Vec<&CheckersMove>>();
+    match short[..] {
+        [mv] => Some(*mv),
+        _ => None,
+    }
+}
+
+impl UGI for CheckersState {
+    fn init(&mut self) {
+        self.uginewgame();
+    }
+
+    fn shutdown(&mut self) {}
+
+    fn name(&self) -> String {
+        format!("Faeries v{}", env!("CARGO_PKG_VERSION"))
+    }
+
+    fn author(&self) -> String {
+        "kz04px".to_owned()
+    }
+
+    fn uginewgame(&mut self) {
+        self.pos = CheckersPosition::startpos();
+    }
+
+    fn isready(&mut self) {
+        println!("readyok");
+    }
+
+    fn position(&mut self, fen: &str) {
+        self.pos.set_fen(fen);
+    }
+
+    fn moves(&mut self, movestr: &str) {
+        if let Some(mv) = parse_move(&self.pos, movestr) {
+            self.pos.makemove(&mv);
+        }
+    }
+
+    fn go(&mut self, settings: &GoSettings) {
+        let settings = &protocols::GoSettings {
+            debug: self.debug.value,
+            ..protocols::GoSettings::from(settings)
+        };
+        let bestmove = self.think(settings, &info_handler);
+        output::emit(&Bestmove {
+            mv: bestmove.map_or("0000".to_owned(), |mv| as_pdn(&self.pos, &mv)),
+            stats: self.stats.to_json(),
+        });
+    }
+
+    fn stop(&mut self) {}
+
+    fn unrecognised_command(
+        &mut self,
+        word: &str,
+        args: &mut std::iter::Peekable<std::str::SplitAsciiWhitespace>,
+    ) {
+        match word {
+            "print" => self.print(),
+            "pprint" => self.pprint(),
+            "bench" => {
+                let depth = args.next().and_then(|word| word.parse::<i32>().ok());
+                println!("{}", bench(depth));
+            }
+            "stats" => output::emit(&Message(self.stats.to_string())),
+            _ => {}
+        }
+    }
+
+    fn print_options(&self) {
+        output::emit(&self.debug);
+        output::emit(&self.search);
+    }
+
+    fn movelist(&self) {
+        for mv in self.pos.legal_moves() {
+            println!("{}", as_pdn(&self.pos, &mv));
+        }
+    }
+
+    fn set_option(&mut self, name: &str, value: &str) {
+        match (name, value) {
+            ("debug", "true") => self.debug.value = true,
+            ("debug", "false") => self.debug.value = false,
+            ("search", _) => self.search.value = value.to_owned(),
+            (_, _) => {}
+        }
+    }
+
+    fn is_debug(&self) -> bool {
+        self.debug.value
+    }
+
+    fn query_p1turn(&self) -> bool {
+        self.pos.get_turn() == Side::Player1
+    }
+
+    fn query_result(&self) -> Option<UGIGameResult> {
+        match self.pos.get_result() {
+            Some(GameResult::Win(Side::Player1)) => Some(UGIGameResult::P1Win),
+            Some(GameResult::Win(Side::Player2)) => Some(UGIGameResult::P2Win),
+            Some(GameResult::Draw) => Some(UGIGameResult::Draw),
+            None => None,
+        }
+    }
+
+    fn perft(&mut self, settings: &GoSettings) {
+        let info_handler = output::perft;
+        let final_handler = output::nodes;
+        perft::perft(
+            &mut self.pos,
+            settings.depth.unwrap(),
+            &info_handler,
+            &final_handler,
+        );
+    }
+
+    fn split(&mut self, settings: &GoSettings) {
+        let pos = self.pos.clone();
+        let info_handler = |mv: CheckersMove, nodes: u64| output::split(as_pdn(&pos, &mv), nodes);
+        let final_handler = output::nodes;
+        perft::split(
+            &mut self.pos,
+            settings.depth.unwrap(),
+            &info_handler,
+            &final_handler,
+        );
+    }
+}
+
+impl CheckersState {
+    fn print(&self) {
+        println!("{}", self.pos);
+        println!("FEN: {}", self.pos.get_fen());
+    }
+
+    pub fn pprint(&self) {
+        let printer = |row: usize, x: i32, y: i32| {
+            if (x + y) % 2 == 1 {
+                print!("{}   {}", Colour::YellowBackground, Colour::Clear);
+                return;
+            }
+
+            let sq = ((7 - y) * 4 + x / 2) as u8;
+            debug_assert_eq!(coords(sq), (x, y));
+            let bb = 1u32 << sq;
+
+            let foreground = if self.pos.get_black() & bb != 0 {
+                Colour::Black
+            } else {
+                Colour::WhiteBright
+            };
+            let piece = if self.pos.get_kings() & bb != 0 {
+                ["◢█◣", "▐K▌", "◥█◤"][row]
+            } else {
+                ["◢█◣", "███", "◥█◤"][row]
+            };
+
+            if (self.pos.get_black() | self.pos.get_white()) & bb != 0 {
+                print!(
+                    "{}{}{}{}",
+                    Colour::GreenBackground,
+                    foreground,
+                    piece,
+                    Colour::Clear
+                );
+            } else {
+                print!("{}   {}", Colour::GreenBackground, Colour::Clear);
+            };
+        };
+
+        for y in (0..8).rev() {
+            for row in 0..3 {
+                for x in 0..8 {
+                    printer(row, x, y);
+                }
+                println!("");
+            }
+        }
+
+        if self.pos.get_turn() == Side::Player1 {
+            println!("Turn: {}●{}", Colour::Black, Colour::Clear);
+        } else {
+            println!("Turn: {}●{}", Colour::WhiteBright, Colour::Clear);
+        }
+    }
+}
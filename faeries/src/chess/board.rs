@@ -0,0 +1,188 @@
+use crate::symmetry::{Cells, cells};
+use games::{chess::ChessPosition, gamerules::GameRules};
+
+const KNIGHT: [(i32, i32); 8] = [
+    (1, 2),
+    (2, 1),
+    (2, -1),
+    (1, -2),
+    (-1, -2),
+    (-2, -1),
+    (-2, 1),
+    (-1, 2),
+];
+const KING: [(i32, i32); 8] = [
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+];
+const ROOK: [(i32, i32); 4] = [(0, 1), (1, 0), (0, -1), (-1, 0)];
+const BISHOP: [(i32, i32); 4] = [(1, 1), (1, -1), (-1, -1), (-1, 1)];
+
+/// The square a name like `e4` stands for, a1 = 0, b1 = 1 ... h8 = 63.
+#[must_use]
+pub fn square(name: &str) -> usize {
+    let bytes = name.as_bytes();
+    (bytes[1] - b'1') as usize * 8 + (bytes[0] - b'a') as usize
+}
+
+/// The pieces on every square the way a FEN writes them, and which side is to
+/// move.
+///
+/// It's read from a FEN once and then kept up to date a move at a time, so the
+/// search can tell when a king is in check without writing a FEN at every node.
+#[derive(Clone, Copy, Debug)]
+pub struct Board {
+    /// Indexed a1 = 0, b1 = 1 ... h8 = 63, with 0 for an empty square
+    squares: [u8; 64],
+    white: bool,
+}
+
+impl Board {
+    #[must_use]
+    pub fn of(pos: &ChessPosition) -> Self {
+        let fen = pos.get_fen();
+        let mut squares = [0; 64];
+        for (sq, piece) in cells(&fen, Cells::Chars)
+            .expect("Unreadable FEN")
+            .iter()
+            .enumerate()
+        {
+            squares[sq] = piece.bytes().next().unwrap_or(0);
+        }
+
+        Self {
+            squares,
+            white: fen.split_ascii_whitespace().nth(1) == Some("w"),
+        }
+    }
+
+    /// The board once a move written the way the engine writes them is made.
+    #[must_use]
+    pub fn after(&self, movestr: &str) -> Self {
+        let mut next = *self;
+        let from = square(&movestr[0..2]);
+        let to = square(&movestr[2..4]);
+        let piece = self.squares[from];
+        let own = self.squares[to] != 0
+            && self.squares[to].is_ascii_uppercase() == piece.is_ascii_uppercase();
+        next.squares[from] = 0;
+
+        match piece.to_ascii_lowercase() {
+            // Castling, written either as the king moving two squares or taking its own rook
+            b'k' if own || (to % 8).abs_diff(from % 8) > 1 => {
+                let rank = from / 8 * 8;
+                let (king, rook_from, rook_to) = if to % 8 > from % 8 {
+                    (6, 7, 5)
+                } else {
+                    (2, 0, 3)
+                };
+                let rook_from = if own { to } else { rank + rook_from };
+                let rook = self.squares[rook_from];
+                next.squares[rook_from] = 0;
+                next.squares[rank + king] = piece;
+                next.squares[rank + rook_to] = rook;
+            }
+            // En passant takes a pawn that isn't on the square moved to
+            b'p' if from % 8 != to % 8 && self.squares[to] == 0 => {
+                next.squares[from / 8 * 8 + to % 8] = 0;
+                next.squares[to] = piece;
+            }
+            _ => {
+                next.squares[to] = match movestr.as_bytes().get(4) {
+                    Some(promo) if self.white => promo.to_ascii_uppercase(),
+                    Some(promo) => promo.to_ascii_lowercase(),
+                    None => piece,
+                };
+            }
+        }
+
+        next.white = !self.white;
+        next
+    }
+
+    /// The board with the other side to move and nothing moved.
+    #[must_use]
+    pub fn after_null(&self) -> Self {
+        Self {
+            white: !self.white,
+            ..*self
+        }
+    }
+
+    /// Whether the side to move is in check.
+    #[must_use]
+    pub fn is_check(&self) -> bool {
+        let king = if self.white { b'K' } else { b'k' };
+
+        match self.squares.iter().position(|p| *p == king) {
+            Some(sq) => self.is_attacked(sq, !self.white),
+            None => false,
+        }
+    }
+
+    /// Whether the side to move has nothing left but its king and pawns.
+    #[must_use]
+    pub fn pawns_only(&self) -> bool {
+        self.squares
+            .iter()
+            .filter(|p| **p != 0 && p.is_ascii_uppercase() == self.white)
+            .all(|p| matches!(p.to_ascii_lowercase(), b'k' | b'p'))
+    }
+
+    #[must_use]
+    fn piece(&self, x: i32, y: i32) -> Option<u8> {
+        if (0..8).contains(&x) && (0..8).contains(&y) {
+            Some(self.squares[(y * 8 + x) as usize]).filter(|p| *p != 0)
+        } else {
+            None
+        }
+    }
+
+    /// Whether a square is attacked by the white or black pieces.
+    #[must_use]
+    fn is_attacked(&self, sq: usize, by_white: bool) -> bool {
+        let (x, y) = ((sq % 8) as i32, (sq / 8) as i32);
+        let ours = |c: u8| if by_white { c.to_ascii_uppercase() } else { c };
+        let is = |dx: i32, dy: i32, kinds: &[u8]| {
+            self.piece(x + dx, y + dy)
+                .is_some_and(|p| kinds.iter().any(|k| ours(*k) == p))
+        };
+
+        // Pawns attack towards the other side
+        let forward = if by_white { -1 } else { 1 };
+        if is(-1, forward, b"p") || is(1, forward, b"p") {
+            return true;
+        }
+
+        if KNIGHT.iter().any(|(dx, dy)| is(*dx, *dy, b"n")) {
+            return true;
+        }
+
+        if KING.iter().any(|(dx, dy)| is(*dx, *dy, b"k")) {
+            return true;
+        }
+
+        for (dirs, kinds) in [(ROOK, b"rq"), (BISHOP, b"bq")] {
+            for (dx, dy) in dirs {
+                let mut dist = 1;
+                while (0..8).contains(&(x + dx * dist)) && (0..8).contains(&(y + dy * dist)) {
+                    if is(dx * dist, dy * dist, kinds) {
+                        return true;
+                    }
+                    if self.piece(x + dx * dist, y + dy * dist).is_some() {
+                        break;
+                    }
+                    dist += 1;
+                }
+            }
+        }
+
+        false
+    }
+}
@@ -1,5 +1,6 @@
 use games::chess::ChessPosition;
 
+/// Every position is level, and the search goes on mates and draws alone.
 #[must_use]
 pub fn eval(_pos: &ChessPosition) -> i32 {
     0
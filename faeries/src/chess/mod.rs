@@ -1,6 +1,8 @@
+pub mod board;
 pub mod eval;
 pub mod manual;
 pub mod root;
+pub mod san;
 pub mod search;
 pub mod state;
 pub mod uci;
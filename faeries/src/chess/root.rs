@@ -1,17 +1,65 @@
-use super::search::negamax;
+use super::search::{INF_SCORE, negamax};
+use super::{san::as_san, ugi::as_ugi};
+use crate::bench::{Bench, quiet};
+use crate::bound::Bound;
 use crate::searchstats::SearchStats;
+use crate::testsuite::{Epd, Report};
 use games::{
     chess::{ChessMove, ChessPosition},
     gamerules::GameRules,
     general::side::Side,
 };
 use protocols::GoSettings;
-use std::time::Instant;
+use protocols::output::{self, Message};
+use std::{
+    cmp::{max, min},
+    time::Instant,
+};
 
 const MAX_DEPTH: i32 = 128;
 
+/// How far either side of the last iteration's score to look at first.
+const ASPIRATION_WINDOW: i32 = 50;
+
+/// Shallower searches are cheap enough to do with a full window.
+const ASPIRATION_DEPTH: i32 = 4;
+
+/// The positions `bench` searches, and how deep.
+pub const BENCH_FENS: [&str; 6] = [
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+    "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+    "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10",
+];
+pub const BENCH_DEPTH: i32 = 5;
+
 #[must_use]
 pub fn primary(
+    pos: ChessPosition,
+    settings: &GoSettings,
+    info_handler: &impl Fn(
+        &ChessPosition,
+        Option<i32>,
+        Option<i32>,
+        Option<i32>,
+        Option<Bound>,
+        Option<i32>,
+        Option<u64>,
+        Option<u128>,
+        Option<i32>,
+        &Vec<ChessMove>,
+        Option<&SearchStats>,
+    ),
+) -> Option<ChessMove> {
+    primary_with_stats(pos, settings, info_handler, &mut SearchStats::default())
+}
+
+/// The same search, leaving what it counted in `stats` to look at afterwards.
+/// In debug mode they're shown after every iteration as well.
+#[must_use]
+pub fn primary_with_stats(
     mut pos: ChessPosition,
     settings: &GoSettings,
     info_handler: &impl Fn(
@@ -19,16 +67,19 @@ pub fn primary(
         Option<i32>,
         Option<i32>,
         Option<i32>,
+        Option<Bound>,
         Option<i32>,
         Option<u64>,
         Option<u128>,
         Option<i32>,
         &Vec<ChessMove>,
+        Option<&SearchStats>,
     ),
+    stats: &mut SearchStats,
 ) -> Option<ChessMove> {
+    *stats = SearchStats::default();
     let start = Instant::now();
     let mut bestmove = None;
-    let mut stats = SearchStats::default();
     let max_depth = settings.depth.unwrap_or(MAX_DEPTH);
     let us = pos.get_turn();
     let should_stop = |stats: &SearchStats| -> bool {
@@ -54,26 +105,93 @@ pub fn primary(
         false
     };
 
+    let mut pv = vec![];
+    let mut score = 0;
+
     for depth in 1..=max_depth {
-        let mut pv = vec![];
-        let score = negamax(&mut pos, depth, 0, &should_stop, &mut stats, &mut pv);
+        // Look for the score close to the last one, and widen the window on
+        // whichever side it turns out to be outside of. Neither side ever comes
+        // back in, so a score that fails high and then low still gets found.
+        let mut delta = ASPIRATION_WINDOW;
+        let (mut alpha, mut beta) = if depth >= ASPIRATION_DEPTH {
+            (
+                max(score - delta, -INF_SCORE),
+                min(score + delta, INF_SCORE),
+            )
+        } else {
+            (-INF_SCORE, INF_SCORE)
+        };
+
+        loop {
+            let mut line = pv.clone();
+            let result = negamax(
+                &mut pos,
+                alpha,
+                beta,
+                depth,
+                0,
+                &should_stop,
+                stats,
+                &mut line,
+            );
 
-        if depth > 1 && should_stop(&stats) {
+            if should_stop(stats) {
+                // The moves that did get searched to the end are still worth
+                // having, if the best of them beats the last iteration's
+                if !line.is_empty() && (bestmove.is_none() || result > score) {
+                    bestmove = Some(line[0]);
+                }
+                break;
+            }
+
+            let bound = if result <= alpha {
+                alpha = max(result - delta, -INF_SCORE);
+                Bound::Upper
+            } else if result >= beta {
+                beta = min(result + delta, INF_SCORE);
+                Bound::Lower
+            } else {
+                score = result;
+                pv = line;
+                break;
+            };
+
+            info_handler(
+                &pos,
+                Some(depth),
+                Some(stats.seldepth),
+                Some(result),
+                Some(bound),
+                None,
+                Some(stats.nodes),
+                Some(start.elapsed().as_millis()),
+                None,
+                &line,
+                None,
+            );
+
+            delta *= 2;
+        }
+
+        if should_stop(stats) {
             info_handler(
                 &pos,
                 None,
                 None,
                 None,
                 None,
+                None,
                 Some(stats.nodes),
                 Some(start.elapsed().as_millis()),
                 None,
                 &vec![],
+                None,
             );
             break;
         }
 
-        bestmove = Some(pv[0]);
+        bestmove = pv.first().copied();
+        stats.iterations.push(stats.nodes);
 
         info_handler(
             &pos,
@@ -81,12 +199,46 @@ pub fn primary(
             Some(stats.seldepth),
             Some(score),
             None,
+            None,
             Some(stats.nodes),
             Some(start.elapsed().as_millis()),
             None,
             &pv,
+            Some(&*stats),
         );
+
+        if settings.debug {
+            output::emit(&Message(stats.to_string()));
+        }
     }
 
-    bestmove
+    // Stopped before searching a single move, but anything legal beats nothing
+    bestmove.or_else(|| pos.legal_moves().first().copied())
+}
+
+/// Search the bench positions, to `BENCH_DEPTH` unless told otherwise.
+#[must_use]
+pub fn bench(depth: Option<i32>) -> Bench {
+    let depth = depth.unwrap_or(BENCH_DEPTH);
+    crate::bench::run(&BENCH_FENS, depth, |fen, settings, stats| {
+        let pos = ChessPosition::from_fen(fen);
+        let _ = primary_with_stats(pos, settings, &quiet, stats);
+    })
+}
+
+/// Search each EPD position within `settings`. Best and avoid moves can be
+/// given in coordinates or SAN, with or without the check mark.
+#[must_use]
+pub fn testsuite(epds: &[Epd], settings: &GoSettings) -> Report {
+    crate::testsuite::run(
+        epds,
+        settings,
+        ChessPosition::from_fen,
+        |pos, mv| {
+            let san = as_san(pos, mv);
+            let bare = san.trim_end_matches(['+', '#']).to_owned();
+            vec![as_ugi(pos, mv), san, bare]
+        },
+        |pos, settings, info_handler| primary(pos, settings, &info_handler),
+    )
 }
@@ -0,0 +1,86 @@
+use super::board::{Board, square};
+use super::ugi::as_ugi;
+use crate::symmetry::{Cells, cells};
+use games::{
+    chess::{ChessMove, ChessPosition},
+    gamerules::{GameResult, GameRules},
+};
+
+/// The board from a FEN, indexed a1 = 0, b1 = 1 ... h8 = 63.
+#[must_use]
+fn board(pos: &ChessPosition) -> Vec<String> {
+    cells(&pos.get_fen(), Cells::Chars).expect("Unreadable FEN")
+}
+
+/// Whether the side to move is in check.
+#[must_use]
+pub fn is_check(pos: &ChessPosition) -> bool {
+    Board::of(pos).is_check()
+}
+
+/// A move in standard algebraic notation: `Nf3`, `exd5`, `O-O`, `e8=Q#`
+#[must_use]
+pub fn as_san(pos: &ChessPosition, mv: &ChessMove) -> String {
+    let board = board(pos);
+    let movestr = as_ugi(pos, mv);
+    let from = square(&movestr[0..2]);
+    let to = square(&movestr[2..4]);
+    let promo = movestr[4..].to_ascii_uppercase();
+    let moving = board[from].to_ascii_uppercase();
+    let white = |sq: usize| board[sq].chars().all(|c| c.is_ascii_uppercase());
+    let own = !board[to].is_empty() && white(to) == white(from);
+
+    let mut san = if (moving == "K" && (to % 8).abs_diff(from % 8) > 1) || own {
+        // Castling, written either as the king moving two squares or taking its own rook
+        if to % 8 > from % 8 {
+            "O-O".to_owned()
+        } else {
+            "O-O-O".to_owned()
+        }
+    } else if moving == "P" {
+        if from % 8 != to % 8 {
+            format!("{}x{}", &movestr[0..1], &movestr[2..4])
+        } else {
+            movestr[2..4].to_owned()
+        }
+    } else {
+        // Other pieces of the same kind that could go to the same square
+        let others = pos
+            .legal_moves()
+            .iter()
+            .map(|other| as_ugi(pos, other))
+            .filter(|other| other[2..4] == movestr[2..4] && other[0..2] != movestr[0..2])
+            .filter(|other| board[square(&other[0..2])] == board[from])
+            .collect::<Vec<String>>();
+
+        let same_file = others.iter().any(|other| other[0..1] == movestr[0..1]);
+        let same_rank = others.iter().any(|other| other[1..2] == movestr[1..2]);
+        let disambiguation = if others.is_empty() {
+            ""
+        } else if !same_file {
+            &movestr[0..1]
+        } else if !same_rank {
+            &movestr[1..2]
+        } else {
+            &movestr[0..2]
+        };
+
+        let capture = if board[to].is_empty() { "" } else { "x" };
+        format!("{}{}{}{}", moving, disambiguation, capture, &movestr[2..4])
+    };
+
+    if !promo.is_empty() {
+        san.push('=');
+        san.push_str(&promo);
+    }
+
+    let mut after = pos.clone();
+    after.makemove(mv);
+    if let Some(GameResult::Win(_)) = after.get_result() {
+        san.push('#');
+    } else if is_check(&after) {
+        san.push('+');
+    }
+
+    san
+}
@@ -1,10 +1,22 @@
+use super::root::primary_with_stats;
+use crate::bound::Bound;
 use crate::prng;
-use games::{chess::ChessPosition, gamerules::GameRules};
+use crate::search;
+use crate::searchstats::SearchStats;
+use games::{
+    chess::{ChessMove, ChessPosition},
+    gamerules::GameRules,
+};
+use protocols::GoSettings;
 use protocols::ugi::options::{Check, Combo};
 
 pub struct ChessState {
     pub pos: ChessPosition,
     pub prng: prng::XorshiftGenerator,
+    pub startfen: String,
+    pub history: Vec<Option<ChessMove>>,
+    /// What the last search counted
+    pub stats: SearchStats,
     // Options
     pub debug: Check,
     pub search: Combo,
@@ -15,6 +27,9 @@ impl Default for ChessState {
         Self {
             pos: ChessPosition::startpos(),
             prng: prng::XorshiftGenerator::new(0xe50076937a9e5b1c),
+            startfen: "startpos".to_owned(),
+            history: vec![],
+            stats: SearchStats::default(),
             // Options
             debug: Check {
                 name: "debug".to_owned(),
@@ -34,3 +49,39 @@ impl Default for ChessState {
         }
     }
 }
+
+impl ChessState {
+    #[must_use]
+    pub fn think(
+        &mut self,
+        settings: &GoSettings,
+        info_handler: &impl Fn(
+            &ChessPosition,
+            Option<i32>,
+            Option<i32>,
+            Option<i32>,
+            Option<Bound>,
+            Option<i32>,
+            Option<u64>,
+            Option<u128>,
+            Option<i32>,
+            &Vec<ChessMove>,
+            Option<&SearchStats>,
+        ),
+    ) -> Option<ChessMove> {
+        let eval = |pos: &ChessPosition| -> i32 {
+            100 * pos.board.get_us().count() - 100 * pos.board.get_them().count()
+        };
+
+        search::think(
+            &self.search.value,
+            &self.pos,
+            settings,
+            info_handler,
+            &eval,
+            &mut self.prng,
+            &mut self.stats,
+            |stats| primary_with_stats(self.pos.clone(), settings, info_handler, stats),
+        )
+    }
+}
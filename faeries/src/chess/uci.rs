@@ -1,15 +1,15 @@
-use super::root::primary;
+use super::root::bench;
 use super::state::ChessState;
-use crate::search::alphabeta::alphabeta;
-use crate::search::flatmc::flatmc;
-use crate::search::minimax::minimax;
-use crate::search::random::random;
+use crate::bound::Bound;
+use crate::searchstats::SearchStats;
 use games::chess::ChessMove;
 use games::gamerules::GameRules;
 use games::general::side::Side;
 use games::perft;
 use games::{chess::ChessPosition, gamerules::GameResult};
 use protocols::GoSettings;
+use protocols::json::ToJson;
+use protocols::output::{self, Bestmove, Info};
 use protocols::uci::{UCI, UCIGameResult};
 
 pub fn info_handler(
@@ -17,34 +17,32 @@ pub fn info_handler(
     depth: Option<i32>,
     seldepth: Option<i32>,
     score: Option<i32>,
+    bound: Option<Bound>,
     mate: Option<i32>,
     nodes: Option<u64>,
     elapsed: Option<u128>,
     hashfull: Option<i32>,
     pv: &Vec<ChessMove>,
+    stats: Option<&SearchStats>,
 ) {
-    print!("info");
-    depth.inspect(|d| print!(" depth {}", d));
-    seldepth.inspect(|d| print!(" seldepth {}", d));
-    score.inspect(|s| print!(" score cp {}", s));
-    mate.inspect(|d| print!(" score mate {}", d));
-    nodes.inspect(|n| print!(" nodes {}", n));
-    elapsed.inspect(|ms| print!(" time {}", ms));
-    if let (Some(t), Some(n)) = (elapsed, nodes) {
-        if t > 0 {
-            print!(" nps {}", (n as u128 * 1000) / t);
-        }
-    }
-    hashfull.inspect(|hashfull| print!(" hashfull {}", hashfull));
-    if !pv.is_empty() {
-        print!(" pv");
-        let mut npos = pos.clone();
-        for mv in pv {
-            print!(" {}", as_uci(&npos, mv));
-            npos.makemove(&mv);
-        }
-    }
-    println!();
+    let mut npos = pos.clone();
+    let mut moves = vec![];
+    for mv in pv {
+        moves.push(as_uci(&npos, mv));
+        npos.makemove(mv);
+    }
+    output::emit(&Info {
+        depth,
+        seldepth,
+        score,
+        bound: bound.map(|b| b.to_string()),
+        mate,
+        nodes,
+        elapsed,
+        hashfull,
+        pv: moves,
+        stats: stats.map(ToJson::to_json),
+    });
 }
 
 #[must_use]
@@ -93,37 +91,27 @@ impl UCI for ChessState {
     }
 
     fn go(&mut self, settings: &GoSettings) {
-        let eval = |pos: &ChessPosition| -> i32 {
-            100 * pos.board.get_us().count() - 100 * pos.board.get_them().count()
-        };
-
-        let bestmove = match self.search.value.as_str() {
-            "primary" => primary(self.pos.clone(), settings, &info_handler),
-            "random" => random(&self.pos, || self.prng.next()),
-            "minimax" => minimax(self.pos.clone(), settings, &info_handler, &eval),
-            "alphabeta" => alphabeta(self.pos.clone(), settings, &info_handler, &eval),
-            "flatmc" => flatmc(self.pos.clone(), settings, &info_handler, || {
-                self.prng.next()
-            }),
-            _ => panic!("Unknown search type"),
-        };
-        if let Some(mv) = bestmove {
-            println!("bestmove {}", as_uci(&self.pos, &mv));
-        } else {
-            println!("bestmove 0000");
-        }
+        let bestmove = self.think(settings, &info_handler);
+        output::emit(&Bestmove {
+            mv: bestmove.map_or("0000".to_owned(), |mv| as_uci(&self.pos, &mv)),
+            stats: self.stats.to_json(),
+        });
     }
 
     fn stop(&mut self) {}
 
+    fn bench(&mut self, depth: Option<i32>) {
+        println!("{}", bench(depth));
+    }
+
     fn print(&self) {
         println!("FEN: {}", self.pos.get_fen());
         print!("{}", self.pos);
     }
 
     fn print_options(&self) {
-        println!("{}", self.debug);
-        println!("{}", self.search);
+        output::emit(&self.debug);
+        output::emit(&self.search);
     }
 
     fn set_option(&mut self, name: &str, value: &str) {
@@ -153,20 +141,8 @@ impl UCI for ChessState {
     }
 
     fn perft(&mut self, settings: &GoSettings) {
-        let info_handler = |depth: i32, elapsed: f32, nodes: u64| {
-            print!("info");
-            print!(" depth {}", depth);
-            print!(" nodes {}", nodes);
-            print!(" time {}", (elapsed * 1000.0) as u64);
-            if elapsed > 0.0 {
-                let nps = nodes as f32 / elapsed;
-                print!(" nps {}", nps as u64);
-            }
-            println!();
-        };
-        let final_handler = |nodes: u64| {
-            println!("nodes {}", nodes);
-        };
+        let info_handler = output::perft;
+        let final_handler = output::nodes;
         perft::perft(
             &mut self.pos,
             settings.depth.unwrap(),
@@ -177,10 +153,8 @@ impl UCI for ChessState {
 
     fn split(&mut self, settings: &GoSettings) {
         let npos = self.pos.clone();
-        let info_handler = |mv: ChessMove, nodes: u64| println!("{} {}", as_uci(&npos, &mv), nodes);
-        let final_handler = |nodes: u64| {
-            println!("nodes {}", nodes);
-        };
+        let info_handler = |mv: ChessMove, nodes: u64| output::split(as_uci(&npos, &mv), nodes);
+        let final_handler = output::nodes;
         perft::split(
             &mut self.pos,
             settings.depth.unwrap(),
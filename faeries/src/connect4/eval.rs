@@ -1,6 +1,64 @@
-use games::connect4::Connect4Position;
+use super::position::{Connect4Position, DIRECTIONS};
+use crate::tune::{Tunable, dot};
+use games::{gamerules::GameRules, general::side::Side};
+
+pub const NAMES: [&str; 6] = ["open_1", "open_2", "open_3", "open_4", "open_5", "open_6"];
+/// A line with `n` discs in is worth `4^n - 1`
+pub const WEIGHTS: [i32; 6] = [3, 15, 63, 255, 1023, 4095];
+
+/// Every line of `connect` squares that only one side has discs in could still be
+/// theirs, and is worth more the fuller it is. These are how many such lines the
+/// side to move has with each number of discs in, less how many the other side
+/// has. Lines fuller than six discs count as six.
+#[must_use]
+pub fn features(pos: &Connect4Position) -> [i32; 6] {
+    let us = pos.get_turn();
+    let mut features = [0; 6];
+
+    for y in 0..pos.get_height() {
+        for x in 0..pos.get_width() {
+            for dir in DIRECTIONS {
+                if pos.step(x, y, dir, pos.get_connect() as i32 - 1).is_none() {
+                    continue;
+                }
+
+                let (mut ours, mut theirs) = (0, 0);
+                for n in 0..pos.get_connect() as i32 {
+                    let (nx, ny) = pos.step(x, y, dir, n).unwrap();
+                    match pos.get(nx, ny) {
+                        Some(side) if side == us => ours += 1,
+                        Some(_) => theirs += 1,
+                        None => {}
+                    }
+                }
+
+                match (ours, theirs) {
+                    (0, 0) => {}
+                    (ours, 0) => features[ours.min(features.len()) - 1] += 1,
+                    (0, theirs) => features[theirs.min(features.len()) - 1] -= 1,
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    features
+}
 
 #[must_use]
-pub fn eval(_pos: &Connect4Position) -> i32 {
-    0
+pub fn eval(pos: &Connect4Position) -> i32 {
+    dot(&WEIGHTS, &features(pos))
+}
+
+impl Tunable for Connect4Position {
+    const NAMES: &'static [&'static str] = &NAMES;
+    const WEIGHTS: &'static [i32] = &WEIGHTS;
+
+    fn features(&self) -> Vec<i32> {
+        features(self).to_vec()
+    }
+
+    fn first_to_move(&self) -> bool {
+        self.get_turn() == Side::Player1
+    }
 }
@@ -1,9 +1,14 @@
-use super::{root::primary, state::Connect4State, ugi::as_ugi};
+use super::position::{Connect4Move, Connect4Position};
+use super::{state::Connect4State, ugi::as_ugi};
+use crate::bound::Bound;
+use crate::record::Record;
+use crate::searchstats::SearchStats;
 use games::{
-    connect4::{Connect4Move, Connect4Position},
     gamerules::{GameResult, GameRules},
     general::side::Side,
 };
+use protocols::json::ToJson;
+use protocols::output::{self, Info};
 use protocols::{
     GoSettings,
     manual::{Manual, ManualGameResult},
@@ -14,32 +19,26 @@ pub fn info_handler(
     depth: Option<i32>,
     seldepth: Option<i32>,
     score: Option<i32>,
+    bound: Option<Bound>,
     mate: Option<i32>,
     nodes: Option<u64>,
     elapsed: Option<u128>,
     hashfull: Option<i32>,
     pv: &Vec<Connect4Move>,
+    stats: Option<&SearchStats>,
 ) {
-    print!("info");
-    depth.inspect(|d| print!(" depth {}", d));
-    seldepth.inspect(|d| print!(" seldepth {}", d));
-    score.inspect(|s| print!(" score cp {}", s));
-    mate.inspect(|d| print!(" score mate {}", d));
-    nodes.inspect(|n| print!(" nodes {}", n));
-    elapsed.inspect(|ms| print!(" time {}", ms));
-    if let (Some(t), Some(n)) = (elapsed, nodes) {
-        if t > 0 {
-            print!(" nps {}", (n as u128 * 1000) / t);
-        }
-    }
-    hashfull.inspect(|hashfull| print!(" hashfull {}", hashfull));
-    if !pv.is_empty() {
-        print!(" pv");
-        for mv in pv {
-            print!(" {}", as_ugi(mv));
-        }
-    }
-    println!();
+    output::emit(&Info {
+        depth,
+        seldepth,
+        score,
+        bound: bound.map(|b| b.to_string()),
+        mate,
+        nodes,
+        elapsed,
+        hashfull,
+        pv: pv.iter().map(as_ugi).collect(),
+        stats: stats.map(ToJson::to_json),
+    });
 }
 
 pub fn empty_handler(
@@ -47,23 +46,45 @@ pub fn empty_handler(
     _: Option<i32>,
     _: Option<i32>,
     _: Option<i32>,
+    _: Option<Bound>,
     _: Option<i32>,
     _: Option<u64>,
     _: Option<u128>,
     _: Option<i32>,
     _: &Vec<Connect4Move>,
+    _: Option<&SearchStats>,
 ) {
 }
 
+impl Connect4State {
+    fn replay(&mut self) {
+        self.pos.set_fen(&self.startfen);
+        for mv in &self.history {
+            match mv {
+                Some(mv) => self.pos.makemove(mv),
+                None => self.pos.makenull(),
+            }
+        }
+    }
+}
+
 impl Manual for Connect4State {
     fn print(&self) {
         println!("{}", self.pos);
     }
 
+    fn pprint(&self) {
+        Connect4State::pprint(self);
+    }
+
     fn is_gameover(&self) -> bool {
         self.pos.is_gameover()
     }
 
+    fn is_p1turn(&self) -> bool {
+        self.pos.get_turn() == Side::Player1
+    }
+
     fn makemove(&mut self, movestr: &str) -> bool {
         let found = self
             .pos
@@ -73,6 +94,7 @@ impl Manual for Connect4State {
 
         if let Some(mv) = found {
             self.pos.makemove(&mv);
+            self.history.push(Some(mv));
             true
         } else {
             false
@@ -81,19 +103,63 @@ impl Manual for Connect4State {
 
     fn makenull(&mut self) {
         self.pos.makenull();
+        self.history.push(None);
+    }
+
+    fn undo(&mut self) -> bool {
+        match self.history.pop() {
+            Some(Some(mv)) => self.pos.undomove(&mv),
+            // Null moves can only be taken back by playing the game again
+            Some(None) => self.replay(),
+            None => return false,
+        }
+        true
     }
 
-    fn play(&mut self, depth: i32) {
-        let settings = GoSettings::from_depth(depth);
-        let bestmove = primary(self.pos.clone(), &settings, &empty_handler);
-        if let Some(mv) = bestmove {
+    fn newgame(&mut self) {
+        self.set_fen("startpos");
+    }
+
+    fn set_fen(&mut self, fen: &str) {
+        self.pos.set_fen(fen);
+        self.startfen = fen.to_owned();
+        self.history.clear();
+    }
+
+    fn set_search(&mut self, name: &str) -> bool {
+        if self.search.options.iter().any(|option| option == name) {
+            self.search.value = name.to_owned();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn history(&self) -> Vec<String> {
+        self.history
+            .iter()
+            .map(|mv| match mv {
+                Some(mv) => as_ugi(mv),
+                None => "0000".to_owned(),
+            })
+            .collect()
+    }
+
+    fn play(&mut self, settings: &GoSettings) {
+        if let Some(mv) = self.think(settings, &empty_handler) {
             self.pos.makemove(&mv);
+            self.history.push(Some(mv));
         }
     }
 
-    fn hint(&mut self, depth: i32) {
-        let settings = GoSettings::from_depth(depth);
-        let _ = primary(self.pos.clone(), &settings, &info_handler);
+    fn hint(&mut self, settings: &GoSettings) {
+        let _ = self.think(settings, &info_handler);
+    }
+
+    fn record(&self, p1: &str, p2: &str) -> String {
+        let mut record = Record::replay::<Connect4Position>(&self.startfen, &self.history);
+        record.set_players(p1, p2);
+        record.to_string()
     }
 
     fn get_result(&self) -> Option<ManualGameResult> {
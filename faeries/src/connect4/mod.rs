@@ -1,5 +1,6 @@
 pub mod eval;
 pub mod manual;
+pub mod position;
 pub mod root;
 pub mod search;
 pub mod state;
@@ -0,0 +1,314 @@
+use games::{
+    gamerules::{GameResult, GameRules},
+    general::side::Side,
+};
+use std::fmt;
+
+pub const WIDTH: usize = 7;
+pub const HEIGHT: usize = 6;
+pub const CONNECT: usize = 4;
+
+/// Along a rank, up a file, and the two diagonals.
+pub const DIRECTIONS: [(i32, i32); 4] = [(1, 0), (0, 1), (1, 1), (1, -1)];
+
+/// The column a disc is dropped into, counting from the a-file.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Connect4Move(pub usize);
+
+impl fmt::Display for Connect4Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", (b'a' + self.0 as u8) as char)
+    }
+}
+
+/// Connect 4 on a board of any size, won by getting `connect` discs in a row.
+///
+/// Discs fall to the lowest empty square of their column. Red moves first, and the
+/// game is drawn once the board is full. Lines longer than `connect` win too.
+#[derive(Clone, Debug)]
+pub struct Connect4Position {
+    width: usize,
+    height: usize,
+    connect: usize,
+    /// Indexed by `y * width + x`, starting from a1
+    board: Vec<Option<Side>>,
+    /// How many discs are in each column
+    heights: Vec<usize>,
+    turn: Side,
+    winner: Option<Side>,
+}
+
+impl Connect4Position {
+    /// An empty board.
+    #[must_use]
+    pub fn new(width: usize, height: usize, connect: usize) -> Self {
+        Self {
+            width,
+            height,
+            connect,
+            board: vec![None; width * height],
+            heights: vec![0; width],
+            turn: Side::Player1,
+            winner: None,
+        }
+    }
+
+    #[must_use]
+    pub fn get_width(&self) -> usize {
+        self.width
+    }
+
+    #[must_use]
+    pub fn get_height(&self) -> usize {
+        self.height
+    }
+
+    #[must_use]
+    pub fn get_connect(&self) -> usize {
+        self.connect
+    }
+
+    /// The disc on a square, if there is one.
+    #[must_use]
+    pub fn get(&self, x: usize, y: usize) -> Option<Side> {
+        self.board[y * self.width + x]
+    }
+
+    /// The square `n` steps from `(x, y)` in a direction, if it's on the board.
+    #[must_use]
+    pub fn step(&self, x: usize, y: usize, (dx, dy): (i32, i32), n: i32) -> Option<(usize, usize)> {
+        let nx = x as i32 + n * dx;
+        let ny = y as i32 + n * dy;
+        let on_board =
+            (0..self.width as i32).contains(&nx) && (0..self.height as i32).contains(&ny);
+        on_board.then_some((nx as usize, ny as usize))
+    }
+
+    /// How many discs of one colour are in an unbroken line through a square of theirs.
+    #[must_use]
+    fn run(&self, x: usize, y: usize, dir: (i32, i32)) -> usize {
+        let side = self.get(x, y);
+        let mut length = 1;
+
+        for sign in [1, -1] {
+            let mut n = sign;
+            while self
+                .step(x, y, dir, n)
+                .is_some_and(|(nx, ny)| self.get(nx, ny) == side)
+            {
+                length += 1;
+                n += sign;
+            }
+        }
+
+        length
+    }
+
+    #[must_use]
+    fn is_connected(&self, x: usize, y: usize) -> bool {
+        DIRECTIONS
+            .iter()
+            .any(|dir| self.run(x, y, *dir) >= self.connect)
+    }
+
+    #[must_use]
+    fn is_full(&self) -> bool {
+        self.heights.iter().all(|h| *h == self.height)
+    }
+}
+
+impl GameRules for Connect4Position {
+    type MoveType = Connect4Move;
+
+    fn startpos() -> Self {
+        Self::new(WIDTH, HEIGHT, CONNECT)
+    }
+
+    fn from_fen(fen: &str) -> Self {
+        let mut pos = Self::startpos();
+        pos.set_fen(fen);
+        pos
+    }
+
+    /// Rows from the top down, `x` for Red and `o` for Yellow, then the side to move.
+    /// The board takes its size from the FEN, and "startpos" empties it. The length
+    /// of line that wins isn't part of the position, so it's left alone.
+    fn set_fen(&mut self, fen: &str) {
+        if fen == "startpos" {
+            *self = Self::new(self.width, self.height, self.connect);
+            return;
+        }
+
+        let mut parts = fen.split_ascii_whitespace();
+        let mut rows = vec![];
+
+        for row in parts.next().unwrap_or("").split('/') {
+            let mut cells = vec![];
+            let mut empty = 0;
+            for c in row.chars() {
+                if let Some(n) = c.to_digit(10) {
+                    empty = 10 * empty + n as usize;
+                    continue;
+                }
+                cells.extend(std::iter::repeat_n(None, empty));
+                empty = 0;
+                match c {
+                    'x' | 'X' | 'r' | 'R' => cells.push(Some(Side::Player1)),
+                    'o' | 'O' | 'y' | 'Y' => cells.push(Some(Side::Player2)),
+                    _ => {}
+                }
+            }
+            cells.extend(std::iter::repeat_n(None, empty));
+            rows.push(cells);
+        }
+
+        let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+        *self = Self::new(width, rows.len(), self.connect);
+
+        for (y, row) in rows.iter().rev().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                self.board[y * width + x] = *cell;
+                if cell.is_some() {
+                    self.heights[x] = y + 1;
+                }
+            }
+        }
+
+        self.turn = match parts.next() {
+            Some("o") | Some("O") | Some("y") | Some("Y") => Side::Player2,
+            _ => Side::Player1,
+        };
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if self.winner.is_none() && self.get(x, y).is_some() && self.is_connected(x, y) {
+                    self.winner = self.get(x, y);
+                }
+            }
+        }
+    }
+
+    fn get_fen(&self) -> String {
+        let mut rows = vec![];
+
+        for y in (0..self.height).rev() {
+            let mut row = String::new();
+            let mut empty = 0;
+            for x in 0..self.width {
+                let c = match self.get(x, y) {
+                    Some(Side::Player1) => 'x',
+                    Some(Side::Player2) => 'o',
+                    None => {
+                        empty += 1;
+                        continue;
+                    }
+                };
+                if empty > 0 {
+                    row += &empty.to_string();
+                    empty = 0;
+                }
+                row.push(c);
+            }
+            if empty > 0 {
+                row += &empty.to_string();
+            }
+            rows.push(row);
+        }
+
+        format!(
+            "{} {}",
+            rows.join("/"),
+            match self.turn {
+                Side::Player1 => 'x',
+                Side::Player2 => 'o',
+            }
+        )
+    }
+
+    fn get_turn(&self) -> Side {
+        self.turn
+    }
+
+    fn legal_moves(&self) -> Vec<Connect4Move> {
+        if self.winner.is_some() {
+            return vec![];
+        }
+
+        (0..self.width)
+            .filter(|x| self.heights[*x] < self.height)
+            .map(Connect4Move)
+            .collect()
+    }
+
+    fn makemove(&mut self, mv: &Connect4Move) {
+        let x = mv.0;
+        let y = self.heights[x];
+
+        self.board[y * self.width + x] = Some(self.turn);
+        self.heights[x] += 1;
+        if self.is_connected(x, y) {
+            self.winner = Some(self.turn);
+        }
+        self.makenull();
+    }
+
+    fn undomove(&mut self, mv: &Connect4Move) {
+        let x = mv.0;
+        self.heights[x] -= 1;
+        self.board[self.heights[x] * self.width + x] = None;
+        // The game stops as soon as someone wins, so nobody had before this move
+        self.winner = None;
+        self.makenull();
+    }
+
+    fn makenull(&mut self) {
+        self.turn = match self.turn {
+            Side::Player1 => Side::Player2,
+            Side::Player2 => Side::Player1,
+        };
+    }
+
+    fn is_gameover(&self) -> bool {
+        self.get_result().is_some()
+    }
+
+    fn get_result(&self) -> Option<GameResult> {
+        if let Some(side) = self.winner {
+            Some(GameResult::Win(side))
+        } else if self.is_full() {
+            Some(GameResult::Draw)
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for Connect4Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for y in (0..self.height).rev() {
+            for x in 0..self.width {
+                let c = match self.get(x, y) {
+                    Some(Side::Player1) => 'x',
+                    Some(Side::Player2) => 'o',
+                    None => '.',
+                };
+                write!(f, "{}", c)?;
+            }
+            writeln!(f)?;
+        }
+        for x in 0..self.width {
+            write!(f, "{}", Connect4Move(x))?;
+        }
+        writeln!(f)?;
+        writeln!(f, "Connect: {}", self.connect)?;
+        writeln!(f, "FEN: {}", self.get_fen())?;
+        write!(
+            f,
+            "Turn: {}",
+            match self.turn {
+                Side::Player1 => 'x',
+                Side::Player2 => 'o',
+            }
+        )
+    }
+}
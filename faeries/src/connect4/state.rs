@@ -1,12 +1,26 @@
+use super::eval::eval;
+use super::position::{CONNECT, Connect4Move, Connect4Position, HEIGHT, WIDTH};
+use super::root::primary_with_stats;
+use crate::bound::Bound;
 use crate::prng;
-use games::{connect4::Connect4Position, gamerules::GameRules};
-use protocols::ugi::options::{Check, Combo};
+use crate::search;
+use crate::searchstats::SearchStats;
+use games::gamerules::GameRules;
+use protocols::GoSettings;
+use protocols::ugi::options::{Check, Combo, Spin};
 
 pub struct Connect4State {
     pub pos: Connect4Position,
     pub prng: prng::XorshiftGenerator,
+    pub startfen: String,
+    pub history: Vec<Option<Connect4Move>>,
+    /// What the last search counted
+    pub stats: SearchStats,
     // Options
     pub debug: Check,
+    pub width: Spin<i32>,
+    pub height: Spin<i32>,
+    pub connect: Spin<i32>,
     pub search: Combo,
 }
 
@@ -15,11 +29,32 @@ impl Default for Connect4State {
         Self {
             pos: Connect4Position::startpos(),
             prng: prng::XorshiftGenerator::new(0xe50076937a9e5b1c),
+            startfen: "startpos".to_owned(),
+            history: vec![],
+            stats: SearchStats::default(),
             // Options
             debug: Check {
                 name: "debug".to_owned(),
                 value: cfg!(debug_assertions),
             },
+            width: Spin {
+                name: "width".to_owned(),
+                min: 4,
+                max: 10,
+                value: WIDTH as i32,
+            },
+            height: Spin {
+                name: "height".to_owned(),
+                min: 4,
+                max: 10,
+                value: HEIGHT as i32,
+            },
+            connect: Spin {
+                name: "connect".to_owned(),
+                min: 3,
+                max: 6,
+                value: CONNECT as i32,
+            },
             search: Combo {
                 name: "search".to_owned(),
                 value: "primary".to_owned(),
@@ -34,3 +69,46 @@ impl Default for Connect4State {
         }
     }
 }
+
+impl Connect4State {
+    /// An empty board the size the options ask for.
+    pub fn reset(&mut self) {
+        self.pos = Connect4Position::new(
+            self.width.value as usize,
+            self.height.value as usize,
+            self.connect.value as usize,
+        );
+        self.startfen = "startpos".to_owned();
+        self.history.clear();
+    }
+
+    #[must_use]
+    pub fn think(
+        &mut self,
+        settings: &GoSettings,
+        info_handler: &impl Fn(
+            &Connect4Position,
+            Option<i32>,
+            Option<i32>,
+            Option<i32>,
+            Option<Bound>,
+            Option<i32>,
+            Option<u64>,
+            Option<u128>,
+            Option<i32>,
+            &Vec<Connect4Move>,
+            Option<&SearchStats>,
+        ),
+    ) -> Option<Connect4Move> {
+        search::think(
+            &self.search.value,
+            &self.pos,
+            settings,
+            info_handler,
+            &eval,
+            &mut self.prng,
+            &mut self.stats,
+            |stats| primary_with_stats(self.pos.clone(), settings, info_handler, stats),
+        )
+    }
+}
@@ -1,16 +1,14 @@
-use super::root::primary;
+use super::position::{Connect4Move, Connect4Position};
+use super::root::bench;
 use super::state::Connect4State;
+use crate::bound::Bound;
 use crate::colour::Colour;
-use crate::search::alphabeta::alphabeta;
-use crate::search::flatmc::flatmc;
-use crate::search::minimax::minimax;
-use crate::search::random::random;
-use games::connect4::Connect4Move;
-use games::gamerules::GameRules;
+use crate::searchstats::SearchStats;
+use games::gamerules::{GameResult, GameRules};
 use games::general::side::Side;
-use games::general::square::Square;
 use games::perft;
-use games::{connect4::Connect4Position, gamerules::GameResult};
+use protocols::json::ToJson;
+use protocols::output::{self, Bestmove, Info, Message};
 use protocols::ugi::{GoSettings, UGI, UGIGameResult};
 
 pub fn info_handler(
@@ -18,37 +16,31 @@ pub fn info_handler(
     depth: Option<i32>,
     seldepth: Option<i32>,
     score: Option<i32>,
+    bound: Option<Bound>,
     mate: Option<i32>,
     nodes: Option<u64>,
     elapsed: Option<u128>,
     hashfull: Option<i32>,
     pv: &Vec<Connect4Move>,
+    stats: Option<&SearchStats>,
 ) {
-    print!("info");
-    depth.inspect(|d| print!(" depth {}", d));
-    seldepth.inspect(|d| print!(" seldepth {}", d));
-    score.inspect(|s| print!(" score cp {}", s));
-    mate.inspect(|d| print!(" score mate {}", d));
-    nodes.inspect(|n| print!(" nodes {}", n));
-    elapsed.inspect(|ms| print!(" time {}", ms));
-    if let (Some(t), Some(n)) = (elapsed, nodes) {
-        if t > 0 {
-            print!(" nps {}", (n as u128 * 1000) / t);
-        }
-    }
-    hashfull.inspect(|hashfull| print!(" hashfull {}", hashfull));
-    if !pv.is_empty() {
-        print!(" pv");
-        for mv in pv {
-            print!(" {}", as_ugi(mv));
-        }
-    }
-    println!();
+    output::emit(&Info {
+        depth,
+        seldepth,
+        score,
+        bound: bound.map(|b| b.to_string()),
+        mate,
+        nodes,
+        elapsed,
+        hashfull,
+        pv: pv.iter().map(as_ugi).collect(),
+        stats: stats.map(ToJson::to_json),
+    });
 }
 
 #[must_use]
 pub fn as_ugi(mv: &Connect4Move) -> String {
-    format!("{}", mv.0).to_owned()
+    mv.to_string()
 }
 
 impl UGI for Connect4State {
@@ -67,7 +59,7 @@ impl UGI for Connect4State {
     }
 
     fn uginewgame(&mut self) {
-        self.pos = Connect4Position::startpos();
+        self.pos.set_fen("startpos");
     }
 
     fn isready(&mut self) {
@@ -92,26 +84,15 @@ impl UGI for Connect4State {
     }
 
     fn go(&mut self, settings: &GoSettings) {
-        let settings = &protocols::GoSettings::from(settings);
-        let eval = |pos: &Connect4Position| -> i32 {
-            100 * pos.get_us().count() - 100 * pos.get_them().count()
+        let settings = &protocols::GoSettings {
+            debug: self.debug.value,
+            ..protocols::GoSettings::from(settings)
         };
-
-        let bestmove = match self.search.value.as_str() {
-            "primary" => primary(self.pos.clone(), settings, &info_handler),
-            "random" => random(&self.pos, || self.prng.next()),
-            "minimax" => minimax(self.pos.clone(), settings, &info_handler, &eval),
-            "alphabeta" => alphabeta(self.pos.clone(), settings, &info_handler, &eval),
-            "flatmc" => flatmc(self.pos.clone(), settings, &info_handler, || {
-                self.prng.next()
-            }),
-            _ => panic!("Unknown search type"),
-        };
-        if let Some(mv) = bestmove {
-            println!("bestmove {}", as_ugi(&mv));
-        } else {
-            println!("bestmove 0000");
-        }
+        let bestmove = self.think(settings, &info_handler);
+        output::emit(&Bestmove {
+            mv: bestmove.map_or("0000".to_owned(), |mv| as_ugi(&mv)),
+            stats: self.stats.to_json(),
+        });
     }
 
     fn stop(&mut self) {}
@@ -119,18 +100,26 @@ impl UGI for Connect4State {
     fn unrecognised_command(
         &mut self,
         word: &str,
-        _args: &mut std::iter::Peekable<std::str::SplitAsciiWhitespace>,
+        args: &mut std::iter::Peekable<std::str::SplitAsciiWhitespace>,
     ) {
         match word {
             "print" => self.print(),
             "pprint" => self.pprint(),
+            "bench" => {
+                let depth = args.next().and_then(|word| word.parse::<i32>().ok());
+                println!("{}", bench(depth));
+            }
+            "stats" => output::emit(&Message(self.stats.to_string())),
             _ => {}
         }
     }
 
     fn print_options(&self) {
-        println!("{}", self.debug);
-        println!("{}", self.search);
+        output::emit(&self.debug);
+        output::emit(&self.width);
+        output::emit(&self.height);
+        output::emit(&self.connect);
+        output::emit(&self.search);
     }
 
     fn movelist(&self) {
@@ -143,6 +132,18 @@ impl UGI for Connect4State {
         match (name, value) {
             ("debug", "true") => self.debug.value = true,
             ("debug", "false") => self.debug.value = false,
+            ("width" | "height" | "connect", _) => {
+                let option = match name {
+                    "width" => &mut self.width,
+                    "height" => &mut self.height,
+                    _ => &mut self.connect,
+                };
+                match value.parse::<i32>() {
+                    Ok(n) if (option.min..=option.max).contains(&n) => option.value = n,
+                    _ => return,
+                }
+                self.reset();
+            }
             ("search", _) => self.search.value = value.to_owned(),
             (_, _) => {}
         }
@@ -166,20 +167,8 @@ impl UGI for Connect4State {
     }
 
     fn perft(&mut self, settings: &GoSettings) {
-        let info_handler = |depth: i32, elapsed: f32, nodes: u64| {
-            print!("info");
-            print!(" depth {}", depth);
-            print!(" nodes {}", nodes);
-            print!(" time {}", (elapsed * 1000.0) as u64);
-            if elapsed > 0.0 {
-                let nps = nodes as f32 / elapsed;
-                print!(" nps {}", nps as u64);
-            }
-            println!();
-        };
-        let final_handler = |nodes: u64| {
-            println!("nodes {}", nodes);
-        };
+        let info_handler = output::perft;
+        let final_handler = output::nodes;
         perft::perft(
             &mut self.pos,
             settings.depth.unwrap(),
@@ -189,10 +178,8 @@ impl UGI for Connect4State {
     }
 
     fn split(&mut self, settings: &GoSettings) {
-        let info_handler = |mv: Connect4Move, nodes: u64| println!("{} {}", as_ugi(&mv), nodes);
-        let final_handler = |nodes: u64| {
-            println!("nodes {}", nodes);
-        };
+        let info_handler = |mv: Connect4Move, nodes: u64| output::split(as_ugi(&mv), nodes);
+        let final_handler = output::nodes;
         perft::split(
             &mut self.pos,
             settings.depth.unwrap(),
@@ -204,19 +191,16 @@ impl UGI for Connect4State {
 
 impl Connect4State {
     fn print(&self) {
-        print!("{}", self.pos);
-    }
-
-    fn pprint(&self) {
-        for y in (0..6).rev() {
-            for x in 0..7 {
-                let sq = Square::<7, 6>::from_coords(x, y);
-                if self.pos.get_red().is_square_set(sq) {
-                    print!("{}●{}", Colour::Red, Colour::Clear);
-                } else if self.pos.get_yellow().is_square_set(sq) {
-                    print!("{}●{}", Colour::Yellow, Colour::Clear);
-                } else {
-                    print!(".");
+        println!("{}", self.pos);
+    }
+
+    pub fn pprint(&self) {
+        for y in (0..self.pos.get_height()).rev() {
+            for x in 0..self.pos.get_width() {
+                match self.pos.get(x, y) {
+                    Some(Side::Player1) => print!("{}●{}", Colour::Red, Colour::Clear),
+                    Some(Side::Player2) => print!("{}●{}", Colour::Yellow, Colour::Clear),
+                    None => print!("."),
                 }
             }
             println!("");
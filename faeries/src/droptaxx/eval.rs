@@ -1,23 +1,36 @@
-use games::droptaxx::DroptaxxPosition;
-use games::general::bitboard::Bitboard;
+use super::position::DroptaxxPosition;
+use crate::tune::{Tunable, difference, dot};
+use games::gamerules::GameRules;
+use games::general::side::Side;
+
+pub const NAMES: [&str; 1] = ["material"];
+pub const WEIGHTS: [i32; 1] = [100];
 
 #[must_use]
-fn eval_side(us: Bitboard<7, 7>, _them: Bitboard<7, 7>, _blockers: Bitboard<7, 7>) -> i32 {
+fn features_side(us: u64) -> [i32; 1] {
     // Material count
-    100 * us.count()
+    [us.count_ones() as i32]
 }
 
 #[must_use]
-fn eval_us(pos: &DroptaxxPosition) -> i32 {
-    eval_side(pos.get_us(), pos.get_them(), pos.get_blockers())
+pub fn features(pos: &DroptaxxPosition) -> [i32; 1] {
+    difference(features_side(pos.get_us()), features_side(pos.get_them()))
 }
 
 #[must_use]
-fn eval_them(pos: &DroptaxxPosition) -> i32 {
-    eval_side(pos.get_them(), pos.get_us(), pos.get_blockers())
+pub fn eval(pos: &DroptaxxPosition) -> i32 {
+    dot(&WEIGHTS, &features(pos))
 }
 
-#[must_use]
-pub fn eval(pos: &DroptaxxPosition) -> i32 {
-    eval_us(pos) - eval_them(pos)
+impl Tunable for DroptaxxPosition {
+    const NAMES: &'static [&'static str] = &NAMES;
+    const WEIGHTS: &'static [i32] = &WEIGHTS;
+
+    fn features(&self) -> Vec<i32> {
+        features(self).to_vec()
+    }
+
+    fn first_to_move(&self) -> bool {
+        self.get_turn() == Side::Player1
+    }
 }
@@ -0,0 +1,368 @@
+use games::{
+    gamerules::{GameResult, GameRules},
+    general::side::Side,
+};
+use std::fmt;
+
+pub const SIZE: usize = 7;
+
+/// The largest board whose squares all fit in a `u64`.
+pub const MAX_SIZE: usize = 8;
+
+/// The square a stone is dropped on, counting files and ranks from a1.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DroptaxxMove {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl fmt::Display for DroptaxxMove {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", (b'a' + self.x as u8) as char, self.y + 1)
+    }
+}
+
+/// Droptaxx on a board of any size up to 8x8.
+///
+/// A stone can be dropped on any empty square, and turns the opponent's stones on
+/// the eight squares around it over. The game is over once the board is full, and
+/// whoever has more stones wins. Blockers, `-` in a FEN, are never played on.
+#[derive(Clone, Debug)]
+pub struct DroptaxxPosition {
+    width: usize,
+    height: usize,
+    /// Bitboards indexed by `y * width + x`, starting from a1
+    black: u64,
+    white: u64,
+    blockers: u64,
+    turn: Side,
+    fullmove: u32,
+    /// The stones each move turned over, so that it can be taken back
+    undo: Vec<u64>,
+}
+
+impl DroptaxxPosition {
+    /// An empty board.
+    #[must_use]
+    pub fn new(width: usize, height: usize) -> Self {
+        debug_assert!(width * height <= MAX_SIZE * MAX_SIZE);
+
+        Self {
+            width,
+            height,
+            black: 0,
+            white: 0,
+            blockers: 0,
+            turn: Side::Player1,
+            fullmove: 1,
+            undo: vec![],
+        }
+    }
+
+    #[must_use]
+    pub fn get_width(&self) -> usize {
+        self.width
+    }
+
+    #[must_use]
+    pub fn get_height(&self) -> usize {
+        self.height
+    }
+
+    #[must_use]
+    pub fn get_black(&self) -> u64 {
+        self.black
+    }
+
+    #[must_use]
+    pub fn get_white(&self) -> u64 {
+        self.white
+    }
+
+    #[must_use]
+    pub fn get_blockers(&self) -> u64 {
+        self.blockers
+    }
+
+    #[must_use]
+    pub fn get_us(&self) -> u64 {
+        match self.turn {
+            Side::Player1 => self.black,
+            Side::Player2 => self.white,
+        }
+    }
+
+    #[must_use]
+    pub fn get_them(&self) -> u64 {
+        match self.turn {
+            Side::Player1 => self.white,
+            Side::Player2 => self.black,
+        }
+    }
+
+    /// The squares nothing can be dropped on, whether a stone or a blocker is there.
+    #[must_use]
+    pub fn get_both(&self) -> u64 {
+        self.black | self.white | self.blockers
+    }
+
+    #[must_use]
+    fn bit(&self, x: usize, y: usize) -> u64 {
+        1 << (y * self.width + x)
+    }
+
+    /// The eight squares around a square, those of them that are on the board.
+    #[must_use]
+    fn neighbours(&self, x: usize, y: usize) -> u64 {
+        let mut bb = 0;
+        for ny in y.saturating_sub(1)..=(y + 1).min(self.height - 1) {
+            for nx in x.saturating_sub(1)..=(x + 1).min(self.width - 1) {
+                if (nx, ny) != (x, y) {
+                    bb |= self.bit(nx, ny);
+                }
+            }
+        }
+        bb
+    }
+
+    #[must_use]
+    fn is_full(&self) -> bool {
+        self.get_both().count_ones() as usize == self.width * self.height
+    }
+
+    fn flip_turn(&mut self) {
+        self.turn = match self.turn {
+            Side::Player1 => Side::Player2,
+            Side::Player2 => {
+                self.fullmove += 1;
+                Side::Player1
+            }
+        };
+    }
+}
+
+impl GameRules for DroptaxxPosition {
+    type MoveType = DroptaxxMove;
+
+    fn startpos() -> Self {
+        Self::new(SIZE, SIZE)
+    }
+
+    fn from_fen(fen: &str) -> Self {
+        let mut pos = Self::startpos();
+        pos.set_fen(fen);
+        pos
+    }
+
+    /// Rows from the top down, `x` for Black, `o` for White and `-` for blockers,
+    /// then the side to move and the fullmove number. The board takes its size from
+    /// the FEN, and "startpos" empties it.
+    fn set_fen(&mut self, fen: &str) {
+        if fen == "startpos" {
+            *self = Self::new(self.width, self.height);
+            return;
+        }
+
+        let mut parts = fen.split_ascii_whitespace();
+        let mut rows = vec![];
+
+        for row in parts.next().unwrap_or("").split('/') {
+            let mut cells = vec![];
+            let mut empty = 0;
+            for c in row.chars() {
+                if let Some(n) = c.to_digit(10) {
+                    empty = 10 * empty + n as usize;
+                    continue;
+                }
+                cells.extend(std::iter::repeat_n(None, empty));
+                empty = 0;
+                match c {
+                    'x' | 'X' | 'b' | 'B' => cells.push(Some('x')),
+                    'o' | 'O' | 'w' | 'W' => cells.push(Some('o')),
+                    '-' => cells.push(Some('-')),
+                    _ => {}
+                }
+            }
+            cells.extend(std::iter::repeat_n(None, empty));
+            rows.push(cells);
+        }
+
+        let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+        *self = Self::new(width.min(MAX_SIZE), rows.len().min(MAX_SIZE));
+
+        for (y, row) in rows.iter().rev().take(self.height).enumerate() {
+            for (x, cell) in row.iter().take(self.width).enumerate() {
+                let bb = self.bit(x, y);
+                match cell {
+                    Some('x') => self.black |= bb,
+                    Some('o') => self.white |= bb,
+                    Some(_) => self.blockers |= bb,
+                    None => {}
+                }
+            }
+        }
+
+        self.turn = match parts.next() {
+            Some("o") | Some("O") | Some("w") | Some("W") => Side::Player2,
+            _ => Side::Player1,
+        };
+        self.fullmove = parts.next().and_then(|n| n.parse().ok()).unwrap_or(1);
+    }
+
+    fn get_fen(&self) -> String {
+        let mut rows = vec![];
+
+        for y in (0..self.height).rev() {
+            let mut row = String::new();
+            let mut empty = 0;
+            for x in 0..self.width {
+                let bb = self.bit(x, y);
+                let c = if self.black & bb != 0 {
+                    'x'
+                } else if self.white & bb != 0 {
+                    'o'
+                } else if self.blockers & bb != 0 {
+                    '-'
+                } else {
+                    empty += 1;
+                    continue;
+                };
+                if empty > 0 {
+                    row += &empty.to_string();
+                    empty = 0;
+                }
+                row.push(c);
+            }
+            if empty > 0 {
+                row += &empty.to_string();
+            }
+            rows.push(row);
+        }
+
+        format!(
+            "{} {} {}",
+            rows.join("/"),
+            match self.turn {
+                Side::Player1 => 'x',
+                Side::Player2 => 'o',
+            },
+            self.fullmove
+        )
+    }
+
+    fn get_turn(&self) -> Side {
+        self.turn
+    }
+
+    fn legal_moves(&self) -> Vec<DroptaxxMove> {
+        let both = self.get_both();
+
+        (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| DroptaxxMove { x, y }))
+            .filter(|mv| both & self.bit(mv.x, mv.y) == 0)
+            .collect()
+    }
+
+    fn makemove(&mut self, mv: &DroptaxxMove) {
+        let bb = self.bit(mv.x, mv.y);
+        let flips = self.neighbours(mv.x, mv.y) & self.get_them();
+
+        match self.turn {
+            Side::Player1 => {
+                self.black |= bb | flips;
+                self.white ^= flips;
+            }
+            Side::Player2 => {
+                self.white |= bb | flips;
+                self.black ^= flips;
+            }
+        }
+
+        self.undo.push(flips);
+        self.flip_turn();
+    }
+
+    fn undomove(&mut self, mv: &DroptaxxMove) {
+        let flips = self.undo.pop().expect("No move to undo");
+        let bb = self.bit(mv.x, mv.y);
+
+        self.turn = match self.turn {
+            Side::Player1 => {
+                self.fullmove -= 1;
+                Side::Player2
+            }
+            Side::Player2 => Side::Player1,
+        };
+
+        match self.turn {
+            Side::Player1 => {
+                self.black ^= bb | flips;
+                self.white |= flips;
+            }
+            Side::Player2 => {
+                self.white ^= bb | flips;
+                self.black |= flips;
+            }
+        }
+    }
+
+    fn makenull(&mut self) {
+        self.flip_turn();
+    }
+
+    fn is_gameover(&self) -> bool {
+        self.is_full()
+    }
+
+    fn get_result(&self) -> Option<GameResult> {
+        if !self.is_full() {
+            return None;
+        }
+
+        let black = self.black.count_ones();
+        let white = self.white.count_ones();
+        if black > white {
+            Some(GameResult::Win(Side::Player1))
+        } else if white > black {
+            Some(GameResult::Win(Side::Player2))
+        } else {
+            Some(GameResult::Draw)
+        }
+    }
+}
+
+impl fmt::Display for DroptaxxPosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for y in (0..self.height).rev() {
+            write!(f, "{} ", y + 1)?;
+            for x in 0..self.width {
+                let bb = self.bit(x, y);
+                let c = if self.black & bb != 0 {
+                    'x'
+                } else if self.white & bb != 0 {
+                    'o'
+                } else if self.blockers & bb != 0 {
+                    ' '
+                } else {
+                    '-'
+                };
+                write!(f, "{}", c)?;
+            }
+            writeln!(f)?;
+        }
+        write!(f, "  ")?;
+        for x in 0..self.width {
+            write!(f, "{}", (b'a' + x as u8) as char)?;
+        }
+        writeln!(f)?;
+        writeln!(f, "FEN: {}", self.get_fen())?;
+        write!(
+            f,
+            "Turn: {}",
+            match self.turn {
+                Side::Player1 => 'x',
+                Side::Player2 => 'o',
+            }
+        )
+    }
+}
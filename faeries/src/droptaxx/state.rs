@@ -1,12 +1,23 @@
+use super::position::{DroptaxxMove, DroptaxxPosition, SIZE};
+use super::root::primary_with_stats;
+use crate::bound::Bound;
 use crate::prng;
-use games::{droptaxx::DroptaxxPosition, gamerules::GameRules};
-use protocols::ugi::options::{Check, Combo};
+use crate::search;
+use crate::searchstats::SearchStats;
+use games::gamerules::GameRules;
+use protocols::GoSettings;
+use protocols::ugi::options::{Check, Combo, Spin};
 
 pub struct DroptaxxState {
     pub pos: DroptaxxPosition,
     pub prng: prng::XorshiftGenerator,
+    pub startfen: String,
+    pub history: Vec<Option<DroptaxxMove>>,
+    /// What the last search counted
+    pub stats: SearchStats,
     // Options
     pub debug: Check,
+    pub size: Spin<i32>,
     pub search: Combo,
 }
 
@@ -15,11 +26,20 @@ impl Default for DroptaxxState {
         Self {
             pos: DroptaxxPosition::startpos(),
             prng: prng::XorshiftGenerator::new(0xe50076937a9e5b1c),
+            startfen: "startpos".to_owned(),
+            history: vec![],
+            stats: SearchStats::default(),
             // Options
             debug: Check {
                 name: "debug".to_owned(),
                 value: cfg!(debug_assertions),
             },
+            size: Spin {
+                name: "size".to_owned(),
+                min: 5,
+                max: 8,
+                value: SIZE as i32,
+            },
             search: Combo {
                 name: "search".to_owned(),
                 value: "primary".to_owned(),
@@ -34,3 +54,47 @@ impl Default for DroptaxxState {
         }
     }
 }
+
+impl DroptaxxState {
+    /// An empty board the size the option asks for.
+    pub fn reset(&mut self) {
+        let size = self.size.value as usize;
+        self.pos = DroptaxxPosition::new(size, size);
+        self.startfen = "startpos".to_owned();
+        self.history.clear();
+    }
+
+    #[must_use]
+    pub fn think(
+        &mut self,
+        settings: &GoSettings,
+        info_handler: &impl Fn(
+            &DroptaxxPosition,
+            Option<i32>,
+            Option<i32>,
+            Option<i32>,
+            Option<Bound>,
+            Option<i32>,
+            Option<u64>,
+            Option<u128>,
+            Option<i32>,
+            &Vec<DroptaxxMove>,
+            Option<&SearchStats>,
+        ),
+    ) -> Option<DroptaxxMove> {
+        let eval = |pos: &DroptaxxPosition| -> i32 {
+            100 * pos.get_us().count_ones() as i32 - 100 * pos.get_them().count_ones() as i32
+        };
+
+        search::think(
+            &self.search.value,
+            &self.pos,
+            settings,
+            info_handler,
+            &eval,
+            &mut self.prng,
+            &mut self.stats,
+            |stats| primary_with_stats(self.pos.clone(), settings, info_handler, stats),
+        )
+    }
+}
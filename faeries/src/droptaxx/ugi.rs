@@ -1,14 +1,14 @@
-use super::root::primary;
+use super::position::{DroptaxxMove, DroptaxxPosition};
+use super::root::bench;
 use super::state::DroptaxxState;
-use crate::search::alphabeta::alphabeta;
-use crate::search::flatmc::flatmc;
-use crate::search::minimax::minimax;
-use crate::search::random::random;
-use games::droptaxx::DroptaxxMove;
+use crate::bound::Bound;
+use crate::searchstats::SearchStats;
+use games::gamerules::GameResult;
 use games::gamerules::GameRules;
 use games::general::side::Side;
 use games::perft;
-use games::{droptaxx::DroptaxxPosition, gamerules::GameResult};
+use protocols::json::ToJson;
+use protocols::output::{self, Bestmove, Info, Message};
 use protocols::ugi::{GoSettings, UGI, UGIGameResult};
 
 pub fn info_handler(
@@ -16,37 +16,31 @@ pub fn info_handler(
     depth: Option<i32>,
     seldepth: Option<i32>,
     score: Option<i32>,
+    bound: Option<Bound>,
     mate: Option<i32>,
     nodes: Option<u64>,
     elapsed: Option<u128>,
     hashfull: Option<i32>,
     pv: &Vec<DroptaxxMove>,
+    stats: Option<&SearchStats>,
 ) {
-    print!("info");
-    depth.inspect(|d| print!(" depth {}", d));
-    seldepth.inspect(|d| print!(" seldepth {}", d));
-    score.inspect(|s| print!(" score cp {}", s));
-    mate.inspect(|d| print!(" score mate {}", d));
-    nodes.inspect(|n| print!(" nodes {}", n));
-    elapsed.inspect(|ms| print!(" time {}", ms));
-    if let (Some(t), Some(n)) = (elapsed, nodes) {
-        if t > 0 {
-            print!(" nps {}", (n as u128 * 1000) / t);
-        }
-    }
-    hashfull.inspect(|hashfull| print!(" hashfull {}", hashfull));
-    if !pv.is_empty() {
-        print!(" pv");
-        for mv in pv {
-            print!(" {}", as_ugi(mv));
-        }
-    }
-    println!();
+    output::emit(&Info {
+        depth,
+        seldepth,
+        score,
+        bound: bound.map(|b| b.to_string()),
+        mate,
+        nodes,
+        elapsed,
+        hashfull,
+        pv: pv.iter().map(as_ugi).collect(),
+        stats: stats.map(ToJson::to_json),
+    });
 }
 
 #[must_use]
 pub fn as_ugi(mv: &DroptaxxMove) -> String {
-    format!("{}", mv.0).to_owned()
+    format!("{}", mv).to_owned()
 }
 
 impl UGI for DroptaxxState {
@@ -65,7 +59,7 @@ impl UGI for DroptaxxState {
     }
 
     fn uginewgame(&mut self) {
-        self.pos = DroptaxxPosition::startpos();
+        self.pos.set_fen("startpos");
     }
 
     fn isready(&mut self) {
@@ -90,26 +84,15 @@ impl UGI for DroptaxxState {
     }
 
     fn go(&mut self, settings: &GoSettings) {
-        let settings = &protocols::GoSettings::from(settings);
-        let eval = |pos: &DroptaxxPosition| -> i32 {
-            100 * pos.get_us().count() - 100 * pos.get_them().count()
+        let settings = &protocols::GoSettings {
+            debug: self.debug.value,
+            ..protocols::GoSettings::from(settings)
         };
-
-        let bestmove = match self.search.value.as_str() {
-            "primary" => primary(self.pos.clone(), settings, &info_handler),
-            "random" => random(&self.pos, || self.prng.next()),
-            "minimax" => minimax(self.pos.clone(), settings, &info_handler, &eval),
-            "alphabeta" => alphabeta(self.pos.clone(), settings, &info_handler, &eval),
-            "flatmc" => flatmc(self.pos.clone(), settings, &info_handler, || {
-                self.prng.next()
-            }),
-            _ => panic!("Unknown search type"),
-        };
-        if let Some(mv) = bestmove {
-            println!("bestmove {}", as_ugi(&mv));
-        } else {
-            println!("bestmove 0000");
-        }
+        let bestmove = self.think(settings, &info_handler);
+        output::emit(&Bestmove {
+            mv: bestmove.map_or("0000".to_owned(), |mv| as_ugi(&mv)),
+            stats: self.stats.to_json(),
+        });
     }
 
     fn stop(&mut self) {}
@@ -117,16 +100,23 @@ impl UGI for DroptaxxState {
     fn unrecognised_command(
         &mut self,
         word: &str,
-        _args: &mut std::iter::Peekable<std::str::SplitAsciiWhitespace>,
+        args: &mut std::iter::Peekable<std::str::SplitAsciiWhitespace>,
     ) {
-        if word == "print" {
-            self.print();
+        match word {
+            "print" => self.print(),
+            "bench" => {
+                let depth = args.next().and_then(|word| word.parse::<i32>().ok());
+                println!("{}", bench(depth));
+            }
+            "stats" => output::emit(&Message(self.stats.to_string())),
+            _ => {}
         }
     }
 
     fn print_options(&self) {
-        println!("{}", self.debug);
-        println!("{}", self.search);
+        output::emit(&self.debug);
+        output::emit(&self.size);
+        output::emit(&self.search);
     }
 
     fn movelist(&self) {
@@ -139,6 +129,13 @@ impl UGI for DroptaxxState {
         match (name, value) {
             ("debug", "true") => self.debug.value = true,
             ("debug", "false") => self.debug.value = false,
+            ("size", _) => {
+                match value.parse::<i32>() {
+                    Ok(n) if (self.size.min..=self.size.max).contains(&n) => self.size.value = n,
+                    _ => return,
+                }
+                self.reset();
+            }
             ("search", _) => self.search.value = value.to_owned(),
             (_, _) => {}
         }
@@ -162,20 +159,8 @@ impl UGI for DroptaxxState {
     }
 
     fn perft(&mut self, settings: &GoSettings) {
-        let info_handler = |depth: i32, elapsed: f32, nodes: u64| {
-            print!("info");
-            print!(" depth {}", depth);
-            print!(" nodes {}", nodes);
-            print!(" time {}", (elapsed * 1000.0) as u64);
-            if elapsed > 0.0 {
-                let nps = nodes as f32 / elapsed;
-                print!(" nps {}", nps as u64);
-            }
-            println!();
-        };
-        let final_handler = |nodes: u64| {
-            println!("nodes {}", nodes);
-        };
+        let info_handler = output::perft;
+        let final_handler = output::nodes;
         perft::perft(
             &mut self.pos,
             settings.depth.unwrap(),
@@ -185,10 +170,8 @@ impl UGI for DroptaxxState {
     }
 
     fn split(&mut self, settings: &GoSettings) {
-        let info_handler = |mv: DroptaxxMove, nodes: u64| println!("{} {}", as_ugi(&mv), nodes);
-        let final_handler = |nodes: u64| {
-            println!("nodes {}", nodes);
-        };
+        let info_handler = |mv: DroptaxxMove, nodes: u64| output::split(as_ugi(&mv), nodes);
+        let final_handler = output::nodes;
         perft::split(
             &mut self.pos,
             settings.depth.unwrap(),
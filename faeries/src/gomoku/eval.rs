@@ -1,6 +1,80 @@
-use games::gomoku::GomokuPosition;
+use super::position::{DIRECTIONS, GomokuPosition};
+use crate::tune::{Tunable, dot};
+use games::gamerules::GameRules;
+use games::general::side::Side;
+
+/// Lines by how many stones are in them.
+pub const NAMES: [&str; 5] = ["one", "two", "three", "four", "five"];
+pub const WEIGHTS: [i32; 5] = [3, 15, 63, 255, 1023];
+
+/// Every line of five squares that only one side has stones in could still be
+/// theirs, and is worth more the fuller it is.
+///
+/// Lines are found from the stones in them, each from its first stone, so that
+/// the empty ones filling most of the board cost nothing.
+#[must_use]
+pub fn features(pos: &GomokuPosition) -> [i32; 5] {
+    let us = pos.get_turn();
+    let mut lines = [0; 5];
+
+    for idx in 0..pos.get_size() * pos.get_size() {
+        if pos.get(idx).is_none() {
+            continue;
+        }
+
+        for dir in DIRECTIONS {
+            for offset in 0..5 {
+                let Some(start) = pos.step(idx, dir, -offset) else {
+                    break;
+                };
+                if pos.step(start, dir, 4).is_none() {
+                    continue;
+                }
+
+                let cells = (0..5).map(|n| pos.get(pos.step(start, dir, n).unwrap()));
+                if cells
+                    .clone()
+                    .take(offset as usize)
+                    .any(|cell| cell.is_some())
+                {
+                    continue;
+                }
+
+                let (mut ours, mut theirs) = (0, 0);
+                for cell in cells {
+                    match cell {
+                        Some(side) if side == us => ours += 1,
+                        Some(_) => theirs += 1,
+                        None => {}
+                    }
+                }
+
+                if theirs == 0 {
+                    lines[ours - 1] += 1;
+                } else if ours == 0 {
+                    lines[theirs - 1] -= 1;
+                }
+            }
+        }
+    }
+
+    lines
+}
 
 #[must_use]
-pub fn eval(_pos: &GomokuPosition) -> i32 {
-    0
+pub fn eval(pos: &GomokuPosition) -> i32 {
+    dot(&WEIGHTS, &features(pos))
+}
+
+impl Tunable for GomokuPosition {
+    const NAMES: &'static [&'static str] = &NAMES;
+    const WEIGHTS: &'static [i32] = &WEIGHTS;
+
+    fn features(&self) -> Vec<i32> {
+        features(self).to_vec()
+    }
+
+    fn first_to_move(&self) -> bool {
+        self.get_turn() == Side::Player1
+    }
 }
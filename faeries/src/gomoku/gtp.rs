@@ -0,0 +1,174 @@
+use super::position::Rule;
+use super::{manual::empty_handler, state::GomokuState};
+use crate::gtp::{budget, clock, parse_vertex, vertex_name};
+use games::{
+    gamerules::{GameResult, GameRules},
+    general::side::Side,
+};
+use protocols::GoSettings;
+use protocols::gtp::{GTP, GTPColour};
+use protocols::manual::Manual;
+
+#[must_use]
+fn side(colour: GTPColour) -> Side {
+    match colour {
+        GTPColour::Black => Side::Player1,
+        GTPColour::White => Side::Player2,
+    }
+}
+
+impl GomokuState {
+    /// GTP says which colour is moving, so skip a turn if it's the other one.
+    fn set_turn(&mut self, colour: GTPColour) {
+        if self.pos.get_turn() != side(colour) {
+            self.makenull();
+        }
+    }
+
+    #[must_use]
+    fn settings(&self, colour: GTPColour) -> GoSettings {
+        match self.time_left[colour as usize] {
+            Some(time_left) => GoSettings::from_movetime(budget(time_left, self.byoyomi)),
+            None => GoSettings::from_depth(2),
+        }
+    }
+}
+
+impl GTP for GomokuState {
+    fn name(&self) -> String {
+        "Faeries".to_owned()
+    }
+
+    fn version(&self) -> String {
+        env!("CARGO_PKG_VERSION").to_owned()
+    }
+
+    fn boardsize(&mut self, size: i32) -> Result<(), String> {
+        let size = size.to_string();
+        if !self.size.options.contains(&size) {
+            return Err("unacceptable size".to_owned());
+        }
+
+        self.size.value = size;
+        self.reset();
+        Ok(())
+    }
+
+    fn clear_board(&mut self) {
+        self.newgame();
+    }
+
+    fn komi(&mut self, _komi: f32) {}
+
+    fn play(&mut self, colour: GTPColour, vertex: &str) -> Result<(), String> {
+        let ply = self.history.len();
+        self.set_turn(colour);
+
+        if vertex.eq_ignore_ascii_case("pass") {
+            self.makenull();
+            return Ok(());
+        }
+
+        let size = self.pos.get_size();
+        let played = parse_vertex(vertex, size, size)
+            .map(|(x, y)| format!("{}{}", (b'a' + x as u8) as char, y + 1))
+            .is_some_and(|movestr| self.makemove(&movestr));
+
+        if played {
+            Ok(())
+        } else {
+            // Take back the skipped turn
+            while self.history.len() > ply {
+                Manual::undo(self);
+            }
+            Err("illegal move".to_owned())
+        }
+    }
+
+    fn genmove(&mut self, colour: GTPColour) -> String {
+        self.set_turn(colour);
+
+        let settings = self.settings(colour);
+        match self.think(&settings, &empty_handler) {
+            Some(mv) => {
+                self.pos.makemove(&mv);
+                self.history.push(Some(mv));
+                let (x, y) = self.pos.coords(mv.0);
+                vertex_name(x, y)
+            }
+            None => {
+                self.makenull();
+                "pass".to_owned()
+            }
+        }
+    }
+
+    fn undo(&mut self) -> Result<(), String> {
+        if Manual::undo(self) {
+            Ok(())
+        } else {
+            Err("cannot undo".to_owned())
+        }
+    }
+
+    fn showboard(&self) -> String {
+        format!("\n{}", self.pos)
+    }
+
+    /// Five in a row wins outright, so there are no points to count.
+    fn final_score(&self) -> String {
+        match self.pos.get_result() {
+            Some(GameResult::Win(Side::Player1)) => "B+R".to_owned(),
+            Some(GameResult::Win(Side::Player2)) => "W+R".to_owned(),
+            _ => "0".to_owned(),
+        }
+    }
+
+    fn final_status_list(&self, status: &str) -> Result<String, String> {
+        match status {
+            "alive" => {
+                let size = self.pos.get_size();
+                let stones = (0..size * size)
+                    .filter(|idx| self.pos.get(*idx).is_some())
+                    .map(|idx| {
+                        let (x, y) = self.pos.coords(idx);
+                        vertex_name(x, y)
+                    })
+                    .collect::<Vec<String>>();
+                Ok(stones.join(" "))
+            }
+            "dead" | "seki" => Ok(String::new()),
+            _ => Err("invalid status".to_owned()),
+        }
+    }
+
+    fn time_settings(&mut self, main: i32, byoyomi: i32, stones: i32) {
+        let clock = clock(main, byoyomi, stones);
+        self.time_left = [clock.map(|(main, _)| (main, 0)); 2];
+        self.byoyomi = clock.and_then(|(_, byoyomi)| byoyomi);
+    }
+
+    fn time_left(&mut self, colour: GTPColour, time: i32, stones: i32) {
+        self.time_left[colour as usize] = Some((time, stones));
+    }
+
+    fn extra_commands(&self) -> Vec<String> {
+        vec!["faeries-rule".to_owned()]
+    }
+
+    fn custom(&mut self, command: &str, args: &[&str]) -> Result<String, String> {
+        match command {
+            // Freestyle, standard or renju, which starts a new game
+            "faeries-rule" => {
+                let rule = args
+                    .first()
+                    .and_then(|name| Rule::parse(name))
+                    .ok_or("syntax error")?;
+                self.rule.value = rule.name().to_owned();
+                self.reset();
+                Ok(String::new())
+            }
+            _ => Err("unknown command".to_owned()),
+        }
+    }
+}
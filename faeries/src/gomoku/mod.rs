@@ -1,5 +1,7 @@
 pub mod eval;
+pub mod gtp;
 pub mod manual;
+pub mod position;
 pub mod root;
 pub mod search;
 pub mod state;
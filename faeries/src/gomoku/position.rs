@@ -0,0 +1,454 @@
+use games::{
+    gamerules::{GameResult, GameRules},
+    general::side::Side,
+};
+use std::fmt;
+
+pub const SIZE: usize = 15;
+
+/// Along a rank, up a file, and the two diagonals.
+pub const DIRECTIONS: [(i32, i32); 4] = [(1, 0), (0, 1), (1, 1), (1, -1)];
+
+/// Which lines win, and what Black isn't allowed to play.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Rule {
+    /// Five or more in a row wins
+    Freestyle,
+    /// Exactly five in a row wins, and longer lines don't count
+    Standard,
+    /// Black has to make exactly five, and can't make a longer line, two fours or
+    /// two open threes with one stone. White wins with five or more.
+    Renju,
+}
+
+impl Rule {
+    pub const ALL: [Rule; 3] = [Rule::Freestyle, Rule::Standard, Rule::Renju];
+
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Rule::Freestyle => "freestyle",
+            Rule::Standard => "standard",
+            Rule::Renju => "renju",
+        }
+    }
+
+    #[must_use]
+    pub fn parse(name: &str) -> Option<Self> {
+        Rule::ALL
+            .into_iter()
+            .find(|rule| rule.name().eq_ignore_ascii_case(name))
+    }
+}
+
+/// A square, indexed by `y * size + x` from a1.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct GomokuMove(pub usize);
+
+/// Gomoku on a square board. Black moves first, and the game is drawn once the
+/// board is full.
+#[derive(Clone, Debug)]
+pub struct GomokuPosition {
+    size: usize,
+    rule: Rule,
+    board: Vec<Option<Side>>,
+    stones: usize,
+    turn: Side,
+    winner: Option<Side>,
+}
+
+impl GomokuPosition {
+    /// An empty board.
+    #[must_use]
+    pub fn new(size: usize, rule: Rule) -> Self {
+        Self {
+            size,
+            rule,
+            board: vec![None; size * size],
+            stones: 0,
+            turn: Side::Player1,
+            winner: None,
+        }
+    }
+
+    #[must_use]
+    pub fn get_size(&self) -> usize {
+        self.size
+    }
+
+    #[must_use]
+    pub fn get_rule(&self) -> Rule {
+        self.rule
+    }
+
+    #[must_use]
+    pub fn get(&self, idx: usize) -> Option<Side> {
+        self.board[idx]
+    }
+
+    #[must_use]
+    pub fn index(&self, x: usize, y: usize) -> usize {
+        y * self.size + x
+    }
+
+    #[must_use]
+    pub fn coords(&self, idx: usize) -> (usize, usize) {
+        (idx % self.size, idx / self.size)
+    }
+
+    /// The square `n` steps away in a direction, if it's on the board.
+    #[must_use]
+    pub fn step(&self, idx: usize, (dx, dy): (i32, i32), n: i32) -> Option<usize> {
+        let (x, y) = self.coords(idx);
+        let nx = x as i32 + n * dx;
+        let ny = y as i32 + n * dy;
+        let size = self.size as i32;
+        ((0..size).contains(&nx) && (0..size).contains(&ny))
+            .then(|| self.index(nx as usize, ny as usize))
+    }
+
+    #[must_use]
+    pub fn square_name(&self, idx: usize) -> String {
+        let (x, y) = self.coords(idx);
+        format!("{}{}", (b'a' + x as u8) as char, y + 1)
+    }
+
+    /// How far an unbroken line of the stone on a square runs behind and ahead of it.
+    #[must_use]
+    fn span(&self, idx: usize, dir: (i32, i32)) -> (i32, i32) {
+        let side = self.board[idx];
+        let reach = |sign: i32| {
+            let mut n = 0;
+            while self
+                .step(idx, dir, sign * (n + 1))
+                .is_some_and(|next| self.board[next] == side)
+            {
+                n += 1;
+            }
+            n
+        };
+        (reach(-1), reach(1))
+    }
+
+    #[must_use]
+    fn run(&self, idx: usize, dir: (i32, i32)) -> usize {
+        let (behind, ahead) = self.span(idx, dir);
+        (behind + ahead + 1) as usize
+    }
+
+    #[must_use]
+    fn is_five(&self, side: Side, length: usize) -> bool {
+        match (self.rule, side) {
+            (Rule::Freestyle, _) | (Rule::Renju, Side::Player2) => length >= 5,
+            (Rule::Standard, _) | (Rule::Renju, Side::Player1) => length == 5,
+        }
+    }
+
+    #[must_use]
+    fn makes_five(&self, idx: usize) -> bool {
+        let side = self.board[idx].expect("No stone to check");
+        DIRECTIONS
+            .iter()
+            .any(|dir| self.is_five(side, self.run(idx, *dir)))
+    }
+
+    /// How many other black stones are within reach of a line of five through a square.
+    #[must_use]
+    fn nearby(&self, idx: usize, dir: (i32, i32)) -> usize {
+        (-4..=4)
+            .filter(|n| *n != 0)
+            .filter_map(|n| self.step(idx, dir, n))
+            .filter(|sq| self.board[*sq] == Some(Side::Player1))
+            .count()
+    }
+
+    /// Where Black could play to make exactly five in a line through a stone of
+    /// theirs, as steps along the line from it.
+    #[must_use]
+    fn fives(&mut self, idx: usize, dir: (i32, i32)) -> Vec<i32> {
+        let mut found = vec![];
+
+        for n in -4..=4 {
+            let Some(sq) = self.step(idx, dir, n) else {
+                continue;
+            };
+            if self.board[sq].is_some() {
+                continue;
+            }
+
+            self.board[sq] = Some(Side::Player1);
+            let (behind, ahead) = self.span(sq, dir);
+            if (-behind..=ahead).contains(&-n) && behind + ahead + 1 == 5 {
+                found.push(n);
+            }
+            self.board[sq] = None;
+        }
+
+        found
+    }
+
+    /// How many fours a line through a black stone holds. A straight four has two
+    /// ways to make five but is still the one four.
+    #[must_use]
+    fn fours(&mut self, idx: usize, dir: (i32, i32)) -> usize {
+        if self.nearby(idx, dir) < 3 {
+            return 0;
+        }
+
+        let fives = self.fives(idx, dir);
+        if fives.len() == 2 && fives[1] - fives[0] == 5 {
+            1
+        } else {
+            fives.len()
+        }
+    }
+
+    /// Whether a line through a black stone holds a three: one more stone, on a
+    /// point Black is allowed to play, would make a straight four.
+    #[must_use]
+    fn is_three(&mut self, idx: usize, dir: (i32, i32)) -> bool {
+        if self.nearby(idx, dir) < 2 {
+            return false;
+        }
+
+        for n in -4..=4 {
+            let Some(sq) = self.step(idx, dir, n) else {
+                continue;
+            };
+            if self.board[sq].is_some() {
+                continue;
+            }
+
+            self.board[sq] = Some(Side::Player1);
+            let fives = self.fives(idx, dir);
+            let straight = fives.len() == 2 && fives[1] - fives[0] == 5;
+            self.board[sq] = None;
+
+            if straight && !self.is_forbidden(sq) {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Whether Black isn't allowed to play on an empty square under the renju rule.
+    /// Making five is always allowed, even when it makes something forbidden too.
+    #[must_use]
+    pub fn is_forbidden(&mut self, idx: usize) -> bool {
+        if self.rule != Rule::Renju {
+            return false;
+        }
+
+        self.board[idx] = Some(Side::Player1);
+        let lengths = DIRECTIONS.map(|dir| self.run(idx, dir));
+        let forbidden = if lengths.contains(&5) {
+            false
+        } else if lengths.iter().any(|length| *length > 5) {
+            true
+        } else {
+            DIRECTIONS
+                .iter()
+                .map(|dir| self.fours(idx, *dir))
+                .sum::<usize>()
+                >= 2
+                || DIRECTIONS
+                    .iter()
+                    .filter(|dir| self.is_three(idx, **dir))
+                    .count()
+                    >= 2
+        };
+        self.board[idx] = None;
+
+        forbidden
+    }
+}
+
+impl GameRules for GomokuPosition {
+    type MoveType = GomokuMove;
+
+    fn startpos() -> Self {
+        Self::new(SIZE, Rule::Freestyle)
+    }
+
+    fn from_fen(fen: &str) -> Self {
+        let mut pos = Self::startpos();
+        pos.set_fen(fen);
+        pos
+    }
+
+    /// Rows from the top down, `x` for Black and `o` for White, then the side to
+    /// move. The board takes its size from the FEN, and "startpos" empties it. The
+    /// rule isn't part of the position, so it's left alone.
+    fn set_fen(&mut self, fen: &str) {
+        if fen == "startpos" {
+            *self = Self::new(self.size, self.rule);
+            return;
+        }
+
+        let mut parts = fen.split_ascii_whitespace();
+        let rows = parts.next().unwrap_or("").split('/').collect::<Vec<&str>>();
+        *self = Self::new(rows.len(), self.rule);
+
+        for (y, row) in rows.iter().rev().enumerate() {
+            let mut x = 0;
+            let mut empty = 0;
+            for c in row.chars() {
+                if let Some(n) = c.to_digit(10) {
+                    empty = 10 * empty + n as usize;
+                    continue;
+                }
+                x += empty;
+                empty = 0;
+                let side = match c {
+                    'x' | 'X' | 'b' | 'B' => Side::Player1,
+                    'o' | 'O' | 'w' | 'W' => Side::Player2,
+                    _ => continue,
+                };
+                if x < self.size {
+                    let idx = self.index(x, y);
+                    self.board[idx] = Some(side);
+                    self.stones += 1;
+                }
+                x += 1;
+            }
+        }
+
+        self.turn = match parts.next() {
+            Some("o") | Some("O") | Some("w") | Some("W") => Side::Player2,
+            _ => Side::Player1,
+        };
+
+        self.winner = (0..self.board.len())
+            .find(|idx| self.board[*idx].is_some() && self.makes_five(*idx))
+            .and_then(|idx| self.board[idx]);
+    }
+
+    fn get_fen(&self) -> String {
+        let mut rows = vec![];
+
+        for y in (0..self.size).rev() {
+            let mut row = String::new();
+            let mut empty = 0;
+            for x in 0..self.size {
+                let c = match self.board[self.index(x, y)] {
+                    Some(Side::Player1) => 'x',
+                    Some(Side::Player2) => 'o',
+                    None => {
+                        empty += 1;
+                        continue;
+                    }
+                };
+                if empty > 0 {
+                    row += &empty.to_string();
+                    empty = 0;
+                }
+                row.push(c);
+            }
+            if empty > 0 {
+                row += &empty.to_string();
+            }
+            rows.push(row);
+        }
+
+        format!(
+            "{} {}",
+            rows.join("/"),
+            match self.turn {
+                Side::Player1 => 'x',
+                Side::Player2 => 'o',
+            }
+        )
+    }
+
+    fn get_turn(&self) -> Side {
+        self.turn
+    }
+
+    fn legal_moves(&self) -> Vec<GomokuMove> {
+        if self.winner.is_some() {
+            return vec![];
+        }
+
+        let empty = (0..self.board.len()).filter(|idx| self.board[*idx].is_none());
+
+        if self.rule == Rule::Renju && self.turn == Side::Player1 {
+            let mut scratch = self.clone();
+            empty
+                .filter(|idx| !scratch.is_forbidden(*idx))
+                .map(GomokuMove)
+                .collect()
+        } else {
+            empty.map(GomokuMove).collect()
+        }
+    }
+
+    fn makemove(&mut self, mv: &GomokuMove) {
+        self.board[mv.0] = Some(self.turn);
+        self.stones += 1;
+        if self.makes_five(mv.0) {
+            self.winner = Some(self.turn);
+        }
+        self.makenull();
+    }
+
+    fn undomove(&mut self, mv: &GomokuMove) {
+        self.board[mv.0] = None;
+        self.stones -= 1;
+        // The game stops as soon as someone wins, so nobody had before this move
+        self.winner = None;
+        self.makenull();
+    }
+
+    fn makenull(&mut self) {
+        self.turn = match self.turn {
+            Side::Player1 => Side::Player2,
+            Side::Player2 => Side::Player1,
+        };
+    }
+
+    fn is_gameover(&self) -> bool {
+        self.get_result().is_some()
+    }
+
+    fn get_result(&self) -> Option<GameResult> {
+        if let Some(side) = self.winner {
+            Some(GameResult::Win(side))
+        } else if self.stones == self.board.len() {
+            Some(GameResult::Draw)
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for GomokuPosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for y in (0..self.size).rev() {
+            write!(f, "{:>2} ", y + 1)?;
+            for x in 0..self.size {
+                let c = match self.board[self.index(x, y)] {
+                    Some(Side::Player1) => 'x',
+                    Some(Side::Player2) => 'o',
+                    None => '.',
+                };
+                write!(f, "{}", c)?;
+            }
+            writeln!(f)?;
+        }
+        write!(f, "   ")?;
+        for x in 0..self.size {
+            write!(f, "{}", (b'a' + x as u8) as char)?;
+        }
+        writeln!(f)?;
+        writeln!(f, "Rule: {}", self.rule.name())?;
+        write!(
+            f,
+            "Turn: {}",
+            match self.turn {
+                Side::Player1 => 'x',
+                Side::Player2 => 'o',
+            }
+        )
+    }
+}
@@ -1,17 +1,60 @@
-use super::search::negamax;
+use super::position::{GomokuMove, GomokuPosition};
+use super::search::{INF_SCORE, negamax};
+use super::ugi::as_ugi;
+use crate::bench::{Bench, quiet};
+use crate::bound::Bound;
 use crate::searchstats::SearchStats;
-use games::{
-    gamerules::GameRules,
-    general::side::Side,
-    gomoku::{GomokuMove, GomokuPosition},
-};
+use crate::testsuite::{Epd, Report};
+use games::{gamerules::GameRules, general::side::Side};
 use protocols::GoSettings;
-use std::time::Instant;
+use protocols::output::{self, Message};
+use std::{
+    cmp::{max, min},
+    time::Instant,
+};
 
 const MAX_DEPTH: i32 = 128;
 
+/// How far either side of the last iteration's score to look at first.
+const ASPIRATION_WINDOW: i32 = 50;
+
+/// Shallower searches are cheap enough to do with a full window.
+const ASPIRATION_DEPTH: i32 = 4;
+
+/// The positions `bench` searches, and how deep.
+pub const BENCH_FENS: [&str; 4] = [
+    "15/15/15/15/15/15/15/7x7/15/15/15/15/15/15/15 o",
+    "15/15/15/15/15/15/7o7/7x7/8x6/15/15/15/15/15/15 o",
+    "15/15/15/15/15/6xo7/7ox6/6xxo6/15/15/15/15/15/15/15 o",
+    "15/15/15/15/6o8/6xo7/5xxxo6/6ox7/7xo6/15/15/15/15/15/15 o",
+];
+pub const BENCH_DEPTH: i32 = 3;
+
 #[must_use]
 pub fn primary(
+    pos: GomokuPosition,
+    settings: &GoSettings,
+    info_handler: &impl Fn(
+        &GomokuPosition,
+        Option<i32>,
+        Option<i32>,
+        Option<i32>,
+        Option<Bound>,
+        Option<i32>,
+        Option<u64>,
+        Option<u128>,
+        Option<i32>,
+        &Vec<GomokuMove>,
+        Option<&SearchStats>,
+    ),
+) -> Option<GomokuMove> {
+    primary_with_stats(pos, settings, info_handler, &mut SearchStats::default())
+}
+
+/// The same search, leaving what it counted in `stats` to look at afterwards.
+/// In debug mode they're shown after every iteration as well.
+#[must_use]
+pub fn primary_with_stats(
     mut pos: GomokuPosition,
     settings: &GoSettings,
     info_handler: &impl Fn(
@@ -19,16 +62,19 @@ pub fn primary(
         Option<i32>,
         Option<i32>,
         Option<i32>,
+        Option<Bound>,
         Option<i32>,
         Option<u64>,
         Option<u128>,
         Option<i32>,
         &Vec<GomokuMove>,
+        Option<&SearchStats>,
     ),
+    stats: &mut SearchStats,
 ) -> Option<GomokuMove> {
+    *stats = SearchStats::default();
     let start = Instant::now();
     let mut bestmove = None;
-    let mut stats = SearchStats::default();
     let max_depth = settings.depth.unwrap_or(MAX_DEPTH);
     let us = pos.get_turn();
     let should_stop = |stats: &SearchStats| -> bool {
@@ -54,13 +100,78 @@ pub fn primary(
         false
     };
 
+    let mut pv = vec![];
+    let mut score = 0;
+
     for depth in 1..=max_depth {
-        let mut pv = vec![];
-        let score = negamax(&mut pos, depth, 0, &should_stop, &mut stats, &mut pv);
+        // Look for the score close to the last one, and widen the window on
+        // whichever side it turns out to be outside of. Neither side ever comes
+        // back in, so a score that fails high and then low still gets found.
+        let mut delta = ASPIRATION_WINDOW;
+        let (mut alpha, mut beta) = if depth >= ASPIRATION_DEPTH {
+            (
+                max(score - delta, -INF_SCORE),
+                min(score + delta, INF_SCORE),
+            )
+        } else {
+            (-INF_SCORE, INF_SCORE)
+        };
+
+        loop {
+            let mut line = pv.clone();
+            let result = negamax(
+                &mut pos,
+                alpha,
+                beta,
+                depth,
+                0,
+                &should_stop,
+                stats,
+                &mut line,
+            );
+
+            if should_stop(stats) {
+                // The moves that did get searched to the end are still worth
+                // having, if the best of them beats the last iteration's
+                if !line.is_empty() && (bestmove.is_none() || result > score) {
+                    bestmove = Some(line[0]);
+                }
+                break;
+            }
+
+            let bound = if result <= alpha {
+                alpha = max(result - delta, -INF_SCORE);
+                Bound::Upper
+            } else if result >= beta {
+                beta = min(result + delta, INF_SCORE);
+                Bound::Lower
+            } else {
+                score = result;
+                pv = line;
+                break;
+            };
 
-        if depth > 1 && should_stop(&stats) {
             info_handler(
                 &pos,
+                Some(depth),
+                Some(stats.seldepth),
+                Some(result),
+                Some(bound),
+                None,
+                Some(stats.nodes),
+                Some(start.elapsed().as_millis()),
+                None,
+                &line,
+                None,
+            );
+
+            delta *= 2;
+        }
+
+        if should_stop(stats) {
+            info_handler(
+                &pos,
+                None,
                 None,
                 None,
                 None,
@@ -69,11 +180,13 @@ pub fn primary(
                 Some(start.elapsed().as_millis()),
                 None,
                 &vec![],
+                None,
             );
             break;
         }
 
-        bestmove = Some(pv[0]);
+        bestmove = pv.first().copied();
+        stats.iterations.push(stats.nodes);
 
         info_handler(
             &pos,
@@ -81,12 +194,41 @@ pub fn primary(
             Some(stats.seldepth),
             Some(score),
             None,
+            None,
             Some(stats.nodes),
             Some(start.elapsed().as_millis()),
             None,
             &pv,
+            Some(&*stats),
         );
+
+        if settings.debug {
+            output::emit(&Message(stats.to_string()));
+        }
     }
 
-    bestmove
+    // Stopped before searching a single move, but anything legal beats nothing
+    bestmove.or_else(|| pos.legal_moves().first().copied())
+}
+
+/// Search the bench positions, to `BENCH_DEPTH` unless told otherwise.
+#[must_use]
+pub fn bench(depth: Option<i32>) -> Bench {
+    let depth = depth.unwrap_or(BENCH_DEPTH);
+    crate::bench::run(&BENCH_FENS, depth, |fen, settings, stats| {
+        let pos = GomokuPosition::from_fen(fen);
+        let _ = primary_with_stats(pos, settings, &quiet, stats);
+    })
+}
+
+/// Search each EPD position within `settings`.
+#[must_use]
+pub fn testsuite(epds: &[Epd], settings: &GoSettings) -> Report {
+    crate::testsuite::run(
+        epds,
+        settings,
+        GomokuPosition::from_fen,
+        |pos, mv| vec![as_ugi(pos, mv)],
+        |pos, settings, info_handler| primary(pos, settings, &info_handler),
+    )
 }
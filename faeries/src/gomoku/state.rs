@@ -1,12 +1,29 @@
+use super::eval::eval;
+use super::position::{GomokuMove, GomokuPosition, Rule, SIZE};
+use super::root::primary_with_stats;
+use crate::bound::Bound;
 use crate::prng;
-use games::{gamerules::GameRules, gomoku::GomokuPosition};
+use crate::search;
+use crate::searchstats::SearchStats;
+use games::gamerules::GameRules;
+use protocols::GoSettings;
 use protocols::ugi::options::{Check, Combo};
 
 pub struct GomokuState {
     pub pos: GomokuPosition,
     pub prng: prng::XorshiftGenerator,
+    pub startfen: String,
+    pub history: Vec<Option<GomokuMove>>,
+    /// Seconds and byo-yomi stones left for each side, when playing over GTP
+    pub time_left: [Option<(i32, i32)>; 2],
+    /// Seconds and stones in each byo-yomi period
+    pub byoyomi: Option<(i32, i32)>,
+    /// What the last search counted
+    pub stats: SearchStats,
     // Options
     pub debug: Check,
+    pub size: Combo,
+    pub rule: Combo,
     pub search: Combo,
 }
 
@@ -15,11 +32,26 @@ impl Default for GomokuState {
         Self {
             pos: GomokuPosition::startpos(),
             prng: prng::XorshiftGenerator::new(0xe50076937a9e5b1c),
+            startfen: "startpos".to_owned(),
+            history: vec![],
+            time_left: [None; 2],
+            byoyomi: None,
+            stats: SearchStats::default(),
             // Options
             debug: Check {
                 name: "debug".to_owned(),
                 value: cfg!(debug_assertions),
             },
+            size: Combo {
+                name: "size".to_owned(),
+                value: SIZE.to_string(),
+                options: vec!["15".to_owned(), "19".to_owned()],
+            },
+            rule: Combo {
+                name: "rule".to_owned(),
+                value: Rule::Freestyle.name().to_owned(),
+                options: Rule::ALL.map(|rule| rule.name().to_owned()).to_vec(),
+            },
             search: Combo {
                 name: "search".to_owned(),
                 value: "primary".to_owned(),
@@ -34,3 +66,44 @@ impl Default for GomokuState {
         }
     }
 }
+
+impl GomokuState {
+    /// An empty board the size the options ask for.
+    pub fn reset(&mut self) {
+        let size = self.size.value.parse().unwrap_or(SIZE);
+        let rule = Rule::parse(&self.rule.value).unwrap_or(Rule::Freestyle);
+        self.pos = GomokuPosition::new(size, rule);
+        self.startfen = "startpos".to_owned();
+        self.history.clear();
+    }
+
+    #[must_use]
+    pub fn think(
+        &mut self,
+        settings: &GoSettings,
+        info_handler: &impl Fn(
+            &GomokuPosition,
+            Option<i32>,
+            Option<i32>,
+            Option<i32>,
+            Option<Bound>,
+            Option<i32>,
+            Option<u64>,
+            Option<u128>,
+            Option<i32>,
+            &Vec<GomokuMove>,
+            Option<&SearchStats>,
+        ),
+    ) -> Option<GomokuMove> {
+        search::think(
+            &self.search.value,
+            &self.pos,
+            settings,
+            info_handler,
+            &eval,
+            &mut self.prng,
+            &mut self.stats,
+            |stats| primary_with_stats(self.pos.clone(), settings, info_handler, stats),
+        )
+    }
+}
@@ -1,52 +1,45 @@
-use super::root::primary;
+use super::position::{GomokuMove, GomokuPosition};
+use super::root::bench;
 use super::state::GomokuState;
-use crate::search::alphabeta::alphabeta;
-use crate::search::flatmc::flatmc;
-use crate::search::minimax::minimax;
-use crate::search::random::random;
-use games::gamerules::GameRules;
+use crate::bound::Bound;
+use crate::searchstats::SearchStats;
+use games::gamerules::{GameResult, GameRules};
 use games::general::side::Side;
-use games::gomoku::GomokuMove;
 use games::perft;
-use games::{gamerules::GameResult, gomoku::GomokuPosition};
+use protocols::json::ToJson;
+use protocols::output::{self, Bestmove, Info, Message};
 use protocols::ugi::{GoSettings, UGI, UGIGameResult};
 
 pub fn info_handler(
-    _: &GomokuPosition,
+    pos: &GomokuPosition,
     depth: Option<i32>,
     seldepth: Option<i32>,
     score: Option<i32>,
+    bound: Option<Bound>,
     mate: Option<i32>,
     nodes: Option<u64>,
     elapsed: Option<u128>,
     hashfull: Option<i32>,
     pv: &Vec<GomokuMove>,
+    stats: Option<&SearchStats>,
 ) {
-    print!("info");
-    depth.inspect(|d| print!(" depth {}", d));
-    seldepth.inspect(|d| print!(" seldepth {}", d));
-    score.inspect(|s| print!(" score cp {}", s));
-    mate.inspect(|d| print!(" score mate {}", d));
-    nodes.inspect(|n| print!(" nodes {}", n));
-    elapsed.inspect(|ms| print!(" time {}", ms));
-    if let (Some(t), Some(n)) = (elapsed, nodes) {
-        if t > 0 {
-            print!(" nps {}", (n as u128 * 1000) / t);
-        }
-    }
-    hashfull.inspect(|hashfull| print!(" hashfull {}", hashfull));
-    if !pv.is_empty() {
-        print!(" pv");
-        for mv in pv {
-            print!(" {}", as_ugi(mv));
-        }
-    }
-    println!();
+    output::emit(&Info {
+        depth,
+        seldepth,
+        score,
+        bound: bound.map(|b| b.to_string()),
+        mate,
+        nodes,
+        elapsed,
+        hashfull,
+        pv: pv.iter().map(|mv| as_ugi(pos, mv)).collect(),
+        stats: stats.map(ToJson::to_json),
+    });
 }
 
 #[must_use]
-pub fn as_ugi(mv: &GomokuMove) -> String {
-    format!("{}", mv.0).to_owned()
+pub fn as_ugi(pos: &GomokuPosition, mv: &GomokuMove) -> String {
+    pos.square_name(mv.0)
 }
 
 impl UGI for GomokuState {
@@ -65,7 +58,7 @@ impl UGI for GomokuState {
     }
 
     fn uginewgame(&mut self) {
-        self.pos = GomokuPosition::startpos();
+        self.pos.set_fen("startpos");
     }
 
     fn isready(&mut self) {
@@ -81,7 +74,7 @@ impl UGI for GomokuState {
             .pos
             .legal_moves()
             .iter()
-            .find(|mv| as_ugi(mv) == movestr)
+            .find(|mv| as_ugi(&self.pos, mv) == movestr)
             .copied();
 
         if let Some(found) = mv {
@@ -90,24 +83,15 @@ impl UGI for GomokuState {
     }
 
     fn go(&mut self, settings: &GoSettings) {
-        let settings = &protocols::GoSettings::from(settings);
-        let eval = |_pos: &GomokuPosition| -> i32 { 0 };
-
-        let bestmove = match self.search.value.as_str() {
-            "primary" => primary(self.pos.clone(), settings, &info_handler),
-            "random" => random(&self.pos, || self.prng.next()),
-            "minimax" => minimax(self.pos.clone(), settings, &info_handler, &eval),
-            "alphabeta" => alphabeta(self.pos.clone(), settings, &info_handler, &eval),
-            "flatmc" => flatmc(self.pos.clone(), settings, &info_handler, || {
-                self.prng.next()
-            }),
-            _ => panic!("Unknown search type"),
+        let settings = &protocols::GoSettings {
+            debug: self.debug.value,
+            ..protocols::GoSettings::from(settings)
         };
-        if let Some(mv) = bestmove {
-            println!("bestmove {}", as_ugi(&mv));
-        } else {
-            println!("bestmove 0000");
-        }
+        let bestmove = self.think(settings, &info_handler);
+        output::emit(&Bestmove {
+            mv: bestmove.map_or("0000".to_owned(), |mv| as_ugi(&self.pos, &mv)),
+            stats: self.stats.to_json(),
+        });
     }
 
     fn stop(&mut self) {}
@@ -115,21 +99,29 @@ impl UGI for GomokuState {
     fn unrecognised_command(
         &mut self,
         word: &str,
-        _args: &mut std::iter::Peekable<std::str::SplitAsciiWhitespace>,
+        args: &mut std::iter::Peekable<std::str::SplitAsciiWhitespace>,
     ) {
-        if word == "print" {
-            self.print();
+        match word {
+            "print" => self.print(),
+            "bench" => {
+                let depth = args.next().and_then(|word| word.parse::<i32>().ok());
+                println!("{}", bench(depth));
+            }
+            "stats" => output::emit(&Message(self.stats.to_string())),
+            _ => {}
         }
     }
 
     fn print_options(&self) {
-        println!("{}", self.debug);
-        println!("{}", self.search);
+        output::emit(&self.debug);
+        output::emit(&self.size);
+        output::emit(&self.rule);
+        output::emit(&self.search);
     }
 
     fn movelist(&self) {
         for mv in self.pos.legal_moves() {
-            println!("{}", as_ugi(&mv));
+            println!("{}", as_ugi(&self.pos, &mv));
         }
     }
 
@@ -137,6 +129,18 @@ impl UGI for GomokuState {
         match (name, value) {
             ("debug", "true") => self.debug.value = true,
             ("debug", "false") => self.debug.value = false,
+            ("size" | "rule", _) => {
+                let option = if name == "size" {
+                    &mut self.size
+                } else {
+                    &mut self.rule
+                };
+                if !option.options.iter().any(|o| o == value) {
+                    return;
+                }
+                option.value = value.to_owned();
+                self.reset();
+            }
             ("search", _) => self.search.value = value.to_owned(),
             (_, _) => {}
         }
@@ -160,20 +164,8 @@ impl UGI for GomokuState {
     }
 
     fn perft(&mut self, settings: &GoSettings) {
-        let info_handler = |depth: i32, elapsed: f32, nodes: u64| {
-            print!("info");
-            print!(" depth {}", depth);
-            print!(" nodes {}", nodes);
-            print!(" time {}", (elapsed * 1000.0) as u64);
-            if elapsed > 0.0 {
-                let nps = nodes as f32 / elapsed;
-                print!(" nps {}", nps as u64);
-            }
-            println!();
-        };
-        let final_handler = |nodes: u64| {
-            println!("nodes {}", nodes);
-        };
+        let info_handler = output::perft;
+        let final_handler = output::nodes;
         perft::perft(
             &mut self.pos,
             settings.depth.unwrap(),
@@ -183,10 +175,9 @@ impl UGI for GomokuState {
     }
 
     fn split(&mut self, settings: &GoSettings) {
-        let info_handler = |mv: GomokuMove, nodes: u64| println!("{} {}", as_ugi(&mv), nodes);
-        let final_handler = |nodes: u64| {
-            println!("nodes {}", nodes);
-        };
+        let pos = self.pos.clone();
+        let info_handler = |mv: GomokuMove, nodes: u64| output::split(as_ugi(&pos, &mv), nodes);
+        let final_handler = output::nodes;
         perft::split(
             &mut self.pos,
             settings.depth.unwrap(),
@@ -198,6 +189,6 @@ impl UGI for GomokuState {
 
 impl GomokuState {
     fn print(&self) {
-        print!("{}", self.pos);
+        println!("{}", self.pos);
     }
 }
@@ -0,0 +1,59 @@
+/// GTP names columns with the letters A to Z, leaving out I so that it can't be
+/// confused with J.
+const COLUMNS: &[u8] = b"ABCDEFGHJKLMNOPQRSTUVWXYZ";
+
+/// The largest board a vertex can be named on.
+pub const MAX_SIZE: usize = COLUMNS.len();
+
+/// Read a vertex such as `D4` or `q16` into a column and row counting from the
+/// bottom left. `pass` isn't a point and is left to the caller.
+#[must_use]
+pub fn parse_vertex(vertex: &str, width: usize, height: usize) -> Option<(usize, usize)> {
+    let upper = vertex.to_ascii_uppercase();
+    let (column, row) = upper.split_at_checked(1)?;
+    let x = COLUMNS.iter().position(|c| *c == column.as_bytes()[0])?;
+    let y = row.parse::<usize>().ok()?.checked_sub(1)?;
+
+    if x < width && y < height {
+        Some((x, y))
+    } else {
+        None
+    }
+}
+
+#[must_use]
+pub fn vertex_name(x: usize, y: usize) -> String {
+    format!("{}{}", COLUMNS[x] as char, y + 1)
+}
+
+/// How long to think about a move, in milliseconds, given what the controller says
+/// is left on the clock: a share of the main time, or of the byo-yomi period when
+/// there are stones to play in it.
+#[must_use]
+pub fn movetime(time: i32, stones: i32) -> i32 {
+    let moves = if stones > 0 { stones } else { 30 };
+    (900 * time / moves).max(1)
+}
+
+/// The clock from `time_settings` as main time and a byo-yomi period of seconds
+/// and stones, or `None` when there's no time limit. GTP spells that `0 0 0`, or a
+/// byo-yomi period with no stones in it.
+#[must_use]
+pub fn clock(main: i32, byoyomi: i32, stones: i32) -> Option<(i32, Option<(i32, i32)>)> {
+    if (main == 0 && byoyomi == 0 && stones == 0) || (byoyomi > 0 && stones == 0) {
+        None
+    } else {
+        Some((main, (byoyomi > 0).then_some((byoyomi, stones))))
+    }
+}
+
+/// How long to think about a move given what is left on the clock. Once main time
+/// has run out, and until the controller says otherwise, the budget comes from the
+/// byo-yomi period.
+#[must_use]
+pub fn budget(time_left: (i32, i32), byoyomi: Option<(i32, i32)>) -> i32 {
+    match (time_left, byoyomi) {
+        ((0, 0), Some((time, stones))) => movetime(time, stones),
+        ((time, stones), _) => movetime(time, stones),
+    }
+}
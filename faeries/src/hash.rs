@@ -0,0 +1,707 @@
+use crate::connect4::position::{Connect4Move, Connect4Position};
+use crate::droptaxx::position::DroptaxxPosition;
+use crate::gomoku::position::{GomokuMove, GomokuPosition};
+use crate::symmetry::{Symmetric, cells, dimensions};
+use games::gamerules::GameRules;
+use games::general::side::Side;
+use games::general::square::Square;
+use games::pijersi::PijersiMove;
+use games::tak::{Dir, PieceType, TakMove};
+use games::{
+    ataxx::{AtaxxMove, AtaxxPosition},
+    chess::ChessPosition,
+    isolation::IsolationPosition,
+    pijersi::PijersiPosition,
+    tak::TakPosition,
+};
+
+/// What's on a square from the bottom up, each piece the character a FEN writes
+/// it with. Most games never have more than one.
+pub type Stack = Vec<u8>;
+
+/// Splitmix64, so that any key can be had without a table of them.
+#[must_use]
+const fn mix(index: u64) -> u64 {
+    let mut z = index.wrapping_add(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+/// The key for a piece at a height of a square's stack.
+///
+/// Squares past the end of the board hold the rest of a position, like the
+/// castling rights in chess or the pieces in hand in shogi.
+#[must_use]
+pub const fn piece_key(square: usize, height: usize, piece: u8) -> u64 {
+    mix(((square as u64) << 32) | ((height as u64) << 8) | piece as u64)
+}
+
+/// The key for the second player being the one to move.
+pub const TURN_KEY: u64 = mix(u64::MAX);
+
+#[must_use]
+fn stack_key(square: usize, stack: &[u8]) -> u64 {
+    stack.iter().enumerate().fold(0, |key, (height, piece)| {
+        key ^ piece_key(square, height, *piece)
+    })
+}
+
+/// The stacks on every square from the bottom left, a rank at a time, then the
+/// fields.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Board {
+    pub width: usize,
+    pub height: usize,
+    pub stacks: Vec<Stack>,
+}
+
+impl Board {
+    /// Read a position's board and fields from its FEN.
+    #[must_use]
+    pub fn of<G: Zobrist>(pos: &G) -> Self {
+        let fen = pos.get_fen();
+        let (width, height) = dimensions(&fen, G::CELLS).expect("Unreadable FEN");
+        let mut stacks = cells(&fen, G::CELLS)
+            .expect("Unreadable FEN")
+            .into_iter()
+            .map(String::into_bytes)
+            .collect::<Vec<Stack>>();
+
+        let fields = fen.split_ascii_whitespace().collect::<Vec<&str>>();
+        for idx in G::FIELDS {
+            stacks.push(match fields.get(*idx) {
+                None | Some(&"-") => vec![],
+                Some(field) => field.as_bytes().to_vec(),
+            });
+        }
+
+        Self {
+            width: width as usize,
+            height: height as usize,
+            stacks,
+        }
+    }
+
+    /// The square at a file and rank counted from the bottom left, if there is one.
+    #[must_use]
+    pub fn index(&self, file: i32, rank: i32) -> Option<usize> {
+        let on_board =
+            (0..self.width as i32).contains(&file) && (0..self.height as i32).contains(&rank);
+        on_board.then(|| rank as usize * self.width + file as usize)
+    }
+
+    #[must_use]
+    fn key(&self) -> u64 {
+        self.stacks
+            .iter()
+            .enumerate()
+            .fold(0, |key, (square, stack)| key ^ stack_key(square, stack))
+    }
+}
+
+/// The most squares one move changes: an ataxx jump that turns over every stone
+/// around where it lands, and the square it left.
+const MAX_CHANGES: usize = 10;
+
+/// The squares a move changes and what they'll hold.
+///
+/// Once the move is made they hold what was there before instead, to put back
+/// when it's unmade.
+#[derive(Default, Debug)]
+pub struct Changes {
+    len: usize,
+    changes: [(usize, Stack); MAX_CHANGES],
+}
+
+impl Changes {
+    fn push(&mut self, square: usize, stack: Stack) {
+        self.changes[self.len] = (square, stack);
+        self.len += 1;
+    }
+
+    #[must_use]
+    fn as_slice(&self) -> &[(usize, Stack)] {
+        &self.changes[..self.len]
+    }
+
+    #[must_use]
+    fn as_mut_slice(&mut self) -> &mut [(usize, Stack)] {
+        &mut self.changes[..self.len]
+    }
+}
+
+/// Changes to a board, each seeing the ones before it.
+struct Edit<'a> {
+    board: &'a Board,
+    changes: Changes,
+}
+
+impl<'a> Edit<'a> {
+    #[must_use]
+    fn new(board: &'a Board) -> Self {
+        Self {
+            board,
+            changes: Changes::default(),
+        }
+    }
+
+    #[must_use]
+    fn get(&self, square: usize) -> &[u8] {
+        self.changes
+            .as_slice()
+            .iter()
+            .find(|(changed, _)| *changed == square)
+            .map_or(&self.board.stacks[square], |(_, stack)| stack)
+    }
+
+    fn set(&mut self, square: usize, stack: Stack) {
+        match self
+            .changes
+            .as_mut_slice()
+            .iter_mut()
+            .find(|(changed, _)| *changed == square)
+        {
+            Some(change) => change.1 = stack,
+            None => self.changes.push(square, stack),
+        }
+    }
+
+    /// Move everything on a square to another, taking whatever was there.
+    fn shift(&mut self, from: usize, to: usize) {
+        let stack = self.get(from).to_vec();
+        self.set(from, vec![]);
+        self.set(to, stack);
+    }
+
+    /// Move the top piece of a square onto another's.
+    fn climb(&mut self, from: usize, to: usize) {
+        let mut below = self.get(from).to_vec();
+        let piece = below.pop().expect("No piece to move");
+        let mut stack = self.get(to).to_vec();
+        stack.push(piece);
+        self.set(from, below);
+        self.set(to, stack);
+    }
+
+    /// Move the top piece of a square to another, taking whatever was there.
+    fn destack(&mut self, from: usize, to: usize) {
+        let mut below = self.get(from).to_vec();
+        let piece = below.pop().expect("No piece to move");
+        self.set(from, below);
+        self.set(to, vec![piece]);
+    }
+
+    /// The squares that changed and what they hold now.
+    #[must_use]
+    fn finish(mut self) -> Changes {
+        let mut changes = Changes::default();
+        for (square, stack) in &mut self.changes.changes[..self.changes.len] {
+            if self.board.stacks[*square] != *stack {
+                changes.push(*square, std::mem::take(stack));
+            }
+        }
+        changes
+    }
+}
+
+/// The file and rank of a square, counted from 0.
+#[must_use]
+fn coords<const W: usize, const H: usize>(sq: &Square<W, H>) -> (i32, i32) {
+    (0..H as i32)
+        .flat_map(|rank| (0..W as i32).map(move |file| (file, rank)))
+        .find(|&(file, rank)| Square::<W, H>::from_coords(file, rank) == *sq)
+        .expect("Square off the board")
+}
+
+/// The file and rank of a square named like `c3`, counted from 0.
+#[must_use]
+fn named(name: &[u8]) -> (i32, i32) {
+    (i32::from(name[0] - b'a'), i32::from(name[1] - b'1'))
+}
+
+/// The pieces of the side to move and the other side, for games that write them
+/// as `x` and `o`.
+#[must_use]
+fn xo(turn: Side) -> (u8, u8) {
+    match turn {
+        Side::Player1 => (b'x', b'o'),
+        Side::Player2 => (b'o', b'x'),
+    }
+}
+
+/// A game that can be Zobrist hashed.
+///
+/// Every piece has a key for each square and height it can be at, and the hash
+/// of a position is theirs xored together with the key for the side to move.
+/// Making a move only xors out the pieces on the squares it changes and xors in
+/// what they hold afterwards.
+pub trait Zobrist: Symmetric + Sized {
+    /// The FEN fields after the board that are part of the position rather than a
+    /// move count, besides the side to move.
+    const FIELDS: &'static [usize] = &[];
+
+    /// Read the board, from scratch.
+    #[must_use]
+    fn board(&self) -> Board {
+        Board::of(self)
+    }
+
+    /// The squares `mv` changes and what they'll hold, worked out from the move
+    /// and the board before it's made.
+    #[must_use]
+    fn changes(&self, board: &Board, mv: &Self::MoveType) -> Changes;
+}
+
+/// Place a piece of `us` on a file and rank and flip the pieces of `them` around
+/// it, jumping from the first square if there is one.
+#[must_use]
+fn ataxx_changes(board: &Board, turn: Side, from: Option<(i32, i32)>, to: (i32, i32)) -> Changes {
+    let (us, them) = xo(turn);
+    let (file, rank) = to;
+    let mut edit = Edit::new(board);
+
+    if let Some((from_file, from_rank)) = from {
+        edit.set(board.index(from_file, from_rank).expect("Bad move"), vec![]);
+    }
+    edit.set(board.index(file, rank).expect("Bad move"), vec![us]);
+
+    for dy in -1..=1 {
+        for dx in -1..=1 {
+            match board.index(file + dx, rank + dy) {
+                Some(sq) if edit.get(sq) == [them] => edit.set(sq, vec![us]),
+                _ => {}
+            }
+        }
+    }
+
+    edit.finish()
+}
+
+impl Zobrist for AtaxxPosition<7, 7> {
+    fn changes(&self, board: &Board, mv: &AtaxxMove<7, 7>) -> Changes {
+        match mv {
+            AtaxxMove::Pass => Changes::default(),
+            AtaxxMove::Single(to) => ataxx_changes(board, self.get_turn(), None, coords(to)),
+            AtaxxMove::Double(from, to) => {
+                ataxx_changes(board, self.get_turn(), Some(coords(from)), coords(to))
+            }
+        }
+    }
+}
+
+impl Zobrist for DroptaxxPosition {
+    fn changes(&self, board: &Board, mv: &Self::MoveType) -> Changes {
+        ataxx_changes(board, self.get_turn(), None, (mv.x as i32, mv.y as i32))
+    }
+}
+
+/// The en passant square a double pawn push to a file and rank leaves behind,
+/// if a pawn of the other side is next to it to take. Squares nobody could take
+/// on aren't hashed, whatever the FEN says.
+#[must_use]
+fn en_passant(edit: &Edit, file: i32, rank: i32) -> Stack {
+    let (pawn, behind) = match edit.get(edit.board.index(file, rank).expect("Bad square")) {
+        [b'P'] => (b'p', rank - 1),
+        [b'p'] => (b'P', rank + 1),
+        _ => return vec![],
+    };
+
+    let takers = [file - 1, file + 1]
+        .iter()
+        .filter_map(|side| edit.board.index(*side, rank))
+        .any(|sq| edit.get(sq) == [pawn]);
+
+    if takers {
+        format!("{}{}", (b'a' + file as u8) as char, behind + 1).into_bytes()
+    } else {
+        vec![]
+    }
+}
+
+const CASTLING: usize = 64;
+const EN_PASSANT: usize = 65;
+
+impl Zobrist for ChessPosition {
+    // Castling rights, en passant square
+    const FIELDS: &'static [usize] = &[2, 3];
+
+    fn board(&self) -> Board {
+        let mut board = Board::of(self);
+
+        let ep = match board.stacks[EN_PASSANT][..] {
+            // The pawn that moved is a rank further on than the square it passed
+            [file, b'3'] => en_passant(&Edit::new(&board), i32::from(file - b'a'), 3),
+            [file, b'6'] => en_passant(&Edit::new(&board), i32::from(file - b'a'), 4),
+            _ => vec![],
+        };
+        board.stacks[EN_PASSANT] = ep;
+
+        board
+    }
+
+    // The squares moved from and to are only to be had from the notation
+    fn changes(&self, board: &Board, mv: &Self::MoveType) -> Changes {
+        let movestr = self.move_to_string(mv);
+        let (from_file, from_rank) = named(&movestr.as_bytes()[0..2]);
+        let (to_file, to_rank) = named(&movestr.as_bytes()[2..4]);
+        let from = board.index(from_file, from_rank).expect("Bad move");
+        let to = board.index(to_file, to_rank).expect("Bad move");
+
+        let mut edit = Edit::new(board);
+        let piece = *edit.get(from).first().expect("No piece to move");
+        let white = piece.is_ascii_uppercase();
+
+        match piece.to_ascii_lowercase() {
+            // En passant takes a pawn that isn't on the square moved to
+            b'p' if from_file != to_file && edit.get(to).is_empty() => {
+                edit.set(board.index(to_file, from_rank).expect("Bad move"), vec![]);
+            }
+            // Castling moves a rook as well
+            b'k' if (to_file - from_file).abs() == 2 => {
+                let (rook_from, rook_to) = if to_file > from_file { (7, 5) } else { (0, 3) };
+                edit.shift(
+                    board.index(rook_from, from_rank).expect("Bad move"),
+                    board.index(rook_to, from_rank).expect("Bad move"),
+                );
+            }
+            _ => {}
+        }
+
+        let placed = match movestr.as_bytes().get(4) {
+            Some(promo) if white => promo.to_ascii_uppercase(),
+            Some(promo) => promo.to_ascii_lowercase(),
+            None => piece,
+        };
+        edit.set(from, vec![]);
+        edit.set(to, vec![placed]);
+
+        // Anything moving to or from a king or rook's first square ends its rights
+        let lost = [
+            (4, 0, "KQ"),
+            (7, 0, "K"),
+            (0, 0, "Q"),
+            (4, 7, "kq"),
+            (7, 7, "k"),
+            (0, 7, "q"),
+        ]
+        .iter()
+        .filter(|(file, rank, _)| {
+            [(from_file, from_rank), (to_file, to_rank)].contains(&(*file, *rank))
+        })
+        .flat_map(|(_, _, rights)| rights.bytes())
+        .collect::<Vec<u8>>();
+        let castling = edit
+            .get(CASTLING)
+            .iter()
+            .copied()
+            .filter(|right| !lost.contains(right))
+            .collect();
+        edit.set(CASTLING, castling);
+
+        let ep = match piece {
+            b'P' | b'p' if (to_rank - from_rank).abs() == 2 => en_passant(&edit, to_file, to_rank),
+            _ => vec![],
+        };
+        edit.set(EN_PASSANT, ep);
+
+        edit.finish()
+    }
+}
+
+impl Zobrist for Connect4Position {
+    fn changes(&self, board: &Board, mv: &Connect4Move) -> Changes {
+        let (us, _) = xo(self.get_turn());
+        let file = mv.0 as i32;
+        let sq = (0..board.height as i32)
+            .filter_map(|rank| board.index(file, rank))
+            .find(|sq| board.stacks[*sq].is_empty())
+            .expect("Column full");
+        let mut edit = Edit::new(board);
+        edit.set(sq, vec![us]);
+        edit.finish()
+    }
+}
+
+impl Zobrist for GomokuPosition {
+    fn changes(&self, board: &Board, mv: &GomokuMove) -> Changes {
+        let (us, _) = xo(self.get_turn());
+        let (x, y) = self.coords(mv.0);
+        let mut edit = Edit::new(board);
+        edit.set(board.index(x as i32, y as i32).expect("Bad move"), vec![us]);
+        edit.finish()
+    }
+}
+
+impl Zobrist for IsolationPosition {
+    fn changes(&self, board: &Board, mv: &Self::MoveType) -> Changes {
+        let (us, _) = xo(self.get_turn());
+        let (to_file, to_rank) = coords(&mv.to);
+        let (remove_file, remove_rank) = coords(&mv.remove);
+
+        let mut edit = Edit::new(board);
+        let from = board
+            .stacks
+            .iter()
+            .position(|stack| *stack == [us])
+            .expect("No pawn to move");
+        edit.set(from, vec![]);
+        edit.set(board.index(to_file, to_rank).expect("Bad move"), vec![us]);
+        edit.set(
+            board.index(remove_file, remove_rank).expect("Bad move"),
+            vec![b'-'],
+        );
+        edit.finish()
+    }
+}
+
+/// The number of cells in each row of a Pijersi board. It's the same from either end.
+const PIJERSI_ROWS: [usize; 7] = [6, 7, 6, 7, 6, 7, 6];
+
+/// A Pijersi square from the name the engine gives it: the row from `a` at the
+/// bottom, then the cell along it from 1.
+#[must_use]
+fn pijersi_square(name: &str) -> usize {
+    // Read the same way as a file and rank
+    let (row, cell) = named(name.as_bytes());
+    PIJERSI_ROWS[..row as usize].iter().sum::<usize>() + cell as usize
+}
+
+/// Pijersi cells are two characters, the bottom piece then the top one, with `-`
+/// for no piece on top.
+impl Zobrist for PijersiPosition {
+    fn board(&self) -> Board {
+        let fen = self.get_fen();
+        let rows = fen.split_ascii_whitespace().next().expect("Unreadable FEN");
+        let mut stacks = vec![];
+
+        // FEN lists the top row first
+        for row in rows.split('/').rev() {
+            let mut chars = row.bytes();
+            while let Some(c) = chars.next() {
+                if c.is_ascii_digit() {
+                    stacks.extend(std::iter::repeat_n(vec![], (c - b'0') as usize));
+                    continue;
+                }
+                match chars.next() {
+                    Some(b'-') | None => stacks.push(vec![c]),
+                    Some(top) => stacks.push(vec![c, top]),
+                }
+            }
+        }
+
+        Board {
+            width: PIJERSI_ROWS.len(),
+            height: PIJERSI_ROWS.len(),
+            stacks,
+        }
+    }
+
+    fn changes(&self, board: &Board, mv: &PijersiMove) -> Changes {
+        let sq = |name: String| pijersi_square(&name);
+        let mut edit = Edit::new(board);
+
+        match mv {
+            // Whatever was on the square moved to is taken
+            PijersiMove::SoloMove(fr, to) | PijersiMove::StackMove(fr, to) => {
+                edit.shift(sq(fr.to_string_inverted()), sq(to.to_string_inverted()));
+            }
+            PijersiMove::SoloStack(fr, to) | PijersiMove::StackStack(fr, to) => {
+                edit.climb(sq(fr.to_string_inverted()), sq(to.to_string_inverted()));
+            }
+            PijersiMove::StackDestack(fr, to) => {
+                edit.destack(sq(fr.to_string_inverted()), sq(to.to_string_inverted()));
+            }
+            PijersiMove::SoloStackMove(fr, sq1, sq2)
+            | PijersiMove::StackStackMove(fr, sq1, sq2) => {
+                edit.climb(sq(fr.to_string_inverted()), sq(sq1.to_string_inverted()));
+                edit.shift(sq(sq1.to_string_inverted()), sq(sq2.to_string_inverted()));
+            }
+            PijersiMove::StackMoveDestack(fr, sq1, sq2) => {
+                edit.shift(sq(fr.to_string_inverted()), sq(sq1.to_string_inverted()));
+                edit.destack(sq(sq1.to_string_inverted()), sq(sq2.to_string_inverted()));
+            }
+        }
+
+        edit.finish()
+    }
+}
+
+/// A Tak stack's stones, and the standing stone or capstone on top if it isn't flat.
+#[must_use]
+fn tak_top(stack: &[u8]) -> (&[u8], Option<u8>) {
+    match stack.split_last() {
+        Some((top @ (b'S' | b'C'), stones)) => (stones, Some(*top)),
+        _ => (stack, None),
+    }
+}
+
+impl<const SIZE: usize> Zobrist for TakPosition<SIZE> {
+    fn changes(&self, board: &Board, mv: &TakMove<SIZE>) -> Changes {
+        let mut edit = Edit::new(board);
+
+        let (sq, dir, height, coverage, crush) = match mv {
+            // Drops: a flat, a standing stone or a capstone
+            TakMove::Drop(sq, kind) => {
+                let (file, rank) = coords(sq);
+                let (us, them) = match self.get_turn() {
+                    Side::Player1 => (b'1', b'2'),
+                    Side::Player2 => (b'2', b'1'),
+                };
+                // Each side's first stone is the other side's
+                let opening = board
+                    .stacks
+                    .iter()
+                    .filter(|stack| !stack.is_empty())
+                    .take(2)
+                    .count()
+                    < 2;
+                let mut stack = vec![if opening { them } else { us }];
+                match kind {
+                    PieceType::Flat => {}
+                    PieceType::Standing => stack.push(b'S'),
+                    PieceType::Cap => stack.push(b'C'),
+                }
+                edit.set(board.index(file, rank).expect("Bad move"), stack);
+                return edit.finish();
+            }
+            TakMove::Spread(sq, dir, height, coverage, crush) => (sq, dir, height, coverage, crush),
+        };
+
+        // Spreads: how many stones to pick up, the direction, how many to drop on each
+        // square and whether a capstone flattens a standing stone at the end. The
+        // drops are read from the coverage the same way the notation is written
+        let (file, rank) = coords(sq);
+        let from = board.index(file, rank).expect("Bad move");
+        let count = *height as usize;
+        let (dx, dy) = match dir {
+            Dir::Up => (0, 1),
+            Dir::Down => (0, -1),
+            Dir::Left => (-1, 0),
+            Dir::Right => (1, 0),
+        };
+        let mut drops = [0; SIZE];
+        let mut num_drops = 0;
+        let mut head = 0;
+        let mut tail = 0;
+        while ((coverage >> head) & 1) == 0 {
+            head += 1;
+            tail += 1;
+        }
+        while head < *height {
+            head += 1;
+            if ((coverage >> head) & 1) == 1 {
+                drops[num_drops] = (head - tail) as usize;
+                num_drops += 1;
+                tail = head;
+            }
+        }
+        if head > tail {
+            drops[num_drops] = (head - tail) as usize;
+            num_drops += 1;
+        }
+        // A single drop isn't written, and is everything picked up
+        if num_drops < 2 {
+            drops[0] = count;
+            num_drops = 1;
+        }
+        let drops = &drops[..num_drops];
+
+        let (stones, top) = tak_top(edit.get(from));
+        let (left, carried) = stones.split_at(stones.len() - count);
+        let (left, mut carried) = (left.to_vec(), carried.to_vec());
+        edit.set(from, left);
+
+        for (n, drop) in drops.iter().enumerate() {
+            let step = n as i32 + 1;
+            let sq = board
+                .index(file + step * dx, rank + step * dy)
+                .expect("Bad move");
+            let (stones, _) = tak_top(edit.get(sq));
+            let mut stack = stones.to_vec();
+            let landing = carried.drain(..*drop);
+            // Only a crush lands on anything but flats, and it flattens what's there
+            debug_assert!(*crush || tak_top(edit.get(sq)).1.is_none());
+            stack.extend(landing);
+            if n + 1 == drops.len() {
+                stack.extend(top);
+            }
+            edit.set(sq, stack);
+        }
+
+        edit.finish()
+    }
+}
+
+/// Hash a position from scratch.
+#[must_use]
+pub fn hash<G: Zobrist>(pos: &G) -> u64 {
+    let turn = match pos.get_turn() {
+        Side::Player1 => 0,
+        Side::Player2 => TURN_KEY,
+    };
+    pos.board().key() ^ turn
+}
+
+/// A position that keeps its hash up to date as moves are made and unmade.
+///
+/// Making a move only touches the keys of the squares it changes, and unmaking
+/// one puts back what they held.
+pub struct Hashed<G: Zobrist> {
+    pub pos: G,
+    board: Board,
+    hash: u64,
+    /// The hash before each move and what the squares it changed held
+    history: Vec<(u64, Changes)>,
+}
+
+impl<G: Zobrist> Hashed<G> {
+    #[must_use]
+    pub fn new(pos: G) -> Self {
+        let board = pos.board();
+        let hash = hash(&pos);
+
+        Self {
+            pos,
+            board,
+            hash,
+            history: vec![],
+        }
+    }
+
+    #[must_use]
+    pub fn get_hash(&self) -> u64 {
+        self.hash
+    }
+
+    pub fn makemove(&mut self, mv: &G::MoveType) {
+        let mut changes = self.pos.changes(&self.board, mv);
+        let mut hash = self.hash ^ TURN_KEY;
+
+        // Swapping leaves what the squares held in the changes, to undo them with
+        for (square, stack) in changes.as_mut_slice() {
+            hash ^= stack_key(*square, &self.board.stacks[*square]) ^ stack_key(*square, stack);
+            std::mem::swap(&mut self.board.stacks[*square], stack);
+        }
+
+        self.pos.makemove(mv);
+        self.history.push((self.hash, changes));
+        self.hash = hash;
+    }
+
+    pub fn undomove(&mut self, mv: &G::MoveType) {
+        self.pos.undomove(mv);
+
+        let (hash, mut undo) = self.history.pop().expect("No move to undo");
+        for (square, stack) in undo.as_mut_slice() {
+            std::mem::swap(&mut self.board.stacks[*square], stack);
+        }
+        self.hash = hash;
+    }
+
+    /// Whether the current position has come up before in this game.
+    #[must_use]
+    pub fn is_repetition(&self) -> bool {
+        self.history.iter().any(|(hash, _)| *hash == self.hash)
+    }
+}
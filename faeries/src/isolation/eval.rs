@@ -1,5 +1,6 @@
 use games::isolation::IsolationPosition;
 
+/// Every position is level, and the search goes on wins alone.
 #[must_use]
 pub fn eval(_pos: &IsolationPosition) -> i32 {
     0
@@ -1,10 +1,22 @@
+use super::root::primary_with_stats;
+use crate::bound::Bound;
 use crate::prng;
-use games::{gamerules::GameRules, isolation::IsolationPosition};
+use crate::search;
+use crate::searchstats::SearchStats;
+use games::{
+    gamerules::GameRules,
+    isolation::{IsolationMove, IsolationPosition},
+};
+use protocols::GoSettings;
 use protocols::ugi::options::{Check, Combo};
 
 pub struct IsolationState {
     pub pos: IsolationPosition,
     pub prng: prng::XorshiftGenerator,
+    pub startfen: String,
+    pub history: Vec<Option<IsolationMove>>,
+    /// What the last search counted
+    pub stats: SearchStats,
     // Options
     pub debug: Check,
     pub search: Combo,
@@ -15,6 +27,9 @@ impl Default for IsolationState {
         Self {
             pos: IsolationPosition::startpos(),
             prng: prng::XorshiftGenerator::new(0xe50076937a9e5b1c),
+            startfen: "startpos".to_owned(),
+            history: vec![],
+            stats: SearchStats::default(),
             // Options
             debug: Check {
                 name: "debug".to_owned(),
@@ -34,3 +49,37 @@ impl Default for IsolationState {
         }
     }
 }
+
+impl IsolationState {
+    #[must_use]
+    pub fn think(
+        &mut self,
+        settings: &GoSettings,
+        info_handler: &impl Fn(
+            &IsolationPosition,
+            Option<i32>,
+            Option<i32>,
+            Option<i32>,
+            Option<Bound>,
+            Option<i32>,
+            Option<u64>,
+            Option<u128>,
+            Option<i32>,
+            &Vec<IsolationMove>,
+            Option<&SearchStats>,
+        ),
+    ) -> Option<IsolationMove> {
+        let eval = |_pos: &IsolationPosition| -> i32 { 0 };
+
+        search::think(
+            &self.search.value,
+            &self.pos,
+            settings,
+            info_handler,
+            &eval,
+            &mut self.prng,
+            &mut self.stats,
+            |stats| primary_with_stats(self.pos.clone(), settings, info_handler, stats),
+        )
+    }
+}
@@ -1,13 +1,27 @@
+pub mod args;
+pub mod bench;
+pub mod bound;
 pub mod colour;
+pub mod gtp;
+pub mod hash;
 pub mod prng;
+pub mod record;
 pub mod search;
 pub mod searchstats;
+pub mod serve;
+pub mod symmetry;
+pub mod testsuite;
+pub mod tune;
 // Games
 pub mod ataxx;
+pub mod baduk;
+pub mod checkers;
 pub mod chess;
 pub mod connect4;
 pub mod droptaxx;
 pub mod gomoku;
 pub mod isolation;
+pub mod othello;
 pub mod pijersi;
+pub mod shogi;
 pub mod tak;
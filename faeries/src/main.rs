@@ -1,42 +1,70 @@
 mod args;
+mod bench;
+mod bound;
 mod colour;
+mod gtp;
+mod hash;
 mod openings;
 mod prng;
+mod record;
 mod search;
 mod searchstats;
+mod serve;
+mod symmetry;
+mod testsuite;
+mod tune;
 // Games
 mod ataxx;
+mod baduk;
+mod checkers;
 mod chess;
 mod connect4;
 mod droptaxx;
 mod gomoku;
 mod isolation;
+mod othello;
 mod pijersi;
+mod shogi;
 mod tak;
 
-use args::parse_args;
-use games::Games;
+use args::{Game, parse_args};
+use checkers::position::CheckersPosition;
+use connect4::position::Connect4Position;
+use droptaxx::position::DroptaxxPosition;
 use games::chess::ChessPosition;
 use games::{
-    ataxx::AtaxxPosition, connect4::Connect4Position, droptaxx::DroptaxxPosition,
-    gamerules::GameRules, gomoku::GomokuPosition, isolation::IsolationPosition,
-    pijersi::PijersiPosition,
+    ataxx::AtaxxPosition, gamerules::GameRules, isolation::IsolationPosition,
+    pijersi::PijersiPosition, tak::TakPosition,
 };
+use gomoku::position::GomokuPosition;
 use openings::generate;
+use othello::position::OthelloPosition;
+use shogi::position::ShogiPosition;
+use std::net::TcpListener;
 use std::ops::DerefMut;
+use testsuite::parse_epds;
 // Protocols
+use protocols::GoSettings;
+use protocols::gtp::GTP;
 use protocols::manual::Manual;
+use protocols::output::{self, Format};
 use protocols::tei::TEI;
 use protocols::uci::UCI;
 use protocols::ugi::UGI;
+use protocols::usi::USI;
 // State
 use ataxx::state::AtaxxState;
+use ataxx::ugi::UGIAtaxxState;
+use baduk::state::BadukState;
+use checkers::state::CheckersState;
 use chess::state::ChessState;
 use connect4::state::Connect4State;
 use droptaxx::state::DroptaxxState;
 use gomoku::state::GomokuState;
 use isolation::state::IsolationState;
+use othello::state::OthelloState;
 use pijersi::state::PijersiState;
+use shogi::state::ShogiState;
 use tak::manual::ManualTakState;
 use tak::tei::TEITakState;
 use tak::ugi::UGITakState;
@@ -48,73 +76,188 @@ fn print_about() {
     }
     println!("Games supported:");
     println!("- Ataxx");
+    println!("- Baduk");
+    println!("- Checkers");
     println!("- Chess");
     println!("- Connect4");
     println!("- Droptaxx");
     println!("- Gomoku");
     println!("- Isolation");
+    println!("- Othello");
     println!("- Pijersi");
+    println!("- Shogi");
     println!("- Tak");
 }
 
 fn main() -> std::io::Result<()> {
     let args = parse_args()?;
+
+    if args.json {
+        output::set_format(Format::Json);
+    }
+
+    if args.serve {
+        let listener = TcpListener::bind(("127.0.0.1", args.port))?;
+        println!("Listening on {}", listener.local_addr()?);
+        return serve::serve(listener);
+    }
+
+    if args.bench {
+        let depth = (args.depth > 0).then_some(args.depth);
+        let game = args
+            .game
+            .ok_or_else(|| std::io::Error::other("bench needs a --game"))?;
+        let result = match game {
+            Game::Ataxx => ataxx::root::bench(depth),
+            Game::Checkers => checkers::root::bench(depth),
+            Game::Chess => chess::root::bench(depth),
+            Game::Connect4 => connect4::root::bench(depth),
+            Game::Droptaxx => droptaxx::root::bench(depth),
+            Game::Gomoku => gomoku::root::bench(depth),
+            Game::Isolation => isolation::root::bench(depth),
+            Game::Othello => othello::root::bench(depth),
+            Game::Pijersi => pijersi::root::bench(depth),
+            Game::Shogi => shogi::root::bench(depth),
+            Game::Tak => tak::root::bench(depth),
+            Game::Baduk => {
+                return Err(std::io::Error::other(format!(
+                    "{:?} has no fixed depth search to bench",
+                    game
+                )));
+            }
+        };
+        println!("{}", result);
+        return Ok(());
+    }
+
+    if args.testsuite {
+        let game = args
+            .game
+            .ok_or_else(|| std::io::Error::other("testsuite needs a --game"))?;
+        let path = args
+            .file
+            .as_deref()
+            .ok_or_else(|| std::io::Error::other("testsuite needs an EPD --file"))?;
+        let epds = parse_epds(&std::fs::read_to_string(path)?);
+        let settings = if args.movetime > 0 {
+            GoSettings::from_movetime(args.movetime)
+        } else if args.nodes > 0 {
+            GoSettings::from_nodes(args.nodes)
+        } else if args.depth > 0 {
+            GoSettings::from_depth(args.depth)
+        } else {
+            GoSettings::from_movetime(1000)
+        };
+        let report = match game {
+            Game::Ataxx => ataxx::root::testsuite(&epds, &settings),
+            Game::Checkers => checkers::root::testsuite(&epds, &settings),
+            Game::Chess => chess::root::testsuite(&epds, &settings),
+            Game::Connect4 => connect4::root::testsuite(&epds, &settings),
+            Game::Droptaxx => droptaxx::root::testsuite(&epds, &settings),
+            Game::Gomoku => gomoku::root::testsuite(&epds, &settings),
+            Game::Isolation => isolation::root::testsuite(&epds, &settings),
+            Game::Othello => othello::root::testsuite(&epds, &settings),
+            Game::Pijersi => pijersi::root::testsuite(&epds, &settings),
+            Game::Shogi => shogi::root::testsuite(&epds, &settings),
+            Game::Tak => tak::root::testsuite(&epds, &settings),
+            Game::Baduk => {
+                return Err(std::io::Error::other(format!(
+                    "{:?} has no EPD test suites",
+                    game
+                )));
+            }
+        };
+        println!("{}", report);
+        return Ok(());
+    }
+
     let mut input = String::new();
 
     std::io::stdin().read_line(&mut input)?;
     match input.as_str().trim_end() {
         "ugi" => {
             let mut state: Box<dyn UGI> = match args.game.unwrap() {
-                Games::Ataxx => Box::new(AtaxxState::default()),
-                Games::Chess => Box::new(ChessState::default()),
-                Games::Connect4 => Box::new(Connect4State::default()),
-                Games::Droptaxx => Box::new(DroptaxxState::default()),
-                Games::Gomoku => Box::new(GomokuState::default()),
-                Games::Isolation => Box::new(IsolationState::default()),
-                Games::Pijersi => Box::new(PijersiState::default()),
-                Games::Tak => Box::new(UGITakState::default()),
+                Game::Ataxx => Box::new(UGIAtaxxState::default()),
+                Game::Checkers => Box::new(CheckersState::default()),
+                Game::Chess => Box::new(ChessState::default()),
+                Game::Connect4 => Box::new(Connect4State::default()),
+                Game::Droptaxx => Box::new(DroptaxxState::default()),
+                Game::Gomoku => Box::new(GomokuState::default()),
+                Game::Isolation => Box::new(IsolationState::default()),
+                Game::Othello => Box::new(OthelloState::default()),
+                Game::Pijersi => Box::new(PijersiState::default()),
+                Game::Tak => Box::new(UGITakState::default()),
+                Game::Baduk => panic!("Baduk is only played over GTP"),
+                Game::Shogi => panic!("Shogi is only played over USI"),
             };
             let _ = protocols::ugi::listen::listen(state.deref_mut());
         }
         "uci" => {
             let mut state: Box<dyn UCI> = match args.game.unwrap() {
-                Games::Chess => Box::new(ChessState::default()),
+                Game::Chess => Box::new(ChessState::default()),
                 _ => panic!("Game must be chess"),
             };
             let get_input = |input: &mut String| std::io::stdin().read_line(input);
             let _ = protocols::uci::listen::listen(state.deref_mut(), get_input);
         }
+        "usi" => {
+            let mut state: Box<dyn USI> = match args.game.unwrap() {
+                Game::Shogi => Box::new(ShogiState::default()),
+                _ => panic!("Game must be shogi"),
+            };
+            let get_input = |input: &mut String| std::io::stdin().read_line(input);
+            let _ = protocols::usi::listen::listen(state.deref_mut(), get_input);
+        }
         "tei" => {
             let mut state: Box<dyn TEI> = match args.game.unwrap() {
-                Games::Tak => Box::new(TEITakState::default()),
+                Game::Tak => Box::new(TEITakState::default()),
                 _ => panic!("Game must be tak"),
             };
             let get_input = |input: &mut String| std::io::stdin().read_line(input);
             let _ = protocols::tei::listen::listen(state.deref_mut(), get_input);
         }
+        "gtp" => {
+            let mut state: Box<dyn GTP> = match args.game.unwrap() {
+                Game::Baduk => Box::new(BadukState::default()),
+                Game::Gomoku => Box::new(GomokuState::default()),
+                _ => panic!("Game must be baduk or gomoku"),
+            };
+            let get_input = |input: &mut String| std::io::stdin().read_line(input);
+            let _ = protocols::gtp::listen::listen(state.deref_mut(), get_input);
+        }
         "manual" => {
             let mut state: Box<dyn Manual> = match args.game.unwrap() {
-                Games::Ataxx => Box::new(AtaxxState::default()),
-                Games::Chess => Box::new(ChessState::default()),
-                Games::Connect4 => Box::new(Connect4State::default()),
-                Games::Droptaxx => Box::new(DroptaxxState::default()),
-                Games::Gomoku => Box::new(GomokuState::default()),
-                Games::Isolation => Box::new(IsolationState::default()),
-                Games::Pijersi => Box::new(PijersiState::default()),
-                Games::Tak => Box::new(ManualTakState::default()),
+                Game::Ataxx => Box::new(AtaxxState::default()),
+                Game::Checkers => Box::new(CheckersState::default()),
+                Game::Chess => Box::new(ChessState::default()),
+                Game::Connect4 => Box::new(Connect4State::default()),
+                Game::Droptaxx => Box::new(DroptaxxState::default()),
+                Game::Gomoku => Box::new(GomokuState::default()),
+                Game::Isolation => Box::new(IsolationState::default()),
+                Game::Othello => Box::new(OthelloState::default()),
+                Game::Pijersi => Box::new(PijersiState::default()),
+                Game::Tak => Box::new(ManualTakState::default()),
+                Game::Baduk => panic!("Baduk is only played over GTP"),
+                Game::Shogi => panic!("Shogi is only played over USI"),
             };
             protocols::manual::listen::listen(state.deref_mut())?
         }
         "openings" => {
             match args.game.unwrap() {
-                Games::Ataxx => generate(&mut AtaxxPosition::<7, 7>::startpos(), args.depth),
-                Games::Chess => generate(&mut ChessPosition::startpos(), args.depth),
-                Games::Connect4 => generate(&mut Connect4Position::startpos(), args.depth),
-                Games::Droptaxx => generate(&mut DroptaxxPosition::startpos(), args.depth),
-                Games::Gomoku => generate(&mut GomokuPosition::startpos(), args.depth),
-                Games::Isolation => generate(&mut IsolationPosition::startpos(), args.depth),
-                Games::Pijersi => generate(&mut PijersiPosition::startpos(), args.depth),
-                Games::Tak => todo!(),
+                Game::Ataxx => generate(&mut AtaxxPosition::<7, 7>::startpos(), args.depth),
+                Game::Checkers => generate(&mut CheckersPosition::startpos(), args.depth),
+                Game::Chess => generate(&mut ChessPosition::startpos(), args.depth),
+                Game::Connect4 => generate(&mut Connect4Position::startpos(), args.depth),
+                Game::Droptaxx => generate(&mut DroptaxxPosition::startpos(), args.depth),
+                Game::Gomoku => generate(&mut GomokuPosition::startpos(), args.depth),
+                Game::Isolation => generate(&mut IsolationPosition::startpos(), args.depth),
+                Game::Pijersi => generate(&mut PijersiPosition::startpos(), args.depth),
+                Game::Othello => generate(&mut OthelloPosition::startpos(), args.depth),
+                Game::Tak => generate(&mut TakPosition::<6>::startpos(), args.depth),
+                Game::Baduk => {
+                    return Err(std::io::Error::other("Baduk has no openings to generate"));
+                }
+                Game::Shogi => generate(&mut ShogiPosition::startpos(), args.depth),
             };
         }
         "about" => print_about(),
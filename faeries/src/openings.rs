@@ -1,13 +1,24 @@
-use games::gamerules::GameRules;
+use crate::symmetry::{Symmetric, canonical_fen};
+use std::collections::HashSet;
 
-pub fn generate(pos: &mut impl GameRules, depth: i32) {
+fn generate_impl<G: Symmetric>(pos: &mut G, depth: i32, seen: &mut HashSet<String>) {
     if depth == 0 {
-        println!("{}", pos.get_fen());
+        let (fen, _) = canonical_fen::<G>(&pos.get_fen());
+        if seen.insert(fen.clone()) {
+            println!("{}", fen);
+        }
     } else {
         for mv in pos.legal_moves() {
             pos.makemove(&mv);
-            generate(pos, depth - 1);
+            generate_impl(pos, depth - 1, seen);
             pos.undomove(&mv);
         }
     }
 }
+
+/// Print every position `depth` moves from `pos`, once each. Positions that are
+/// the same up to symmetry are printed once, in their canonical form.
+pub fn generate<G: Symmetric>(pos: &mut G, depth: i32) {
+    let mut seen = HashSet::new();
+    generate_impl(pos, depth, &mut seen);
+}
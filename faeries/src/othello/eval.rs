@@ -0,0 +1,65 @@
+use super::position::{CORNERS, OthelloPosition, moves_bb};
+use crate::tune::{Tunable, difference, dot};
+use games::gamerules::GameRules;
+use games::general::side::Side;
+
+pub const NAMES: [&str; 4] = ["disc", "mobility", "corner", "corner_neighbour"];
+pub const WEIGHTS: [i32; 4] = [1, 10, 100, -40];
+
+/// The squares next to an empty corner, which hand it to the other side.
+#[must_use]
+fn corner_neighbours(empty: u64) -> u64 {
+    let mut bb = 0;
+    if empty & (1 << 0) != 0 {
+        bb |= 0x0000_0000_0000_0302;
+    }
+    if empty & (1 << 7) != 0 {
+        bb |= 0x0000_0000_0000_c040;
+    }
+    if empty & (1 << 56) != 0 {
+        bb |= 0x0203_0000_0000_0000;
+    }
+    if empty & (1 << 63) != 0 {
+        bb |= 0x40c0_0000_0000_0000;
+    }
+    bb
+}
+
+#[must_use]
+fn features_side(us: u64, them: u64) -> [i32; 4] {
+    let empty = !(us | them);
+
+    // Discs matter less than where they are until the end
+    let discs = us.count_ones() as i32;
+    let mobility = moves_bb(us, them).count_ones() as i32;
+    let corners = (us & CORNERS).count_ones() as i32;
+    let danger = (us & corner_neighbours(empty & CORNERS)).count_ones() as i32;
+
+    [discs, mobility, corners, danger]
+}
+
+#[must_use]
+pub fn features(pos: &OthelloPosition) -> [i32; 4] {
+    difference(
+        features_side(pos.get_us(), pos.get_them()),
+        features_side(pos.get_them(), pos.get_us()),
+    )
+}
+
+#[must_use]
+pub fn eval(pos: &OthelloPosition) -> i32 {
+    dot(&WEIGHTS, &features(pos))
+}
+
+impl Tunable for OthelloPosition {
+    const NAMES: &'static [&'static str] = &NAMES;
+    const WEIGHTS: &'static [i32] = &WEIGHTS;
+
+    fn features(&self) -> Vec<i32> {
+        features(self).to_vec()
+    }
+
+    fn first_to_move(&self) -> bool {
+        self.get_turn() == Side::Player1
+    }
+}
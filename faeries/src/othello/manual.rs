@@ -0,0 +1,162 @@
+use super::position::{OthelloMove, OthelloPosition};
+use super::{
+    state::OthelloState,
+    ugi::{as_ugi, parse_move},
+};
+use crate::bound::Bound;
+use crate::record::{Format, Record};
+use crate::searchstats::SearchStats;
+use games::{
+    gamerules::{GameResult, GameRules},
+    general::side::Side,
+};
+use protocols::json::ToJson;
+use protocols::output::{self, Info};
+use protocols::{
+    GoSettings,
+    manual::{Manual, ManualGameResult},
+};
+
+pub fn info_handler(
+    _: &OthelloPosition,
+    depth: Option<i32>,
+    seldepth: Option<i32>,
+    score: Option<i32>,
+    bound: Option<Bound>,
+    mate: Option<i32>,
+    nodes: Option<u64>,
+    elapsed: Option<u128>,
+    hashfull: Option<i32>,
+    pv: &Vec<OthelloMove>,
+    stats: Option<&SearchStats>,
+) {
+    output::emit(&Info {
+        depth,
+        seldepth,
+        score,
+        bound: bound.map(|b| b.to_string()),
+        mate,
+        nodes,
+        elapsed,
+        hashfull,
+        pv: pv.iter().map(as_ugi).collect(),
+        stats: stats.map(ToJson::to_json),
+    });
+}
+
+pub fn empty_handler(
+    _: &OthelloPosition,
+    _: Option<i32>,
+    _: Option<i32>,
+    _: Option<i32>,
+    _: Option<Bound>,
+    _: Option<i32>,
+    _: Option<u64>,
+    _: Option<u128>,
+    _: Option<i32>,
+    _: &Vec<OthelloMove>,
+    _: Option<&SearchStats>,
+) {
+}
+
+impl Manual for OthelloState {
+    fn print(&self) {
+        println!("{}", self.pos);
+    }
+
+    fn pprint(&self) {
+        OthelloState::pprint(self);
+    }
+
+    fn is_gameover(&self) -> bool {
+        self.pos.is_gameover()
+    }
+
+    fn is_p1turn(&self) -> bool {
+        self.pos.get_turn() == Side::Player1
+    }
+
+    fn makemove(&mut self, movestr: &str) -> bool {
+        if let Some(mv) = parse_move(&self.pos, movestr) {
+            self.pos.makemove(&mv);
+            self.history.push(mv);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Passing is a move like any other here, so it can be taken back the same way.
+    fn makenull(&mut self) {
+        self.pos.makenull();
+        self.history.push(OthelloMove::Pass);
+    }
+
+    fn undo(&mut self) -> bool {
+        match self.history.pop() {
+            Some(mv) => self.pos.undomove(&mv),
+            None => return false,
+        }
+        true
+    }
+
+    fn newgame(&mut self) {
+        self.set_fen("startpos");
+    }
+
+    fn set_fen(&mut self, fen: &str) {
+        self.pos.set_fen(fen);
+        self.startfen = fen.to_owned();
+        self.history.clear();
+    }
+
+    fn set_search(&mut self, name: &str) -> bool {
+        if self.search.options.iter().any(|option| option == name) {
+            self.search.value = name.to_owned();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn history(&self) -> Vec<String> {
+        self.history.iter().map(as_ugi).collect()
+    }
+
+    fn play(&mut self, settings: &GoSettings) {
+        if let Some(mv) = self.think(settings, &empty_handler) {
+            self.pos.makemove(&mv);
+            self.history.push(mv);
+        }
+    }
+
+    fn hint(&mut self, settings: &GoSettings) {
+        let _ = self.think(settings, &info_handler);
+    }
+
+    fn record(&self, p1: &str, p2: &str) -> String {
+        let start = OthelloPosition::from_fen(&self.startfen);
+        let startfen =
+            (start.get_fen() != OthelloPosition::startpos().get_fen()).then(|| start.get_fen());
+
+        let mut record = Record::custom(
+            "othello",
+            Format::Generic,
+            startfen,
+            start.get_turn() == Side::Player1,
+        );
+        record.moves = self.history();
+        record.result = self.pos.get_result();
+        record.set_players(p1, p2);
+        record.to_string()
+    }
+
+    fn get_result(&self) -> Option<ManualGameResult> {
+        match self.pos.get_result() {
+            Some(GameResult::Win(Side::Player1)) => Some(ManualGameResult::P1Win),
+            Some(GameResult::Win(Side::Player2)) => Some(ManualGameResult::P2Win),
+            Some(GameResult::Draw) => Some(ManualGameResult::Draw),
+            None => None,
+        }
+    }
+}
@@ -0,0 +1,9 @@
+pub mod eval;
+pub mod manual;
+pub mod perft;
+pub mod position;
+pub mod root;
+pub mod search;
+pub mod solver;
+pub mod state;
+pub mod ugi;
@@ -0,0 +1,41 @@
+use super::position::{OthelloMove, OthelloPosition};
+use games::gamerules::GameRules;
+
+/// Count the leaf nodes `depth` moves deep. Passes count as moves.
+#[must_use]
+pub fn perft(pos: &mut OthelloPosition, depth: i32) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+
+    let moves = pos.legal_moves();
+    if depth == 1 {
+        return moves.len() as u64;
+    }
+
+    let mut nodes = 0;
+    for mv in moves {
+        pos.makemove(&mv);
+        nodes += perft(pos, depth - 1);
+        pos.undomove(&mv);
+    }
+    nodes
+}
+
+/// Perft for each legal move on its own.
+#[must_use]
+pub fn split(pos: &mut OthelloPosition, depth: i32) -> Vec<(OthelloMove, u64)> {
+    if depth == 0 {
+        return vec![];
+    }
+
+    pos.legal_moves()
+        .into_iter()
+        .map(|mv| {
+            pos.makemove(&mv);
+            let nodes = perft(pos, depth - 1);
+            pos.undomove(&mv);
+            (mv, nodes)
+        })
+        .collect()
+}
@@ -0,0 +1,386 @@
+use games::{
+    gamerules::{GameResult, GameRules},
+    general::side::Side,
+};
+use std::fmt;
+
+pub const STARTPOS_FEN: &str = "8/8/8/3xo3/3ox3/8/8/8 x";
+
+const NOT_FILE_A: u64 = 0xfefe_fefe_fefe_fefe;
+const NOT_FILE_H: u64 = 0x7f7f_7f7f_7f7f_7f7f;
+pub const CORNERS: u64 = 0x8100_0000_0000_0081;
+
+/// Squares are numbered a1 = 0 to h8 = 63.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum OthelloMove {
+    Place(u8),
+    Pass,
+}
+
+#[must_use]
+fn shift(bb: u64, dir: usize) -> u64 {
+    match dir {
+        0 => bb << 8,
+        1 => bb >> 8,
+        2 => (bb << 1) & NOT_FILE_A,
+        3 => (bb >> 1) & NOT_FILE_H,
+        4 => (bb << 9) & NOT_FILE_A,
+        5 => (bb << 7) & NOT_FILE_H,
+        6 => (bb >> 7) & NOT_FILE_A,
+        7 => (bb >> 9) & NOT_FILE_H,
+        _ => unreachable!(),
+    }
+}
+
+/// Every empty square where `us` can play, outflanking at least one of `them`.
+#[must_use]
+pub fn moves_bb(us: u64, them: u64) -> u64 {
+    let empty = !(us | them);
+    let mut moves = 0;
+
+    for dir in 0..8 {
+        let mut line = shift(us, dir) & them;
+        for _ in 0..5 {
+            line |= shift(line, dir) & them;
+        }
+        moves |= shift(line, dir) & empty;
+    }
+
+    moves
+}
+
+/// The discs of `them` turned over by `us` playing on `sq`.
+#[must_use]
+pub fn flips_bb(us: u64, them: u64, sq: u8) -> u64 {
+    let mut flips = 0;
+
+    for dir in 0..8 {
+        let mut line = 0;
+        let mut bb = shift(1 << sq, dir);
+        while bb & them != 0 {
+            line |= bb;
+            bb = shift(bb, dir);
+        }
+        if bb & us != 0 {
+            flips |= line;
+        }
+    }
+
+    flips
+}
+
+/// Othello on the usual 8x8 board. Black moves first.
+///
+/// A side with nowhere to play has to pass, and the game is over once neither side
+/// can play.
+#[derive(Clone, Debug)]
+pub struct OthelloPosition {
+    black: u64,
+    white: u64,
+    turn: Side,
+    /// The discs each move turned over, so that it can be taken back
+    undo: Vec<u64>,
+}
+
+impl OthelloPosition {
+    #[must_use]
+    pub fn get_black(&self) -> u64 {
+        self.black
+    }
+
+    #[must_use]
+    pub fn get_white(&self) -> u64 {
+        self.white
+    }
+
+    #[must_use]
+    pub fn get_us(&self) -> u64 {
+        match self.turn {
+            Side::Player1 => self.black,
+            Side::Player2 => self.white,
+        }
+    }
+
+    #[must_use]
+    pub fn get_them(&self) -> u64 {
+        match self.turn {
+            Side::Player1 => self.white,
+            Side::Player2 => self.black,
+        }
+    }
+
+    #[must_use]
+    pub fn get_empty(&self) -> u64 {
+        !(self.black | self.white)
+    }
+
+    #[must_use]
+    pub fn get_ply(&self) -> usize {
+        self.undo.len()
+    }
+
+    #[must_use]
+    pub fn is_legal(&self, mv: &OthelloMove) -> bool {
+        self.legal_moves().contains(mv)
+    }
+
+    /// The final disc count for the side to move less the other side's, with any
+    /// empty squares going to whoever has more discs.
+    #[must_use]
+    pub fn final_score(&self) -> i32 {
+        final_score(self.get_us(), self.get_them())
+    }
+}
+
+impl GameRules for OthelloPosition {
+    type MoveType = OthelloMove;
+
+    fn startpos() -> Self {
+        Self::from_fen(STARTPOS_FEN)
+    }
+
+    /// Read a position written as ranks 8 to 1, `x` for Black and `o` for White,
+    /// then the side to move.
+    fn from_fen(fen: &str) -> Self {
+        let mut pos = Self {
+            black: 0,
+            white: 0,
+            turn: Side::Player1,
+            undo: vec![],
+        };
+        pos.set_fen(fen);
+        pos
+    }
+
+    fn set_fen(&mut self, fen: &str) {
+        let fen = if fen == "startpos" { STARTPOS_FEN } else { fen };
+        let mut parts = fen.split_ascii_whitespace();
+
+        self.black = 0;
+        self.white = 0;
+        self.undo.clear();
+
+        let mut x = 0;
+        let mut y = 7;
+        for c in parts.next().unwrap_or("").chars() {
+            match c {
+                'x' | 'X' | 'b' | 'B' if x < 8 => {
+                    self.black |= 1 << (8 * y + x);
+                    x += 1;
+                }
+                'o' | 'O' | 'w' | 'W' if x < 8 => {
+                    self.white |= 1 << (8 * y + x);
+                    x += 1;
+                }
+                '1'..='8' => x += c as i32 - '0' as i32,
+                '/' if y > 0 => {
+                    x = 0;
+                    y -= 1;
+                }
+                _ => {}
+            }
+        }
+
+        self.turn = match parts.next() {
+            Some("o") | Some("O") | Some("w") | Some("W") => Side::Player2,
+            _ => Side::Player1,
+        };
+    }
+
+    fn get_fen(&self) -> String {
+        let mut fen = String::new();
+
+        for y in (0..8).rev() {
+            let mut empty = 0;
+            for x in 0..8 {
+                let bb = 1 << (8 * y + x);
+                let c = if self.black & bb != 0 {
+                    'x'
+                } else if self.white & bb != 0 {
+                    'o'
+                } else {
+                    empty += 1;
+                    continue;
+                };
+                if empty > 0 {
+                    fen.push_str(&empty.to_string());
+                    empty = 0;
+                }
+                fen.push(c);
+            }
+            if empty > 0 {
+                fen.push_str(&empty.to_string());
+            }
+            if y > 0 {
+                fen.push('/');
+            }
+        }
+
+        fen.push_str(match self.turn {
+            Side::Player1 => " x",
+            Side::Player2 => " o",
+        });
+        fen
+    }
+
+    fn get_turn(&self) -> Side {
+        self.turn
+    }
+
+    fn legal_moves(&self) -> Vec<OthelloMove> {
+        let mut bb = moves_bb(self.get_us(), self.get_them());
+        if bb == 0 {
+            return if self.is_gameover() {
+                vec![]
+            } else {
+                vec![OthelloMove::Pass]
+            };
+        }
+
+        let mut moves = Vec::with_capacity(bb.count_ones() as usize);
+        while bb != 0 {
+            moves.push(OthelloMove::Place(bb.trailing_zeros() as u8));
+            bb &= bb - 1;
+        }
+        moves
+    }
+
+    fn makemove(&mut self, mv: &OthelloMove) {
+        let flips = match mv {
+            OthelloMove::Place(sq) => {
+                let flips = flips_bb(self.get_us(), self.get_them(), *sq);
+                debug_assert!(flips != 0);
+                let placed = flips | (1 << sq);
+                match self.turn {
+                    Side::Player1 => {
+                        self.black |= placed;
+                        self.white ^= flips;
+                    }
+                    Side::Player2 => {
+                        self.white |= placed;
+                        self.black ^= flips;
+                    }
+                }
+                flips
+            }
+            OthelloMove::Pass => 0,
+        };
+
+        self.undo.push(flips);
+        self.turn = match self.turn {
+            Side::Player1 => Side::Player2,
+            Side::Player2 => Side::Player1,
+        };
+    }
+
+    /// Hand the move to the other side, whether or not there was a move to play.
+    fn makenull(&mut self) {
+        self.makemove(&OthelloMove::Pass);
+    }
+
+    fn undomove(&mut self, mv: &OthelloMove) {
+        let flips = self.undo.pop().expect("No move to undo");
+
+        self.turn = match self.turn {
+            Side::Player1 => Side::Player2,
+            Side::Player2 => Side::Player1,
+        };
+
+        if let OthelloMove::Place(sq) = mv {
+            let placed = flips | (1 << sq);
+            match self.turn {
+                Side::Player1 => {
+                    self.black ^= placed;
+                    self.white |= flips;
+                }
+                Side::Player2 => {
+                    self.white ^= placed;
+                    self.black |= flips;
+                }
+            }
+        }
+    }
+
+    fn is_gameover(&self) -> bool {
+        moves_bb(self.black, self.white) == 0 && moves_bb(self.white, self.black) == 0
+    }
+
+    fn get_result(&self) -> Option<GameResult> {
+        if !self.is_gameover() {
+            return None;
+        }
+
+        let black = self.black.count_ones();
+        let white = self.white.count_ones();
+        if black > white {
+            Some(GameResult::Win(Side::Player1))
+        } else if white > black {
+            Some(GameResult::Win(Side::Player2))
+        } else {
+            Some(GameResult::Draw)
+        }
+    }
+}
+
+#[must_use]
+pub fn final_score(us: u64, them: u64) -> i32 {
+    let ours = us.count_ones() as i32;
+    let theirs = them.count_ones() as i32;
+    let empty = 64 - ours - theirs;
+
+    if ours > theirs {
+        ours - theirs + empty
+    } else if theirs > ours {
+        ours - theirs - empty
+    } else {
+        0
+    }
+}
+
+#[must_use]
+pub fn square_name(sq: u8) -> String {
+    format!("{}{}", (b'a' + sq % 8) as char, sq / 8 + 1)
+}
+
+#[must_use]
+pub fn parse_square(name: &str) -> Option<u8> {
+    let bytes = name.as_bytes();
+    if bytes.len() != 2 || !(b'a'..=b'h').contains(&bytes[0]) || !(b'1'..=b'8').contains(&bytes[1])
+    {
+        return None;
+    }
+    Some(8 * (bytes[1] - b'1') + bytes[0] - b'a')
+}
+
+impl fmt::Display for OthelloPosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let moves = moves_bb(self.get_us(), self.get_them());
+
+        for y in (0..8).rev() {
+            write!(f, "{} ", y + 1)?;
+            for x in 0..8 {
+                let bb = 1 << (8 * y + x);
+                let c = if self.black & bb != 0 {
+                    'x'
+                } else if self.white & bb != 0 {
+                    'o'
+                } else if moves & bb != 0 {
+                    '*'
+                } else {
+                    '.'
+                };
+                write!(f, "{}", c)?;
+            }
+            writeln!(f)?;
+        }
+        writeln!(f, "  abcdefgh")?;
+        write!(
+            f,
+            "Turn: {}",
+            match self.turn {
+                Side::Player1 => 'x',
+                Side::Player2 => 'o',
+            }
+        )
+    }
+}
@@ -0,0 +1,263 @@
+use super::position::{OthelloMove, OthelloPosition};
+use super::search::{INF_SCORE, negamax};
+use super::solver::{SOLVE_EMPTIES, solve_root};
+use super::ugi::as_ugi;
+use crate::bench::{Bench, quiet};
+use crate::bound::Bound;
+use crate::searchstats::SearchStats;
+use crate::testsuite::{Epd, Report};
+use games::gamerules::GameRules;
+use games::general::side::Side;
+use protocols::GoSettings;
+use protocols::output::{self, Message};
+use std::{
+    cmp::{max, min},
+    time::Instant,
+};
+
+const MAX_DEPTH: i32 = 128;
+
+/// How far either side of the last iteration's score to look at first.
+const ASPIRATION_WINDOW: i32 = 50;
+
+/// Shallower searches are cheap enough to do with a full window.
+const ASPIRATION_DEPTH: i32 = 4;
+
+/// The positions `bench` searches, and how deep.
+pub const BENCH_FENS: [&str; 6] = [
+    "8/8/8/3xo3/3ox3/8/8/8 x",
+    "8/8/8/2oxxx2/xxoxx3/2o5/1x1o4/8 x",
+    "8/1x6/2xoox2/1xooo3/1xxooxx1/2o1ox2/8/8 x",
+    "1o1xo3/1xoo1x2/2oox3/1o1oxx2/3xoxx1/2xx1o2/2xx4/3x4 x",
+    "4o3/ooooo3/ooox1xx1/xooxxx2/1xooxxx1/2xxox2/2x1x3/8 x",
+    "3ox2x/1o1xx1x1/1oxoxo1x/oooxxoxo/xxxoxx1o/1xxxo2o/2o1xo2/8 x",
+];
+pub const BENCH_DEPTH: i32 = 9;
+
+#[must_use]
+pub fn primary(
+    pos: OthelloPosition,
+    settings: &GoSettings,
+    info_handler: &impl Fn(
+        &OthelloPosition,
+        Option<i32>,
+        Option<i32>,
+        Option<i32>,
+        Option<Bound>,
+        Option<i32>,
+        Option<u64>,
+        Option<u128>,
+        Option<i32>,
+        &Vec<OthelloMove>,
+        Option<&SearchStats>,
+    ),
+) -> Option<OthelloMove> {
+    primary_with_stats(pos, settings, info_handler, &mut SearchStats::default())
+}
+
+/// The same search, leaving what it counted in `stats` to look at afterwards.
+/// In debug mode they're shown after every iteration as well.
+#[must_use]
+pub fn primary_with_stats(
+    mut pos: OthelloPosition,
+    settings: &GoSettings,
+    info_handler: &impl Fn(
+        &OthelloPosition,
+        Option<i32>,
+        Option<i32>,
+        Option<i32>,
+        Option<Bound>,
+        Option<i32>,
+        Option<u64>,
+        Option<u128>,
+        Option<i32>,
+        &Vec<OthelloMove>,
+        Option<&SearchStats>,
+    ),
+    stats: &mut SearchStats,
+) -> Option<OthelloMove> {
+    *stats = SearchStats::default();
+    if pos.is_gameover() {
+        return None;
+    }
+    let start = Instant::now();
+    let mut bestmove = None;
+    let max_depth = settings.depth.unwrap_or(MAX_DEPTH);
+    let us = pos.get_turn();
+    let should_stop = |stats: &SearchStats| -> bool {
+        if let Some(nodes) = settings.nodes {
+            return stats.nodes > nodes;
+        }
+
+        if let Some(movetime) = settings.movetime {
+            return start.elapsed().as_millis() >= movetime as u128;
+        }
+
+        let our_time = if us == Side::Player1 {
+            settings.p1time
+        } else {
+            settings.p2time
+        };
+
+        if let Some(time) = our_time {
+            let to_use = time / 30;
+            return start.elapsed().as_millis() >= to_use as u128;
+        }
+
+        false
+    };
+
+    let mut pv = vec![];
+    let mut score = 0;
+
+    for depth in 1..=max_depth {
+        // Close to the end the whole game can be read out, with the final disc
+        // difference as the score. Failing that, stick with what's been found.
+        if depth > 1 && pos.get_empty().count_ones() <= SOLVE_EMPTIES {
+            if let Some((mv, score)) = solve_root(&mut pos, &should_stop, stats) {
+                bestmove = Some(mv);
+                info_handler(
+                    &pos,
+                    Some(pos.get_empty().count_ones() as i32),
+                    Some(stats.seldepth),
+                    Some(100 * score),
+                    None,
+                    None,
+                    Some(stats.nodes),
+                    Some(start.elapsed().as_millis()),
+                    None,
+                    &vec![mv],
+                    Some(&*stats),
+                );
+            }
+            break;
+        }
+
+        // Look for the score close to the last one, and widen the window on
+        // whichever side it turns out to be outside of. Neither side ever comes
+        // back in, so a score that fails high and then low still gets found.
+        let mut delta = ASPIRATION_WINDOW;
+        let (mut alpha, mut beta) = if depth >= ASPIRATION_DEPTH {
+            (
+                max(score - delta, -INF_SCORE),
+                min(score + delta, INF_SCORE),
+            )
+        } else {
+            (-INF_SCORE, INF_SCORE)
+        };
+
+        loop {
+            let mut line = pv.clone();
+            let result = negamax(
+                &mut pos,
+                alpha,
+                beta,
+                depth,
+                0,
+                &should_stop,
+                stats,
+                &mut line,
+            );
+
+            if should_stop(stats) {
+                // The moves that did get searched to the end are still worth
+                // having, if the best of them beats the last iteration's
+                if !line.is_empty() && (bestmove.is_none() || result > score) {
+                    bestmove = Some(line[0]);
+                }
+                break;
+            }
+
+            let bound = if result <= alpha {
+                alpha = max(result - delta, -INF_SCORE);
+                Bound::Upper
+            } else if result >= beta {
+                beta = min(result + delta, INF_SCORE);
+                Bound::Lower
+            } else {
+                score = result;
+                pv = line;
+                break;
+            };
+
+            info_handler(
+                &pos,
+                Some(depth),
+                Some(stats.seldepth),
+                Some(result),
+                Some(bound),
+                None,
+                Some(stats.nodes),
+                Some(start.elapsed().as_millis()),
+                None,
+                &line,
+                None,
+            );
+
+            delta *= 2;
+        }
+
+        if should_stop(stats) {
+            info_handler(
+                &pos,
+                None,
+                None,
+                None,
+                None,
+                None,
+                Some(stats.nodes),
+                Some(start.elapsed().as_millis()),
+                None,
+                &vec![],
+                None,
+            );
+            break;
+        }
+
+        bestmove = pv.first().copied();
+        stats.iterations.push(stats.nodes);
+
+        info_handler(
+            &pos,
+            Some(depth),
+            Some(stats.seldepth),
+            Some(score),
+            None,
+            None,
+            Some(stats.nodes),
+            Some(start.elapsed().as_millis()),
+            None,
+            &pv,
+            Some(&*stats),
+        );
+
+        if settings.debug {
+            output::emit(&Message(stats.to_string()));
+        }
+    }
+
+    // Stopped before searching a single move, but anything legal beats nothing
+    bestmove.or_else(|| pos.legal_moves().first().copied())
+}
+
+/// Search the bench positions, to `BENCH_DEPTH` unless told otherwise.
+#[must_use]
+pub fn bench(depth: Option<i32>) -> Bench {
+    let depth = depth.unwrap_or(BENCH_DEPTH);
+    crate::bench::run(&BENCH_FENS, depth, |fen, settings, stats| {
+        let pos = OthelloPosition::from_fen(fen);
+        let _ = primary_with_stats(pos, settings, &quiet, stats);
+    })
+}
+
+/// Search each EPD position within `settings`.
+#[must_use]
+pub fn testsuite(epds: &[Epd], settings: &GoSettings) -> Report {
+    crate::testsuite::run(
+        epds,
+        settings,
+        OthelloPosition::from_fen,
+        |_, mv| vec![as_ugi(mv)],
+        |pos, settings, info_handler| primary(pos, settings, &info_handler),
+    )
+}
@@ -0,0 +1,112 @@
+use super::position::{OthelloMove, OthelloPosition, final_score, flips_bb, moves_bb};
+use crate::searchstats::SearchStats;
+use games::gamerules::GameRules;
+use std::cmp::max;
+
+/// Positions with this many empty squares or fewer get searched to the end.
+pub const SOLVE_EMPTIES: u32 = 14;
+
+/// Below this many empties, ordering the moves costs more than it saves.
+const ORDER_EMPTIES: u32 = 7;
+
+/// The final disc difference for `us` with perfect play, within (alpha, beta).
+/// Returns `None` if told to stop before the end.
+#[must_use]
+fn solve(
+    us: u64,
+    them: u64,
+    mut alpha: i32,
+    beta: i32,
+    passed: bool,
+    should_stop: &impl Fn(&SearchStats) -> bool,
+    stats: &mut SearchStats,
+) -> Option<i32> {
+    if stats.nodes.is_multiple_of(4096) && should_stop(stats) {
+        return None;
+    }
+
+    stats.nodes += 1;
+
+    let mut moves = moves_bb(us, them);
+    if moves == 0 {
+        if passed {
+            return Some(final_score(us, them));
+        }
+        return solve(them, us, -beta, -alpha, true, should_stop, stats).map(|s| -s);
+    }
+
+    let mut children = Vec::with_capacity(moves.count_ones() as usize);
+    while moves != 0 {
+        let sq = moves.trailing_zeros() as u8;
+        let flips = flips_bb(us, them, sq);
+        children.push((them ^ flips, us | flips | (1 << sq)));
+        moves &= moves - 1;
+    }
+
+    // Fastest first: leaving the opponent the fewest replies finds cutoffs early
+    if (!(us | them)).count_ones() > ORDER_EMPTIES {
+        children.sort_by_key(|(us, them)| moves_bb(*us, *them).count_ones());
+    }
+
+    let mut best = -64;
+    for (child_us, child_them) in children {
+        let score = -solve(
+            child_us,
+            child_them,
+            -beta,
+            -alpha,
+            false,
+            should_stop,
+            stats,
+        )?;
+        if score > best {
+            best = score;
+            if score > alpha {
+                alpha = score;
+                if alpha >= beta {
+                    break;
+                }
+            }
+        }
+    }
+
+    Some(best)
+}
+
+/// The best move and the final disc difference it leads to for the side to move,
+/// searching every line to the end. Returns `None` if told to stop first.
+#[must_use]
+pub fn solve_root(
+    pos: &mut OthelloPosition,
+    should_stop: &impl Fn(&SearchStats) -> bool,
+    stats: &mut SearchStats,
+) -> Option<(OthelloMove, i32)> {
+    let mut best = None;
+    let mut alpha = -65;
+    let empties = pos.get_empty().count_ones() as i32;
+    stats.seldepth = max(stats.seldepth, empties);
+
+    for mv in pos.legal_moves() {
+        pos.makemove(&mv);
+        let passed = mv == OthelloMove::Pass;
+        let score = solve(
+            pos.get_us(),
+            pos.get_them(),
+            -65,
+            -alpha,
+            passed,
+            should_stop,
+            stats,
+        )
+        .map(|s| -s);
+        pos.undomove(&mv);
+
+        let score = score?;
+        if score > alpha {
+            alpha = score;
+            best = Some((mv, score));
+        }
+    }
+
+    best
+}
@@ -0,0 +1,78 @@
+use super::position::{OthelloMove, OthelloPosition};
+use super::root::primary_with_stats;
+use crate::bound::Bound;
+use crate::prng;
+use crate::searchstats::SearchStats;
+use games::gamerules::GameRules;
+use protocols::GoSettings;
+use protocols::ugi::options::{Check, Combo};
+
+pub struct OthelloState {
+    pub pos: OthelloPosition,
+    pub prng: prng::XorshiftGenerator,
+    pub startfen: String,
+    pub history: Vec<OthelloMove>,
+    /// What the last search counted
+    pub stats: SearchStats,
+    // Options
+    pub debug: Check,
+    pub search: Combo,
+}
+
+impl Default for OthelloState {
+    fn default() -> Self {
+        Self {
+            pos: OthelloPosition::startpos(),
+            prng: prng::XorshiftGenerator::new(0xe50076937a9e5b1c),
+            startfen: "startpos".to_owned(),
+            history: vec![],
+            stats: SearchStats::default(),
+            // Options
+            debug: Check {
+                name: "debug".to_owned(),
+                value: cfg!(debug_assertions),
+            },
+            search: Combo {
+                name: "search".to_owned(),
+                value: "primary".to_owned(),
+                options: vec!["primary".to_owned(), "random".to_owned()],
+            },
+        }
+    }
+}
+
+impl OthelloState {
+    #[must_use]
+    pub fn think(
+        &mut self,
+        settings: &GoSettings,
+        info_handler: &impl Fn(
+            &OthelloPosition,
+            Option<i32>,
+            Option<i32>,
+            Option<i32>,
+            Option<Bound>,
+            Option<i32>,
+            Option<u64>,
+            Option<u128>,
+            Option<i32>,
+            &Vec<OthelloMove>,
+            Option<&SearchStats>,
+        ),
+    ) -> Option<OthelloMove> {
+        match self.search.value.as_str() {
+            "primary" => {
+                primary_with_stats(self.pos.clone(), settings, info_handler, &mut self.stats)
+            }
+            "random" => {
+                let moves = self.pos.legal_moves();
+                if moves.is_empty() {
+                    None
+                } else {
+                    Some(moves[self.prng.next() as usize % moves.len()])
+                }
+            }
+            _ => panic!("Unknown search type"),
+        }
+    }
+}
@@ -0,0 +1,240 @@
+use super::perft;
+use super::position::{OthelloMove, OthelloPosition, parse_square, square_name};
+use super::root::bench;
+use super::state::OthelloState;
+use crate::bound::Bound;
+use crate::colour::Colour;
+use crate::searchstats::SearchStats;
+use games::gamerules::{GameResult, GameRules};
+use games::general::side::Side;
+use protocols::json::ToJson;
+use protocols::output::{self, Bestmove, Info, Message};
+use protocols::ugi::{GoSettings, UGI, UGIGameResult};
+use std::time::Instant;
+
+pub fn info_handler(
+    _: &OthelloPosition,
+    depth: Option<i32>,
+    seldepth: Option<i32>,
+    score: Option<i32>,
+    bound: Option<Bound>,
+    mate: Option<i32>,
+    nodes: Option<u64>,
+    elapsed: Option<u128>,
+    hashfull: Option<i32>,
+    pv: &Vec<OthelloMove>,
+    stats: Option<&SearchStats>,
+) {
+    output::emit(&Info {
+        depth,
+        seldepth,
+        score,
+        bound: bound.map(|b| b.to_string()),
+        mate,
+        nodes,
+        elapsed,
+        hashfull,
+        pv: pv.iter().map(as_ugi).collect(),
+        stats: stats.map(ToJson::to_json),
+    });
+}
+
+#[must_use]
+pub fn as_ugi(mv: &OthelloMove) -> String {
+    match mv {
+        OthelloMove::Pass => "0000".to_owned(),
+        OthelloMove::Place(sq) => square_name(*sq),
+    }
+}
+
+#[must_use]
+pub fn parse_move(pos: &OthelloPosition, movestr: &str) -> Option<OthelloMove> {
+    let mv = match movestr {
+        "0000" | "pass" => OthelloMove::Pass,
+        _ => OthelloMove::Place(parse_square(movestr)?),
+    };
+    pos.is_legal(&mv).then_some(mv)
+}
+
+impl UGI for OthelloState {
+    fn init(&mut self) {
+        self.uginewgame();
+    }
+
+    fn shutdown(&mut self) {}
+
+    fn name(&self) -> String {
+        format!("Faeries v{}", env!("CARGO_PKG_VERSION"))
+    }
+
+    fn author(&self) -> String {
+        "kz04px".to_owned()
+    }
+
+    fn uginewgame(&mut self) {
+        self.pos = OthelloPosition::startpos();
+    }
+
+    fn isready(&mut self) {
+        println!("readyok");
+    }
+
+    fn position(&mut self, fen: &str) {
+        self.pos.set_fen(fen);
+    }
+
+    fn moves(&mut self, movestr: &str) {
+        if let Some(mv) = parse_move(&self.pos, movestr) {
+            self.pos.makemove(&mv);
+        }
+    }
+
+    fn go(&mut self, settings: &GoSettings) {
+        let settings = &protocols::GoSettings {
+            debug: self.debug.value,
+            ..protocols::GoSettings::from(settings)
+        };
+        let bestmove = self.think(settings, &info_handler);
+        output::emit(&Bestmove {
+            mv: bestmove.map_or("0000".to_owned(), |mv| as_ugi(&mv)),
+            stats: self.stats.to_json(),
+        });
+    }
+
+    fn stop(&mut self) {}
+
+    fn unrecognised_command(
+        &mut self,
+        word: &str,
+        args: &mut std::iter::Peekable<std::str::SplitAsciiWhitespace>,
+    ) {
+        match word {
+            "print" => self.print(),
+            "pprint" => self.pprint(),
+            "bench" => {
+                let depth = args.next().and_then(|word| word.parse::<i32>().ok());
+                println!("{}", bench(depth));
+            }
+            "stats" => output::emit(&Message(self.stats.to_string())),
+            _ => {}
+        }
+    }
+
+    fn print_options(&self) {
+        output::emit(&self.debug);
+        output::emit(&self.search);
+    }
+
+    fn movelist(&self) {
+        for mv in self.pos.legal_moves() {
+            println!("{}", as_ugi(&mv));
+        }
+    }
+
+    fn set_option(&mut self, name: &str, value: &str) {
+        match (name, value) {
+            ("debug", "true") => self.debug.value = true,
+            ("debug", "false") => self.debug.value = false,
+            ("search", _) => self.search.value = value.to_owned(),
+            (_, _) => {}
+        }
+    }
+
+    fn is_debug(&self) -> bool {
+        self.debug.value
+    }
+
+    fn query_p1turn(&self) -> bool {
+        self.pos.get_turn() == Side::Player1
+    }
+
+    fn query_result(&self) -> Option<UGIGameResult> {
+        match self.pos.get_result() {
+            Some(GameResult::Win(Side::Player1)) => Some(UGIGameResult::P1Win),
+            Some(GameResult::Win(Side::Player2)) => Some(UGIGameResult::P2Win),
+            Some(GameResult::Draw) => Some(UGIGameResult::Draw),
+            None => None,
+        }
+    }
+
+    fn perft(&mut self, settings: &GoSettings) {
+        let start = Instant::now();
+        let mut nodes = 0;
+        for depth in 1..=settings.depth.unwrap() {
+            nodes = perft::perft(&mut self.pos, depth);
+            let elapsed = start.elapsed().as_secs_f32();
+            output::perft(depth, elapsed, nodes);
+        }
+        output::nodes(nodes);
+    }
+
+    fn split(&mut self, settings: &GoSettings) {
+        let mut total = 0;
+        for (mv, nodes) in perft::split(&mut self.pos, settings.depth.unwrap()) {
+            output::split(as_ugi(&mv), nodes);
+            total += nodes;
+        }
+        output::nodes(total);
+    }
+}
+
+impl OthelloState {
+    fn print(&self) {
+        println!("{}", self.pos);
+        println!("FEN: {}", self.pos.get_fen());
+    }
+
+    pub fn pprint(&self) {
+        let printer = |piece: &str, x: i32, y: i32| {
+            let bb = 1u64 << (8 * y + x);
+
+            let foreground = if self.pos.get_black() & bb != 0 {
+                Colour::Black
+            } else {
+                Colour::WhiteBright
+            };
+
+            if (self.pos.get_black() | self.pos.get_white()) & bb != 0 {
+                print!(
+                    "{}{}{}{}",
+                    Colour::GreenBackground,
+                    foreground,
+                    piece,
+                    Colour::Clear
+                );
+            } else {
+                print!("{}   {}", Colour::GreenBackground, Colour::Clear);
+            };
+        };
+
+        for y in (0..8).rev() {
+            for x in 0..8 {
+                printer("◢█◣", x, y);
+            }
+            println!("");
+            for x in 0..8 {
+                printer("███", x, y);
+            }
+            println!("");
+            for x in 0..8 {
+                printer("◥█◤", x, y);
+            }
+            println!("");
+        }
+
+        println!(
+            "Discs: {}●{} {} {}●{} {}",
+            Colour::Black,
+            Colour::Clear,
+            self.pos.get_black().count_ones(),
+            Colour::WhiteBright,
+            Colour::Clear,
+            self.pos.get_white().count_ones()
+        );
+        if self.pos.get_turn() == Side::Player1 {
+            println!("Turn: {}●{}", Colour::Black, Colour::Clear);
+        } else {
+            println!("Turn: {}●{}", Colour::WhiteBright, Colour::Clear);
+        }
+    }
+}
@@ -1,7 +1,13 @@
+use crate::tune::{Tunable, dot};
+use games::gamerules::GameRules;
+use games::general::side::Side;
 use games::pijersi::PijersiPosition;
 
+pub const NAMES: [&str; 1] = ["material"];
+pub const WEIGHTS: [i32; 1] = [100];
+
 #[must_use]
-pub fn eval(pos: &PijersiPosition) -> i32 {
+pub fn features(pos: &PijersiPosition) -> [i32; 1] {
     let us_lower = pos.get_lower() & pos.get_us();
     let us_upper = pos.get_upper() & pos.get_us();
     let num_us = us_lower.count() + us_upper.count();
@@ -10,5 +16,23 @@ pub fn eval(pos: &PijersiPosition) -> i32 {
     let them_upper = pos.get_upper() & pos.get_them();
     let num_them = them_lower.count() + them_upper.count();
 
-    100 * (num_us - num_them)
+    [num_us - num_them]
+}
+
+#[must_use]
+pub fn eval(pos: &PijersiPosition) -> i32 {
+    dot(&WEIGHTS, &features(pos))
+}
+
+impl Tunable for PijersiPosition {
+    const NAMES: &'static [&'static str] = &NAMES;
+    const WEIGHTS: &'static [i32] = &WEIGHTS;
+
+    fn features(&self) -> Vec<i32> {
+        features(self).to_vec()
+    }
+
+    fn first_to_move(&self) -> bool {
+        self.get_turn() == Side::Player1
+    }
 }
@@ -1,11 +1,23 @@
+use super::root::primary_with_stats;
+use crate::bound::Bound;
 use crate::prng;
-use games::{gamerules::GameRules, pijersi::PijersiPosition};
-use std::u64;
+use crate::search;
+use crate::searchstats::SearchStats;
+use games::{
+    gamerules::GameRules,
+    pijersi::{PijersiMove, PijersiPosition},
+};
+use protocols::GoSettings;
 use protocols::ugi::options::{Check, Combo, Spin};
+use std::u64;
 
 pub struct PijersiState {
     pub pos: PijersiPosition,
     pub prng: prng::XorshiftGenerator,
+    pub startfen: String,
+    pub history: Vec<Option<PijersiMove>>,
+    /// What the last search counted
+    pub stats: SearchStats,
     // Options
     pub debug: Check,
     pub search: Combo,
@@ -17,6 +29,9 @@ impl Default for PijersiState {
         Self {
             pos: PijersiPosition::startpos(),
             prng: prng::XorshiftGenerator::new(0xe50076937a9e5b1c),
+            startfen: "startpos".to_owned(),
+            history: vec![],
+            stats: SearchStats::default(),
             // Options
             debug: Check {
                 name: "debug".to_owned(),
@@ -42,3 +57,47 @@ impl Default for PijersiState {
         }
     }
 }
+
+impl PijersiState {
+    #[must_use]
+    pub fn think(
+        &mut self,
+        settings: &GoSettings,
+        info_handler: &impl Fn(
+            &PijersiPosition,
+            Option<i32>,
+            Option<i32>,
+            Option<i32>,
+            Option<Bound>,
+            Option<i32>,
+            Option<u64>,
+            Option<u128>,
+            Option<i32>,
+            &Vec<PijersiMove>,
+            Option<&SearchStats>,
+        ),
+    ) -> Option<PijersiMove> {
+        let eval = |pos: &PijersiPosition| -> i32 {
+            let us_lower = pos.get_lower() & pos.get_us();
+            let us_upper = pos.get_upper() & pos.get_us();
+            let num_us = us_lower.count() + us_upper.count();
+
+            let them_lower = pos.get_lower() & pos.get_them();
+            let them_upper = pos.get_upper() & pos.get_them();
+            let num_them = them_lower.count() + them_upper.count();
+
+            100 * (num_us - num_them)
+        };
+
+        search::think(
+            &self.search.value,
+            &self.pos,
+            settings,
+            info_handler,
+            &eval,
+            &mut self.prng,
+            &mut self.stats,
+            |stats| primary_with_stats(self.pos.clone(), settings, info_handler, stats),
+        )
+    }
+}
@@ -13,4 +13,11 @@ impl XorshiftGenerator {
         self.0 ^= self.0 << 17;
         self.0
     }
+
+    /// Where the sequence has got to. A generator made with this as its seed
+    /// carries on from here.
+    #[must_use]
+    pub fn state(&self) -> u64 {
+        self.0
+    }
 }
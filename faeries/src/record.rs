@@ -0,0 +1,387 @@
+use crate::checkers::position::CheckersPosition;
+use crate::connect4::position::Connect4Position;
+use crate::droptaxx::position::DroptaxxPosition;
+use crate::gomoku::position::GomokuPosition;
+use crate::othello::position::OthelloPosition;
+use crate::shogi::position::ShogiPosition;
+use crate::{ataxx, checkers, chess, connect4, droptaxx, gomoku, isolation, othello, pijersi, tak};
+use games::{
+    ataxx::AtaxxPosition,
+    chess::ChessPosition,
+    gamerules::{GameResult, GameRules},
+    general::side::Side,
+    isolation::IsolationPosition,
+    pijersi::PijersiPosition,
+    tak::TakPosition,
+};
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Format {
+    /// Portable Game Notation, with moves in SAN
+    Pgn,
+    /// Portable Tak Notation
+    Ptn,
+    /// PGN-like tags and move numbers, with moves as the engine writes them
+    Generic,
+}
+
+/// A game that can be written down.
+pub trait Recordable: GameRules {
+    const NAME: &'static str;
+    const FORMAT: Format;
+
+    /// The move as it appears in a record, which isn't always how it's sent to an engine.
+    #[must_use]
+    fn notation(&self, mv: &Self::MoveType) -> String;
+
+    /// Tags every record of the game has.
+    #[must_use]
+    fn tags(&self) -> Vec<(String, String)> {
+        vec![]
+    }
+}
+
+impl Recordable for AtaxxPosition<7, 7> {
+    const NAME: &'static str = "ataxx";
+    const FORMAT: Format = Format::Generic;
+
+    fn notation(&self, mv: &Self::MoveType) -> String {
+        ataxx::ugi::as_ugi(mv)
+    }
+}
+
+impl Recordable for CheckersPosition {
+    const NAME: &'static str = "checkers";
+    const FORMAT: Format = Format::Generic;
+
+    fn notation(&self, mv: &Self::MoveType) -> String {
+        checkers::ugi::as_pdn(self, mv)
+    }
+}
+
+impl Recordable for ChessPosition {
+    const NAME: &'static str = "chess";
+    const FORMAT: Format = Format::Pgn;
+
+    fn notation(&self, mv: &Self::MoveType) -> String {
+        chess::san::as_san(self, mv)
+    }
+}
+
+impl Recordable for Connect4Position {
+    const NAME: &'static str = "connect4";
+    const FORMAT: Format = Format::Generic;
+
+    fn notation(&self, mv: &Self::MoveType) -> String {
+        connect4::ugi::as_ugi(mv)
+    }
+}
+
+impl Recordable for DroptaxxPosition {
+    const NAME: &'static str = "droptaxx";
+    const FORMAT: Format = Format::Generic;
+
+    fn notation(&self, mv: &Self::MoveType) -> String {
+        droptaxx::ugi::as_ugi(mv)
+    }
+}
+
+impl Recordable for GomokuPosition {
+    const NAME: &'static str = "gomoku";
+    const FORMAT: Format = Format::Generic;
+
+    fn notation(&self, mv: &Self::MoveType) -> String {
+        gomoku::ugi::as_ugi(self, mv)
+    }
+}
+
+impl Recordable for IsolationPosition {
+    const NAME: &'static str = "isolation";
+    const FORMAT: Format = Format::Generic;
+
+    fn notation(&self, mv: &Self::MoveType) -> String {
+        isolation::ugi::as_ugi(mv)
+    }
+}
+
+impl Recordable for OthelloPosition {
+    const NAME: &'static str = "othello";
+    const FORMAT: Format = Format::Generic;
+
+    fn notation(&self, mv: &Self::MoveType) -> String {
+        othello::ugi::as_ugi(mv)
+    }
+}
+
+impl Recordable for PijersiPosition {
+    const NAME: &'static str = "pijersi";
+    const FORMAT: Format = Format::Generic;
+
+    fn notation(&self, mv: &Self::MoveType) -> String {
+        pijersi::ugi::as_ugi(mv)
+    }
+}
+
+impl Recordable for ShogiPosition {
+    const NAME: &'static str = "shogi";
+    const FORMAT: Format = Format::Generic;
+
+    fn notation(&self, mv: &Self::MoveType) -> String {
+        mv.to_string()
+    }
+}
+
+impl<const SIZE: usize> Recordable for TakPosition<SIZE> {
+    const NAME: &'static str = "tak";
+    const FORMAT: Format = Format::Ptn;
+
+    fn notation(&self, mv: &Self::MoveType) -> String {
+        tak::tei::as_tei(self, mv)
+    }
+
+    fn tags(&self) -> Vec<(String, String)> {
+        vec![("Size".to_owned(), SIZE.to_string())]
+    }
+}
+
+/// Today's date as `YYYY.MM.DD`
+#[must_use]
+fn today() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    // Days since 1970-01-01 to a civil date
+    let z = (secs / 86400) as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!("{:04}.{:02}.{:02}", year, month, day)
+}
+
+/// A finished or unfinished game: where it started, the moves, the result and
+/// whatever else is known about it.
+#[derive(Clone, Debug)]
+pub struct Record {
+    pub format: Format,
+    pub tags: Vec<(String, String)>,
+    pub startfen: Option<String>,
+    pub moves: Vec<String>,
+    pub result: Option<GameResult>,
+    p1_first: bool,
+    first_number: i32,
+}
+
+impl Record {
+    #[must_use]
+    pub fn new<G: Recordable>(pos: &G) -> Self {
+        let fen = pos.get_fen();
+        let startfen = if fen == G::startpos().get_fen() {
+            None
+        } else {
+            Some(fen.clone())
+        };
+
+        // Where the FEN keeps the move number, if it does
+        let number_field = match G::FORMAT {
+            Format::Pgn => Some(5),
+            Format::Ptn => Some(2),
+            Format::Generic => None,
+        };
+        let first_number = number_field
+            .and_then(|idx| fen.split_ascii_whitespace().nth(idx))
+            .and_then(|n| n.parse::<i32>().ok())
+            .unwrap_or(1);
+
+        let mut record = Self::custom(
+            G::NAME,
+            G::FORMAT,
+            startfen,
+            pos.get_turn() == Side::Player1,
+        );
+        record.first_number = first_number;
+        record.tags.extend(pos.tags());
+        record
+    }
+
+    /// A record for a game the games crate doesn't know about, which has to say
+    /// where it started and who moved first.
+    #[must_use]
+    pub fn custom(name: &str, format: Format, startfen: Option<String>, p1_first: bool) -> Self {
+        let (p1, p2) = match format {
+            Format::Pgn => ("White", "Black"),
+            _ => ("Player1", "Player2"),
+        };
+
+        let mut tags = vec![];
+        if format == Format::Generic {
+            tags.push(("Game".to_owned(), name.to_owned()));
+        }
+        tags.push(("Event".to_owned(), "?".to_owned()));
+        tags.push(("Site".to_owned(), "?".to_owned()));
+        tags.push(("Date".to_owned(), today()));
+        tags.push(("Round".to_owned(), "?".to_owned()));
+        tags.push((p1.to_owned(), "?".to_owned()));
+        tags.push((p2.to_owned(), "?".to_owned()));
+
+        Self {
+            format,
+            tags,
+            startfen,
+            moves: vec![],
+            result: None,
+            p1_first,
+            first_number: 1,
+        }
+    }
+
+    /// Write down a whole game from its starting position and the moves played,
+    /// with `None` for a null move.
+    #[must_use]
+    pub fn replay<G: Recordable>(startfen: &str, history: &[Option<G::MoveType>]) -> Self {
+        let mut pos = G::startpos();
+        pos.set_fen(startfen);
+        let mut record = Self::new(&pos);
+
+        for mv in history {
+            match mv {
+                Some(mv) => {
+                    record.push(&pos, mv);
+                    pos.makemove(mv);
+                }
+                None => {
+                    record.push_null();
+                    pos.makenull();
+                }
+            }
+        }
+
+        record.result = pos.get_result();
+        record
+    }
+
+    /// Add a move, which is written from the position before it's played.
+    pub fn push<G: Recordable>(&mut self, pos: &G, mv: &G::MoveType) {
+        self.moves.push(pos.notation(mv));
+    }
+
+    pub fn push_null(&mut self) {
+        let null = match self.format {
+            Format::Pgn | Format::Ptn => "--",
+            Format::Generic => "0000",
+        };
+        self.moves.push(null.to_owned());
+    }
+
+    /// Set a tag, replacing it if it's already there.
+    pub fn set_tag(&mut self, name: &str, value: &str) {
+        match self.tags.iter_mut().find(|(tag, _)| tag == name) {
+            Some((_, old)) => *old = value.to_owned(),
+            None => self.tags.push((name.to_owned(), value.to_owned())),
+        }
+    }
+
+    pub fn set_players(&mut self, p1: &str, p2: &str) {
+        match self.format {
+            Format::Pgn => {
+                self.set_tag("White", p1);
+                self.set_tag("Black", p2);
+            }
+            _ => {
+                self.set_tag("Player1", p1);
+                self.set_tag("Player2", p2);
+            }
+        }
+    }
+
+    #[must_use]
+    pub fn get_tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag, _)| tag == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    #[must_use]
+    pub fn result_string(&self) -> &'static str {
+        match self.result {
+            Some(GameResult::Win(Side::Player1)) => "1-0",
+            Some(GameResult::Win(Side::Player2)) => "0-1",
+            Some(GameResult::Draw) => "1/2-1/2",
+            None => "*",
+        }
+    }
+
+    /// The numbered moves and the result, without tags.
+    #[must_use]
+    pub fn movetext(&self) -> String {
+        let mut tokens = vec![];
+        let mut number = self.first_number;
+        let mut p1_turn = self.p1_first;
+
+        for (idx, mv) in self.moves.iter().enumerate() {
+            if p1_turn {
+                tokens.push(format!("{}.", number));
+            } else if idx == 0 {
+                tokens.push(format!("{}...", number));
+            }
+
+            tokens.push(mv.clone());
+
+            if !p1_turn {
+                number += 1;
+            }
+            p1_turn = !p1_turn;
+        }
+
+        tokens.push(self.result_string().to_owned());
+
+        // Keep lines under 80 characters
+        let mut text = String::new();
+        let mut line_len = 0;
+        for token in tokens {
+            if line_len > 0 && line_len + 1 + token.len() > 79 {
+                text.push('\n');
+                line_len = 0;
+            } else if line_len > 0 {
+                text.push(' ');
+                line_len += 1;
+            }
+            line_len += token.len();
+            text.push_str(&token);
+        }
+        text
+    }
+}
+
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (name, value) in &self.tags {
+            writeln!(f, "[{} \"{}\"]", name, value)?;
+        }
+        writeln!(f, "[Result \"{}\"]", self.result_string())?;
+
+        if let Some(fen) = &self.startfen {
+            match self.format {
+                Format::Pgn => {
+                    writeln!(f, "[SetUp \"1\"]")?;
+                    writeln!(f, "[FEN \"{}\"]", fen)?;
+                }
+                Format::Ptn => writeln!(f, "[TPS \"{}\"]", fen)?,
+                Format::Generic => writeln!(f, "[FEN \"{}\"]", fen)?,
+            }
+        }
+
+        writeln!(f)?;
+        writeln!(f, "{}", self.movetext())
+    }
+}
@@ -1,13 +1,20 @@
+use crate::bound::Bound;
 use crate::searchstats::SearchStats;
 use games::{
     gamerules::{GameResult, GameRules},
     general::side::Side,
 };
 use protocols::GoSettings;
-use std::{cmp::max, time::Instant};
+use protocols::output::{self, Message};
+use std::{
+    cmp::{max, min},
+    time::Instant,
+};
 
 const INF_SCORE: i32 = 10_000_000;
 const MATE_SCORE: i32 = 1_000_000;
+const ASPIRATION_WINDOW: i32 = 50;
+const ASPIRATION_DEPTH: i32 = 4;
 
 #[must_use]
 fn alphabeta_impl<G: GameRules>(
@@ -51,7 +58,7 @@ fn alphabeta_impl<G: GameRules>(
 
     let mut best_score = -INF_SCORE;
 
-    for mv in pos.legal_moves() {
+    for (idx, mv) in pos.legal_moves().into_iter().enumerate() {
         pos.makemove(&mv);
         let mut next_pv = vec![];
         let score = -alphabeta_impl(
@@ -67,6 +74,11 @@ fn alphabeta_impl<G: GameRules>(
         );
         pos.undomove(&mv);
 
+        // A move whose search got cut short can't be trusted
+        if should_stop(stats) {
+            break;
+        }
+
         if score > best_score {
             best_score = score;
 
@@ -82,11 +94,15 @@ fn alphabeta_impl<G: GameRules>(
         }
 
         if alpha >= beta {
+            stats.beta_cutoffs += 1;
+            if idx == 0 {
+                stats.first_move_cutoffs += 1;
+            }
             break;
         }
     }
 
-    debug_assert!(-MATE_SCORE <= best_score);
+    debug_assert!(-MATE_SCORE <= best_score || should_stop(stats));
     debug_assert!(best_score <= MATE_SCORE);
 
     best_score
@@ -94,6 +110,36 @@ fn alphabeta_impl<G: GameRules>(
 
 #[must_use]
 pub fn alphabeta<G: GameRules>(
+    pos: G,
+    settings: &GoSettings,
+    info_handler: &impl Fn(
+        &G,
+        Option<i32>,
+        Option<i32>,
+        Option<i32>,
+        Option<Bound>,
+        Option<i32>,
+        Option<u64>,
+        Option<u128>,
+        Option<i32>,
+        &Vec<G::MoveType>,
+        Option<&SearchStats>,
+    ),
+    eval: &impl Fn(&G) -> i32,
+) -> Option<G::MoveType> {
+    alphabeta_with_stats(
+        pos,
+        settings,
+        info_handler,
+        eval,
+        &mut SearchStats::default(),
+    )
+}
+
+/// The same search, leaving what it counted in `stats` to look at afterwards.
+/// In debug mode they're shown after every iteration as well.
+#[must_use]
+pub fn alphabeta_with_stats<G: GameRules>(
     mut pos: G,
     settings: &GoSettings,
     info_handler: &impl Fn(
@@ -101,21 +147,24 @@ pub fn alphabeta<G: GameRules>(
         Option<i32>,
         Option<i32>,
         Option<i32>,
+        Option<Bound>,
         Option<i32>,
         Option<u64>,
         Option<u128>,
         Option<i32>,
         &Vec<G::MoveType>,
+        Option<&SearchStats>,
     ),
     eval: &impl Fn(&G) -> i32,
+    stats: &mut SearchStats,
 ) -> Option<G::MoveType> {
+    *stats = SearchStats::default();
     if pos.is_gameover() {
         return None;
     }
     let mut bestmove = None;
     let start = Instant::now();
     let max_depth = settings.depth.unwrap_or(128);
-    let mut stats = SearchStats::default();
     let us = pos.get_turn();
     let should_stop = |stats: &SearchStats| -> bool {
         if let Some(nodes) = settings.nodes {
@@ -140,51 +189,113 @@ pub fn alphabeta<G: GameRules>(
         false
     };
 
+    let mut score = 0;
+
     // Iterative deepening
     for depth in 1..=max_depth {
         let mut pv = vec![];
-        let score = alphabeta_impl(
-            &mut pos,
-            -INF_SCORE,
-            INF_SCORE,
-            depth,
-            0,
-            &mut stats,
-            &mut pv,
-            &should_stop,
-            &eval,
-        );
+
+        // Aspiration windows
+        let mut delta = ASPIRATION_WINDOW;
+        let (mut alpha, mut beta) = if depth >= ASPIRATION_DEPTH {
+            (
+                max(score - delta, -INF_SCORE),
+                min(score + delta, INF_SCORE),
+            )
+        } else {
+            (-INF_SCORE, INF_SCORE)
+        };
+
+        loop {
+            let result = alphabeta_impl(
+                &mut pos,
+                alpha,
+                beta,
+                depth,
+                0,
+                stats,
+                &mut pv,
+                &should_stop,
+                &eval,
+            );
+
+            if should_stop(stats) {
+                // Keep what got searched to the end if it beats the last iteration
+                if !pv.is_empty() && (bestmove.is_none() || result > score) {
+                    bestmove = Some(pv[0]);
+                }
+                break;
+            }
+
+            let bound = if result <= alpha {
+                alpha = max(result - delta, -INF_SCORE);
+                Bound::Upper
+            } else if result >= beta {
+                beta = min(result + delta, INF_SCORE);
+                Bound::Lower
+            } else {
+                score = result;
+                break;
+            };
+
+            info_handler(
+                &pos,
+                Some(depth),
+                Some(stats.seldepth),
+                Some(result),
+                Some(bound),
+                None,
+                Some(stats.nodes),
+                Some(start.elapsed().as_millis()),
+                None,
+                &pv,
+                None,
+            );
+
+            delta *= 2;
+        }
         let elapsed = Instant::now() - start;
 
-        if depth > 1 && should_stop(&stats) {
+        if should_stop(stats) {
             info_handler(
                 &pos,
                 None,
                 None,
                 None,
                 None,
+                None,
                 Some(stats.nodes),
                 Some(elapsed.as_millis()),
                 None,
                 &pv,
+                None,
             );
             break;
         }
 
+        stats.iterations.push(stats.nodes);
+
         info_handler(
             &pos,
             Some(depth),
-            None,
+            Some(stats.seldepth),
             Some(score),
             None,
+            None,
             Some(stats.nodes),
             Some(elapsed.as_millis()),
             None,
             &pv,
+            Some(&*stats),
         );
 
-        bestmove = Some(pv[0]);
+        bestmove = pv.first().copied();
+
+        if settings.debug {
+            output::emit(&Message(stats.to_string()));
+        }
     }
 
-    bestmove
+    // Stopped before searching a single move, but anything legal beats nothing
+    bestmove.or_else(|| pos.legal_moves().first().copied())
 }
@@ -1,9 +1,12 @@
+use crate::bound::Bound;
+use crate::searchstats::SearchStats;
 use games::{
     gamerules::{GameResult, GameRules},
     general::side::Side,
 };
 use protocols::GoSettings;
-use std::time::Instant;
+use protocols::output::{self, Message};
+use std::{cmp::max, time::Instant};
 
 #[must_use]
 fn find_idx(scores: &[f32], visits: &[u64]) -> Option<usize> {
@@ -26,6 +29,37 @@ fn find_idx(scores: &[f32], visits: &[u64]) -> Option<usize> {
 
 #[must_use]
 pub fn flatmc<G: GameRules>(
+    pos: G,
+    settings: &GoSettings,
+    info_handler: &impl Fn(
+        &G,
+        Option<i32>,
+        Option<i32>,
+        Option<i32>,
+        Option<Bound>,
+        Option<i32>,
+        Option<u64>,
+        Option<u128>,
+        Option<i32>,
+        &Vec<G::MoveType>,
+        Option<&SearchStats>,
+    ),
+    random_generator: impl FnMut() -> u64,
+) -> Option<G::MoveType> {
+    flatmc_with_stats(
+        pos,
+        settings,
+        info_handler,
+        random_generator,
+        &mut SearchStats::default(),
+    )
+}
+
+/// The same search, leaving what it counted in `stats` to look at afterwards.
+/// Every playout is a node, and the longest of them is the seldepth. In debug
+/// mode they're shown with every update as well.
+#[must_use]
+pub fn flatmc_with_stats<G: GameRules>(
     mut pos: G,
     settings: &GoSettings,
     info_handler: &impl Fn(
@@ -33,14 +67,18 @@ pub fn flatmc<G: GameRules>(
         Option<i32>,
         Option<i32>,
         Option<i32>,
+        Option<Bound>,
         Option<i32>,
         Option<u64>,
         Option<u128>,
         Option<i32>,
         &Vec<G::MoveType>,
+        Option<&SearchStats>,
     ),
     mut random_generator: impl FnMut() -> u64,
+    stats: &mut SearchStats,
 ) -> Option<G::MoveType> {
+    *stats = SearchStats::default();
     if pos.is_gameover() {
         return None;
     }
@@ -91,6 +129,7 @@ pub fn flatmc<G: GameRules>(
                 pos.makemove(&mv);
             }
         }
+        stats.seldepth = max(stats.seldepth, history.len() as i32 + 1);
 
         // Score
         let score = match pos.get_result() {
@@ -115,6 +154,7 @@ pub fn flatmc<G: GameRules>(
 
         // Finished
         iterations += 1;
+        stats.nodes = iterations;
 
         let is_last = should_stop(iterations);
         let should_update = (iterations % 10 == 0 && iterations <= 100)
@@ -133,11 +173,17 @@ pub fn flatmc<G: GameRules>(
                 None,
                 None,
                 None,
+                None,
                 Some(iterations),
                 Some(start.elapsed().as_millis()),
                 None,
                 &vec![root_moves[best_idx.unwrap()]],
+                Some(&*stats),
             );
+
+            if settings.debug {
+                output::emit(&Message(stats.to_string()));
+            }
         }
 
         if is_last {
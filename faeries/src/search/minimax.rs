@@ -1,9 +1,11 @@
+use crate::bound::Bound;
 use crate::searchstats::SearchStats;
 use games::{
     gamerules::{GameResult, GameRules},
     general::side::Side,
 };
 use protocols::GoSettings;
+use protocols::output::{self, Message};
 use std::{cmp::max, time::Instant};
 
 const INF_SCORE: i32 = 10_000_000;
@@ -60,6 +62,11 @@ fn minimax_impl<G: GameRules>(
         );
         pos.undomove(&mv);
 
+        // A move whose search got cut short can't be trusted
+        if should_stop(stats) {
+            break;
+        }
+
         if score > best_score {
             best_score = score;
 
@@ -71,7 +78,7 @@ fn minimax_impl<G: GameRules>(
         }
     }
 
-    debug_assert!(-MATE_SCORE <= best_score);
+    debug_assert!(-MATE_SCORE <= best_score || should_stop(stats));
     debug_assert!(best_score <= MATE_SCORE);
 
     best_score
@@ -79,6 +86,36 @@ fn minimax_impl<G: GameRules>(
 
 #[must_use]
 pub fn minimax<G: GameRules>(
+    pos: G,
+    settings: &GoSettings,
+    info_handler: &impl Fn(
+        &G,
+        Option<i32>,
+        Option<i32>,
+        Option<i32>,
+        Option<Bound>,
+        Option<i32>,
+        Option<u64>,
+        Option<u128>,
+        Option<i32>,
+        &Vec<G::MoveType>,
+        Option<&SearchStats>,
+    ),
+    eval: &impl Fn(&G) -> i32,
+) -> Option<G::MoveType> {
+    minimax_with_stats(
+        pos,
+        settings,
+        info_handler,
+        eval,
+        &mut SearchStats::default(),
+    )
+}
+
+/// The same search, leaving what it counted in `stats` to look at afterwards.
+/// In debug mode they're shown after every iteration as well.
+#[must_use]
+pub fn minimax_with_stats<G: GameRules>(
     mut pos: G,
     settings: &GoSettings,
     info_handler: &impl Fn(
@@ -86,21 +123,24 @@ pub fn minimax<G: GameRules>(
         Option<i32>,
         Option<i32>,
         Option<i32>,
+        Option<Bound>,
         Option<i32>,
         Option<u64>,
         Option<u128>,
         Option<i32>,
         &Vec<G::MoveType>,
+        Option<&SearchStats>,
     ),
     eval: &impl Fn(&G) -> i32,
+    stats: &mut SearchStats,
 ) -> Option<G::MoveType> {
+    *stats = SearchStats::default();
     if pos.is_gameover() {
         return None;
     }
     let mut bestmove = None;
     let start = Instant::now();
     let max_depth = settings.depth.unwrap_or(128);
-    let mut stats = SearchStats::default();
     let us = pos.get_turn();
     let should_stop = |stats: &SearchStats| -> bool {
         if let Some(nodes) = settings.nodes {
@@ -125,41 +165,59 @@ pub fn minimax<G: GameRules>(
         false
     };
 
+    let mut last_score = 0;
+
     // Iterative deepening
     for depth in 1..=max_depth {
         let mut pv = vec![];
-        let score = minimax_impl(&mut pos, depth, 0, &mut stats, &mut pv, &should_stop, &eval);
+        let score = minimax_impl(&mut pos, depth, 0, stats, &mut pv, &should_stop, &eval);
         let elapsed = Instant::now() - start;
 
-        if depth > 1 && should_stop(&stats) {
+        if should_stop(stats) {
+            // Keep what got searched to the end if it beats the last iteration
+            if !pv.is_empty() && (bestmove.is_none() || score > last_score) {
+                bestmove = Some(pv[0]);
+            }
             info_handler(
                 &pos,
                 None,
                 None,
                 None,
                 None,
+                None,
                 Some(stats.nodes),
                 Some(elapsed.as_millis()),
                 None,
                 &pv,
+                None,
             );
             break;
         }
 
+        stats.iterations.push(stats.nodes);
+
         info_handler(
             &pos,
             Some(depth),
-            None,
+            Some(stats.seldepth),
             Some(score),
             None,
+            None,
             Some(stats.nodes),
             Some(elapsed.as_millis()),
             None,
             &pv,
+            Some(&*stats),
         );
 
-        bestmove = Some(pv[0]);
+        bestmove = pv.first().copied();
+        last_score = score;
+
+        if settings.debug {
+            output::emit(&Message(stats.to_string()));
+        }
     }
 
-    bestmove
+    // Stopped before searching a single move, but anything legal beats nothing
+    bestmove.or_else(|| pos.legal_moves().first().copied())
 }
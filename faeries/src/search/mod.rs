@@ -2,3 +2,57 @@ pub mod alphabeta;
 pub mod flatmc;
 pub mod minimax;
 pub mod random;
+
+use crate::bound::Bound;
+use crate::prng::XorshiftGenerator;
+use crate::searchstats::SearchStats;
+use alphabeta::alphabeta_with_stats;
+use flatmc::flatmc_with_stats;
+use games::gamerules::GameRules;
+use minimax::minimax_with_stats;
+use protocols::GoSettings;
+use random::random;
+
+/// Every search `think` knows by name.
+pub const NAMES: [&str; 5] = ["primary", "random", "minimax", "alphabeta", "flatmc"];
+
+/// Search with the search named by a game's `search` option. `primary` is the
+/// game's own search, and the rest are the generic ones with `eval` for those
+/// that need an evaluation. Whichever it is starts `stats` afresh and leaves
+/// what it counted there; a random move counts the root as its only node.
+#[must_use]
+pub fn think<G: GameRules + Clone>(
+    search: &str,
+    pos: &G,
+    settings: &GoSettings,
+    info_handler: &impl Fn(
+        &G,
+        Option<i32>,
+        Option<i32>,
+        Option<i32>,
+        Option<Bound>,
+        Option<i32>,
+        Option<u64>,
+        Option<u128>,
+        Option<i32>,
+        &Vec<G::MoveType>,
+        Option<&SearchStats>,
+    ),
+    eval: &impl Fn(&G) -> i32,
+    prng: &mut XorshiftGenerator,
+    stats: &mut SearchStats,
+    primary: impl FnOnce(&mut SearchStats) -> Option<G::MoveType>,
+) -> Option<G::MoveType> {
+    *stats = SearchStats::default();
+    match search {
+        "primary" => primary(stats),
+        "random" => {
+            stats.nodes = 1;
+            random(pos, || prng.next())
+        }
+        "minimax" => minimax_with_stats(pos.clone(), settings, info_handler, eval, stats),
+        "alphabeta" => alphabeta_with_stats(pos.clone(), settings, info_handler, eval, stats),
+        "flatmc" => flatmc_with_stats(pos.clone(), settings, info_handler, || prng.next(), stats),
+        _ => panic!("Unknown search type"),
+    }
+}
@@ -1,5 +1,93 @@
-#[derive(Default)]
+use protocols::json::{ToJson, Value};
+use std::fmt;
+
+/// What a search counted on its way, to tune move ordering and pruning with.
+///
+/// Searches that never look past the nominal depth leave `qnodes` at zero.
+#[derive(Clone, Default, Debug)]
 pub struct SearchStats {
     pub nodes: u64,
     pub seldepth: i32,
+    /// Nodes searched past the nominal depth to settle the position
+    pub qnodes: u64,
+    /// Nodes where a move scored at least beta
+    pub beta_cutoffs: u64,
+    /// How many of those were down to the first move searched
+    pub first_move_cutoffs: u64,
+    pub null_moves: u64,
+    pub null_cutoffs: u64,
+    pub lmr_reductions: u64,
+    /// Reduced searches that beat alpha and had to be done again at full depth
+    pub lmr_researches: u64,
+    /// The node count as each iteration finished
+    pub iterations: Vec<u64>,
+}
+
+impl SearchStats {
+    /// How often the best move was already at the front. The closer to 1 the
+    /// better the move ordering.
+    #[must_use]
+    pub fn first_move_cutoff_rate(&self) -> Option<f64> {
+        (self.beta_cutoffs > 0).then(|| self.first_move_cutoffs as f64 / self.beta_cutoffs as f64)
+    }
+
+    /// How many times more nodes the last iteration took than the one before.
+    #[must_use]
+    pub fn branching_factor(&self) -> Option<f64> {
+        let costs = std::iter::once(&0)
+            .chain(&self.iterations)
+            .zip(&self.iterations)
+            .map(|(before, after)| after - before)
+            .collect::<Vec<u64>>();
+        let [.., previous, last] = costs[..] else {
+            return None;
+        };
+        (previous > 0).then(|| last as f64 / previous as f64)
+    }
+}
+
+impl fmt::Display for SearchStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "nodes {} qnodes {}", self.nodes, self.qnodes)?;
+        write!(f, " seldepth {}", self.seldepth)?;
+        write!(f, " cutoffs {}", self.beta_cutoffs)?;
+        if let Some(rate) = self.first_move_cutoff_rate() {
+            write!(f, " firstcutoffrate {:.3}", rate)?;
+        }
+        write!(
+            f,
+            " nullmoves {} nullcutoffs {}",
+            self.null_moves, self.null_cutoffs
+        )?;
+        write!(f, " reductions {}", self.lmr_reductions)?;
+        write!(f, " researches {}", self.lmr_researches)?;
+        if let Some(ebf) = self.branching_factor() {
+            write!(f, " ebf {:.2}", ebf)?;
+        }
+        Ok(())
+    }
+}
+
+/// Every field, with the rates worked out from them, null where there's nothing
+/// to work them out from.
+impl ToJson for SearchStats {
+    fn to_json(&self) -> Value {
+        Value::Object(vec![
+            ("nodes", self.nodes.into()),
+            ("qnodes", self.qnodes.into()),
+            ("seldepth", self.seldepth.into()),
+            ("beta_cutoffs", self.beta_cutoffs.into()),
+            ("first_move_cutoffs", self.first_move_cutoffs.into()),
+            (
+                "first_move_cutoff_rate",
+                self.first_move_cutoff_rate().into(),
+            ),
+            ("null_moves", self.null_moves.into()),
+            ("null_cutoffs", self.null_cutoffs.into()),
+            ("lmr_reductions", self.lmr_reductions.into()),
+            ("lmr_researches", self.lmr_researches.into()),
+            ("iterations", self.iterations.clone().into()),
+            ("branching_factor", self.branching_factor().into()),
+        ])
+    }
 }
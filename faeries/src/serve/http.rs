@@ -0,0 +1,118 @@
+use protocols::json::Value;
+use std::fmt;
+use std::io::{self, BufRead, ErrorKind, Write};
+
+/// Requests are a few fields of JSON, so anything much bigger isn't one.
+const MAX_BODY: usize = 1 << 20;
+
+/// As much of an HTTP request as the server looks at.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub body: String,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Status {
+    Ok,
+    BadRequest,
+    NotFound,
+    MethodNotAllowed,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Ok => write!(f, "200 OK"),
+            Status::BadRequest => write!(f, "400 Bad Request"),
+            Status::NotFound => write!(f, "404 Not Found"),
+            Status::MethodNotAllowed => write!(f, "405 Method Not Allowed"),
+        }
+    }
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, msg)
+}
+
+/// Read the request line, the headers, and as much body as `Content-Length`
+/// says there is.
+pub fn read_request(reader: &mut impl BufRead) -> io::Result<Request> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Err(ErrorKind::UnexpectedEof.into());
+    }
+    let mut words = line.split_ascii_whitespace();
+    let (Some(method), Some(path)) = (words.next(), words.next()) else {
+        return Err(invalid("bad request line"));
+    };
+
+    let mut length = 0;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 {
+            return Err(ErrorKind::UnexpectedEof.into());
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+
+        match header.split_once(':') {
+            Some((name, value)) if name.eq_ignore_ascii_case("content-length") => {
+                length = value
+                    .trim()
+                    .parse::<usize>()
+                    .map_err(|_| invalid("bad content length"))?;
+            }
+            _ => {}
+        }
+    }
+
+    if length > MAX_BODY {
+        return Err(invalid("body too long"));
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+
+    Ok(Request {
+        method: method.to_owned(),
+        path: path.to_owned(),
+        body: String::from_utf8(body).map_err(|_| invalid("body isn't UTF-8"))?,
+    })
+}
+
+/// A whole response, one JSON value long.
+pub fn respond(stream: &mut impl Write, status: Status, body: &Value) -> io::Result<()> {
+    let body = format!("{}\n", body);
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+/// Start a response that's sent a JSON line at a time, each as it's ready.
+pub fn start_lines(stream: &mut impl Write) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/x-ndjson\r\nTransfer-Encoding: chunked\r\nConnection: close\r\n\r\n",
+        Status::Ok
+    )?;
+    stream.flush()
+}
+
+pub fn write_line(stream: &mut impl Write, line: &Value) -> io::Result<()> {
+    let line = format!("{}\n", line);
+    write!(stream, "{:x}\r\n{}\r\n", line.len(), line)?;
+    stream.flush()
+}
+
+pub fn finish_lines(stream: &mut impl Write) -> io::Result<()> {
+    stream.write_all(b"0\r\n\r\n")?;
+    stream.flush()
+}
@@ -0,0 +1,283 @@
+pub mod http;
+pub mod servable;
+
+use crate::args::{Game, parse_game};
+use crate::checkers::position::CheckersPosition;
+use crate::connect4::position::Connect4Position;
+use crate::droptaxx::position::DroptaxxPosition;
+use crate::gomoku::position::GomokuPosition;
+use crate::othello::position::OthelloPosition;
+use crate::searchstats::SearchStats;
+use crate::shogi::position::ShogiPosition;
+use games::gamerules::GameResult;
+use games::general::side::Side;
+use games::perft;
+use games::{
+    ataxx::AtaxxPosition, chess::ChessPosition, isolation::IsolationPosition,
+    pijersi::PijersiPosition, tak::TakPosition,
+};
+use http::{Request, Status};
+use protocols::GoSettings;
+use protocols::json::{ToJson, Value, parse_object};
+use protocols::output::{self, Bestmove};
+use servable::Servable;
+use std::io::{self, BufReader};
+use std::net::{TcpListener, TcpStream};
+
+/// The games that can be asked about, by the names they're asked for with.
+pub const GAMES: [&str; 11] = [
+    "ataxx",
+    "checkers",
+    "chess",
+    "connect4",
+    "droptaxx",
+    "gomoku",
+    "isolation",
+    "othello",
+    "pijersi",
+    "shogi",
+    "tak",
+];
+
+type Fields = [(String, Value)];
+
+/// Answer requests on `listener`, each connection on a thread of its own, for
+/// as long as it accepts them.
+///
+/// Every request but `GET /` is a POST of a JSON object naming the `game`, with
+/// an optional `fen` and `moves` to play from it:
+///
+/// - `/moves` lists the legal moves
+/// - `/play` gives the position the moves lead to, whose turn it is, the result
+///   and the legal moves
+/// - `/perft` counts to a `depth`
+/// - `/analyse` searches to a `depth`, `nodes` or `movetime`
+///
+/// Perft and analysis are streamed back a JSON line at a time in a chunked
+/// response, in the same schema as `--json` output, ending with their node
+/// count or best move.
+pub fn serve(listener: TcpListener) -> io::Result<()> {
+    for stream in listener.incoming() {
+        let stream = stream?;
+        std::thread::spawn(move || {
+            // Nothing can be done for a client that's gone
+            let _ = handle(stream);
+        });
+    }
+    Ok(())
+}
+
+fn error(msg: &str) -> Value {
+    output::Error(msg.to_owned()).to_json()
+}
+
+fn reject(stream: &mut TcpStream, msg: &str) -> io::Result<()> {
+    http::respond(stream, Status::BadRequest, &error(msg))
+}
+
+fn handle(mut stream: TcpStream) -> io::Result<()> {
+    let request = match http::read_request(&mut BufReader::new(stream.try_clone()?)) {
+        Ok(request) => request,
+        Err(e) => return reject(&mut stream, &e.to_string()),
+    };
+
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/") => http::respond(&mut stream, Status::Ok, &about()),
+        ("POST", "/moves" | "/play" | "/perft" | "/analyse") => dispatch(&mut stream, &request),
+        (_, "/" | "/moves" | "/play" | "/perft" | "/analyse") => {
+            let msg = format!("{} not allowed on {}", request.method, request.path);
+            http::respond(&mut stream, Status::MethodNotAllowed, &error(&msg))
+        }
+        _ => {
+            let msg = format!("no such path {}", request.path);
+            http::respond(&mut stream, Status::NotFound, &error(&msg))
+        }
+    }
+}
+
+fn about() -> Value {
+    Value::Object(vec![
+        ("type", "about".into()),
+        ("name", "Faeries".into()),
+        ("version", env!("CARGO_PKG_VERSION").into()),
+        ("games", GAMES.to_vec().into()),
+    ])
+}
+
+fn dispatch(stream: &mut TcpStream, request: &Request) -> io::Result<()> {
+    let fields = match parse_object(&request.body) {
+        Ok(fields) => fields,
+        Err(e) => return reject(stream, &format!("bad request {}", e)),
+    };
+    let Some(name) = field(&fields, "game").and_then(Value::as_str) else {
+        return reject(stream, "expected a game");
+    };
+
+    let path = request.path.as_str();
+    match parse_game(name) {
+        Some(Game::Ataxx) => answer::<AtaxxPosition<7, 7>>(stream, path, &fields),
+        Some(Game::Checkers) => answer::<CheckersPosition>(stream, path, &fields),
+        Some(Game::Chess) => answer::<ChessPosition>(stream, path, &fields),
+        Some(Game::Connect4) => answer::<Connect4Position>(stream, path, &fields),
+        Some(Game::Droptaxx) => answer::<DroptaxxPosition>(stream, path, &fields),
+        Some(Game::Gomoku) => answer::<GomokuPosition>(stream, path, &fields),
+        Some(Game::Isolation) => answer::<IsolationPosition>(stream, path, &fields),
+        Some(Game::Othello) => answer::<OthelloPosition>(stream, path, &fields),
+        Some(Game::Pijersi) => answer::<PijersiPosition>(stream, path, &fields),
+        Some(Game::Shogi) => answer::<ShogiPosition>(stream, path, &fields),
+        Some(Game::Tak) => answer::<TakPosition<6>>(stream, path, &fields),
+        Some(Game::Baduk) => reject(stream, &format!("can't serve {}", name)),
+        None => reject(stream, &format!("unknown game {}", name)),
+    }
+}
+
+fn answer<G: Servable>(stream: &mut TcpStream, path: &str, fields: &Fields) -> io::Result<()> {
+    let mut pos = match position::<G>(fields) {
+        Ok(pos) => pos,
+        Err(msg) => return reject(stream, &msg),
+    };
+
+    match path {
+        "/moves" => {
+            let moves = Value::Object(vec![
+                ("type", "moves".into()),
+                ("moves", legal_moves(&pos).into()),
+            ]);
+            http::respond(stream, Status::Ok, &moves)
+        }
+        "/play" => http::respond(stream, Status::Ok, &describe(&pos)),
+        "/perft" => {
+            let depth = match number::<i32>(fields, "depth") {
+                Ok(Some(depth)) if depth > 0 => depth,
+                Ok(_) => return reject(stream, "perft needs a depth"),
+                Err(msg) => return reject(stream, &msg),
+            };
+            stream_lines(stream, || {
+                perft::perft(&mut pos, depth, &output::perft, &output::nodes);
+            })
+        }
+        "/analyse" => {
+            let settings = match settings(fields) {
+                Ok(settings) => settings,
+                Err(msg) => return reject(stream, &msg),
+            };
+            stream_lines(stream, || {
+                let mut stats = SearchStats::default();
+                let bestmove = pos.search(&settings, &mut stats);
+                output::emit(&Bestmove {
+                    mv: bestmove.map_or("0000".to_owned(), |mv| pos.move_string(&mv)),
+                    stats: stats.to_json(),
+                });
+            })
+        }
+        _ => unreachable!(),
+    }
+}
+
+/// Send every line `f` emits as soon as it's emitted. A client that goes away
+/// doesn't stop `f`, which runs to its limits regardless.
+fn stream_lines(stream: &mut TcpStream, f: impl FnOnce()) -> io::Result<()> {
+    http::start_lines(stream)?;
+    let mut sink = stream.try_clone()?;
+    output::capture(
+        move |line| {
+            let _ = http::write_line(&mut sink, line);
+        },
+        f,
+    );
+    http::finish_lines(stream)
+}
+
+fn field<'a>(fields: &'a Fields, name: &str) -> Option<&'a Value> {
+    fields
+        .iter()
+        .find(|(field, _)| field == name)
+        .map(|(_, value)| value)
+}
+
+/// A number that has to fit `T`, if it's there at all.
+fn number<T: TryFrom<i128>>(fields: &Fields, name: &str) -> Result<Option<T>, String> {
+    match field(fields, name) {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => value
+            .as_int()
+            .and_then(|n| T::try_from(n).ok())
+            .map(Some)
+            .ok_or_else(|| format!("bad {}", name)),
+    }
+}
+
+/// The `fen`, or the start position, with the `moves` played on it. Some games
+/// panic on a FEN they can't read, which only costs the request.
+fn position<G: Servable>(fields: &Fields) -> Result<G, String> {
+    let mut pos = match field(fields, "fen") {
+        None | Some(Value::Null) => G::startpos(),
+        Some(Value::String(fen)) if fen == "startpos" => G::startpos(),
+        Some(Value::String(fen)) => match std::panic::catch_unwind(|| G::from_fen(fen)) {
+            Ok(pos) => pos,
+            Err(_) => return Err(format!("bad fen {}", fen)),
+        },
+        Some(_) => return Err("bad fen".to_owned()),
+    };
+
+    let moves = match field(fields, "moves") {
+        None | Some(Value::Null) => &[][..],
+        Some(value) => value.as_array().ok_or("bad moves")?,
+    };
+    for movestr in moves {
+        let Some(movestr) = movestr.as_str() else {
+            return Err("bad moves".to_owned());
+        };
+        if pos.is_gameover() {
+            return Err(format!("game over before {}", movestr));
+        }
+        match pos.parse_move(movestr) {
+            Some(mv) => pos.makemove(&mv),
+            None => return Err(format!("illegal move {}", movestr)),
+        }
+    }
+
+    Ok(pos)
+}
+
+/// An analysis has to stop by itself, so it needs at least one limit.
+fn settings(fields: &Fields) -> Result<GoSettings, String> {
+    let settings = GoSettings {
+        depth: number(fields, "depth")?,
+        nodes: number(fields, "nodes")?,
+        movetime: number(fields, "movetime")?,
+        ..GoSettings::default()
+    };
+
+    if settings.depth.is_none() && settings.nodes.is_none() && settings.movetime.is_none() {
+        return Err("analyse needs a depth, nodes or movetime".to_owned());
+    }
+    Ok(settings)
+}
+
+fn legal_moves<G: Servable>(pos: &G) -> Vec<String> {
+    pos.legal_moves()
+        .iter()
+        .map(|mv| pos.move_string(mv))
+        .collect()
+}
+
+fn describe<G: Servable>(pos: &G) -> Value {
+    let side = |side: Side| match side {
+        Side::Player1 => "p1",
+        Side::Player2 => "p2",
+    };
+    let result = match pos.get_result() {
+        Some(GameResult::Win(winner)) => side(winner).into(),
+        Some(GameResult::Draw) => "draw".into(),
+        None => Value::Null,
+    };
+
+    Value::Object(vec![
+        ("type", "position".into()),
+        ("fen", pos.get_fen().into()),
+        ("turn", side(pos.get_turn()).into()),
+        ("result", result),
+        ("moves", legal_moves(pos).into()),
+    ])
+}
@@ -0,0 +1,160 @@
+use crate::checkers::position::CheckersPosition;
+use crate::connect4::position::Connect4Position;
+use crate::droptaxx::position::DroptaxxPosition;
+use crate::gomoku::position::GomokuPosition;
+use crate::othello::position::OthelloPosition;
+use crate::searchstats::SearchStats;
+use crate::shogi::position::ShogiPosition;
+use crate::{
+    ataxx, checkers, chess, connect4, droptaxx, gomoku, isolation, othello, pijersi, shogi, tak,
+};
+use games::{
+    ataxx::AtaxxPosition, chess::ChessPosition, gamerules::GameRules, isolation::IsolationPosition,
+    pijersi::PijersiPosition, tak::TakPosition,
+};
+use protocols::GoSettings;
+
+/// A game the server can analyse, with its moves written as its protocol
+/// writes them.
+pub trait Servable: GameRules + Clone {
+    #[must_use]
+    fn move_string(&self, mv: &Self::MoveType) -> String;
+
+    /// Search with the engine's own search, its info going through the game's
+    /// usual info handler.
+    #[must_use]
+    fn search(&self, settings: &GoSettings, stats: &mut SearchStats) -> Option<Self::MoveType>;
+
+    #[must_use]
+    fn parse_move(&self, movestr: &str) -> Option<Self::MoveType> {
+        self.legal_moves()
+            .into_iter()
+            .find(|mv| self.move_string(mv) == movestr)
+    }
+}
+
+impl Servable for AtaxxPosition<7, 7> {
+    fn move_string(&self, mv: &Self::MoveType) -> String {
+        ataxx::ugi::as_ugi(mv)
+    }
+
+    fn search(&self, settings: &GoSettings, stats: &mut SearchStats) -> Option<Self::MoveType> {
+        let info_handler = ataxx::ugi::info_handler;
+        ataxx::root::primary_with_stats(self.clone(), settings, &info_handler, stats)
+    }
+}
+
+impl Servable for CheckersPosition {
+    fn move_string(&self, mv: &Self::MoveType) -> String {
+        checkers::ugi::as_pdn(self, mv)
+    }
+
+    fn search(&self, settings: &GoSettings, stats: &mut SearchStats) -> Option<Self::MoveType> {
+        let info_handler = checkers::ugi::info_handler;
+        checkers::root::primary_with_stats(self.clone(), settings, &info_handler, stats)
+    }
+
+    /// Captures can be given by their first and last squares alone.
+    fn parse_move(&self, movestr: &str) -> Option<Self::MoveType> {
+        checkers::ugi::parse_move(self, movestr)
+    }
+}
+
+impl Servable for ChessPosition {
+    fn move_string(&self, mv: &Self::MoveType) -> String {
+        chess::ugi::as_ugi(self, mv)
+    }
+
+    fn search(&self, settings: &GoSettings, stats: &mut SearchStats) -> Option<Self::MoveType> {
+        let info_handler = chess::ugi::info_handler;
+        chess::root::primary_with_stats(self.clone(), settings, &info_handler, stats)
+    }
+}
+
+impl Servable for Connect4Position {
+    fn move_string(&self, mv: &Self::MoveType) -> String {
+        connect4::ugi::as_ugi(mv)
+    }
+
+    fn search(&self, settings: &GoSettings, stats: &mut SearchStats) -> Option<Self::MoveType> {
+        let info_handler = connect4::ugi::info_handler;
+        connect4::root::primary_with_stats(self.clone(), settings, &info_handler, stats)
+    }
+}
+
+impl Servable for DroptaxxPosition {
+    fn move_string(&self, mv: &Self::MoveType) -> String {
+        droptaxx::ugi::as_ugi(mv)
+    }
+
+    fn search(&self, settings: &GoSettings, stats: &mut SearchStats) -> Option<Self::MoveType> {
+        let info_handler = droptaxx::ugi::info_handler;
+        droptaxx::root::primary_with_stats(self.clone(), settings, &info_handler, stats)
+    }
+}
+
+impl Servable for GomokuPosition {
+    fn move_string(&self, mv: &Self::MoveType) -> String {
+        gomoku::ugi::as_ugi(self, mv)
+    }
+
+    fn search(&self, settings: &GoSettings, stats: &mut SearchStats) -> Option<Self::MoveType> {
+        let info_handler = gomoku::ugi::info_handler;
+        gomoku::root::primary_with_stats(self.clone(), settings, &info_handler, stats)
+    }
+}
+
+impl Servable for IsolationPosition {
+    fn move_string(&self, mv: &Self::MoveType) -> String {
+        isolation::ugi::as_ugi(mv)
+    }
+
+    fn search(&self, settings: &GoSettings, stats: &mut SearchStats) -> Option<Self::MoveType> {
+        let info_handler = isolation::ugi::info_handler;
+        isolation::root::primary_with_stats(self.clone(), settings, &info_handler, stats)
+    }
+}
+
+impl Servable for OthelloPosition {
+    fn move_string(&self, mv: &Self::MoveType) -> String {
+        othello::ugi::as_ugi(mv)
+    }
+
+    fn search(&self, settings: &GoSettings, stats: &mut SearchStats) -> Option<Self::MoveType> {
+        let info_handler = othello::ugi::info_handler;
+        othello::root::primary_with_stats(self.clone(), settings, &info_handler, stats)
+    }
+}
+
+impl Servable for PijersiPosition {
+    fn move_string(&self, mv: &Self::MoveType) -> String {
+        pijersi::ugi::as_ugi(mv)
+    }
+
+    fn search(&self, settings: &GoSettings, stats: &mut SearchStats) -> Option<Self::MoveType> {
+        let info_handler = pijersi::ugi::info_handler;
+        pijersi::root::primary_with_stats(self.clone(), settings, &info_handler, stats)
+    }
+}
+
+impl Servable for ShogiPosition {
+    fn move_string(&self, mv: &Self::MoveType) -> String {
+        mv.to_string()
+    }
+
+    fn search(&self, settings: &GoSettings, stats: &mut SearchStats) -> Option<Self::MoveType> {
+        let info_handler = shogi::usi::info_handler;
+        shogi::root::primary_with_stats(self.clone(), settings, &info_handler, stats)
+    }
+}
+
+impl Servable for TakPosition<6> {
+    fn move_string(&self, mv: &Self::MoveType) -> String {
+        tak::ugi::as_ugi(self, mv)
+    }
+
+    fn search(&self, settings: &GoSettings, stats: &mut SearchStats) -> Option<Self::MoveType> {
+        let info_handler = tak::ugi::info_handler;
+        tak::root::primary_with_stats(self.clone(), settings, &info_handler, stats)
+    }
+}
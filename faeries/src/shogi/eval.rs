@@ -0,0 +1,86 @@
+use super::position::{HAND_PIECES, ShogiPosition};
+use crate::tune::{Tunable, difference, dot};
+use games::{gamerules::GameRules, general::side::Side};
+
+/// Pieces on the board in `Piece` order, then pieces in hand in `HAND_PIECES`
+/// order.
+pub const NAMES: [&str; 21] = [
+    "pawn",
+    "lance",
+    "knight",
+    "silver",
+    "gold",
+    "bishop",
+    "rook",
+    "king",
+    "tokin",
+    "promoted_lance",
+    "promoted_knight",
+    "promoted_silver",
+    "horse",
+    "dragon",
+    "hand_rook",
+    "hand_bishop",
+    "hand_gold",
+    "hand_silver",
+    "hand_knight",
+    "hand_lance",
+    "hand_pawn",
+];
+
+/// A piece in hand can be dropped wherever it's needed, so it's worth a tenth
+/// more than on the board.
+pub const WEIGHTS: [i32; 21] = [
+    100, 300, 350, 450, 500, 700, 800, 0, 550, 500, 500, 500, 950, 1100, 880, 770, 550, 495, 385,
+    330, 110,
+];
+
+const BOARD_FEATURES: usize = 14;
+
+#[must_use]
+fn features_side(pos: &ShogiPosition, side: Side) -> [i32; 21] {
+    let mut features = [0; 21];
+
+    for sq in 0..81 {
+        if let Some((owner, piece)) = pos.get_piece(sq) {
+            if owner == side {
+                features[piece as usize] += 1;
+            }
+        }
+    }
+
+    for (idx, piece) in HAND_PIECES.into_iter().enumerate() {
+        features[BOARD_FEATURES + idx] = pos.get_hand(side, piece) as i32;
+    }
+
+    features
+}
+
+#[must_use]
+pub fn features(pos: &ShogiPosition) -> [i32; 21] {
+    let black = features_side(pos, Side::Player1);
+    let white = features_side(pos, Side::Player2);
+
+    match pos.get_turn() {
+        Side::Player1 => difference(black, white),
+        Side::Player2 => difference(white, black),
+    }
+}
+
+#[must_use]
+pub fn eval(pos: &ShogiPosition) -> i32 {
+    dot(&WEIGHTS, &features(pos))
+}
+
+impl Tunable for ShogiPosition {
+    const NAMES: &'static [&'static str] = &NAMES;
+    const WEIGHTS: &'static [i32] = &WEIGHTS;
+
+    fn features(&self) -> Vec<i32> {
+        features(self).to_vec()
+    }
+
+    fn first_to_move(&self) -> bool {
+        self.get_turn() == Side::Player1
+    }
+}
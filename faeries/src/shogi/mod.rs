@@ -0,0 +1,6 @@
+pub mod eval;
+pub mod position;
+pub mod root;
+pub mod search;
+pub mod state;
+pub mod usi;
@@ -0,0 +1,850 @@
+use crate::hash::{self, TURN_KEY};
+use games::{
+    gamerules::{GameResult, GameRules},
+    general::side::Side,
+};
+use std::fmt;
+
+pub const STARTPOS_FEN: &str = "lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1";
+
+/// How many times the same position has to come up for the game to be over.
+pub const REPETITIONS: usize = 4;
+
+/// The first seven can be held in hand, and are in the same order as a hand is
+/// stored.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Piece {
+    Pawn,
+    Lance,
+    Knight,
+    Silver,
+    Gold,
+    Bishop,
+    Rook,
+    King,
+    Tokin,
+    PromotedLance,
+    PromotedKnight,
+    PromotedSilver,
+    Horse,
+    Dragon,
+}
+
+/// The pieces in hand, in the order SFEN lists them.
+pub const HAND_PIECES: [Piece; 7] = [
+    Piece::Rook,
+    Piece::Bishop,
+    Piece::Gold,
+    Piece::Silver,
+    Piece::Knight,
+    Piece::Lance,
+    Piece::Pawn,
+];
+
+const GOLD_STEPS: &[(i32, i32)] = &[(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (0, 1)];
+const SILVER_STEPS: &[(i32, i32)] = &[(-1, -1), (0, -1), (1, -1), (-1, 1), (1, 1)];
+const DIAGONALS: &[(i32, i32)] = &[(-1, -1), (1, -1), (-1, 1), (1, 1)];
+const ORTHOGONALS: &[(i32, i32)] = &[(0, -1), (-1, 0), (1, 0), (0, 1)];
+const ALL_STEPS: &[(i32, i32)] = &[
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+impl Piece {
+    #[must_use]
+    pub fn promoted(self) -> Option<Piece> {
+        match self {
+            Piece::Pawn => Some(Piece::Tokin),
+            Piece::Lance => Some(Piece::PromotedLance),
+            Piece::Knight => Some(Piece::PromotedKnight),
+            Piece::Silver => Some(Piece::PromotedSilver),
+            Piece::Bishop => Some(Piece::Horse),
+            Piece::Rook => Some(Piece::Dragon),
+            _ => None,
+        }
+    }
+
+    /// What the piece goes back to when it's captured.
+    #[must_use]
+    pub fn unpromoted(self) -> Piece {
+        match self {
+            Piece::Tokin => Piece::Pawn,
+            Piece::PromotedLance => Piece::Lance,
+            Piece::PromotedKnight => Piece::Knight,
+            Piece::PromotedSilver => Piece::Silver,
+            Piece::Horse => Piece::Bishop,
+            Piece::Dragon => Piece::Rook,
+            _ => self,
+        }
+    }
+
+    #[must_use]
+    pub fn is_promoted(self) -> bool {
+        self.unpromoted() != self
+    }
+
+    /// The SFEN letter for Black's piece, without the `+` of a promoted one.
+    #[must_use]
+    pub fn letter(self) -> char {
+        match self.unpromoted() {
+            Piece::Pawn => 'P',
+            Piece::Lance => 'L',
+            Piece::Knight => 'N',
+            Piece::Silver => 'S',
+            Piece::Gold => 'G',
+            Piece::Bishop => 'B',
+            Piece::Rook => 'R',
+            _ => 'K',
+        }
+    }
+
+    #[must_use]
+    pub fn from_letter(c: char) -> Option<Piece> {
+        match c.to_ascii_uppercase() {
+            'P' => Some(Piece::Pawn),
+            'L' => Some(Piece::Lance),
+            'N' => Some(Piece::Knight),
+            'S' => Some(Piece::Silver),
+            'G' => Some(Piece::Gold),
+            'B' => Some(Piece::Bishop),
+            'R' => Some(Piece::Rook),
+            'K' => Some(Piece::King),
+            _ => None,
+        }
+    }
+
+    /// Single steps, as seen by Black with the far side of the board up.
+    #[must_use]
+    fn steps(self) -> &'static [(i32, i32)] {
+        match self {
+            Piece::Pawn => &[(0, -1)],
+            Piece::Knight => &[(-1, -2), (1, -2)],
+            Piece::Silver => SILVER_STEPS,
+            Piece::Gold
+            | Piece::Tokin
+            | Piece::PromotedLance
+            | Piece::PromotedKnight
+            | Piece::PromotedSilver => GOLD_STEPS,
+            Piece::King => ALL_STEPS,
+            Piece::Horse => ORTHOGONALS,
+            Piece::Dragon => DIAGONALS,
+            Piece::Lance | Piece::Bishop | Piece::Rook => &[],
+        }
+    }
+
+    /// Directions the piece can move any distance in, as seen by Black.
+    #[must_use]
+    fn slides(self) -> &'static [(i32, i32)] {
+        match self {
+            Piece::Lance => &[(0, -1)],
+            Piece::Bishop | Piece::Horse => DIAGONALS,
+            Piece::Rook | Piece::Dragon => ORTHOGONALS,
+            _ => &[],
+        }
+    }
+}
+
+/// Squares are numbered from 9a = 0 across to 1a = 8 and down to 1i = 80, the
+/// order SFEN lists them in. Black starts at the bottom.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ShogiMove {
+    Move { from: u8, to: u8, promote: bool },
+    Drop { piece: Piece, to: u8 },
+}
+
+impl fmt::Display for ShogiMove {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            ShogiMove::Move { from, to, promote } => write!(
+                f,
+                "{}{}{}",
+                square_name(from),
+                square_name(to),
+                if promote { "+" } else { "" }
+            ),
+            ShogiMove::Drop { piece, to } => write!(f, "{}*{}", piece.letter(), square_name(to)),
+        }
+    }
+}
+
+type Board = [Option<(Side, Piece)>; 81];
+
+/// Pieces are keyed by side and kind, and hands come after the board as a stack
+/// for each side, its height the number of a piece held.
+#[must_use]
+fn piece_key(sq: usize, side: Side, piece: Piece) -> u64 {
+    hash::piece_key(sq, 0, (side_index(side) * 14 + piece as usize) as u8)
+}
+
+#[must_use]
+fn hand_key(side: usize, piece: usize, count: u8) -> u64 {
+    hash::piece_key(81 + side, count as usize, piece as u8)
+}
+
+#[must_use]
+fn side_index(side: Side) -> usize {
+    match side {
+        Side::Player1 => 0,
+        Side::Player2 => 1,
+    }
+}
+
+#[must_use]
+fn other(side: Side) -> Side {
+    match side {
+        Side::Player1 => Side::Player2,
+        Side::Player2 => Side::Player1,
+    }
+}
+
+/// Turn a direction as seen by Black into one for `side`, or back again.
+#[must_use]
+fn relative(side: Side, dx: i32, dy: i32) -> (i32, i32) {
+    match side {
+        Side::Player1 => (dx, dy),
+        Side::Player2 => (dx, -dy),
+    }
+}
+
+#[must_use]
+fn offset(sq: u8, dx: i32, dy: i32) -> Option<u8> {
+    let x = (sq % 9) as i32 + dx;
+    let y = (sq / 9) as i32 + dy;
+    if (0..9).contains(&x) && (0..9).contains(&y) {
+        Some((9 * y + x) as u8)
+    } else {
+        None
+    }
+}
+
+/// How many ranks there are in front of `sq` for `side`.
+#[must_use]
+fn ranks_ahead(side: Side, sq: u8) -> u8 {
+    match side {
+        Side::Player1 => sq / 9,
+        Side::Player2 => 8 - sq / 9,
+    }
+}
+
+#[must_use]
+fn in_zone(side: Side, sq: u8) -> bool {
+    ranks_ahead(side, sq) < 3
+}
+
+/// Whether a piece on `sq` would never be able to move again.
+#[must_use]
+fn is_stuck(side: Side, piece: Piece, sq: u8) -> bool {
+    match piece {
+        Piece::Pawn | Piece::Lance => ranks_ahead(side, sq) < 1,
+        Piece::Knight => ranks_ahead(side, sq) < 2,
+        _ => false,
+    }
+}
+
+#[must_use]
+fn king_square(board: &Board, side: Side) -> Option<u8> {
+    (0..81).find(|&sq| board[sq as usize] == Some((side, Piece::King)))
+}
+
+#[must_use]
+fn is_attacked(board: &Board, sq: u8, by: Side) -> bool {
+    for &(dx, dy) in ALL_STEPS {
+        let mut at = sq;
+        let mut adjacent = true;
+        while let Some(next) = offset(at, dx, dy) {
+            at = next;
+            if let Some((side, piece)) = board[at as usize] {
+                // The piece would have to come back the way we looked
+                let back = relative(by, -dx, -dy);
+                if side == by
+                    && ((adjacent && piece.steps().contains(&back))
+                        || piece.slides().contains(&back))
+                {
+                    return true;
+                }
+                break;
+            }
+            adjacent = false;
+        }
+    }
+
+    Piece::Knight.steps().iter().any(|&(dx, dy)| {
+        let (dx, dy) = relative(by, dx, dy);
+        offset(sq, -dx, -dy).is_some_and(|from| board[from as usize] == Some((by, Piece::Knight)))
+    })
+}
+
+#[must_use]
+fn in_check(board: &Board, side: Side) -> bool {
+    king_square(board, side).is_some_and(|sq| is_attacked(board, sq, other(side)))
+}
+
+/// Make a move on the board alone, for checking what it leaves attacked.
+fn apply(board: &mut Board, side: Side, mv: &ShogiMove) {
+    match *mv {
+        ShogiMove::Move { from, to, promote } => {
+            let (_, piece) = board[from as usize].expect("No piece to move");
+            let piece = if promote {
+                piece.promoted().expect("Piece can't promote")
+            } else {
+                piece
+            };
+            board[from as usize] = None;
+            board[to as usize] = Some((side, piece));
+        }
+        ShogiMove::Drop { piece, to } => board[to as usize] = Some((side, piece)),
+    }
+}
+
+fn add_move(side: Side, piece: Piece, from: u8, to: u8, moves: &mut Vec<ShogiMove>) {
+    if piece.promoted().is_some() && (in_zone(side, from) || in_zone(side, to)) {
+        moves.push(ShogiMove::Move {
+            from,
+            to,
+            promote: true,
+        });
+    }
+    if !is_stuck(side, piece, to) {
+        moves.push(ShogiMove::Move {
+            from,
+            to,
+            promote: false,
+        });
+    }
+}
+
+/// Every move of a piece on the board, whether or not it leaves the king in check.
+fn add_board_moves(board: &Board, side: Side, moves: &mut Vec<ShogiMove>) {
+    for from in 0..81 {
+        let Some((owner, piece)) = board[from as usize] else {
+            continue;
+        };
+        if owner != side {
+            continue;
+        }
+
+        for &(dx, dy) in piece.steps() {
+            let (dx, dy) = relative(side, dx, dy);
+            if let Some(to) = offset(from, dx, dy) {
+                if !board[to as usize].is_some_and(|(owner, _)| owner == side) {
+                    add_move(side, piece, from, to, moves);
+                }
+            }
+        }
+
+        for &(dx, dy) in piece.slides() {
+            let (dx, dy) = relative(side, dx, dy);
+            let mut to = from;
+            while let Some(next) = offset(to, dx, dy) {
+                to = next;
+                match board[to as usize] {
+                    None => add_move(side, piece, from, to, moves),
+                    Some((owner, _)) => {
+                        if owner != side {
+                            add_move(side, piece, from, to, moves);
+                        }
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Shogi with drops. A side that can't get out of check has lost, as has one with
+/// no moves at all. Black moves first.
+///
+/// Pawns can't be dropped on a file that already has one of the same side's
+/// unpromoted pawns, nor to give mate. The same position four times over is a
+/// draw, unless one side gave check every move since it first came up, in which
+/// case that side loses.
+#[derive(Clone, Debug)]
+pub struct ShogiPosition {
+    board: Board,
+    hands: [[u8; 7]; 2],
+    turn: Side,
+    /// The SFEN move number, which counts every move by either side
+    moves: i32,
+    /// The piece each move took, so that it can be taken back
+    undo: Vec<Option<Piece>>,
+    /// The hash of every position since the last `set_fen`, and whether the side to
+    /// move was in check there. The last is the current position.
+    keys: Vec<u64>,
+    checks: Vec<bool>,
+}
+
+impl ShogiPosition {
+    #[must_use]
+    pub fn get_piece(&self, sq: u8) -> Option<(Side, Piece)> {
+        self.board[sq as usize]
+    }
+
+    #[must_use]
+    pub fn get_hand(&self, side: Side, piece: Piece) -> u8 {
+        self.hands[side_index(side)][piece as usize]
+    }
+
+    #[must_use]
+    pub fn get_hash(&self) -> u64 {
+        *self.keys.last().expect("No position")
+    }
+
+    #[must_use]
+    pub fn is_check(&self) -> bool {
+        *self.checks.last().expect("No position")
+    }
+
+    #[must_use]
+    fn square_key(&self, sq: usize) -> u64 {
+        self.board[sq].map_or(0, |(side, piece)| piece_key(sq, side, piece))
+    }
+
+    #[must_use]
+    fn hand_key(&self, side: usize, piece: Piece) -> u64 {
+        hand_key(side, piece as usize, self.hands[side][piece as usize])
+    }
+
+    #[must_use]
+    fn hash(&self) -> u64 {
+        let mut hash = match self.turn {
+            Side::Player1 => 0,
+            Side::Player2 => TURN_KEY,
+        };
+
+        for (sq, square) in self.board.iter().enumerate() {
+            if let Some((side, piece)) = *square {
+                hash ^= piece_key(sq, side, piece);
+            }
+        }
+
+        for (side, hand) in self.hands.iter().enumerate() {
+            for (piece, count) in hand.iter().enumerate() {
+                hash ^= hand_key(side, piece, *count);
+            }
+        }
+
+        hash
+    }
+
+    /// Start the record of positions again from this one.
+    fn reset_history(&mut self) {
+        self.undo.clear();
+        self.keys = vec![self.hash()];
+        self.checks = vec![in_check(&self.board, self.turn)];
+    }
+
+    #[must_use]
+    fn has_pawn_on_file(&self, side: Side, file: u8) -> bool {
+        (0..9).any(|rank| self.board[(9 * rank + file) as usize] == Some((side, Piece::Pawn)))
+    }
+
+    fn add_drops(&self, moves: &mut Vec<ShogiMove>) {
+        let hand = &self.hands[side_index(self.turn)];
+
+        for piece in HAND_PIECES {
+            if hand[piece as usize] == 0 {
+                continue;
+            }
+
+            for to in 0..81 {
+                if self.board[to as usize].is_some()
+                    || is_stuck(self.turn, piece, to)
+                    || (piece == Piece::Pawn && self.has_pawn_on_file(self.turn, to % 9))
+                {
+                    continue;
+                }
+                moves.push(ShogiMove::Drop { piece, to });
+            }
+        }
+    }
+
+    #[must_use]
+    fn leaves_check(&self, mv: &ShogiMove) -> bool {
+        let mut board = self.board;
+        apply(&mut board, self.turn, mv);
+        in_check(&board, self.turn)
+    }
+
+    /// Uchifuzume: a pawn can't be dropped to give mate. Dropping a piece never
+    /// gets out of a pawn's check, so only moves on the board need looking at.
+    #[must_use]
+    fn is_pawn_drop_mate(&self, mv: &ShogiMove) -> bool {
+        if !matches!(
+            mv,
+            ShogiMove::Drop {
+                piece: Piece::Pawn,
+                ..
+            }
+        ) {
+            return false;
+        }
+
+        let them = other(self.turn);
+        let mut board = self.board;
+        apply(&mut board, self.turn, mv);
+        if !in_check(&board, them) {
+            return false;
+        }
+
+        let mut replies = vec![];
+        add_board_moves(&board, them, &mut replies);
+        !replies.iter().any(|reply| {
+            let mut after = board;
+            apply(&mut after, them, reply);
+            !in_check(&after, them)
+        })
+    }
+
+    /// Sennichite, decided once the current position has come up for the fourth
+    /// time.
+    #[must_use]
+    fn repetition(&self) -> Option<GameResult> {
+        let now = self.keys.len() - 1;
+        let key = self.keys[now];
+        let mut seen = 1;
+        let mut first = now;
+
+        // The same side has to be on move, so only every other position can match
+        for idx in (0..now).rev().skip(1).step_by(2) {
+            if self.keys[idx] == key {
+                seen += 1;
+                first = idx;
+                if seen == REPETITIONS {
+                    break;
+                }
+            }
+        }
+
+        if seen < REPETITIONS {
+            return None;
+        }
+
+        // Whether the side to move now (0) or the other side (1) was in check in
+        // every position of theirs since the first time
+        let always_checked = |parity: usize| {
+            (first + 1..=now)
+                .filter(|idx| (now - idx) % 2 == parity)
+                .all(|idx| self.checks[idx])
+        };
+
+        if always_checked(0) {
+            Some(GameResult::Win(self.turn))
+        } else if always_checked(1) {
+            Some(GameResult::Win(other(self.turn)))
+        } else {
+            Some(GameResult::Draw)
+        }
+    }
+}
+
+impl GameRules for ShogiPosition {
+    type MoveType = ShogiMove;
+
+    fn startpos() -> Self {
+        Self::from_fen(STARTPOS_FEN)
+    }
+
+    fn from_fen(fen: &str) -> Self {
+        let mut pos = Self {
+            board: [None; 81],
+            hands: [[0; 7]; 2],
+            turn: Side::Player1,
+            moves: 1,
+            undo: vec![],
+            keys: vec![],
+            checks: vec![],
+        };
+        pos.set_fen(fen);
+        pos
+    }
+
+    /// SFEN: the board from 9a to 1i with Black's pieces in capitals and `+` in front
+    /// of promoted ones, the side to move, the pieces in hand, then the move number.
+    fn set_fen(&mut self, fen: &str) {
+        let fen = if fen == "startpos" { STARTPOS_FEN } else { fen };
+        let mut parts = fen.split_ascii_whitespace();
+
+        self.board = [None; 81];
+        self.hands = [[0; 7]; 2];
+
+        let mut x = 0;
+        let mut y = 0;
+        let mut promoted = false;
+        for c in parts.next().unwrap_or("").chars() {
+            match c {
+                '+' => promoted = true,
+                '1'..='9' => x += c as usize - '0' as usize,
+                '/' => {
+                    x = 0;
+                    y += 1;
+                }
+                _ => {
+                    let Some(piece) = Piece::from_letter(c) else {
+                        continue;
+                    };
+                    let piece = match piece.promoted() {
+                        Some(promotion) if promoted => promotion,
+                        _ => piece,
+                    };
+                    let side = if c.is_ascii_uppercase() {
+                        Side::Player1
+                    } else {
+                        Side::Player2
+                    };
+                    if x < 9 && y < 9 {
+                        self.board[9 * y + x] = Some((side, piece));
+                    }
+                    x += 1;
+                    promoted = false;
+                }
+            }
+        }
+
+        self.turn = match parts.next() {
+            Some("w") | Some("W") => Side::Player2,
+            _ => Side::Player1,
+        };
+
+        let mut count = 0;
+        for c in parts.next().unwrap_or("-").chars() {
+            if let Some(digit) = c.to_digit(10) {
+                count = 10 * count + digit as u8;
+            } else if let Some(piece) = Piece::from_letter(c) {
+                if piece != Piece::King {
+                    let side = if c.is_ascii_uppercase() { 0 } else { 1 };
+                    self.hands[side][piece as usize] += count.max(1);
+                }
+                count = 0;
+            }
+        }
+
+        self.moves = parts.next().and_then(|n| n.parse().ok()).unwrap_or(1);
+        self.reset_history();
+    }
+
+    fn get_fen(&self) -> String {
+        let mut fen = String::new();
+
+        for y in 0..9 {
+            let mut empty = 0;
+            for x in 0..9 {
+                let Some((side, piece)) = self.board[9 * y + x] else {
+                    empty += 1;
+                    continue;
+                };
+                if empty > 0 {
+                    fen.push_str(&empty.to_string());
+                    empty = 0;
+                }
+                if piece.is_promoted() {
+                    fen.push('+');
+                }
+                fen.push(match side {
+                    Side::Player1 => piece.letter(),
+                    Side::Player2 => piece.letter().to_ascii_lowercase(),
+                });
+            }
+            if empty > 0 {
+                fen.push_str(&empty.to_string());
+            }
+            if y < 8 {
+                fen.push('/');
+            }
+        }
+
+        fen.push_str(match self.turn {
+            Side::Player1 => " b ",
+            Side::Player2 => " w ",
+        });
+
+        let mut hands = String::new();
+        for (side, hand) in self.hands.iter().enumerate() {
+            for piece in HAND_PIECES {
+                let count = hand[piece as usize];
+                if count > 1 {
+                    hands.push_str(&count.to_string());
+                }
+                if count > 0 {
+                    hands.push(match side {
+                        0 => piece.letter(),
+                        _ => piece.letter().to_ascii_lowercase(),
+                    });
+                }
+            }
+        }
+        if hands.is_empty() {
+            hands.push('-');
+        }
+        fen.push_str(&hands);
+
+        fen.push_str(&format!(" {}", self.moves));
+        fen
+    }
+
+    fn get_turn(&self) -> Side {
+        self.turn
+    }
+
+    fn legal_moves(&self) -> Vec<ShogiMove> {
+        if self.repetition().is_some() {
+            return vec![];
+        }
+
+        let mut moves = vec![];
+        add_board_moves(&self.board, self.turn, &mut moves);
+        self.add_drops(&mut moves);
+        moves.retain(|mv| !self.leaves_check(mv) && !self.is_pawn_drop_mate(mv));
+        moves
+    }
+
+    fn makemove(&mut self, mv: &ShogiMove) {
+        let us = side_index(self.turn);
+        // Only the squares moved from and to and a hand change
+        let squares = match *mv {
+            ShogiMove::Move { from, to, .. } => [Some(from as usize), Some(to as usize)],
+            ShogiMove::Drop { to, .. } => [None, Some(to as usize)],
+        };
+        let mut hash = self.get_hash() ^ TURN_KEY;
+        for sq in squares.iter().flatten() {
+            hash ^= self.square_key(*sq);
+        }
+
+        let captured = match *mv {
+            ShogiMove::Move { to, .. } => self.board[to as usize].map(|(_, piece)| piece),
+            ShogiMove::Drop { piece, .. } => {
+                hash ^= self.hand_key(us, piece);
+                self.hands[us][piece as usize] -= 1;
+                hash ^= self.hand_key(us, piece);
+                None
+            }
+        };
+        if let Some(piece) = captured {
+            let piece = piece.unpromoted();
+            hash ^= self.hand_key(us, piece);
+            self.hands[us][piece as usize] += 1;
+            hash ^= self.hand_key(us, piece);
+        }
+
+        apply(&mut self.board, self.turn, mv);
+        for sq in squares.iter().flatten() {
+            hash ^= self.square_key(*sq);
+        }
+
+        self.undo.push(captured);
+        self.turn = other(self.turn);
+        self.moves += 1;
+        self.keys.push(hash);
+        self.checks.push(in_check(&self.board, self.turn));
+    }
+
+    fn undomove(&mut self, mv: &ShogiMove) {
+        let captured = self.undo.pop().expect("No move to undo");
+        self.keys.pop();
+        self.checks.pop();
+        self.turn = other(self.turn);
+        self.moves -= 1;
+
+        let us = side_index(self.turn);
+        match *mv {
+            ShogiMove::Move { from, to, promote } => {
+                let (side, piece) = self.board[to as usize].expect("No piece to take back");
+                let piece = if promote { piece.unpromoted() } else { piece };
+                self.board[from as usize] = Some((side, piece));
+                self.board[to as usize] = captured.map(|piece| (other(side), piece));
+                if let Some(piece) = captured {
+                    self.hands[us][piece.unpromoted() as usize] -= 1;
+                }
+            }
+            ShogiMove::Drop { piece, to } => {
+                self.board[to as usize] = None;
+                self.hands[us][piece as usize] += 1;
+            }
+        }
+    }
+
+    /// Hand the move over. There's nothing to take back, so the positions before it
+    /// no longer count towards a repetition.
+    fn makenull(&mut self) {
+        self.turn = other(self.turn);
+        self.moves += 1;
+        self.reset_history();
+    }
+
+    fn is_gameover(&self) -> bool {
+        self.legal_moves().is_empty()
+    }
+
+    fn get_result(&self) -> Option<GameResult> {
+        if let Some(result) = self.repetition() {
+            Some(result)
+        } else if self.legal_moves().is_empty() {
+            Some(GameResult::Win(other(self.turn)))
+        } else {
+            None
+        }
+    }
+}
+
+#[must_use]
+pub fn square_name(sq: u8) -> String {
+    format!("{}{}", 9 - sq % 9, (b'a' + sq / 9) as char)
+}
+
+#[must_use]
+pub fn parse_square(name: &str) -> Option<u8> {
+    let bytes = name.as_bytes();
+    if bytes.len() != 2 || !(b'1'..=b'9').contains(&bytes[0]) || !(b'a'..=b'i').contains(&bytes[1])
+    {
+        return None;
+    }
+    Some(9 * (bytes[1] - b'a') + (b'9' - bytes[0]))
+}
+
+impl fmt::Display for ShogiPosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let hand = |side: Side| {
+            let mut hand = String::new();
+            for piece in HAND_PIECES {
+                for _ in 0..self.get_hand(side, piece) {
+                    hand.push(piece.letter());
+                }
+            }
+            hand
+        };
+
+        writeln!(f, "White hand: {}", hand(Side::Player2))?;
+        writeln!(f, "  9  8  7  6  5  4  3  2  1")?;
+        for y in 0..9 {
+            for x in 0..9 {
+                let cell = match self.board[9 * y + x] {
+                    Some((side, piece)) => {
+                        let letter = match side {
+                            Side::Player1 => piece.letter(),
+                            Side::Player2 => piece.letter().to_ascii_lowercase(),
+                        };
+                        let prefix = if piece.is_promoted() { "+" } else { "" };
+                        format!("{}{}", prefix, letter)
+                    }
+                    None => ".".to_owned(),
+                };
+                write!(f, "{:>3}", cell)?;
+            }
+            writeln!(f, "  {}", (b'a' + y as u8) as char)?;
+        }
+        writeln!(f, "Black hand: {}", hand(Side::Player1))?;
+        write!(
+            f,
+            "Turn: {}",
+            match self.turn {
+                Side::Player1 => 'b',
+                Side::Player2 => 'w',
+            }
+        )
+    }
+}
@@ -0,0 +1,204 @@
+use super::eval::eval;
+use super::position::{ShogiMove, ShogiPosition};
+use crate::searchstats::SearchStats;
+use games::gamerules::{GameResult, GameRules};
+use std::cmp::max;
+
+pub const INF_SCORE: i32 = 10_000_000;
+const MATE_SCORE: i32 = 1_000_000;
+
+/// With pieces in hand there's nearly always a useful move, so zugzwang is rare.
+const NULL_MOVE: bool = true;
+
+/// How much less deeply the position after a null move gets searched.
+const NULL_REDUCTION: i32 = 2;
+
+/// Principal variation search within (alpha, beta).
+///
+/// The line left in `pv` by the previous iteration gets searched first, and is
+/// replaced by the best line found.
+#[must_use]
+pub fn negamax(
+    pos: &mut ShogiPosition,
+    mut alpha: i32,
+    beta: i32,
+    mut depth: i32,
+    ply: i32,
+    should_stop: &impl Fn(&SearchStats) -> bool,
+    stats: &mut SearchStats,
+    pv: &mut Vec<ShogiMove>,
+) -> i32 {
+    debug_assert!(-INF_SCORE <= alpha);
+    debug_assert!(alpha < beta);
+    debug_assert!(beta <= INF_SCORE);
+
+    let mut line = std::mem::take(pv);
+
+    if should_stop(stats) {
+        return 0;
+    }
+
+    stats.nodes += 1;
+    stats.seldepth = max(stats.seldepth, ply);
+
+    match pos.get_result() {
+        Some(GameResult::Win(s)) => {
+            if s == pos.get_turn() {
+                return MATE_SCORE - ply;
+            } else {
+                return -MATE_SCORE + ply;
+            }
+        }
+        Some(GameResult::Draw) => return 0,
+        None => {}
+    }
+
+    // Don't leave the search with the king in danger
+    let in_check = pos.is_check();
+    if in_check {
+        // Anything past the nominal depth is only here to settle the position
+        if depth <= 0 {
+            stats.qnodes += 1;
+        }
+        depth += 1;
+    }
+
+    if depth <= 0 {
+        return eval(pos);
+    }
+
+    // If passing is still good enough, a real move is sure to be. Never in the
+    // principal variation though, where the exact score matters.
+    if NULL_MOVE && beta - alpha == 1 && depth > NULL_REDUCTION && !in_check {
+        let mut passed = pos.clone();
+        passed.makenull();
+        stats.null_moves += 1;
+        let score = -negamax(
+            &mut passed,
+            -beta,
+            -beta + 1,
+            depth - 1 - NULL_REDUCTION,
+            ply + 1,
+            should_stop,
+            stats,
+            &mut vec![],
+        );
+        if score >= beta {
+            stats.null_cutoffs += 1;
+            return beta;
+        }
+    }
+
+    let mut moves = pos.legal_moves();
+
+    // Follow the previous iteration's line
+    match line
+        .first()
+        .and_then(|first| moves.iter().position(|mv| mv == first))
+    {
+        Some(idx) => {
+            moves[..=idx].rotate_right(1);
+            line.remove(0);
+        }
+        None => line.clear(),
+    }
+
+    let mut best_score = -INF_SCORE;
+
+    for (idx, mv) in moves.into_iter().enumerate() {
+        pos.makemove(&mv);
+        let mut next_pv = if idx == 0 {
+            std::mem::take(&mut line)
+        } else {
+            vec![]
+        };
+
+        let score = if idx == 0 {
+            -negamax(
+                pos,
+                -beta,
+                -alpha,
+                depth - 1,
+                ply + 1,
+                should_stop,
+                stats,
+                &mut next_pv,
+            )
+        } else {
+            // Later moves only need showing to be no better than alpha. Away from the
+            // principal variation, the latest of them get a shallower look too
+            let reduction = i32::from(beta - alpha == 1 && depth >= 3 && idx >= 3 && !in_check);
+            if reduction > 0 {
+                stats.lmr_reductions += 1;
+            }
+            let mut score = -negamax(
+                pos,
+                -alpha - 1,
+                -alpha,
+                depth - 1 - reduction,
+                ply + 1,
+                should_stop,
+                stats,
+                &mut next_pv,
+            );
+            if score > alpha && reduction > 0 {
+                stats.lmr_researches += 1;
+                score = -negamax(
+                    pos,
+                    -alpha - 1,
+                    -alpha,
+                    depth - 1,
+                    ply + 1,
+                    should_stop,
+                    stats,
+                    &mut next_pv,
+                );
+            }
+            if score > alpha && score < beta {
+                score = -negamax(
+                    pos,
+                    -beta,
+                    -alpha,
+                    depth - 1,
+                    ply + 1,
+                    should_stop,
+                    stats,
+                    &mut next_pv,
+                );
+            }
+            score
+        };
+        pos.undomove(&mv);
+
+        // A move whose search got cut short can't be trusted
+        if should_stop(stats) {
+            break;
+        }
+
+        if score > best_score {
+            best_score = score;
+
+            // Update PV
+            *pv = vec![mv];
+            for trash in next_pv {
+                pv.push(trash);
+            }
+        }
+
+        if score > alpha {
+            alpha = score;
+        }
+
+        if alpha >= beta {
+            stats.beta_cutoffs += 1;
+            if idx == 0 {
+                stats.first_move_cutoffs += 1;
+            }
+            break;
+        }
+    }
+
+    debug_assert!(best_score > -INF_SCORE || should_stop(stats));
+
+    best_score
+}
@@ -0,0 +1,78 @@
+use super::eval::eval;
+use super::position::{ShogiMove, ShogiPosition};
+use super::root::primary_with_stats;
+use crate::bound::Bound;
+use crate::prng;
+use crate::search;
+use crate::searchstats::SearchStats;
+use games::gamerules::GameRules;
+use protocols::GoSettings;
+use protocols::usi::options::{Check, Combo};
+
+pub struct ShogiState {
+    pub pos: ShogiPosition,
+    pub prng: prng::XorshiftGenerator,
+    /// What the last search counted
+    pub stats: SearchStats,
+    // Options
+    pub debug: Check,
+    pub search: Combo,
+}
+
+impl Default for ShogiState {
+    fn default() -> Self {
+        Self {
+            pos: ShogiPosition::startpos(),
+            prng: prng::XorshiftGenerator::new(0xe50076937a9e5b1c),
+            stats: SearchStats::default(),
+            // Options
+            debug: Check {
+                name: "debug".to_owned(),
+                value: cfg!(debug_assertions),
+            },
+            search: Combo {
+                name: "search".to_owned(),
+                value: "primary".to_owned(),
+                options: vec![
+                    "primary".to_owned(),
+                    "random".to_owned(),
+                    "minimax".to_owned(),
+                    "alphabeta".to_owned(),
+                    "flatmc".to_owned(),
+                ],
+            },
+        }
+    }
+}
+
+impl ShogiState {
+    #[must_use]
+    pub fn think(
+        &mut self,
+        settings: &GoSettings,
+        info_handler: &impl Fn(
+            &ShogiPosition,
+            Option<i32>,
+            Option<i32>,
+            Option<i32>,
+            Option<Bound>,
+            Option<i32>,
+            Option<u64>,
+            Option<u128>,
+            Option<i32>,
+            &Vec<ShogiMove>,
+            Option<&SearchStats>,
+        ),
+    ) -> Option<ShogiMove> {
+        search::think(
+            &self.search.value,
+            &self.pos,
+            settings,
+            info_handler,
+            &eval,
+            &mut self.prng,
+            &mut self.stats,
+            |stats| primary_with_stats(self.pos.clone(), settings, info_handler, stats),
+        )
+    }
+}
@@ -0,0 +1,154 @@
+use super::position::{ShogiMove, ShogiPosition};
+use super::state::ShogiState;
+use crate::bound::Bound;
+use crate::searchstats::SearchStats;
+use games::gamerules::{GameResult, GameRules};
+use games::general::side::Side;
+use games::perft;
+use protocols::GoSettings;
+use protocols::json::ToJson;
+use protocols::output::{self, Bestmove, Info};
+use protocols::usi::{USI, USIGameResult};
+
+pub fn info_handler(
+    _pos: &ShogiPosition,
+    depth: Option<i32>,
+    seldepth: Option<i32>,
+    score: Option<i32>,
+    bound: Option<Bound>,
+    mate: Option<i32>,
+    nodes: Option<u64>,
+    elapsed: Option<u128>,
+    hashfull: Option<i32>,
+    pv: &Vec<ShogiMove>,
+    stats: Option<&SearchStats>,
+) {
+    output::emit(&Info {
+        depth,
+        seldepth,
+        score,
+        bound: bound.map(|b| b.to_string()),
+        mate,
+        nodes,
+        elapsed,
+        hashfull,
+        pv: pv.iter().map(ToString::to_string).collect(),
+        stats: stats.map(ToJson::to_json),
+    });
+}
+
+/// Find a legal move from its USI string: `7g7f`, `8h2b+` or `P*5e`.
+#[must_use]
+pub fn parse_move(pos: &ShogiPosition, movestr: &str) -> Option<ShogiMove> {
+    pos.legal_moves()
+        .into_iter()
+        .find(|mv| mv.to_string() == movestr)
+}
+
+impl USI for ShogiState {
+    fn init(&mut self) {
+        self.usinewgame();
+    }
+
+    fn shutdown(&mut self) {}
+
+    fn name(&self) -> String {
+        format!("Faeries v{}", env!("CARGO_PKG_VERSION"))
+    }
+
+    fn author(&self) -> String {
+        "kz04px".to_owned()
+    }
+
+    fn usinewgame(&mut self) {
+        self.pos = ShogiPosition::startpos();
+    }
+
+    fn isready(&mut self) {
+        println!("readyok");
+    }
+
+    fn position(&mut self, sfen: &str) {
+        self.pos.set_fen(sfen);
+    }
+
+    fn moves(&mut self, movestr: &str) {
+        if let Some(mv) = parse_move(&self.pos, movestr) {
+            self.pos.makemove(&mv);
+        }
+    }
+
+    fn go(&mut self, settings: &GoSettings) {
+        let bestmove = self.think(settings, &info_handler);
+        output::emit(&Bestmove {
+            mv: bestmove.map_or("resign".to_owned(), |mv| mv.to_string()),
+            stats: self.stats.to_json(),
+        });
+    }
+
+    fn stop(&mut self) {}
+
+    fn print(&self) {
+        println!("{}", self.pos);
+        println!("SFEN: {}", self.pos.get_fen());
+    }
+
+    fn print_options(&self) {
+        output::emit(&self.debug);
+        output::emit(&self.search);
+    }
+
+    fn movelist(&self) {
+        for mv in self.pos.legal_moves() {
+            println!("{}", mv);
+        }
+    }
+
+    fn set_option(&mut self, name: &str, value: &str) {
+        match (name, value) {
+            ("debug", "true") => self.debug.value = true,
+            ("debug", "false") => self.debug.value = false,
+            ("search", _) => self.search.value = value.to_owned(),
+            (_, _) => {}
+        }
+    }
+
+    fn is_debug(&self) -> bool {
+        self.debug.value
+    }
+
+    fn query_p1turn(&self) -> bool {
+        self.pos.get_turn() == Side::Player1
+    }
+
+    fn query_result(&self) -> Option<USIGameResult> {
+        match self.pos.get_result() {
+            Some(GameResult::Win(Side::Player1)) => Some(USIGameResult::P1Win),
+            Some(GameResult::Win(Side::Player2)) => Some(USIGameResult::P2Win),
+            Some(GameResult::Draw) => Some(USIGameResult::Draw),
+            None => None,
+        }
+    }
+
+    fn perft(&mut self, settings: &GoSettings) {
+        let info_handler = output::perft;
+        let final_handler = output::nodes;
+        perft::perft(
+            &mut self.pos,
+            settings.depth.unwrap(),
+            &info_handler,
+            &final_handler,
+        );
+    }
+
+    fn split(&mut self, settings: &GoSettings) {
+        let info_handler = |mv: ShogiMove, nodes: u64| output::split(mv.to_string(), nodes);
+        let final_handler = output::nodes;
+        perft::split(
+            &mut self.pos,
+            settings.depth.unwrap(),
+            &info_handler,
+            &final_handler,
+        );
+    }
+}
@@ -0,0 +1,459 @@
+use crate::checkers::position::CheckersPosition;
+use crate::connect4::position::Connect4Position;
+use crate::droptaxx::position::DroptaxxPosition;
+use crate::gomoku::position::GomokuPosition;
+use crate::othello::position::OthelloPosition;
+use crate::shogi::position::ShogiPosition;
+use games::{
+    ataxx::AtaxxPosition, chess::ChessPosition, gamerules::GameRules, isolation::IsolationPosition,
+    pijersi::PijersiPosition, tak::TakPosition,
+};
+
+/// One of the eight ways a square board can be turned or flipped onto itself.
+///
+/// Coordinates count from the bottom left, the way squares are named, and the
+/// rotations are clockwise.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Symmetry {
+    Identity,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    FlipFiles,
+    FlipRanks,
+    FlipDiagonal,
+    FlipAntidiagonal,
+}
+
+/// Every symmetry of a square board.
+pub const DIHEDRAL: [Symmetry; 8] = [
+    Symmetry::Identity,
+    Symmetry::Rotate90,
+    Symmetry::Rotate180,
+    Symmetry::Rotate270,
+    Symmetry::FlipFiles,
+    Symmetry::FlipRanks,
+    Symmetry::FlipDiagonal,
+    Symmetry::FlipAntidiagonal,
+];
+
+/// Left to right only, for games with gravity.
+pub const MIRROR: [Symmetry; 2] = [Symmetry::Identity, Symmetry::FlipFiles];
+
+/// For games whose rules aren't symmetric at all.
+pub const NONE: [Symmetry; 1] = [Symmetry::Identity];
+
+impl Symmetry {
+    #[must_use]
+    pub fn inverse(self) -> Self {
+        match self {
+            Symmetry::Rotate90 => Symmetry::Rotate270,
+            Symmetry::Rotate270 => Symmetry::Rotate90,
+            other => other,
+        }
+    }
+
+    /// Whether the width and height of the board trade places.
+    #[must_use]
+    pub fn is_transpose(self) -> bool {
+        matches!(
+            self,
+            Symmetry::Rotate90
+                | Symmetry::Rotate270
+                | Symmetry::FlipDiagonal
+                | Symmetry::FlipAntidiagonal
+        )
+    }
+
+    #[must_use]
+    pub fn apply(self, x: i32, y: i32, width: i32, height: i32) -> (i32, i32) {
+        match self {
+            Symmetry::Identity => (x, y),
+            Symmetry::Rotate90 => (y, width - 1 - x),
+            Symmetry::Rotate180 => (width - 1 - x, height - 1 - y),
+            Symmetry::Rotate270 => (height - 1 - y, x),
+            Symmetry::FlipFiles => (width - 1 - x, y),
+            Symmetry::FlipRanks => (x, height - 1 - y),
+            Symmetry::FlipDiagonal => (y, x),
+            Symmetry::FlipAntidiagonal => (height - 1 - y, width - 1 - x),
+        }
+    }
+}
+
+/// How the cells of a row are written in a game's FEN.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Cells {
+    /// One character per piece, with digits counting empty squares: `x5o`
+    Chars,
+    /// Comma separated stacks, with `x` counting empty squares: `x2,1,12S`
+    Commas,
+}
+
+/// The board from a FEN, with empty squares as empty strings.
+struct Grid {
+    width: i32,
+    height: i32,
+    cells: Vec<String>,
+}
+
+impl Grid {
+    #[must_use]
+    fn parse(board: &str, style: Cells) -> Option<Self> {
+        let mut rows = vec![];
+
+        for row in board.split('/') {
+            let mut cells = vec![];
+
+            match style {
+                Cells::Chars => {
+                    let mut empty = 0;
+                    for c in row.chars() {
+                        if let Some(n) = c.to_digit(10) {
+                            empty = 10 * empty + n as usize;
+                        } else {
+                            cells.extend(std::iter::repeat_n(String::new(), empty));
+                            empty = 0;
+                            cells.push(c.to_string());
+                        }
+                    }
+                    cells.extend(std::iter::repeat_n(String::new(), empty));
+                }
+                Cells::Commas => {
+                    for token in row.split(',') {
+                        if let Some(count) = token.strip_prefix('x') {
+                            let n = if count.is_empty() {
+                                1
+                            } else {
+                                count.parse::<usize>().ok()?
+                            };
+                            cells.extend(std::iter::repeat_n(String::new(), n));
+                        } else {
+                            cells.push(token.to_owned());
+                        }
+                    }
+                }
+            }
+
+            rows.push(cells);
+        }
+
+        let width = rows.first()?.len();
+        if width == 0 || rows.iter().any(|row| row.len() != width) {
+            return None;
+        }
+
+        // FEN lists the top row first
+        let height = rows.len();
+        let cells = rows.into_iter().rev().flatten().collect();
+
+        Some(Self {
+            width: width as i32,
+            height: height as i32,
+            cells,
+        })
+    }
+
+    #[must_use]
+    fn get(&self, x: i32, y: i32) -> &str {
+        &self.cells[(y * self.width + x) as usize]
+    }
+
+    #[must_use]
+    fn transform(&self, symmetry: Symmetry) -> Self {
+        let (width, height) = if symmetry.is_transpose() {
+            (self.height, self.width)
+        } else {
+            (self.width, self.height)
+        };
+        let mut cells = vec![String::new(); self.cells.len()];
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let (nx, ny) = symmetry.apply(x, y, self.width, self.height);
+                cells[(ny * width + nx) as usize] = self.get(x, y).to_owned();
+            }
+        }
+
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    #[must_use]
+    fn write(&self, style: Cells) -> String {
+        let mut rows = vec![];
+
+        for y in (0..self.height).rev() {
+            let mut tokens: Vec<String> = vec![];
+            let mut empty = 0;
+
+            for x in 0..self.width {
+                let cell = self.get(x, y);
+                if cell.is_empty() {
+                    empty += 1;
+                    continue;
+                }
+
+                if empty > 0 {
+                    tokens.push(empty_token(empty, style));
+                    empty = 0;
+                }
+                tokens.push(cell.to_owned());
+            }
+
+            if empty > 0 {
+                tokens.push(empty_token(empty, style));
+            }
+
+            match style {
+                Cells::Chars => rows.push(tokens.concat()),
+                Cells::Commas => rows.push(tokens.join(",")),
+            }
+        }
+
+        rows.join("/")
+    }
+}
+
+#[must_use]
+fn empty_token(count: usize, style: Cells) -> String {
+    match (style, count) {
+        (Cells::Chars, _) => count.to_string(),
+        (Cells::Commas, 1) => "x".to_owned(),
+        (Cells::Commas, _) => format!("x{}", count),
+    }
+}
+
+/// The contents of every square in a FEN from the bottom left, with empty squares
+/// as empty strings.
+#[must_use]
+pub fn cells(fen: &str, style: Cells) -> Option<Vec<String>> {
+    let board = fen.split_ascii_whitespace().next()?;
+    Some(Grid::parse(board, style)?.cells)
+}
+
+/// The board part of a FEN from the contents of every square, the way `cells`
+/// gives them.
+#[must_use]
+pub fn board_fen(cells: &[String], width: i32, style: Cells) -> String {
+    Grid {
+        width,
+        height: cells.len() as i32 / width,
+        cells: cells.to_vec(),
+    }
+    .write(style)
+}
+
+/// The width and height of the board in a FEN.
+#[must_use]
+pub fn dimensions(fen: &str, style: Cells) -> Option<(i32, i32)> {
+    let board = fen.split_ascii_whitespace().next()?;
+    let grid = Grid::parse(board, style)?;
+    Some((grid.width, grid.height))
+}
+
+/// Turn or flip the board in a FEN, leaving the rest of it alone.
+///
+/// A symmetry that would change the shape of a board that isn't square gives
+/// `None`, as does a FEN the board can't be read from.
+#[must_use]
+pub fn transform_fen(fen: &str, symmetry: Symmetry, style: Cells) -> Option<String> {
+    if symmetry == Symmetry::Identity {
+        return Some(fen.to_owned());
+    }
+
+    let (board, rest) = match fen.split_once(' ') {
+        Some((board, rest)) => (board, Some(rest)),
+        None => (fen, None),
+    };
+    let grid = Grid::parse(board, style)?;
+
+    if symmetry.is_transpose() && grid.width != grid.height {
+        return None;
+    }
+
+    let board = grid.transform(symmetry).write(style);
+    match rest {
+        Some(rest) => Some(format!("{} {}", board, rest)),
+        None => Some(board),
+    }
+}
+
+/// Turn or flip a move written in the usual notation.
+///
+/// Squares such as `c3` are moved, as are Tak's spread directions. A move that's
+/// nothing but a file is taken to be on the first rank, which is only meaningful
+/// for symmetries that keep files as files. Everything else is copied, so passes,
+/// promotions and piece letters survive untouched. A square that isn't on the
+/// board gives `None`.
+#[must_use]
+pub fn transform_move(
+    movestr: &str,
+    symmetry: Symmetry,
+    width: i32,
+    height: i32,
+) -> Option<String> {
+    if symmetry == Symmetry::Identity {
+        return Some(movestr.to_owned());
+    }
+
+    let chars = movestr.chars().collect::<Vec<char>>();
+    let mut result = String::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let rank_follows = chars.get(i + 1).is_some_and(char::is_ascii_digit);
+
+        if c.is_ascii_lowercase() && (rank_follows || chars.len() == 1) {
+            let x = c as i32 - 'a' as i32;
+            let mut j = i + 1;
+            while j < chars.len() && chars[j].is_ascii_digit() {
+                j += 1;
+            }
+
+            let rank = chars[i + 1..j].iter().collect::<String>();
+            let y = if rank.is_empty() {
+                0
+            } else {
+                rank.parse::<i32>().ok()? - 1
+            };
+            if !(0..width).contains(&x) || !(0..height).contains(&y) {
+                return None;
+            }
+            let (nx, ny) = symmetry.apply(x, y, width, height);
+
+            result.push((b'a' + nx as u8) as char);
+            if j > i + 1 {
+                result += &(ny + 1).to_string();
+            }
+            i = j;
+            continue;
+        }
+
+        let direction = match c {
+            '+' => Some((0, 1)),
+            '-' => Some((0, -1)),
+            '<' => Some((-1, 0)),
+            '>' => Some((1, 0)),
+            _ => None,
+        };
+
+        if let Some((dx, dy)) = direction {
+            // Symmetries are affine, so the origin's image gives the translation
+            let (ox, oy) = symmetry.apply(0, 0, width, height);
+            let (nx, ny) = symmetry.apply(dx, dy, width, height);
+            result.push(match (nx - ox, ny - oy) {
+                (0, 1) => '+',
+                (0, -1) => '-',
+                (-1, 0) => '<',
+                _ => '>',
+            });
+        } else {
+            result.push(c);
+        }
+
+        i += 1;
+    }
+
+    Some(result)
+}
+
+/// A game whose positions can be turned and flipped through their FEN.
+pub trait Symmetric: GameRules {
+    const CELLS: Cells;
+
+    /// The symmetries the rules don't care about. The identity comes first.
+    const SYMMETRIES: &'static [Symmetry];
+}
+
+impl Symmetric for AtaxxPosition<7, 7> {
+    const CELLS: Cells = Cells::Chars;
+    const SYMMETRIES: &'static [Symmetry] = &DIHEDRAL;
+}
+
+impl Symmetric for DroptaxxPosition {
+    const CELLS: Cells = Cells::Chars;
+    const SYMMETRIES: &'static [Symmetry] = &DIHEDRAL;
+}
+
+impl Symmetric for GomokuPosition {
+    const CELLS: Cells = Cells::Chars;
+    const SYMMETRIES: &'static [Symmetry] = &DIHEDRAL;
+}
+
+impl Symmetric for OthelloPosition {
+    const CELLS: Cells = Cells::Chars;
+    const SYMMETRIES: &'static [Symmetry] = &DIHEDRAL;
+}
+
+impl Symmetric for IsolationPosition {
+    const CELLS: Cells = Cells::Chars;
+    const SYMMETRIES: &'static [Symmetry] = &DIHEDRAL;
+}
+
+impl<const SIZE: usize> Symmetric for TakPosition<SIZE> {
+    const CELLS: Cells = Cells::Commas;
+    const SYMMETRIES: &'static [Symmetry] = &DIHEDRAL;
+}
+
+impl Symmetric for Connect4Position {
+    const CELLS: Cells = Cells::Chars;
+    const SYMMETRIES: &'static [Symmetry] = &MIRROR;
+}
+
+impl Symmetric for CheckersPosition {
+    const CELLS: Cells = Cells::Chars;
+    const SYMMETRIES: &'static [Symmetry] = &NONE;
+}
+
+impl Symmetric for ChessPosition {
+    const CELLS: Cells = Cells::Chars;
+    const SYMMETRIES: &'static [Symmetry] = &NONE;
+}
+
+impl Symmetric for PijersiPosition {
+    const CELLS: Cells = Cells::Chars;
+    const SYMMETRIES: &'static [Symmetry] = &NONE;
+}
+
+/// Promoted pieces take two characters, a `+` and the piece, so the board
+/// can't be read a character to a square to be mirrored.
+impl Symmetric for ShogiPosition {
+    const CELLS: Cells = Cells::Chars;
+    const SYMMETRIES: &'static [Symmetry] = &NONE;
+}
+
+/// The canonical form of a FEN and the symmetry that takes the FEN to it.
+///
+/// The canonical form is the smallest of the FEN's images, so equivalent positions
+/// share it. Symmetries that don't fit the board are skipped, which is how a
+/// rectangular board ends up with four of the eight.
+#[must_use]
+pub fn canonical_fen<G: Symmetric>(fen: &str) -> (String, Symmetry) {
+    let mut best = (fen.to_owned(), Symmetry::Identity);
+
+    for symmetry in G::SYMMETRIES.iter().skip(1) {
+        if let Some(image) = transform_fen(fen, *symmetry, G::CELLS) {
+            if image < best.0 {
+                best = (image, *symmetry);
+            }
+        }
+    }
+
+    best
+}
+
+/// The canonical form of a position, and the symmetry that takes the position to it.
+///
+/// A move looked up for the canonical position is brought back to the original with
+/// `transform_move` and the inverse of the symmetry. Opening generation and
+/// datagen's deduplication canonicalise positions; there's no opening book to look
+/// moves up in yet.
+#[must_use]
+pub fn canonical<G: Symmetric>(pos: &G) -> (G, Symmetry) {
+    let (fen, symmetry) = canonical_fen::<G>(&pos.get_fen());
+    (G::from_fen(&fen), symmetry)
+}
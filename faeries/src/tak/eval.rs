@@ -1,5 +1,6 @@
 use games::tak::TakPosition;
 
+/// Every position is level, and the search goes on roads and flat wins alone.
 #[must_use]
 pub fn eval<const SIZE: usize>(_pos: &TakPosition<SIZE>) -> i32 {
     0
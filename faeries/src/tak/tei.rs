@@ -1,15 +1,16 @@
-use super::root::primary;
+use super::root::{bench, primary};
+use crate::bound::Bound;
 use crate::prng;
-use crate::search::alphabeta::alphabeta;
-use crate::search::flatmc::flatmc;
-use crate::search::minimax::minimax;
-use crate::search::random::random;
+use crate::search;
+use crate::searchstats::SearchStats;
 use games::gamerules::GameRules;
 use games::perft;
 use games::tak::Dir;
 use games::tak::TakMove;
 use games::tak::TakPosition;
 use protocols::GoSettings;
+use protocols::json::{ToJson, Value};
+use protocols::output::{self, Bestmove, Info};
 use protocols::tei::TEI;
 use protocols::ugi::options::{Check, Combo};
 
@@ -60,32 +61,26 @@ pub fn info_handler<const SIZE: usize>(
     depth: Option<i32>,
     seldepth: Option<i32>,
     score: Option<i32>,
+    bound: Option<Bound>,
     mate: Option<i32>,
     nodes: Option<u64>,
     elapsed: Option<u128>,
     hashfull: Option<i32>,
     pv: &Vec<TakMove<SIZE>>,
+    stats: Option<&SearchStats>,
 ) {
-    print!("info");
-    depth.inspect(|d| print!(" depth {}", d));
-    seldepth.inspect(|d| print!(" seldepth {}", d));
-    score.inspect(|s| print!(" score cp {}", s));
-    mate.inspect(|d| print!(" score mate {}", d));
-    nodes.inspect(|n| print!(" nodes {}", n));
-    elapsed.inspect(|ms| print!(" time {}", ms));
-    if let (Some(t), Some(n)) = (elapsed, nodes) {
-        if t > 0 {
-            print!(" nps {}", (n as u128 * 1000) / t);
-        }
-    }
-    hashfull.inspect(|hashfull| print!(" hashfull {}", hashfull));
-    if !pv.is_empty() {
-        print!(" pv");
-        for mv in pv {
-            print!(" {}", as_tei(pos, mv));
-        }
-    }
-    println!();
+    output::emit(&Info {
+        depth,
+        seldepth,
+        score,
+        bound: bound.map(|b| b.to_string()),
+        mate,
+        nodes,
+        elapsed,
+        hashfull,
+        pv: pv.iter().map(|mv| as_tei(pos, mv)).collect(),
+        stats: stats.map(ToJson::to_json),
+    });
 }
 
 #[must_use]
@@ -272,105 +267,115 @@ impl TEI for TEITakState {
         match &self.pos {
             TakPositions::Size3(pos) => {
                 let eval = |_pos: &TakPosition<3>| -> i32 { 0 };
-                let bestmove = match self.search.value.as_str() {
-                    "primary" => primary(pos.clone(), settings, &info_handler),
-                    "random" => random(&pos.clone(), || self.prng.next()),
-                    "minimax" => minimax(pos.clone(), settings, &info_handler, &eval),
-                    "alphabeta" => alphabeta(pos.clone(), settings, &info_handler, &eval),
-                    "flatmc" => flatmc(pos.clone(), settings, &info_handler, || self.prng.next()),
-                    _ => panic!("Unknown search type"),
-                };
-                if let Some(mv) = bestmove {
-                    println!("bestmove {}", as_tei(&pos, &mv));
-                } else {
-                    println!("bestmove 0000");
-                }
+                let bestmove = search::think(
+                    &self.search.value,
+                    pos,
+                    settings,
+                    &info_handler,
+                    &eval,
+                    &mut self.prng,
+                    &mut SearchStats::default(),
+                    |_| primary(pos.clone(), settings, &info_handler),
+                );
+                output::emit(&Bestmove {
+                    mv: bestmove.map_or("0000".to_owned(), |mv| as_tei(&pos, &mv)),
+                    stats: Value::Null,
+                });
             }
             TakPositions::Size4(pos) => {
                 let eval = |_pos: &TakPosition<4>| -> i32 { 0 };
-                let bestmove = match self.search.value.as_str() {
-                    "primary" => primary(pos.clone(), settings, &info_handler),
-                    "random" => random(&pos.clone(), || self.prng.next()),
-                    "minimax" => minimax(pos.clone(), settings, &info_handler, &eval),
-                    "alphabeta" => alphabeta(pos.clone(), settings, &info_handler, &eval),
-                    "flatmc" => flatmc(pos.clone(), settings, &info_handler, || self.prng.next()),
-                    _ => panic!("Unknown search type"),
-                };
-                if let Some(mv) = bestmove {
-                    println!("bestmove {}", as_tei(&pos, &mv));
-                } else {
-                    println!("bestmove 0000");
-                }
+                let bestmove = search::think(
+                    &self.search.value,
+                    pos,
+                    settings,
+                    &info_handler,
+                    &eval,
+                    &mut self.prng,
+                    &mut SearchStats::default(),
+                    |_| primary(pos.clone(), settings, &info_handler),
+                );
+                output::emit(&Bestmove {
+                    mv: bestmove.map_or("0000".to_owned(), |mv| as_tei(&pos, &mv)),
+                    stats: Value::Null,
+                });
             }
             TakPositions::Size5(pos) => {
                 let eval = |_pos: &TakPosition<5>| -> i32 { 0 };
-                let bestmove = match self.search.value.as_str() {
-                    "primary" => primary(pos.clone(), settings, &info_handler),
-                    "random" => random(&pos.clone(), || self.prng.next()),
-                    "minimax" => minimax(pos.clone(), settings, &info_handler, &eval),
-                    "alphabeta" => alphabeta(pos.clone(), settings, &info_handler, &eval),
-                    "flatmc" => flatmc(pos.clone(), settings, &info_handler, || self.prng.next()),
-                    _ => panic!("Unknown search type"),
-                };
-                if let Some(mv) = bestmove {
-                    println!("bestmove {}", as_tei(&pos, &mv));
-                } else {
-                    println!("bestmove 0000");
-                }
+                let bestmove = search::think(
+                    &self.search.value,
+                    pos,
+                    settings,
+                    &info_handler,
+                    &eval,
+                    &mut self.prng,
+                    &mut SearchStats::default(),
+                    |_| primary(pos.clone(), settings, &info_handler),
+                );
+                output::emit(&Bestmove {
+                    mv: bestmove.map_or("0000".to_owned(), |mv| as_tei(&pos, &mv)),
+                    stats: Value::Null,
+                });
             }
             TakPositions::Size6(pos) => {
                 let eval = |_pos: &TakPosition<6>| -> i32 { 0 };
-                let bestmove = match self.search.value.as_str() {
-                    "primary" => primary(pos.clone(), settings, &info_handler),
-                    "random" => random(&pos.clone(), || self.prng.next()),
-                    "minimax" => minimax(pos.clone(), settings, &info_handler, &eval),
-                    "alphabeta" => alphabeta(pos.clone(), settings, &info_handler, &eval),
-                    "flatmc" => flatmc(pos.clone(), settings, &info_handler, || self.prng.next()),
-                    _ => panic!("Unknown search type"),
-                };
-                if let Some(mv) = bestmove {
-                    println!("bestmove {}", as_tei(&pos, &mv));
-                } else {
-                    println!("bestmove 0000");
-                }
+                let bestmove = search::think(
+                    &self.search.value,
+                    pos,
+                    settings,
+                    &info_handler,
+                    &eval,
+                    &mut self.prng,
+                    &mut SearchStats::default(),
+                    |_| primary(pos.clone(), settings, &info_handler),
+                );
+                output::emit(&Bestmove {
+                    mv: bestmove.map_or("0000".to_owned(), |mv| as_tei(&pos, &mv)),
+                    stats: Value::Null,
+                });
             }
             TakPositions::Size7(pos) => {
                 let eval = |_pos: &TakPosition<7>| -> i32 { 0 };
-                let bestmove = match self.search.value.as_str() {
-                    "primary" => primary(pos.clone(), settings, &info_handler),
-                    "random" => random(&pos.clone(), || self.prng.next()),
-                    "minimax" => minimax(pos.clone(), settings, &info_handler, &eval),
-                    "alphabeta" => alphabeta(pos.clone(), settings, &info_handler, &eval),
-                    "flatmc" => flatmc(pos.clone(), settings, &info_handler, || self.prng.next()),
-                    _ => panic!("Unknown search type"),
-                };
-                if let Some(mv) = bestmove {
-                    println!("bestmove {}", as_tei(&pos, &mv));
-                } else {
-                    println!("bestmove 0000");
-                }
+                let bestmove = search::think(
+                    &self.search.value,
+                    pos,
+                    settings,
+                    &info_handler,
+                    &eval,
+                    &mut self.prng,
+                    &mut SearchStats::default(),
+                    |_| primary(pos.clone(), settings, &info_handler),
+                );
+                output::emit(&Bestmove {
+                    mv: bestmove.map_or("0000".to_owned(), |mv| as_tei(&pos, &mv)),
+                    stats: Value::Null,
+                });
             }
             TakPositions::Size8(pos) => {
                 let eval = |_pos: &TakPosition<8>| -> i32 { 0 };
-                let bestmove = match self.search.value.as_str() {
-                    "primary" => primary(pos.clone(), settings, &info_handler),
-                    "random" => random(&pos.clone(), || self.prng.next()),
-                    "minimax" => minimax(pos.clone(), settings, &info_handler, &eval),
-                    "alphabeta" => alphabeta(pos.clone(), settings, &info_handler, &eval),
-                    "flatmc" => flatmc(pos.clone(), settings, &info_handler, || self.prng.next()),
-                    _ => panic!("Unknown search type"),
-                };
-                if let Some(mv) = bestmove {
-                    println!("bestmove {}", as_tei(&pos, &mv));
-                } else {
-                    println!("bestmove 0000");
-                }
+                let bestmove = search::think(
+                    &self.search.value,
+                    pos,
+                    settings,
+                    &info_handler,
+                    &eval,
+                    &mut self.prng,
+                    &mut SearchStats::default(),
+                    |_| primary(pos.clone(), settings, &info_handler),
+                );
+                output::emit(&Bestmove {
+                    mv: bestmove.map_or("0000".to_owned(), |mv| as_tei(&pos, &mv)),
+                    stats: Value::Null,
+                });
             }
         }
     }
 
     fn stop(&mut self) {}
 
+    fn bench(&mut self, depth: Option<i32>) {
+        println!("{}", bench(depth));
+    }
+
     fn print(&self) {
         match &self.pos {
             TakPositions::Size3(pos) => {
@@ -405,8 +410,8 @@ impl TEI for TEITakState {
     }
 
     fn print_options(&self) {
-        println!("{}", self.debug);
-        println!("{}", self.search);
+        output::emit(&self.debug);
+        output::emit(&self.search);
     }
 
     fn set_option(&mut self, name: &str, value: &str) {
@@ -423,21 +428,9 @@ impl TEI for TEITakState {
     }
 
     fn perft(&mut self, settings: &GoSettings) {
-        let info_handler = |depth: i32, elapsed: f32, nodes: u64| {
-            print!("info");
-            print!(" depth {}", depth);
-            print!(" nodes {}", nodes);
-            print!(" time {}", (elapsed * 1000.0) as u64);
-            if elapsed > 0.0 {
-                let nps = nodes as f32 / elapsed;
-                print!(" nps {}", nps as u64);
-            }
-            println!();
-        };
+        let info_handler = output::perft;
 
-        let final_handler = |nodes: u64| {
-            println!("nodes {}", nodes);
-        };
+        let final_handler = output::nodes;
 
         match &mut self.pos {
             TakPositions::Size3(pos) => {
@@ -462,45 +455,43 @@ impl TEI for TEITakState {
     }
 
     fn split(&mut self, settings: &GoSettings) {
-        let final_handler = |nodes: u64| {
-            println!("nodes {}", nodes);
-        };
+        let final_handler = output::nodes;
 
         match &mut self.pos {
             TakPositions::Size3(pos) => {
                 let fudge = pos.clone();
                 let info_handler =
-                    |mv: TakMove<3>, nodes: u64| println!("{} {}", as_tei(&fudge, &mv), nodes);
+                    |mv: TakMove<3>, nodes: u64| output::split(as_tei(&fudge, &mv), nodes);
                 perft::split(pos, settings.depth.unwrap(), &info_handler, &final_handler);
             }
             TakPositions::Size4(pos) => {
                 let fudge = pos.clone();
                 let info_handler =
-                    |mv: TakMove<4>, nodes: u64| println!("{} {}", as_tei(&fudge, &mv), nodes);
+                    |mv: TakMove<4>, nodes: u64| output::split(as_tei(&fudge, &mv), nodes);
                 perft::split(pos, settings.depth.unwrap(), &info_handler, &final_handler);
             }
             TakPositions::Size5(pos) => {
                 let fudge = pos.clone();
                 let info_handler =
-                    |mv: TakMove<5>, nodes: u64| println!("{} {}", as_tei(&fudge, &mv), nodes);
+                    |mv: TakMove<5>, nodes: u64| output::split(as_tei(&fudge, &mv), nodes);
                 perft::split(pos, settings.depth.unwrap(), &info_handler, &final_handler);
             }
             TakPositions::Size6(pos) => {
                 let fudge = pos.clone();
                 let info_handler =
-                    |mv: TakMove<6>, nodes: u64| println!("{} {}", as_tei(&fudge, &mv), nodes);
+                    |mv: TakMove<6>, nodes: u64| output::split(as_tei(&fudge, &mv), nodes);
                 perft::split(pos, settings.depth.unwrap(), &info_handler, &final_handler);
             }
             TakPositions::Size7(pos) => {
                 let fudge = pos.clone();
                 let info_handler =
-                    |mv: TakMove<7>, nodes: u64| println!("{} {}", as_tei(&fudge, &mv), nodes);
+                    |mv: TakMove<7>, nodes: u64| output::split(as_tei(&fudge, &mv), nodes);
                 perft::split(pos, settings.depth.unwrap(), &info_handler, &final_handler);
             }
             TakPositions::Size8(pos) => {
                 let fudge = pos.clone();
                 let info_handler =
-                    |mv: TakMove<8>, nodes: u64| println!("{} {}", as_tei(&fudge, &mv), nodes);
+                    |mv: TakMove<8>, nodes: u64| output::split(as_tei(&fudge, &mv), nodes);
                 perft::split(pos, settings.depth.unwrap(), &info_handler, &final_handler);
             }
         }
@@ -1,15 +1,16 @@
-use super::root::primary;
+use super::root::{bench, primary_with_stats};
+use crate::bound::Bound;
 use crate::prng;
-use crate::search::alphabeta::alphabeta;
-use crate::search::flatmc::flatmc;
-use crate::search::minimax::minimax;
-use crate::search::random::random;
+use crate::search;
+use crate::searchstats::SearchStats;
 use games::gamerules::GameRules;
 use games::general::side::Side;
 use games::perft;
 use games::tak::Dir;
 use games::tak::TakMove;
 use games::{gamerules::GameResult, tak::TakPosition};
+use protocols::json::ToJson;
+use protocols::output::{self, Bestmove, Info, Message};
 use protocols::ugi::options::*;
 use protocols::ugi::{GoSettings, UGI, UGIGameResult};
 
@@ -25,6 +26,8 @@ pub enum TakPositions {
 pub struct UGITakState {
     pub pos: TakPositions,
     pub prng: prng::XorshiftGenerator,
+    /// What the last search counted
+    pub stats: SearchStats,
     // Options
     pub debug: Check,
     pub size: Spin<i32>,
@@ -36,6 +39,7 @@ impl Default for UGITakState {
         Self {
             pos: TakPositions::Size6(TakPosition::startpos()),
             prng: prng::XorshiftGenerator::new(0xe50076937a9e5b1c),
+            stats: SearchStats::default(),
             // Options
             debug: Check {
                 name: "debug".to_owned(),
@@ -67,32 +71,26 @@ pub fn info_handler<const SIZE: usize>(
     depth: Option<i32>,
     seldepth: Option<i32>,
     score: Option<i32>,
+    bound: Option<Bound>,
     mate: Option<i32>,
     nodes: Option<u64>,
     elapsed: Option<u128>,
     hashfull: Option<i32>,
     pv: &Vec<TakMove<SIZE>>,
+    stats: Option<&SearchStats>,
 ) {
-    print!("info");
-    depth.inspect(|d| print!(" depth {}", d));
-    seldepth.inspect(|d| print!(" seldepth {}", d));
-    score.inspect(|s| print!(" score cp {}", s));
-    mate.inspect(|d| print!(" score mate {}", d));
-    nodes.inspect(|n| print!(" nodes {}", n));
-    elapsed.inspect(|ms| print!(" time {}", ms));
-    if let (Some(t), Some(n)) = (elapsed, nodes) {
-        if t > 0 {
-            print!(" nps {}", (n as u128 * 1000) / t);
-        }
-    }
-    hashfull.inspect(|hashfull| print!(" hashfull {}", hashfull));
-    if !pv.is_empty() {
-        print!(" pv");
-        for mv in pv {
-            print!(" {}", as_ugi(pos, mv));
-        }
-    }
-    println!();
+    output::emit(&Info {
+        depth,
+        seldepth,
+        score,
+        bound: bound.map(|b| b.to_string()),
+        mate,
+        nodes,
+        elapsed,
+        hashfull,
+        pv: pv.iter().map(|mv| as_ugi(pos, mv)).collect(),
+        stats: stats.map(ToJson::to_json),
+    });
 }
 
 #[must_use]
@@ -264,103 +262,112 @@ impl UGI for UGITakState {
     }
 
     fn go(&mut self, settings: &GoSettings) {
-        let settings = &protocols::GoSettings::from(settings);
+        let settings = &protocols::GoSettings {
+            debug: self.debug.value,
+            ..protocols::GoSettings::from(settings)
+        };
         match &self.pos {
             TakPositions::Size3(pos) => {
                 let eval = |_pos: &TakPosition<3>| -> i32 { 0 };
-                let bestmove = match self.search.value.as_str() {
-                    "primary" => primary(pos.clone(), settings, &info_handler),
-                    "random" => random(&pos.clone(), || self.prng.next()),
-                    "minimax" => minimax(pos.clone(), settings, &info_handler, &eval),
-                    "alphabeta" => alphabeta(pos.clone(), settings, &info_handler, &eval),
-                    "flatmc" => flatmc(pos.clone(), settings, &info_handler, || self.prng.next()),
-                    _ => panic!("Unknown search type"),
-                };
-                if let Some(mv) = bestmove {
-                    println!("bestmove {}", as_ugi(&pos, &mv));
-                } else {
-                    println!("bestmove 0000");
-                }
+                let bestmove = search::think(
+                    &self.search.value,
+                    pos,
+                    settings,
+                    &info_handler,
+                    &eval,
+                    &mut self.prng,
+                    &mut self.stats,
+                    |stats| primary_with_stats(pos.clone(), settings, &info_handler, stats),
+                );
+                output::emit(&Bestmove {
+                    mv: bestmove.map_or("0000".to_owned(), |mv| as_ugi(&pos, &mv)),
+                    stats: self.stats.to_json(),
+                });
             }
             TakPositions::Size4(pos) => {
                 let eval = |_pos: &TakPosition<4>| -> i32 { 0 };
-                let bestmove = match self.search.value.as_str() {
-                    "primary" => primary(pos.clone(), settings, &info_handler),
-                    "random" => random(&pos.clone(), || self.prng.next()),
-                    "minimax" => minimax(pos.clone(), settings, &info_handler, &eval),
-                    "alphabeta" => alphabeta(pos.clone(), settings, &info_handler, &eval),
-                    "flatmc" => flatmc(pos.clone(), settings, &info_handler, || self.prng.next()),
-                    _ => panic!("Unknown search type"),
-                };
-                if let Some(mv) = bestmove {
-                    println!("bestmove {}", as_ugi(&pos, &mv));
-                } else {
-                    println!("bestmove 0000");
-                }
+                let bestmove = search::think(
+                    &self.search.value,
+                    pos,
+                    settings,
+                    &info_handler,
+                    &eval,
+                    &mut self.prng,
+                    &mut self.stats,
+                    |stats| primary_with_stats(pos.clone(), settings, &info_handler, stats),
+                );
+                output::emit(&Bestmove {
+                    mv: bestmove.map_or("0000".to_owned(), |mv| as_ugi(&pos, &mv)),
+                    stats: self.stats.to_json(),
+                });
             }
             TakPositions::Size5(pos) => {
                 let eval = |_pos: &TakPosition<5>| -> i32 { 0 };
-                let bestmove = match self.search.value.as_str() {
-                    "primary" => primary(pos.clone(), settings, &info_handler),
-                    "random" => random(&pos.clone(), || self.prng.next()),
-                    "minimax" => minimax(pos.clone(), settings, &info_handler, &eval),
-                    "alphabeta" => alphabeta(pos.clone(), settings, &info_handler, &eval),
-                    "flatmc" => flatmc(pos.clone(), settings, &info_handler, || self.prng.next()),
-                    _ => panic!("Unknown search type"),
-                };
-                if let Some(mv) = bestmove {
-                    println!("bestmove {}", as_ugi(&pos, &mv));
-                } else {
-                    println!("bestmove 0000");
-                }
+                let bestmove = search::think(
+                    &self.search.value,
+                    pos,
+                    settings,
+                    &info_handler,
+                    &eval,
+                    &mut self.prng,
+                    &mut self.stats,
+                    |stats| primary_with_stats(pos.clone(), settings, &info_handler, stats),
+                );
+                output::emit(&Bestmove {
+                    mv: bestmove.map_or("0000".to_owned(), |mv| as_ugi(&pos, &mv)),
+                    stats: self.stats.to_json(),
+                });
             }
             TakPositions::Size6(pos) => {
                 let eval = |_pos: &TakPosition<6>| -> i32 { 0 };
-                let bestmove = match self.search.value.as_str() {
-                    "primary" => primary(pos.clone(), settings, &info_handler),
-                    "random" => random(&pos.clone(), || self.prng.next()),
-                    "minimax" => minimax(pos.clone(), settings, &info_handler, &eval),
-                    "alphabeta" => alphabeta(pos.clone(), settings, &info_handler, &eval),
-                    "flatmc" => flatmc(pos.clone(), settings, &info_handler, || self.prng.next()),
-                    _ => panic!("Unknown search type"),
-                };
-                if let Some(mv) = bestmove {
-                    println!("bestmove {}", as_ugi(&pos, &mv));
-                } else {
-                    println!("bestmove 0000");
-                }
+                let bestmove = search::think(
+                    &self.search.value,
+                    pos,
+                    settings,
+                    &info_handler,
+                    &eval,
+                    &mut self.prng,
+                    &mut self.stats,
+                    |stats| primary_with_stats(pos.clone(), settings, &info_handler, stats),
+                );
+                output::emit(&Bestmove {
+                    mv: bestmove.map_or("0000".to_owned(), |mv| as_ugi(&pos, &mv)),
+                    stats: self.stats.to_json(),
+                });
             }
             TakPositions::Size7(pos) => {
                 let eval = |_pos: &TakPosition<7>| -> i32 { 0 };
-                let bestmove = match self.search.value.as_str() {
-                    "primary" => primary(pos.clone(), settings, &info_handler),
-                    "random" => random(&pos.clone(), || self.prng.next()),
-                    "minimax" => minimax(pos.clone(), settings, &info_handler, &eval),
-                    "alphabeta" => alphabeta(pos.clone(), settings, &info_handler, &eval),
-                    "flatmc" => flatmc(pos.clone(), settings, &info_handler, || self.prng.next()),
-                    _ => panic!("Unknown search type"),
-                };
-                if let Some(mv) = bestmove {
-                    println!("bestmove {}", as_ugi(&pos, &mv));
-                } else {
-                    println!("bestmove 0000");
-                }
+                let bestmove = search::think(
+                    &self.search.value,
+                    pos,
+                    settings,
+                    &info_handler,
+                    &eval,
+                    &mut self.prng,
+                    &mut self.stats,
+                    |stats| primary_with_stats(pos.clone(), settings, &info_handler, stats),
+                );
+                output::emit(&Bestmove {
+                    mv: bestmove.map_or("0000".to_owned(), |mv| as_ugi(&pos, &mv)),
+                    stats: self.stats.to_json(),
+                });
             }
             TakPositions::Size8(pos) => {
                 let eval = |_pos: &TakPosition<8>| -> i32 { 0 };
-                let bestmove = match self.search.value.as_str() {
-                    "primary" => primary(pos.clone(), settings, &info_handler),
-                    "random" => random(&pos.clone(), || self.prng.next()),
-                    "minimax" => minimax(pos.clone(), settings, &info_handler, &eval),
-                    "alphabeta" => alphabeta(pos.clone(), settings, &info_handler, &eval),
-                    "flatmc" => flatmc(pos.clone(), settings, &info_handler, || self.prng.next()),
-                    _ => panic!("Unknown search type"),
-                };
-                if let Some(mv) = bestmove {
-                    println!("bestmove {}", as_ugi(&pos, &mv));
-                } else {
-                    println!("bestmove 0000");
-                }
+                let bestmove = search::think(
+                    &self.search.value,
+                    pos,
+                    settings,
+                    &info_handler,
+                    &eval,
+                    &mut self.prng,
+                    &mut self.stats,
+                    |stats| primary_with_stats(pos.clone(), settings, &info_handler, stats),
+                );
+                output::emit(&Bestmove {
+                    mv: bestmove.map_or("0000".to_owned(), |mv| as_ugi(&pos, &mv)),
+                    stats: self.stats.to_json(),
+                });
             }
         }
     }
@@ -370,17 +377,23 @@ impl UGI for UGITakState {
     fn unrecognised_command(
         &mut self,
         word: &str,
-        _args: &mut std::iter::Peekable<std::str::SplitAsciiWhitespace>,
+        args: &mut std::iter::Peekable<std::str::SplitAsciiWhitespace>,
     ) {
-        if word == "print" {
-            self.print();
+        match word {
+            "print" => self.print(),
+            "bench" => {
+                let depth = args.next().and_then(|word| word.parse::<i32>().ok());
+                println!("{}", bench(depth));
+            }
+            "stats" => output::emit(&Message(self.stats.to_string())),
+            _ => {}
         }
     }
 
     fn print_options(&self) {
-        println!("{}", self.debug);
-        println!("{}", self.size);
-        println!("{}", self.search);
+        output::emit(&self.debug);
+        output::emit(&self.size);
+        output::emit(&self.search);
     }
 
     fn set_option(&mut self, name: &str, value: &str) {
@@ -438,21 +451,9 @@ impl UGI for UGITakState {
     }
 
     fn perft(&mut self, settings: &GoSettings) {
-        let info_handler = |depth: i32, elapsed: f32, nodes: u64| {
-            print!("info");
-            print!(" depth {}", depth);
-            print!(" nodes {}", nodes);
-            print!(" time {}", (elapsed * 1000.0) as u64);
-            if elapsed > 0.0 {
-                let nps = nodes as f32 / elapsed;
-                print!(" nps {}", nps as u64);
-            }
-            println!();
-        };
+        let info_handler = output::perft;
 
-        let final_handler = |nodes: u64| {
-            println!("nodes {}", nodes);
-        };
+        let final_handler = output::nodes;
 
         match &mut self.pos {
             TakPositions::Size3(pos) => {
@@ -477,45 +478,43 @@ impl UGI for UGITakState {
     }
 
     fn split(&mut self, settings: &GoSettings) {
-        let final_handler = |nodes: u64| {
-            println!("nodes {}", nodes);
-        };
+        let final_handler = output::nodes;
 
         match &mut self.pos {
             TakPositions::Size3(pos) => {
                 let fudge = pos.clone();
                 let info_handler =
-                    |mv: TakMove<3>, nodes: u64| println!("{} {}", as_ugi(&fudge, &mv), nodes);
+                    |mv: TakMove<3>, nodes: u64| output::split(as_ugi(&fudge, &mv), nodes);
                 perft::split(pos, settings.depth.unwrap(), &info_handler, &final_handler);
             }
             TakPositions::Size4(pos) => {
                 let fudge = pos.clone();
                 let info_handler =
-                    |mv: TakMove<4>, nodes: u64| println!("{} {}", as_ugi(&fudge, &mv), nodes);
+                    |mv: TakMove<4>, nodes: u64| output::split(as_ugi(&fudge, &mv), nodes);
                 perft::split(pos, settings.depth.unwrap(), &info_handler, &final_handler);
             }
             TakPositions::Size5(pos) => {
                 let fudge = pos.clone();
                 let info_handler =
-                    |mv: TakMove<5>, nodes: u64| println!("{} {}", as_ugi(&fudge, &mv), nodes);
+                    |mv: TakMove<5>, nodes: u64| output::split(as_ugi(&fudge, &mv), nodes);
                 perft::split(pos, settings.depth.unwrap(), &info_handler, &final_handler);
             }
             TakPositions::Size6(pos) => {
                 let fudge = pos.clone();
                 let info_handler =
-                    |mv: TakMove<6>, nodes: u64| println!("{} {}", as_ugi(&fudge, &mv), nodes);
+                    |mv: TakMove<6>, nodes: u64| output::split(as_ugi(&fudge, &mv), nodes);
                 perft::split(pos, settings.depth.unwrap(), &info_handler, &final_handler);
             }
             TakPositions::Size7(pos) => {
                 let fudge = pos.clone();
                 let info_handler =
-                    |mv: TakMove<7>, nodes: u64| println!("{} {}", as_ugi(&fudge, &mv), nodes);
+                    |mv: TakMove<7>, nodes: u64| output::split(as_ugi(&fudge, &mv), nodes);
                 perft::split(pos, settings.depth.unwrap(), &info_handler, &final_handler);
             }
             TakPositions::Size8(pos) => {
                 let fudge = pos.clone();
                 let info_handler =
-                    |mv: TakMove<8>, nodes: u64| println!("{} {}", as_ugi(&fudge, &mv), nodes);
+                    |mv: TakMove<8>, nodes: u64| output::split(as_ugi(&fudge, &mv), nodes);
                 perft::split(pos, settings.depth.unwrap(), &info_handler, &final_handler);
             }
         }
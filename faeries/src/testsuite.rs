@@ -0,0 +1,299 @@
+use crate::bound::Bound;
+use crate::searchstats::SearchStats;
+use protocols::GoSettings;
+use std::{cell::RefCell, fmt, time::Instant};
+
+/// One line of an EPD file: a position, and what should be played there.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Epd {
+    pub fen: String,
+    /// `bm`, any of which solves the position
+    pub best: Vec<String>,
+    /// `am`, none of which may be played
+    pub avoid: Vec<String>,
+    pub id: Option<String>,
+}
+
+impl Epd {
+    /// Everything up to the first operation is the FEN, however many fields
+    /// the game's FENs have. Operations are an opcode and its operands, ended
+    /// by a `;`, and a quoted operand is a single one. Blank lines and `#`
+    /// comments give `None`.
+    ///
+    /// Castling rights and shogi hands look like opcodes, so only the standard
+    /// ones start the operations. Once they've started anything goes.
+    #[must_use]
+    pub fn parse(line: &str) -> Option<Epd> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+
+        let mut fen = vec![];
+        let mut rest = line;
+        while let Some(word) = rest.split_ascii_whitespace().next() {
+            if is_opcode(word.trim_end_matches(';')) {
+                break;
+            }
+            fen.push(word);
+            rest = rest.trim_start()[word.len()..].trim_start();
+        }
+
+        let mut epd = Epd {
+            fen: fen.join(" "),
+            best: vec![],
+            avoid: vec![],
+            id: None,
+        };
+
+        for operation in operations(rest) {
+            let Some((opcode, operands)) = operation.split_first() else {
+                continue;
+            };
+            match opcode.as_str() {
+                "bm" => epd.best.extend_from_slice(operands),
+                "am" => epd.avoid.extend_from_slice(operands),
+                "id" => epd.id = Some(operands.join(" ")),
+                _ => {}
+            }
+        }
+
+        Some(epd)
+    }
+
+    /// Whether playing a move known by any of `names` solves the position.
+    #[must_use]
+    pub fn is_solved_by(&self, names: &[String]) -> bool {
+        let best = self.best.is_empty() || names.iter().any(|name| self.best.contains(name));
+        let avoided = names.iter().all(|name| !self.avoid.contains(name));
+        best && avoided
+    }
+
+    #[must_use]
+    pub fn name(&self) -> &str {
+        self.id.as_deref().unwrap_or(&self.fen)
+    }
+}
+
+/// The opcodes the EPD standard gives, numbered comments and variations too.
+const OPCODES: [&str; 25] = [
+    "acd",
+    "acn",
+    "acs",
+    "am",
+    "bm",
+    "ce",
+    "dm",
+    "draw_accept",
+    "draw_claim",
+    "draw_offer",
+    "draw_reject",
+    "eco",
+    "fmvn",
+    "hmvc",
+    "id",
+    "nic",
+    "noop",
+    "pm",
+    "pv",
+    "rc",
+    "resign",
+    "sm",
+    "tcgi",
+    "tcri",
+    "tcsi",
+];
+
+#[must_use]
+fn is_opcode(word: &str) -> bool {
+    match word.as_bytes() {
+        [b'c' | b'v', digit] => digit.is_ascii_digit(),
+        _ => OPCODES.contains(&word),
+    }
+}
+
+/// Split the operations into their words, keeping a quoted string, `;`s and
+/// spaces and all, as one word without its quotes. The last operation counts
+/// even without its `;`.
+#[must_use]
+fn operations(text: &str) -> Vec<Vec<String>> {
+    let mut operations = vec![];
+    let mut words = vec![];
+    let mut word = String::new();
+    let mut quoted = false;
+
+    for c in text.chars().chain(std::iter::once(';')) {
+        match c {
+            '"' => quoted = !quoted,
+            _ if quoted => word.push(c),
+            ';' => {
+                if !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+                if !words.is_empty() {
+                    operations.push(std::mem::take(&mut words));
+                }
+            }
+            _ if c.is_ascii_whitespace() => {
+                if !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+            }
+            _ => word.push(c),
+        }
+    }
+
+    operations
+}
+
+/// Read a whole EPD file. Lines with no best or avoid move have nothing to
+/// check, so they're left out.
+#[must_use]
+pub fn parse_epds(text: &str) -> Vec<Epd> {
+    text.lines()
+        .filter_map(Epd::parse)
+        .filter(|epd| !epd.best.is_empty() || !epd.avoid.is_empty())
+        .collect()
+}
+
+/// How one position went.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Outcome {
+    pub name: String,
+    pub played: Option<String>,
+    pub solved: bool,
+    /// When the search settled on a solving move for good, in milliseconds
+    pub time_to_solution: Option<u128>,
+}
+
+/// How a whole suite went.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Report {
+    pub outcomes: Vec<Outcome>,
+    pub elapsed: u128,
+}
+
+impl Report {
+    #[must_use]
+    pub fn solved(&self) -> usize {
+        self.outcomes
+            .iter()
+            .filter(|outcome| outcome.solved)
+            .count()
+    }
+
+    pub fn failed(&self) -> impl Iterator<Item = &Outcome> {
+        self.outcomes.iter().filter(|outcome| !outcome.solved)
+    }
+
+    /// The time to solution summed over the solved positions.
+    #[must_use]
+    pub fn time_to_solution(&self) -> u128 {
+        self.outcomes
+            .iter()
+            .filter_map(|outcome| outcome.time_to_solution)
+            .sum()
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for outcome in self.failed() {
+            writeln!(
+                f,
+                "failed {} played {}",
+                outcome.name,
+                outcome.played.as_deref().unwrap_or("none")
+            )?;
+        }
+        write!(
+            f,
+            "solved {} failed {} total {} tts {} ms time {} ms",
+            self.solved(),
+            self.outcomes.len() - self.solved(),
+            self.outcomes.len(),
+            self.time_to_solution(),
+            self.elapsed
+        )
+    }
+}
+
+/// What a search is given to report its progress with.
+pub type InfoHandler<'a, P, M> = dyn Fn(
+        &P,
+        Option<i32>,
+        Option<i32>,
+        Option<i32>,
+        Option<Bound>,
+        Option<i32>,
+        Option<u64>,
+        Option<u128>,
+        Option<i32>,
+        &Vec<M>,
+        Option<&SearchStats>,
+    ) + 'a;
+
+/// Search every position within `settings` and check the move played.
+/// `names` gives every way a move might be written in the EPD, and `search`
+/// searches a position with the info handler it's given.
+///
+/// The time to solution is when the first completed iteration of an unbroken
+/// run of solving moves came in, or the whole search if none did.
+#[must_use]
+pub fn run<P: Clone, M>(
+    epds: &[Epd],
+    settings: &GoSettings,
+    from_fen: impl Fn(&str) -> P,
+    names: impl Fn(&P, &M) -> Vec<String>,
+    search: impl Fn(P, &GoSettings, &InfoHandler<P, M>) -> Option<M>,
+) -> Report {
+    let start = Instant::now();
+    let mut outcomes = vec![];
+
+    for epd in epds {
+        let pos = from_fen(&epd.fen);
+        let started = Instant::now();
+        let settled = RefCell::new(None);
+        let info_handler = |pos: &P,
+                            _: Option<i32>,
+                            _: Option<i32>,
+                            _: Option<i32>,
+                            bound: Option<Bound>,
+                            _: Option<i32>,
+                            _: Option<u64>,
+                            elapsed: Option<u128>,
+                            _: Option<i32>,
+                            pv: &Vec<M>,
+                            _: Option<&SearchStats>| {
+            let (None, Some(mv)) = (bound, pv.first()) else {
+                return;
+            };
+            let mut settled = settled.borrow_mut();
+            if !epd.is_solved_by(&names(pos, mv)) {
+                *settled = None;
+            } else if settled.is_none() {
+                *settled = Some(elapsed.unwrap_or(started.elapsed().as_millis()));
+            }
+        };
+
+        let played = search(pos.clone(), settings, &info_handler).map(|mv| names(&pos, &mv));
+        let solved = played.as_ref().is_some_and(|names| epd.is_solved_by(names));
+        let time_to_solution = solved.then(|| {
+            settled
+                .into_inner()
+                .unwrap_or(started.elapsed().as_millis())
+        });
+
+        outcomes.push(Outcome {
+            name: epd.name().to_owned(),
+            played: played.and_then(|names| names.into_iter().next()),
+            solved,
+            time_to_solution,
+        });
+    }
+
+    Report {
+        outcomes,
+        elapsed: start.elapsed().as_millis(),
+    }
+}
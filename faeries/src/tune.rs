@@ -0,0 +1,228 @@
+/// An evaluation that's a weighted sum of features, so that the weights can be
+/// fitted to the results of games rather than guessed.
+pub trait Tunable {
+    /// What each weight is for, in the order the features are counted
+    const NAMES: &'static [&'static str];
+    /// The weights the evaluation uses now
+    const WEIGHTS: &'static [i32];
+
+    /// How many more of each feature the side to move has than the other side.
+    /// The evaluation is these times the weights.
+    fn features(&self) -> Vec<i32>;
+
+    /// Results are given from the first player's view, and evaluations from
+    /// the side to move's.
+    fn first_to_move(&self) -> bool;
+}
+
+#[must_use]
+pub fn dot<const N: usize>(weights: &[i32; N], features: &[i32; N]) -> i32 {
+    weights.iter().zip(features).map(|(w, f)| w * f).sum()
+}
+
+#[must_use]
+pub fn difference<const N: usize>(us: [i32; N], them: [i32; N]) -> [i32; N] {
+    std::array::from_fn(|i| us[i] - them[i])
+}
+
+/// A position's features, and how its game went for the side to move: 1 for a
+/// win, 0.5 for a draw and 0 for a loss.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Sample {
+    pub features: Vec<f64>,
+    pub result: f64,
+}
+
+impl Sample {
+    #[must_use]
+    pub fn new<G: Tunable>(pos: &G, result: f64) -> Self {
+        Sample {
+            features: pos.features().into_iter().map(f64::from).collect(),
+            result: if pos.first_to_move() {
+                result
+            } else {
+                1.0 - result
+            },
+        }
+    }
+
+    #[must_use]
+    pub fn eval(&self, weights: &[f64]) -> f64 {
+        weights.iter().zip(&self.features).map(|(w, f)| w * f).sum()
+    }
+}
+
+/// A result from the first player's view, as `1-0`, `1/2-1/2`, `0-1`, or a
+/// score between 0 and 1, with or without square brackets.
+#[must_use]
+pub fn parse_result(word: &str) -> Option<f64> {
+    match word.trim_matches(|c| c == '[' || c == ']' || c == '"' || c == ';') {
+        "1-0" => Some(1.0),
+        "1/2-1/2" | "=" => Some(0.5),
+        "0-1" => Some(0.0),
+        score => score
+            .parse::<f64>()
+            .ok()
+            .filter(|s| (0.0..=1.0).contains(s)),
+    }
+}
+
+/// A labelled position: the FEN followed by the result, or datagen's
+/// `fen | score | result`. Blank lines and `#` comments give `None`, as do
+/// lines without a result.
+#[must_use]
+pub fn parse_line(line: &str) -> Option<(&str, f64)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    if let Some((fen, rest)) = line.split_once('|') {
+        let (_, result) = rest.rsplit_once('|')?;
+        return Some((fen.trim(), parse_result(result.trim())?));
+    }
+    let (fen, result) = line.rsplit_once(char::is_whitespace)?;
+    Some((fen.trim_end().trim_end_matches(';'), parse_result(result)?))
+}
+
+/// The chance of winning an evaluation is taken to mean.
+#[must_use]
+pub fn sigmoid(score: f64, k: f64) -> f64 {
+    1.0 / (1.0 + (-k * score / 400.0).exp())
+}
+
+/// How far the evaluations are from the results, as the mean squared error.
+#[must_use]
+pub fn error(samples: &[Sample], weights: &[f64], k: f64) -> f64 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    let total: f64 = samples
+        .iter()
+        .map(|sample| (sample.result - sigmoid(sample.eval(weights), k)).powi(2))
+        .sum();
+    total / samples.len() as f64
+}
+
+/// The scale that turns the evaluations into the results best, so that tuning
+/// changes how positions compare rather than how big the numbers are.
+#[must_use]
+pub fn fit_scale(samples: &[Sample], weights: &[f64]) -> f64 {
+    let mut best = 1.0;
+    let mut step = 1.0;
+
+    // Each pass looks either side of the best so far, ten times closer
+    for _ in 0..6 {
+        let start = best - 10.0 * step;
+        best = (0..=20)
+            .map(|i| start + i as f64 * step)
+            .filter(|k| *k > 0.0)
+            .min_by(|a, b| error(samples, weights, *a).total_cmp(&error(samples, weights, *b)))
+            .unwrap_or(best);
+        step /= 10.0;
+    }
+
+    best
+}
+
+/// Gradient descent on the error, with Adam to take steps of about the same
+/// size whichever weight they're for and however rare its feature is.
+pub struct Tuner {
+    pub weights: Vec<f64>,
+    pub k: f64,
+    pub rate: f64,
+    momentum: Vec<f64>,
+    velocity: Vec<f64>,
+    epoch: i32,
+}
+
+impl Tuner {
+    const BETA1: f64 = 0.9;
+    const BETA2: f64 = 0.999;
+    const EPSILON: f64 = 1e-8;
+
+    #[must_use]
+    pub fn new(weights: &[i32], k: f64, rate: f64) -> Self {
+        Tuner {
+            weights: weights.iter().map(|w| f64::from(*w)).collect(),
+            k,
+            rate,
+            momentum: vec![0.0; weights.len()],
+            velocity: vec![0.0; weights.len()],
+            epoch: 0,
+        }
+    }
+
+    /// The gradient of the error with respect to each weight.
+    #[must_use]
+    pub fn gradient(&self, samples: &[Sample]) -> Vec<f64> {
+        let mut gradient = vec![0.0; self.weights.len()];
+        for sample in samples {
+            let s = sigmoid(sample.eval(&self.weights), self.k);
+            let scale = (s - sample.result) * s * (1.0 - s) * self.k / 400.0;
+            for (g, f) in gradient.iter_mut().zip(&sample.features) {
+                *g += scale * f;
+            }
+        }
+        let n = samples.len().max(1) as f64;
+        gradient.iter().map(|g| 2.0 * g / n).collect()
+    }
+
+    /// One pass over every sample.
+    pub fn step(&mut self, samples: &[Sample]) {
+        let gradient = self.gradient(samples);
+        self.epoch += 1;
+
+        for (i, g) in gradient.iter().enumerate() {
+            self.momentum[i] = Self::BETA1 * self.momentum[i] + (1.0 - Self::BETA1) * g;
+            self.velocity[i] = Self::BETA2 * self.velocity[i] + (1.0 - Self::BETA2) * g * g;
+            let m = self.momentum[i] / (1.0 - Self::BETA1.powi(self.epoch));
+            let v = self.velocity[i] / (1.0 - Self::BETA2.powi(self.epoch));
+            self.weights[i] -= self.rate * m / (v.sqrt() + Self::EPSILON);
+        }
+    }
+
+    #[must_use]
+    pub fn rounded(&self) -> Vec<i32> {
+        self.weights.iter().map(|w| w.round() as i32).collect()
+    }
+}
+
+/// The weights as the `WEIGHTS` constant of an eval module.
+#[must_use]
+pub fn as_rust(names: &[&str], weights: &[i32]) -> String {
+    let mut source = format!("pub const WEIGHTS: [i32; {}] = [\n", weights.len());
+    for (name, weight) in names.iter().zip(weights) {
+        source += &format!("    {}, // {}\n", weight, name);
+    }
+    source + "];"
+}
+
+/// The weights one to a line as `name value`, to carry on tuning from later.
+#[must_use]
+pub fn as_weights(names: &[&str], weights: &[i32]) -> String {
+    names
+        .iter()
+        .zip(weights)
+        .map(|(name, weight)| format!("{} {}", name, weight))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Read weights written by `as_weights`. Any weight the file leaves out keeps
+/// its value in `defaults`.
+pub fn parse_weights(names: &[&str], defaults: &[i32], text: &str) -> Result<Vec<i32>, String> {
+    let mut weights = defaults.to_vec();
+    for line in text.lines().filter(|line| !line.trim().is_empty()) {
+        let Some((name, value)) = line.trim().split_once(char::is_whitespace) else {
+            return Err(format!("Bad weight: '{}'", line));
+        };
+        let Some(idx) = names.iter().position(|n| *n == name) else {
+            return Err(format!("Unknown weight: '{}'", name));
+        };
+        weights[idx] = value
+            .trim()
+            .parse::<i32>()
+            .map_err(|e| format!("Bad value for {}: {}", name, e))?;
+    }
+    Ok(weights)
+}
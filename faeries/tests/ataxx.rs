@@ -1,13 +1,24 @@
+mod common;
+
 #[cfg(test)]
 mod ataxx {
+    use crate::common;
     use faeries::{
-        ataxx::{root::primary, ugi::as_ugi},
+        ataxx::{
+            nnue::{Evaluator, HIDDEN, INPUTS, MAX_EVAL, Network},
+            root::{primary, primary_with_stats},
+            ugi::{as_ugi, info_handler as print_handler},
+        },
+        bench::quiet,
+        bound::Bound,
         prng,
         search::{alphabeta::alphabeta, flatmc::flatmc, minimax::minimax, random::random},
+        searchstats::SearchStats,
     };
     use games::{
         ataxx::{AtaxxMove, AtaxxPosition},
-        gamerules::GameRules,
+        gamerules::{GameResult, GameRules},
+        general::side::Side,
     };
     use protocols::GoSettings;
 
@@ -16,11 +27,13 @@ mod ataxx {
         _: Option<i32>,
         _: Option<i32>,
         _: Option<i32>,
+        _: Option<Bound>,
         _: Option<i32>,
         _: Option<u64>,
         _: Option<u128>,
         _: Option<i32>,
         _: &Vec<AtaxxMove<7, 7>>,
+        _: Option<&SearchStats>,
     ) {
     }
 
@@ -29,19 +42,19 @@ mod ataxx {
         pos.get_us().count() - pos.get_them().count()
     }
 
+    const WIN_IN_ONE: [(&str, &str); 4] = [
+        ("7/7/7/3o3/7/7/x6 x 0 1", "a1c3"),
+        ("7/7/7/3o3/7/7/x6 o 0 1", "d4b2"),
+        ("7/7/2ooo2/2o1o2/2ooo2/3x3/7 x 0 1", "d2d4"),
+        ("7/7/2xxx2/2x1x2/2xxx2/3o3/7 o 0 1", "d2d4"),
+    ];
+
     #[test]
     fn win_in_one() {
-        let tests = [
-            ("7/7/7/3o3/7/7/x6 x 0 1", "a1c3"),
-            ("7/7/7/3o3/7/7/x6 o 0 1", "d4b2"),
-            ("7/7/2ooo2/2o1o2/2ooo2/3x3/7 x 0 1", "d2d4"),
-            ("7/7/2xxx2/2x1x2/2xxx2/3o3/7 o 0 1", "d2d4"),
-        ];
-
         let settings = GoSettings::from_depth(3);
         let mut prng = prng::XorshiftGenerator::new(0xe50076937a9e5b1c);
 
-        for (fen, movestr) in tests {
+        for (fen, movestr) in WIN_IN_ONE {
             let pos = AtaxxPosition::from_fen(fen);
 
             // Primary
@@ -82,17 +95,17 @@ mod ataxx {
         }
     }
 
+    const EASY_MOVES: [(&str, &str); 2] = [
+        ("7/3o3/2ooo2/2o1o2/2ooo2/3x3/7 x 0 1", "d2d4"),
+        ("7/3x3/2xxx2/2x1x2/2xxx2/3o3/7 o 0 1", "d2d4"),
+    ];
+
     #[test]
     fn easy_moves() {
-        let tests = [
-            ("7/3o3/2ooo2/2o1o2/2ooo2/3x3/7 x 0 1", "d2d4"),
-            ("7/3x3/2xxx2/2x1x2/2xxx2/3o3/7 o 0 1", "d2d4"),
-        ];
-
         let settings = GoSettings::from_depth(3);
         let mut prng = prng::XorshiftGenerator::new(0xe50076937a9e5b1c);
 
-        for (fen, movestr) in tests {
+        for (fen, movestr) in EASY_MOVES {
             let pos = AtaxxPosition::from_fen(fen);
 
             // Primary
@@ -190,4 +203,125 @@ mod ataxx {
         );
         assert_eq!(random(&pos, || prng.next()), None);
     }
+
+    #[test]
+    fn sizes() {
+        #[must_use]
+        fn wins<const W: usize, const H: usize>(fen: &str) -> bool {
+            let mut pos = AtaxxPosition::<W, H>::from_fen(fen);
+            let Some(mv) = primary(pos.clone(), &GoSettings::from_depth(2), &print_handler) else {
+                return false;
+            };
+            pos.makemove(&mv);
+            pos.get_result() == Some(GameResult::Win(Side::Player1))
+        }
+
+        #[must_use]
+        fn plays<const W: usize, const H: usize>() -> bool {
+            let pos = AtaxxPosition::<W, H>::startpos();
+            let mv = primary(pos.clone(), &GoSettings::from_depth(2), &print_handler);
+            mv.is_some_and(|mv| pos.legal_moves().contains(&mv))
+        }
+
+        assert!(wins::<5, 5>("5/5/2o2/5/x4 x 0 1"));
+        assert!(wins::<6, 6>("6/6/6/2o3/6/x5 x 0 1"));
+        assert!(wins::<8, 8>("8/8/8/8/8/2o5/8/x7 x 0 1"));
+
+        assert!(plays::<5, 5>());
+        assert!(plays::<6, 6>());
+        assert!(plays::<7, 7>());
+        assert!(plays::<8, 8>());
+    }
+
+    #[test]
+    fn fewer_nodes() {
+        common::fewer_nodes(
+            WIN_IN_ONE.into_iter().chain(EASY_MOVES).map(|(fen, _)| fen),
+            3,
+            |pos, settings, stats| primary_with_stats(pos, settings, &quiet, stats),
+            eval,
+            |_, mv| as_ugi(mv),
+        );
+    }
+
+    #[test]
+    fn network() {
+        let mut prng = prng::XorshiftGenerator::new(0x1234_5678_9abc_def0);
+        let mut random = || (prng.next() % 201) as i16 - 100;
+        let network = Network {
+            feature_weights: (0..INPUTS)
+                .map(|_| std::array::from_fn(|_| random()))
+                .collect(),
+            feature_bias: std::array::from_fn(|_| random()),
+            output_weights: std::array::from_fn(|_| random()),
+            output_bias: random(),
+        };
+
+        // Files
+        let bytes = network.to_bytes();
+        assert_eq!(bytes.len(), 2 * (INPUTS * HIDDEN + 3 * HIDDEN + 1));
+        assert_eq!(Network::from_bytes(&bytes).unwrap(), network);
+        assert!(Network::from_bytes(&bytes[1..]).is_err());
+
+        // The accumulators kept up to date agree with ones worked out again
+        for fen in ["x5o/7/7/7/7/7/o5x x 0 1", "x5o/7/2-1-2/7/2-1-2/7/o5x o 0 1"] {
+            let mut pos = AtaxxPosition::<7, 7>::from_fen(fen);
+            let mut evaluator = Evaluator::new(Some(&network), &pos);
+            let start = evaluator.eval(&pos);
+            let mut played = vec![];
+
+            for ply in 0..20 {
+                let mut moves = pos.legal_moves();
+                if moves.is_empty() {
+                    break;
+                }
+                let mv = moves.swap_remove((ply * 7) % moves.len());
+                evaluator.makemove(&mut pos, &mv);
+                played.push(mv);
+                assert_eq!(
+                    evaluator.eval(&pos),
+                    network.evaluate(&network.accumulate(&pos), pos.get_turn()),
+                    "{}",
+                    pos.get_fen()
+                );
+            }
+
+            for mv in played.iter().rev() {
+                evaluator.undomove(&mut pos, mv);
+            }
+            assert_eq!(
+                pos.get_fen(),
+                AtaxxPosition::<7, 7>::from_fen(fen).get_fen()
+            );
+            assert_eq!(evaluator.eval(&pos), start);
+        }
+
+        // Other sizes keep to the handcrafted evaluation
+        let pos = AtaxxPosition::<5, 5>::startpos();
+        let evaluator = Evaluator::new(Some(&network), &pos);
+        assert_eq!(evaluator.eval(&pos), faeries::ataxx::eval::eval(&pos));
+    }
+
+    #[test]
+    fn network_limits() {
+        // Every hidden value clipped to the top and weighted as heavily as it gets
+        let network = |weight: i16| Network {
+            feature_weights: vec![[i16::MAX; HIDDEN]; INPUTS],
+            feature_bias: [i16::MAX; HIDDEN],
+            output_weights: [weight; 2 * HIDDEN],
+            output_bias: weight,
+        };
+        let pos = AtaxxPosition::<7, 7>::startpos();
+
+        let best = network(i16::MAX);
+        assert_eq!(
+            best.evaluate(&best.accumulate(&pos), pos.get_turn()),
+            MAX_EVAL
+        );
+        let worst = network(i16::MIN);
+        assert_eq!(
+            worst.evaluate(&worst.accumulate(&pos), pos.get_turn()),
+            -MAX_EVAL
+        );
+    }
 }
@@ -0,0 +1,283 @@
+#[cfg(test)]
+mod baduk {
+    use faeries::baduk::state::BadukState;
+    use faeries::gtp::{budget, clock, movetime, parse_vertex, vertex_name};
+    use protocols::gtp::listen::handle;
+
+    fn run(state: &mut BadukState, line: &str) -> Result<String, String> {
+        let words = line.split_ascii_whitespace().collect::<Vec<&str>>();
+        handle(state, words[0], &words[1..])
+    }
+
+    fn setup(size: i32, komi: f32, moves: &[&str]) -> BadukState {
+        let mut state = BadukState::default();
+        run(&mut state, &format!("boardsize {}", size)).unwrap();
+        run(&mut state, &format!("komi {}", komi)).unwrap();
+        for line in moves {
+            assert_eq!(run(&mut state, line), Ok(String::new()), "{}", line);
+        }
+        state
+    }
+
+    #[test]
+    fn vertices() {
+        assert_eq!(parse_vertex("A1", 19, 19), Some((0, 0)));
+        assert_eq!(parse_vertex("j1", 19, 19), Some((8, 0)));
+        assert_eq!(parse_vertex("T19", 19, 19), Some((18, 18)));
+        assert_eq!(parse_vertex("I1", 19, 19), None);
+        assert_eq!(parse_vertex("T20", 19, 19), None);
+        assert_eq!(parse_vertex("F1", 5, 5), None);
+        assert_eq!(parse_vertex("A0", 5, 5), None);
+        assert_eq!(parse_vertex("", 5, 5), None);
+        assert_eq!(vertex_name(8, 0), "J1");
+        assert_eq!(vertex_name(18, 18), "T19");
+    }
+
+    #[test]
+    fn boardsize() {
+        let mut state = BadukState::default();
+        assert_eq!(run(&mut state, "boardsize 9"), Ok(String::new()));
+        assert_eq!(run(&mut state, "boardsize 25"), Ok(String::new()));
+        assert_eq!(
+            run(&mut state, "boardsize 26"),
+            Err("unacceptable size".to_owned())
+        );
+        assert_eq!(
+            run(&mut state, "boardsize 1"),
+            Err("unacceptable size".to_owned())
+        );
+    }
+
+    #[test]
+    fn capture() {
+        let mut state = setup(
+            5,
+            0.0,
+            &[
+                "play black B1",
+                "play white B2",
+                "play black A2",
+                "play black C2",
+                "play black B3",
+            ],
+        );
+        assert_eq!(
+            run(&mut state, "final_status_list alive"),
+            Ok("B1 A2 C2 B3".to_owned())
+        );
+        assert_eq!(run(&mut state, "final_score"), Ok("B+25".to_owned()));
+
+        // Taking it back puts the captured stone back, though it's as good as dead
+        run(&mut state, "undo").unwrap();
+        assert_eq!(
+            run(&mut state, "final_status_list alive"),
+            Ok("B1 A2 C2".to_owned())
+        );
+        assert_eq!(
+            run(&mut state, "final_status_list dead"),
+            Ok("B2".to_owned())
+        );
+    }
+
+    #[test]
+    fn ko() {
+        let mut state = setup(
+            5,
+            0.5,
+            &[
+                "play black B4",
+                "play black A3",
+                "play black B2",
+                "play white C4",
+                "play white B3",
+                "play white D3",
+                "play white C2",
+                "play black C3",
+            ],
+        );
+
+        // White can't take straight back
+        assert_eq!(
+            run(&mut state, "play white B3"),
+            Err("illegal move".to_owned())
+        );
+
+        // But can after a move elsewhere
+        run(&mut state, "play white E5").unwrap();
+        run(&mut state, "play black E1").unwrap();
+        assert_eq!(run(&mut state, "play white B3"), Ok(String::new()));
+    }
+
+    #[test]
+    fn suicide() {
+        let mut state = setup(5, 0.5, &["play black A2", "play black B1"]);
+        assert_eq!(
+            run(&mut state, "play white A1"),
+            Err("illegal move".to_owned())
+        );
+
+        // Taking a stone isn't suicide
+        let mut state = setup(
+            5,
+            0.5,
+            &[
+                "play black A2",
+                "play black B1",
+                "play white A3",
+                "play white B2",
+                "play white C1",
+            ],
+        );
+        assert_eq!(run(&mut state, "play white A1"), Ok(String::new()));
+
+        // Unless it's allowed
+        let mut state = setup(
+            5,
+            0.5,
+            &["faeries-suicide on", "play black A2", "play black B1"],
+        );
+        assert_eq!(run(&mut state, "play white A1"), Ok(String::new()));
+        assert_eq!(
+            run(&mut state, "final_status_list alive"),
+            Ok("B1 A2".to_owned())
+        );
+    }
+
+    #[test]
+    fn scoring() {
+        let walls = [
+            "play black B1",
+            "play black B2",
+            "play black B3",
+            "play black B4",
+            "play black B5",
+            "play white D1",
+            "play white D2",
+            "play white D3",
+            "play white D4",
+            "play white D5",
+        ];
+
+        let mut state = setup(5, 0.5, &walls);
+        assert_eq!(run(&mut state, "final_score"), Ok("W+0.5".to_owned()));
+
+        let mut state = setup(5, 0.0, &walls);
+        assert_eq!(run(&mut state, "final_score"), Ok("0".to_owned()));
+
+        let mut state = setup(5, 0.0, &walls);
+        run(&mut state, "play black C3").unwrap();
+        assert_eq!(run(&mut state, "final_score"), Ok("B+1".to_owned()));
+    }
+
+    #[test]
+    fn dead() {
+        let mut state = setup(
+            5,
+            0.5,
+            &[
+                "play black B1",
+                "play black B2",
+                "play black B3",
+                "play black B4",
+                "play black B5",
+                "play white D1",
+                "play white D2",
+                "play white D3",
+                "play white D4",
+                "play white D5",
+                "play white A3",
+            ],
+        );
+        assert_eq!(
+            run(&mut state, "final_status_list dead"),
+            Ok("A3".to_owned())
+        );
+        assert_eq!(run(&mut state, "final_score"), Ok("W+0.5".to_owned()));
+    }
+
+    #[test]
+    fn time_settings() {
+        assert_eq!(clock(0, 0, 0), None);
+        assert_eq!(clock(0, 30, 0), None);
+        assert_eq!(clock(600, 0, 0), Some((600, None)));
+        assert_eq!(clock(600, 30, 5), Some((600, Some((30, 5)))));
+        assert_eq!(budget((0, 0), Some((30, 5))), movetime(30, 5));
+        assert_eq!(budget((20, 3), Some((30, 5))), movetime(20, 3));
+        assert_eq!(budget((600, 0), None), movetime(600, 0));
+
+        let mut state = setup(5, 0.5, &["time_settings 0 30 5"]);
+        assert_eq!(state.time_left, [Some((0, 0)); 2]);
+        assert_eq!(state.byoyomi, Some((30, 5)));
+        run(&mut state, "time_settings 0 30 0").unwrap();
+        assert_eq!(state.time_left, [None; 2]);
+        assert_eq!(state.byoyomi, None);
+    }
+
+    #[test]
+    fn status() {
+        let mut state = setup(5, 0.5, &["play black C3"]);
+        assert_eq!(run(&mut state, "final_status_list dead"), Ok(String::new()));
+        assert_eq!(run(&mut state, "final_status_list seki"), Ok(String::new()));
+        assert_eq!(
+            run(&mut state, "final_status_list sleepy"),
+            Err("invalid status".to_owned())
+        );
+    }
+
+    #[test]
+    fn undo() {
+        let mut state = setup(5, 0.5, &[]);
+        assert_eq!(run(&mut state, "undo"), Err("cannot undo".to_owned()));
+        run(&mut state, "play black C3").unwrap();
+        run(&mut state, "undo").unwrap();
+        assert_eq!(
+            run(&mut state, "final_status_list alive"),
+            Ok(String::new())
+        );
+        assert_eq!(run(&mut state, "undo"), Err("cannot undo".to_owned()));
+    }
+
+    #[test]
+    fn genmove() {
+        let mut state = setup(5, 0.5, &["faeries-playouts 100"]);
+
+        for colour in ["black", "white", "black", "white"] {
+            let vertex = run(&mut state, &format!("genmove {}", colour)).unwrap();
+            assert!(parse_vertex(&vertex, 5, 5).is_some(), "{}", vertex);
+        }
+        assert_eq!(state.history.len(), 4);
+    }
+
+    #[test]
+    fn genmove_pass() {
+        // White passed and Black has won, so there's nothing left to do
+        let mut state = setup(
+            5,
+            0.5,
+            &["faeries-playouts 100", "play black C3", "play white pass"],
+        );
+        assert_eq!(run(&mut state, "genmove black"), Ok("pass".to_owned()));
+    }
+
+    #[test]
+    fn genmove_capture() {
+        // Taking the stones in atari wins the game, and leaving them loses it
+        let mut state = setup(
+            5,
+            0.5,
+            &[
+                "faeries-playouts 2000",
+                "play white A1",
+                "play white B1",
+                "play white C1",
+                "play white D1",
+                "play white E1",
+                "play black A2",
+                "play black B2",
+                "play black C2",
+                "play black D2",
+            ],
+        );
+        assert_eq!(run(&mut state, "genmove black"), Ok("E2".to_owned()));
+    }
+}
@@ -0,0 +1,176 @@
+mod common;
+
+#[cfg(test)]
+mod checkers {
+    use crate::common;
+    use faeries::bench::quiet;
+    use faeries::checkers::{
+        eval::eval,
+        position::CheckersPosition,
+        root::primary_with_stats,
+        ugi::{as_pdn, parse_move},
+    };
+    use faeries::prng::XorshiftGenerator;
+    use games::{
+        gamerules::{GameResult, GameRules},
+        general::side::Side,
+    };
+
+    #[must_use]
+    fn perft(pos: &mut CheckersPosition, depth: i32) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+
+        let mut nodes = 0;
+        for mv in pos.legal_moves() {
+            pos.makemove(&mv);
+            nodes += perft(pos, depth - 1);
+            pos.undomove(&mv);
+        }
+        nodes
+    }
+
+    #[must_use]
+    fn moves(fen: &str) -> Vec<String> {
+        let pos = CheckersPosition::from_fen(fen);
+        pos.legal_moves()
+            .iter()
+            .map(|mv| as_pdn(&pos, mv))
+            .collect()
+    }
+
+    #[must_use]
+    fn play(fen: &str, movestr: &str) -> CheckersPosition {
+        let mut pos = CheckersPosition::from_fen(fen);
+        let mv = parse_move(&pos, movestr).unwrap_or_else(|| panic!("{} {}", fen, movestr));
+        pos.makemove(&mv);
+        pos
+    }
+
+    #[test]
+    fn perft_startpos() {
+        let mut pos = CheckersPosition::startpos();
+        let nodes = [1, 7, 49, 302, 1469, 7361, 36768, 179740];
+        for (depth, expected) in nodes.iter().enumerate() {
+            assert_eq!(perft(&mut pos, depth as i32), *expected);
+        }
+        assert_eq!(pos.get_fen(), CheckersPosition::startpos().get_fen());
+    }
+
+    #[test]
+    fn fen() {
+        let fens = [
+            "B:W21,22,23,24,25,26,27,28,29,30,31,32:B1,2,3,4,5,6,7,8,9,10,11,12 0 1",
+            "W:WK3,18,24:B2,K30 12 40",
+            "B:W:B 0 1",
+        ];
+        for fen in fens {
+            assert_eq!(CheckersPosition::from_fen(fen).get_fen(), fen);
+        }
+
+        // Ranges, and no move counters
+        assert_eq!(
+            CheckersPosition::from_fen("B:W21-32:B1-12").get_fen(),
+            CheckersPosition::startpos().get_fen()
+        );
+    }
+
+    #[test]
+    fn forced_capture() {
+        assert_eq!(moves("B:W14:B1,9"), vec!["9x18"]);
+        assert_eq!(moves("B:W14:B1"), vec!["1-5", "1-6"]);
+    }
+
+    #[test]
+    fn multi_jump() {
+        assert_eq!(moves("B:W14,23:B9"), vec!["9x18x27"]);
+
+        // The first and last squares are enough when there's only one way
+        let pos = play("B:W14,23:B9", "9x27");
+        assert_eq!(pos.get_fen(), "W:W:B27 0 1");
+        assert_eq!(pos.get_result(), Some(GameResult::Win(Side::Player1)));
+    }
+
+    #[test]
+    fn crowning() {
+        // Being crowned ends the move, even with more to take
+        assert_eq!(moves("B:W25,26:B23"), vec!["23x30"]);
+        let pos = play("B:W25,26:B23", "23x30");
+        assert_eq!(pos.get_fen(), "W:W25:BK30 0 1");
+
+        let pos = play("W:W5:B20", "5-1");
+        assert_eq!(pos.get_fen(), "B:WK1:B20 0 2");
+    }
+
+    #[test]
+    fn kings() {
+        assert_eq!(moves("B:W14:BK18"), vec!["18x9"]);
+        assert_eq!(
+            moves("B:W32:BK18"),
+            vec!["18-14", "18-15", "18-22", "18-23"]
+        );
+
+        // All the way round and back to the start
+        let fen = "B:W6,7,14,15:BK18";
+        assert_eq!(moves(fen), vec!["18x9x2x11x18"]);
+        let mut pos = CheckersPosition::from_fen(fen);
+        let mv = pos.legal_moves()[0];
+        pos.makemove(&mv);
+        assert_eq!(pos.get_fen(), "W:W:BK18 0 1");
+        pos.undomove(&mv);
+        assert_eq!(pos.get_fen(), fen.to_owned() + " 0 1");
+    }
+
+    #[test]
+    fn draw() {
+        let pos = play("B:W32:BK1 79 50", "1-5");
+        assert_eq!(pos.get_result(), Some(GameResult::Draw));
+        assert!(pos.is_gameover());
+        assert!(pos.legal_moves().is_empty());
+
+        // Moving a man starts the count again
+        let pos = play("B:W32:B1 79 50", "1-5");
+        assert_eq!(pos.get_halfmoves(), 0);
+        assert_eq!(pos.get_result(), None);
+    }
+
+    #[test]
+    fn blocked() {
+        // White can't move, so White has lost
+        let pos = CheckersPosition::from_fen("W:W29:B25,K22");
+        assert_eq!(pos.get_result(), Some(GameResult::Win(Side::Player1)));
+    }
+
+    #[test]
+    fn undo() {
+        let mut prng = XorshiftGenerator::new(0xe50076937a9e5b1c);
+        let mut pos = CheckersPosition::startpos();
+        let mut history = vec![];
+        let mut fens = vec![];
+
+        while !pos.is_gameover() {
+            let moves = pos.legal_moves();
+            let mv = moves[prng.next() as usize % moves.len()];
+            fens.push(pos.get_fen());
+            history.push(mv);
+            pos.makemove(&mv);
+        }
+
+        while let Some(mv) = history.pop() {
+            pos.undomove(&mv);
+            assert_eq!(pos.get_fen(), fens.pop().unwrap());
+        }
+    }
+
+    #[test]
+    fn fewer_nodes() {
+        common::fewer_nodes(
+            [CheckersPosition::startpos().get_fen().as_str()],
+            4,
+            |pos, settings, stats| primary_with_stats(pos, settings, &quiet, stats),
+            eval,
+            as_pdn,
+        );
+    }
+}
@@ -0,0 +1,43 @@
+mod common;
+
+#[cfg(test)]
+mod chess {
+    use crate::common;
+    use faeries::{
+        bench::quiet,
+        chess::{
+            eval::eval,
+            root::{primary, primary_with_stats},
+            ugi::as_ugi,
+        },
+    };
+    use games::{chess::ChessPosition, gamerules::GameRules};
+    use protocols::GoSettings;
+
+    const WIN_IN_ONE: [(&str, &str); 2] = [
+        ("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1", "a1a8"),
+        ("r5k1/8/8/8/8/8/5PPP/6K1 b - - 0 1", "a8a1"),
+    ];
+
+    #[test]
+    fn win_in_one() {
+        let settings = GoSettings::from_depth(3);
+
+        for (fen, movestr) in WIN_IN_ONE {
+            let pos = ChessPosition::from_fen(fen);
+            let mv = primary(pos.clone(), &settings, &quiet).unwrap();
+            assert_eq!(as_ugi(&pos, &mv), movestr, "{}", fen);
+        }
+    }
+
+    #[test]
+    fn fewer_nodes() {
+        common::fewer_nodes(
+            WIN_IN_ONE.map(|(fen, _)| fen),
+            3,
+            |pos, settings, stats| primary_with_stats(pos, settings, &quiet, stats),
+            eval,
+            as_ugi,
+        );
+    }
+}
@@ -0,0 +1,39 @@
+use faeries::{bench::quiet, search::minimax::minimax_with_stats, searchstats::SearchStats};
+use games::gamerules::GameRules;
+use protocols::GoSettings;
+
+/// Check that a game's primary search plays the move minimax does from every
+/// FEN, and searches fewer nodes to find it.
+pub fn fewer_nodes<'a, G: GameRules + Clone>(
+    fens: impl IntoIterator<Item = &'a str>,
+    depth: i32,
+    primary: impl Fn(G, &GoSettings, &mut SearchStats) -> Option<G::MoveType>,
+    eval: impl Fn(&G) -> i32,
+    as_ugi: impl Fn(&G, &G::MoveType) -> String,
+) {
+    let settings = GoSettings::from_depth(depth);
+
+    for fen in fens {
+        let pos = G::from_fen(fen);
+        let mut primary_stats = SearchStats::default();
+        let mut minimax_stats = SearchStats::default();
+
+        let primary_move = primary(pos.clone(), &settings, &mut primary_stats).unwrap();
+        let minimax_move =
+            minimax_with_stats(pos.clone(), &settings, &quiet, &eval, &mut minimax_stats).unwrap();
+
+        assert_eq!(
+            as_ugi(&pos, &primary_move),
+            as_ugi(&pos, &minimax_move),
+            "{}",
+            fen
+        );
+        assert!(
+            primary_stats.nodes < minimax_stats.nodes,
+            "{} {} {}",
+            fen,
+            primary_stats.nodes,
+            minimax_stats.nodes
+        );
+    }
+}
@@ -0,0 +1,389 @@
+mod common;
+
+#[cfg(test)]
+mod connect4 {
+    use crate::common;
+    use faeries::{
+        bench::quiet,
+        bound::Bound,
+        connect4::{
+            eval::eval,
+            position::{Connect4Move, Connect4Position},
+            root::{BENCH_FENS, bench, primary, primary_with_stats, testsuite},
+            state::Connect4State,
+            ugi::{as_ugi, info_handler},
+        },
+        search::{alphabeta::alphabeta, minimax::minimax},
+        searchstats::SearchStats,
+        testsuite::{Epd, parse_epds},
+    };
+    use games::{
+        gamerules::{GameResult, GameRules},
+        general::side::Side,
+    };
+    use protocols::GoSettings;
+    use std::cell::RefCell;
+
+    #[must_use]
+    fn perft(pos: &mut Connect4Position, depth: i32) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+
+        let mut nodes = 0;
+        for mv in pos.legal_moves() {
+            pos.makemove(&mv);
+            nodes += perft(pos, depth - 1);
+            pos.undomove(&mv);
+        }
+        nodes
+    }
+
+    #[must_use]
+    fn position(width: usize, height: usize, connect: usize, fen: &str) -> Connect4Position {
+        let mut pos = Connect4Position::new(width, height, connect);
+        pos.set_fen(fen);
+        pos
+    }
+
+    fn play(pos: &mut Connect4Position, movestrs: &str) {
+        for movestr in movestrs.split_ascii_whitespace() {
+            let mv = pos
+                .legal_moves()
+                .into_iter()
+                .find(|mv| mv.to_string() == movestr)
+                .unwrap_or_else(|| panic!("{} {}", pos.get_fen(), movestr));
+            pos.makemove(&mv);
+        }
+    }
+
+    #[test]
+    fn perft_startpos() {
+        let mut pos = Connect4Position::startpos();
+        let nodes = [1, 7, 49, 343, 2401, 16807, 117649];
+        for (depth, expected) in nodes.iter().enumerate() {
+            assert_eq!(perft(&mut pos, depth as i32), *expected);
+        }
+        assert_eq!(pos.get_fen(), Connect4Position::startpos().get_fen());
+    }
+
+    #[test]
+    fn fen() {
+        let fens = [
+            "7/7/7/7/7/7 x",
+            "7/7/7/7/7/x6 o",
+            "7/7/3o3/3x3/3ox2/2xxo2 x",
+            "8/8/8/8/8/8/3xo3 x",
+        ];
+        for fen in fens {
+            assert_eq!(Connect4Position::from_fen(fen).get_fen(), fen);
+        }
+
+        // The board takes its size from the FEN
+        let pos = Connect4Position::from_fen("8/8/8/8/8/8/3xo3 x");
+        assert_eq!((pos.get_width(), pos.get_height()), (8, 7));
+        assert_eq!(pos.legal_moves().len(), 8);
+
+        // And keeps it for a new game
+        let pos = position(9, 7, 5, "startpos");
+        assert_eq!(pos.get_fen(), "9/9/9/9/9/9/9 x");
+        assert_eq!(pos.get_connect(), 5);
+    }
+
+    #[test]
+    fn moves() {
+        assert_eq!(Connect4Move(0).to_string(), "a");
+        assert_eq!(Connect4Move(7).to_string(), "h");
+
+        // A full column can't be played in
+        let pos = Connect4Position::from_fen("o6/x6/o6/x6/o6/x6 x");
+        assert!(!pos.legal_moves().contains(&Connect4Move(0)));
+        assert_eq!(pos.legal_moves().len(), 6);
+    }
+
+    #[test]
+    fn win() {
+        let mut pos = Connect4Position::startpos();
+        play(&mut pos, "a b a b a b");
+        assert_eq!(pos.get_result(), None);
+        play(&mut pos, "a");
+        assert_eq!(pos.get_result(), Some(GameResult::Win(Side::Player1)));
+        assert!(pos.legal_moves().is_empty());
+
+        // Found from a FEN too
+        let pos = Connect4Position::from_fen("7/7/7/7/7/oooo3 x");
+        assert_eq!(pos.get_result(), Some(GameResult::Win(Side::Player2)));
+    }
+
+    #[test]
+    fn connect() {
+        // Four in a row isn't enough for Connect 5
+        let mut pos = position(8, 7, 5, "startpos");
+        play(&mut pos, "a a b b c c d d");
+        assert_eq!(pos.get_result(), None);
+        play(&mut pos, "e");
+        assert_eq!(pos.get_result(), Some(GameResult::Win(Side::Player1)));
+
+        // Diagonals count
+        let mut pos = position(5, 5, 3, "startpos");
+        play(&mut pos, "a b b c d c");
+        assert_eq!(pos.get_result(), None);
+        play(&mut pos, "c");
+        assert_eq!(pos.get_result(), Some(GameResult::Win(Side::Player1)));
+    }
+
+    #[test]
+    fn draw() {
+        let pos = Connect4Position::from_fen("xxoo/ooxx/xxoo/ooxx o");
+        assert_eq!(pos.get_result(), Some(GameResult::Draw));
+        assert!(pos.is_gameover());
+    }
+
+    #[test]
+    fn eval_startpos() {
+        assert_eq!(eval(&Connect4Position::startpos()), 0);
+
+        // The middle of the board is in more lines than the edge
+        let middle = Connect4Position::from_fen("7/7/7/7/7/3x3 o");
+        let edge = Connect4Position::from_fen("7/7/7/7/7/x6 o");
+        assert!(eval(&middle) < eval(&edge));
+        assert!(eval(&middle) < 0);
+    }
+
+    #[test]
+    fn primary_connects() {
+        for (width, height, connect, movestrs) in [
+            (7, 6, 4, "a b a b a b"),
+            (8, 7, 5, "a a b b c c d d"),
+            (10, 10, 6, "a a b b c c d d e e"),
+        ] {
+            let mut pos = position(width, height, connect, "startpos");
+            play(&mut pos, movestrs);
+            let mv = primary(pos.clone(), &GoSettings::from_depth(2), &info_handler).unwrap();
+            pos.makemove(&mv);
+            assert_eq!(pos.get_result(), Some(GameResult::Win(Side::Player1)));
+        }
+    }
+
+    #[test]
+    fn fewer_nodes() {
+        common::fewer_nodes(
+            [
+                "7/7/7/7/7/7 x",
+                "7/7/7/7/7/xxx1ooo x",
+                "7/7/7/7/7/1xx1oo1 o",
+            ],
+            4,
+            |pos, settings, stats| primary_with_stats(pos, settings, &quiet, stats),
+            eval,
+            |_, mv| as_ugi(mv),
+        );
+    }
+
+    #[test]
+    fn aspiration() {
+        let infos = RefCell::new(vec![]);
+        let record = |_: &Connect4Position,
+                      depth: Option<i32>,
+                      _: Option<i32>,
+                      score: Option<i32>,
+                      bound: Option<Bound>,
+                      _: Option<i32>,
+                      _: Option<u64>,
+                      _: Option<u128>,
+                      _: Option<i32>,
+                      _: &Vec<Connect4Move>,
+                      _: Option<&SearchStats>| {
+            infos
+                .borrow_mut()
+                .push((depth.unwrap(), score.unwrap(), bound));
+        };
+
+        let pos = Connect4Position::from_fen("7/7/7/3o3/3xo2/2xxo2 x");
+        primary(pos, &GoSettings::from_depth(8), &record).unwrap();
+        let infos = infos.into_inner();
+        assert!(infos.iter().any(|(_, _, bound)| bound.is_some()));
+
+        // What was said about the score while looking for it holds for the one found
+        for (depth, bounded, bound) in &infos {
+            let (_, score, _) = infos
+                .iter()
+                .find(|(d, _, b)| d == depth && b.is_none())
+                .unwrap();
+            match bound {
+                Some(Bound::Lower) => assert!(score >= bounded),
+                Some(Bound::Upper) => assert!(score <= bounded),
+                None => {}
+            }
+        }
+
+        assert_eq!(Bound::Lower.to_string(), "lowerbound");
+        assert_eq!(Bound::Upper.to_string(), "upperbound");
+    }
+
+    #[test]
+    fn stopped() {
+        // However early the search is stopped, there's a move to play
+        let pos = Connect4Position::startpos();
+        for nodes in 0..100 {
+            let settings = GoSettings::from_nodes(nodes);
+            for mv in [
+                primary(pos.clone(), &settings, &info_handler),
+                minimax(pos.clone(), &settings, &info_handler, &eval),
+                alphabeta(pos.clone(), &settings, &info_handler, &eval),
+            ] {
+                assert!(pos.legal_moves().contains(&mv.unwrap()), "{}", nodes);
+            }
+        }
+
+        // Stopped part way through, it still blocks
+        let pos = Connect4Position::from_fen("7/7/7/7/7/xooo3 x");
+        let mv = primary(pos, &GoSettings::from_nodes(100), &info_handler);
+        assert_eq!(mv, Some(Connect4Move(4)));
+    }
+
+    #[test]
+    fn stats() {
+        let mut stats = SearchStats::default();
+        let pos = Connect4Position::startpos();
+        let settings = GoSettings::from_depth(6);
+        primary_with_stats(pos.clone(), &settings, &info_handler, &mut stats).unwrap();
+
+        assert_eq!(stats.iterations.len(), 6);
+        assert_eq!(stats.iterations.last(), Some(&stats.nodes));
+        assert!(stats.first_move_cutoffs <= stats.beta_cutoffs);
+        let rate = stats.first_move_cutoff_rate().unwrap();
+        assert!(rate > 0.0 && rate <= 1.0);
+        assert!(stats.lmr_researches <= stats.lmr_reductions);
+        assert!(stats.branching_factor().unwrap() > 1.0);
+
+        // Connect 4 never passes, and stops at the nominal depth
+        assert_eq!((stats.null_moves, stats.qnodes), (0, 0));
+        assert!(
+            stats
+                .to_string()
+                .starts_with(&format!("nodes {} ", stats.nodes))
+        );
+
+        // Counting starts again with every search
+        let settings = GoSettings::from_depth(2);
+        primary_with_stats(pos, &settings, &info_handler, &mut stats).unwrap();
+        assert_eq!(stats.iterations.len(), 2);
+    }
+
+    #[test]
+    fn generic_stats() {
+        let mut state = Connect4State::default();
+        let settings = GoSettings::from_depth(4);
+
+        for search in ["minimax", "alphabeta"] {
+            state.search.value = search.to_owned();
+            state.think(&settings, &info_handler).unwrap();
+            assert_eq!(state.stats.iterations.len(), 4, "{}", search);
+            assert_eq!(
+                state.stats.iterations.last(),
+                Some(&state.stats.nodes),
+                "{}",
+                search
+            );
+            assert_eq!(state.stats.seldepth, 4, "{}", search);
+        }
+        assert!(state.stats.beta_cutoffs > 0);
+        assert!(state.stats.first_move_cutoffs <= state.stats.beta_cutoffs);
+
+        // Playouts count as nodes, and nothing is left over from before
+        state.search.value = "flatmc".to_owned();
+        state
+            .think(&GoSettings::from_nodes(50), &info_handler)
+            .unwrap();
+        assert_eq!(state.stats.nodes, 50);
+        assert!(state.stats.iterations.is_empty());
+        assert_eq!(state.stats.beta_cutoffs, 0);
+
+        state.search.value = "random".to_owned();
+        state.think(&settings, &info_handler).unwrap();
+        assert_eq!(state.stats.nodes, 1);
+        assert_eq!(state.stats.seldepth, 0);
+    }
+
+    #[test]
+    fn bench_nodes() {
+        // Searching the same positions the same way always takes the same nodes
+        let first = bench(Some(4));
+        assert!(first.nodes > 0);
+        assert_eq!(bench(Some(4)).nodes, first.nodes);
+
+        // However many each of them would have taken on its own
+        let settings = GoSettings::from_depth(4);
+        let mut stats = SearchStats::default();
+        let mut nodes = 0;
+        for fen in BENCH_FENS {
+            let pos = Connect4Position::from_fen(fen);
+            primary_with_stats(pos, &settings, &info_handler, &mut stats).unwrap();
+            nodes += stats.nodes;
+        }
+        assert_eq!(first.nodes, nodes);
+        assert!(first.to_string().starts_with(&format!("{} nodes ", nodes)));
+    }
+
+    #[test]
+    fn epd() {
+        let epd = Epd::parse("7/7/7/7/ooo4/xxx4 x bm d; id \"win 1\";").unwrap();
+        assert_eq!(epd.fen, "7/7/7/7/ooo4/xxx4 x");
+        assert_eq!(epd.best, vec!["d"]);
+        assert!(epd.avoid.is_empty());
+        assert_eq!(epd.name(), "win 1");
+
+        let epd = Epd::parse("7/7/7/7/x1x4/ooo1x2 x am a b;").unwrap();
+        assert_eq!(epd.avoid, vec!["a", "b"]);
+        assert_eq!(epd.name(), "7/7/7/7/x1x4/ooo1x2 x");
+        assert!(epd.is_solved_by(&["d".to_owned()]));
+        assert!(!epd.is_solved_by(&["b".to_owned()]));
+
+        // Any operation can come first, and a quoted operand keeps its `;`
+        let epd =
+            Epd::parse("7/7/7/7/ooo4/xxx4 x c0 \"from a; game\"; acd 12; bm d e; id \"win 2\"")
+                .unwrap();
+        assert_eq!(epd.fen, "7/7/7/7/ooo4/xxx4 x");
+        assert_eq!(epd.best, vec!["d", "e"]);
+        assert_eq!(epd.name(), "win 2");
+
+        // Once the operations have started, opcodes needn't be standard ones
+        let epd = Epd::parse("7/7/7/7/ooo4/xxx4 x noop; D1 7; bm d;").unwrap();
+        assert_eq!(epd.fen, "7/7/7/7/ooo4/xxx4 x");
+        assert_eq!(epd.best, vec!["d"]);
+
+        assert_eq!(Epd::parse("# comment"), None);
+        assert_eq!(Epd::parse("  "), None);
+    }
+
+    #[test]
+    fn testsuite_report() {
+        let epds = parse_epds(
+            "7/7/7/7/ooo4/xxx4 x bm d; id \"win\";
+            7/7/7/7/x1x4/ooo1x2 x bm d; id \"block\";
+            # Nothing to check
+            7/7/7/7/7/7 x
+            7/7/7/7/ooo4/xxx4 x am d; id \"miss\";",
+        );
+        assert_eq!(epds.len(), 3);
+
+        let report = testsuite(&epds, &GoSettings::from_depth(4));
+        assert_eq!(report.solved(), 2);
+        let failed = report.failed().collect::<Vec<_>>();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].name, "miss");
+        assert_eq!(failed[0].played.as_deref(), Some("d"));
+        assert_eq!(failed[0].time_to_solution, None);
+        assert!(
+            report.outcomes[..2]
+                .iter()
+                .all(|outcome| outcome.time_to_solution.is_some())
+        );
+        assert!(
+            report
+                .to_string()
+                .starts_with("failed miss played d\nsolved 2 failed 1 total 3 ")
+        );
+    }
+}
@@ -1,45 +1,71 @@
+mod common;
+
 #[cfg(test)]
 mod droptaxx {
+    use crate::common;
     use faeries::{
-        droptaxx::{root::primary, ugi::as_ugi},
+        bench::quiet,
+        bound::Bound,
+        droptaxx::{
+            position::{DroptaxxMove, DroptaxxPosition},
+            root::{primary, primary_with_stats},
+            state::DroptaxxState,
+            ugi::as_ugi,
+        },
         prng,
         search::{alphabeta::alphabeta, minimax::minimax, random::random},
+        searchstats::SearchStats,
     };
-    use games::{
-        droptaxx::{DroptaxxMove, DroptaxxPosition},
-        gamerules::GameRules,
-    };
+    use games::gamerules::{GameResult, GameRules};
+    use games::general::side::Side;
     use protocols::GoSettings;
+    use protocols::ugi::UGI;
 
     fn info_handler(
         _: &DroptaxxPosition,
         _: Option<i32>,
         _: Option<i32>,
         _: Option<i32>,
+        _: Option<Bound>,
         _: Option<i32>,
         _: Option<u64>,
         _: Option<u128>,
         _: Option<i32>,
         _: &Vec<DroptaxxMove>,
+        _: Option<&SearchStats>,
     ) {
     }
 
     #[must_use]
     fn eval(pos: &DroptaxxPosition) -> i32 {
-        pos.get_us().count() - pos.get_them().count()
+        pos.get_us().count_ones() as i32 - pos.get_them().count_ones() as i32
     }
 
+    fn perft(pos: &mut DroptaxxPosition, depth: i32) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+
+        let mut nodes = 0;
+        for mv in pos.legal_moves() {
+            pos.makemove(&mv);
+            nodes += perft(pos, depth - 1);
+            pos.undomove(&mv);
+        }
+        nodes
+    }
+
+    const EASY_MOVES: [(&str, &str); 2] = [
+        ("7/3o3/2ooo2/2o1o2/2ooo2/7/7 x 1", "d4"),
+        ("7/3x3/2xxx2/2x1x2/2xxx2/7/7 o 1", "d4"),
+    ];
+
     #[test]
     fn easy_moves() {
-        let tests = [
-            ("7/3o3/2ooo2/2o1o2/2ooo2/7/7 x 1", "d4"),
-            ("7/3x3/2xxx2/2x1x2/2xxx2/7/7 o 1", "d4"),
-        ];
-
         let settings = GoSettings::from_depth(3);
         let mut prng = prng::XorshiftGenerator::new(0xe50076937a9e5b1c);
 
-        for (fen, movestr) in tests {
+        for (fen, movestr) in EASY_MOVES {
             let pos = DroptaxxPosition::from_fen(fen);
 
             // Primary
@@ -67,4 +93,81 @@ mod droptaxx {
             assert!(random(&pos, || prng.next()).is_some(), "{}", fen);
         }
     }
+
+    #[test]
+    fn fewer_nodes() {
+        common::fewer_nodes(
+            EASY_MOVES.map(|(fen, _)| fen),
+            3,
+            |pos, settings, stats| primary_with_stats(pos, settings, &quiet, stats),
+            eval,
+            |_, mv| as_ugi(mv),
+        );
+    }
+
+    #[test]
+    fn perft_sizes() {
+        for size in 5..=8 {
+            let squares = (size * size) as u64;
+            let mut pos = DroptaxxPosition::new(size, size);
+            let fen = pos.get_fen();
+            assert_eq!(perft(&mut pos, 1), squares);
+            assert_eq!(perft(&mut pos, 2), squares * (squares - 1));
+            assert_eq!(perft(&mut pos, 3), squares * (squares - 1) * (squares - 2));
+            assert_eq!(pos.get_fen(), fen);
+        }
+    }
+
+    #[test]
+    fn fen() {
+        let fens = [
+            "7/7/7/7/7/7/7 x 1",
+            "7/1xo4/2xx3/3oo2/4x2/2o4/7 x 5",
+            "5/1x-2/2o2/1-x2/5 o 3",
+            "8/8/8/3xo3/3ox3/8/8/8 o 2",
+        ];
+
+        for fen in fens {
+            assert_eq!(DroptaxxPosition::from_fen(fen).get_fen(), fen);
+        }
+    }
+
+    #[test]
+    fn flips() {
+        let mut pos = DroptaxxPosition::from_fen("5/5/1ooo1/1o1o1/1ooo1 x 4");
+        let mv = pos
+            .legal_moves()
+            .into_iter()
+            .find(|mv| as_ugi(mv) == "c2")
+            .unwrap();
+        pos.makemove(&mv);
+        assert_eq!(pos.get_fen(), "5/5/1xxx1/1xxx1/1xxx1 o 4");
+        pos.undomove(&mv);
+        assert_eq!(pos.get_fen(), "5/5/1ooo1/1o1o1/1ooo1 x 4");
+    }
+
+    #[test]
+    fn full() {
+        let pos = DroptaxxPosition::from_fen("xxxxx/xxxxx/xxooo/ooooo/ooooo x 13");
+        assert!(pos.is_gameover());
+        assert!(pos.legal_moves().is_empty());
+        assert_eq!(pos.get_result(), Some(GameResult::Win(Side::Player2)));
+    }
+
+    #[test]
+    fn size_option() {
+        let mut state = DroptaxxState::default();
+        state.set_option("size", "5");
+        assert_eq!(state.pos.get_fen(), "5/5/5/5/5 x 1");
+
+        // A new game keeps the size
+        state.position("5/5/2x2/5/5 o 1");
+        state.uginewgame();
+        assert_eq!(state.pos.get_fen(), "5/5/5/5/5 x 1");
+
+        // Sizes out of range are ignored
+        state.set_option("size", "9");
+        assert_eq!(state.size.value, 5);
+        assert_eq!(state.pos.get_width(), 5);
+    }
 }
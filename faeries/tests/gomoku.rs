@@ -0,0 +1,232 @@
+mod common;
+
+#[cfg(test)]
+mod gomoku {
+    use crate::common;
+    use faeries::bench::quiet;
+    use faeries::gomoku::{
+        eval::eval,
+        position::{GomokuMove, GomokuPosition, Rule},
+        root::primary_with_stats,
+        state::GomokuState,
+        ugi::as_ugi,
+    };
+    use games::{
+        gamerules::{GameResult, GameRules},
+        general::side::Side,
+    };
+    use protocols::gtp::listen::handle;
+
+    fn run(state: &mut GomokuState, line: &str) -> Result<String, String> {
+        let words = line.split_ascii_whitespace().collect::<Vec<&str>>();
+        handle(state, words[0], &words[1..])
+    }
+
+    #[must_use]
+    fn square(pos: &GomokuPosition, name: &str) -> GomokuMove {
+        let x = (name.as_bytes()[0] - b'a') as usize;
+        let y = name[1..].parse::<usize>().unwrap() - 1;
+        GomokuMove(pos.index(x, y))
+    }
+
+    /// Stones put straight on the board, with Black to move.
+    #[must_use]
+    fn setup(rule: Rule, black: &[&str], white: &[&str]) -> GomokuPosition {
+        let mut pos = GomokuPosition::new(15, rule);
+        for name in black {
+            pos.makemove(&square(&pos, name));
+            pos.makenull();
+        }
+        pos.makenull();
+        for name in white {
+            pos.makemove(&square(&pos, name));
+            pos.makenull();
+        }
+        pos.makenull();
+        pos
+    }
+
+    #[must_use]
+    fn is_legal(pos: &GomokuPosition, name: &str) -> bool {
+        pos.legal_moves().contains(&square(pos, name))
+    }
+
+    #[test]
+    fn boardsize() {
+        let mut state = GomokuState::default();
+        assert_eq!(run(&mut state, "boardsize 15"), Ok(String::new()));
+        assert_eq!(run(&mut state, "boardsize 19"), Ok(String::new()));
+        assert_eq!(state.pos.get_size(), 19);
+        assert_eq!(run(&mut state, "play black T19"), Ok(String::new()));
+        assert_eq!(
+            run(&mut state, "boardsize 13"),
+            Err("unacceptable size".to_owned())
+        );
+    }
+
+    #[test]
+    fn play() {
+        let mut state = GomokuState::default();
+        run(&mut state, "boardsize 15").unwrap();
+        assert_eq!(run(&mut state, "play black H8"), Ok(String::new()));
+        assert_eq!(
+            run(&mut state, "play white H8"),
+            Err("illegal move".to_owned())
+        );
+        assert_eq!(run(&mut state, "play white J8"), Ok(String::new()));
+
+        // Black twice in a row, as a handicap game would
+        assert_eq!(run(&mut state, "play black A1"), Ok(String::new()));
+        assert_eq!(run(&mut state, "play black P15"), Ok(String::new()));
+        assert_eq!(
+            run(&mut state, "final_status_list alive"),
+            Ok("A1 H8 J8 P15".to_owned())
+        );
+
+        // A failed move leaves the game alone
+        assert_eq!(
+            run(&mut state, "play white A1"),
+            Err("illegal move".to_owned())
+        );
+        assert_eq!(state.history.len(), 5);
+    }
+
+    #[test]
+    fn five() {
+        let mut state = GomokuState::default();
+        for x in ["A", "B", "C", "D", "E"] {
+            run(&mut state, &format!("play black {}1", x)).unwrap();
+        }
+        assert_eq!(run(&mut state, "final_score"), Ok("B+R".to_owned()));
+    }
+
+    #[test]
+    fn genmove() {
+        let mut state = GomokuState::default();
+        run(&mut state, "play black H8").unwrap();
+        let vertex = run(&mut state, "genmove white").unwrap();
+        assert_ne!(vertex, "H8");
+        assert_ne!(vertex, "pass");
+        assert_eq!(run(&mut state, "undo"), Ok(String::new()));
+        assert_eq!(run(&mut state, "undo"), Ok(String::new()));
+        assert_eq!(run(&mut state, "undo"), Err("cannot undo".to_owned()));
+    }
+
+    #[test]
+    fn rule() {
+        let mut state = GomokuState::default();
+        assert_eq!(run(&mut state, "faeries-rule renju"), Ok(String::new()));
+        assert_eq!(state.pos.get_rule(), Rule::Renju);
+        for vertex in ["H6", "H7", "F8", "G8"] {
+            run(&mut state, &format!("play black {}", vertex)).unwrap();
+        }
+        assert_eq!(
+            run(&mut state, "play black H8"),
+            Err("illegal move".to_owned())
+        );
+        assert_eq!(
+            run(&mut state, "faeries-rule gomoku"),
+            Err("syntax error".to_owned())
+        );
+    }
+
+    #[test]
+    fn fen() {
+        let fens = [
+            "15/15/15/15/15/15/15/7x7/15/15/15/15/15/15/15 o",
+            "19/19/19/19/19/19/19/19/19/9x9/19/19/19/19/19/19/19/19/19 o",
+            "x14/15/15/15/15/15/15/15/15/15/15/15/15/15/14o x",
+        ];
+        for fen in fens {
+            assert_eq!(GomokuPosition::from_fen(fen).get_fen(), fen);
+        }
+        assert_eq!(
+            GomokuPosition::from_fen("19/19/19/19/19/19/19/19/19/19/19/19/19/19/19/19/19/19/19 x")
+                .get_size(),
+            19
+        );
+    }
+
+    #[test]
+    fn overline() {
+        let black = ["a8", "b8", "c8", "d8", "f8"];
+
+        // Six in a row wins in freestyle
+        let mut pos = setup(Rule::Freestyle, &black, &[]);
+        pos.makemove(&square(&pos, "e8"));
+        assert_eq!(pos.get_result(), Some(GameResult::Win(Side::Player1)));
+
+        // But not when it has to be exactly five
+        let mut pos = setup(Rule::Standard, &black, &[]);
+        pos.makemove(&square(&pos, "e8"));
+        assert_eq!(pos.get_result(), None);
+
+        // And under renju Black can't even play there
+        let pos = setup(Rule::Renju, &black, &[]);
+        assert!(!is_legal(&pos, "e8"));
+        assert!(is_legal(&pos, "g8"));
+
+        // While White still wins with six
+        let mut pos = setup(Rule::Renju, &["a1"], &black);
+        pos.makenull();
+        pos.makemove(&square(&pos, "e8"));
+        assert_eq!(pos.get_result(), Some(GameResult::Win(Side::Player2)));
+    }
+
+    #[test]
+    fn renju() {
+        // Two fours
+        let pos = setup(Rule::Renju, &["h5", "h6", "h7", "e8", "f8", "g8"], &[]);
+        assert!(!is_legal(&pos, "h8"));
+
+        // Two open threes
+        let pos = setup(Rule::Renju, &["h6", "h7", "f8", "g8"], &[]);
+        assert!(!is_legal(&pos, "h8"));
+        assert!(is_legal(&pos, "h9"));
+
+        // A three that's blocked can never be a straight four, so it doesn't count
+        let pos = setup(Rule::Renju, &["h6", "h7", "f8", "g8"], &["h5", "h10"]);
+        assert!(is_legal(&pos, "h8"));
+
+        // Making five is allowed whatever else it makes
+        let pos = setup(
+            Rule::Renju,
+            &["d8", "e8", "f8", "g8", "h5", "h6", "h7"],
+            &["c8"],
+        );
+        assert!(is_legal(&pos, "h8"));
+
+        // None of it applies to White, or to other rules
+        let mut pos = setup(Rule::Renju, &["a1"], &["h6", "h7", "f8", "g8"]);
+        pos.makenull();
+        assert!(is_legal(&pos, "h8"));
+        let pos = setup(Rule::Freestyle, &["h6", "h7", "f8", "g8"], &[]);
+        assert!(is_legal(&pos, "h8"));
+    }
+
+    #[test]
+    fn eval_lines() {
+        let pos = GomokuPosition::startpos();
+        assert_eq!(eval(&pos), 0);
+
+        // Black's stone is in lines White can't use
+        let pos = setup(Rule::Freestyle, &["h8"], &[]);
+        assert!(eval(&pos) > 0);
+        let pos = setup(Rule::Freestyle, &["h8", "h9"], &["a1"]);
+        assert!(eval(&pos) > eval(&setup(Rule::Freestyle, &["h8", "h10"], &["a1"])));
+    }
+
+    #[test]
+    fn fewer_nodes() {
+        common::fewer_nodes(
+            [
+                "15/15/15/15/15/15/15/15/15/15/15/15/15/oooo11/xxxx11 x",
+                "15/15/15/15/15/15/15/15/15/15/15/15/15/xxxx11/oooo11 o",
+            ],
+            2,
+            |pos, settings, stats| primary_with_stats(pos, settings, &quiet, stats),
+            eval,
+            as_ugi,
+        );
+    }
+}
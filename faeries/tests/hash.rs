@@ -0,0 +1,128 @@
+#[cfg(test)]
+mod hash {
+    use faeries::connect4::position::Connect4Position;
+    use faeries::droptaxx::position::DroptaxxPosition;
+    use faeries::gomoku::position::GomokuPosition;
+    use faeries::hash::{Hashed, Zobrist, hash};
+    use games::{
+        ataxx::AtaxxPosition, chess::ChessPosition, gamerules::GameRules,
+        isolation::IsolationPosition, pijersi::PijersiPosition, tak::TakPosition,
+    };
+    use std::collections::HashMap;
+
+    fn walk<G: Zobrist>(hashed: &mut Hashed<G>, depth: i32, seen: &mut HashMap<u64, String>) {
+        let fen = hashed.pos.get_fen();
+        assert_eq!(hashed.get_hash(), hash(&hashed.pos), "{}", fen);
+
+        // Transpositions share a hash, and nothing else should
+        let board = fen.split_ascii_whitespace().take(2).collect::<Vec<&str>>();
+        let previous = seen.entry(hashed.get_hash()).or_insert(fen.clone());
+        let other = previous
+            .split_ascii_whitespace()
+            .take(2)
+            .collect::<Vec<&str>>();
+        assert_eq!(board, other, "{} {}", fen, previous);
+
+        if depth == 0 {
+            return;
+        }
+
+        for mv in hashed.pos.legal_moves() {
+            hashed.makemove(&mv);
+            walk(hashed, depth - 1, seen);
+            hashed.undomove(&mv);
+        }
+
+        assert_eq!(hashed.pos.get_fen(), fen);
+    }
+
+    #[test]
+    fn ataxx() {
+        let fens = [
+            "x5o/7/7/7/7/7/o5x x 0 1",
+            "x5o/7/2-1-2/7/2-1-2/7/o5x o 0 1",
+            "7/7/7/7/ooooooo/ooooooo/xxxxxxx x 0 1",
+        ];
+
+        for fen in fens {
+            let mut hashed = Hashed::new(AtaxxPosition::<7, 7>::from_fen(fen));
+            walk(&mut hashed, 3, &mut HashMap::new());
+        }
+    }
+
+    #[test]
+    fn chess() {
+        let fens = [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        ];
+
+        for fen in fens {
+            let mut hashed = Hashed::new(ChessPosition::from_fen(fen));
+            walk(&mut hashed, 3, &mut HashMap::new());
+        }
+    }
+
+    #[test]
+    fn connect4() {
+        let mut hashed = Hashed::new(Connect4Position::startpos());
+        walk(&mut hashed, 5, &mut HashMap::new());
+    }
+
+    #[test]
+    fn droptaxx() {
+        let mut hashed = Hashed::new(DroptaxxPosition::startpos());
+        walk(&mut hashed, 2, &mut HashMap::new());
+    }
+
+    #[test]
+    fn gomoku() {
+        let mut hashed = Hashed::new(GomokuPosition::startpos());
+        walk(&mut hashed, 2, &mut HashMap::new());
+    }
+
+    #[test]
+    fn isolation() {
+        // With a square removed every move there are too many to go deeper, so
+        // look one move ahead from a few positions along a game instead
+        let mut hashed = Hashed::new(IsolationPosition::startpos());
+
+        for _ in 0..4 {
+            walk(&mut hashed, 1, &mut HashMap::new());
+            let mv = hashed.pos.legal_moves().into_iter().next().unwrap();
+            hashed.makemove(&mv);
+        }
+    }
+
+    #[test]
+    fn pijersi() {
+        let mut hashed = Hashed::new(PijersiPosition::startpos());
+        walk(&mut hashed, 2, &mut HashMap::new());
+    }
+
+    #[test]
+    fn tak() {
+        let mut hashed = Hashed::new(TakPosition::<5>::startpos());
+        walk(&mut hashed, 3, &mut HashMap::new());
+    }
+
+    #[test]
+    fn repetition() {
+        let mut hashed = Hashed::new(ChessPosition::startpos());
+        assert!(!hashed.is_repetition());
+
+        for movestr in ["g1f3", "g8f6", "f3g1", "f6g8"] {
+            let mv = hashed
+                .pos
+                .legal_moves()
+                .into_iter()
+                .find(|mv| hashed.pos.move_to_string(mv) == movestr)
+                .unwrap();
+            hashed.makemove(&mv);
+        }
+
+        assert!(hashed.is_repetition());
+    }
+}
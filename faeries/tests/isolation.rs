@@ -0,0 +1,22 @@
+mod common;
+
+#[cfg(test)]
+mod isolation {
+    use crate::common;
+    use faeries::{
+        bench::quiet,
+        isolation::{eval::eval, root::primary_with_stats, ugi::as_ugi},
+    };
+
+    #[test]
+    fn fewer_nodes() {
+        // Every position is level, so both searches keep the first move they try
+        common::fewer_nodes(
+            ["startpos"],
+            2,
+            |pos, settings, stats| primary_with_stats(pos, settings, &quiet, stats),
+            eval,
+            |_, mv| as_ugi(mv),
+        );
+    }
+}
@@ -0,0 +1,191 @@
+mod common;
+
+#[cfg(test)]
+mod othello {
+    use crate::common;
+    use faeries::bench::quiet;
+    use faeries::othello::{
+        eval::eval,
+        manual::empty_handler,
+        perft::perft,
+        position::{OthelloMove, OthelloPosition, parse_square},
+        root::{primary, primary_with_stats},
+        solver::solve_root,
+        ugi::as_ugi,
+    };
+    use faeries::prng::XorshiftGenerator;
+    use faeries::searchstats::SearchStats;
+    use games::{
+        gamerules::{GameResult, GameRules},
+        general::side::Side,
+    };
+    use protocols::GoSettings;
+
+    /// Every line played out in full, for checking the solver against.
+    #[must_use]
+    fn minimax(pos: &mut OthelloPosition) -> i32 {
+        if pos.is_gameover() {
+            return pos.final_score();
+        }
+
+        let mut best = -64;
+        for mv in pos.legal_moves() {
+            pos.makemove(&mv);
+            best = best.max(-minimax(pos));
+            pos.undomove(&mv);
+        }
+        best
+    }
+
+    #[must_use]
+    fn random_position(seed: u64, empties: u32) -> OthelloPosition {
+        let mut prng = XorshiftGenerator::new(seed);
+        let mut pos = OthelloPosition::startpos();
+        while pos.get_empty().count_ones() > empties && !pos.is_gameover() {
+            let moves = pos.legal_moves();
+            let mv = moves[prng.next() as usize % moves.len()];
+            pos.makemove(&mv);
+        }
+        pos
+    }
+
+    #[test]
+    fn perft_startpos() {
+        let mut pos = OthelloPosition::startpos();
+        let nodes = [1, 4, 12, 56, 244, 1396, 8200, 55092];
+        for (depth, expected) in nodes.iter().enumerate() {
+            assert_eq!(perft(&mut pos, depth as i32), *expected);
+        }
+        assert_eq!(pos.get_fen(), OthelloPosition::startpos().get_fen());
+    }
+
+    #[test]
+    fn fen() {
+        let fens = [
+            "8/8/8/3xo3/3ox3/8/8/8 x",
+            "8/8/8/3xo3/3ox3/8/8/8 o",
+            "xxxxxxxx/oooooooo/8/8/8/8/8/7x o",
+            "8/8/8/8/8/8/8/8 x",
+        ];
+
+        for fen in fens {
+            assert_eq!(OthelloPosition::from_fen(fen).get_fen(), fen);
+        }
+        assert_eq!(
+            OthelloPosition::from_fen("startpos").get_fen(),
+            OthelloPosition::startpos().get_fen()
+        );
+    }
+
+    #[test]
+    fn flips() {
+        let mut pos = OthelloPosition::startpos();
+        let mv = OthelloMove::Place(parse_square("d3").unwrap());
+        pos.makemove(&mv);
+        assert_eq!(pos.get_fen(), "8/8/8/3xo3/3xx3/3x4/8/8 o");
+        pos.undomove(&mv);
+        assert_eq!(pos.get_fen(), OthelloPosition::startpos().get_fen());
+
+        // Lines are turned over in every direction at once
+        let mut pos = OthelloPosition::from_fen("o1o1o3/1xxx4/ox1xo3/1xxx4/o1o1o3/8/8/8 o");
+        let mv = OthelloMove::Place(parse_square("c6").unwrap());
+        assert!(pos.is_legal(&mv));
+        pos.makemove(&mv);
+        assert_eq!(pos.get_fen(), "o1o1o3/1ooo4/ooooo3/1ooo4/o1o1o3/8/8/8 x");
+        pos.undomove(&mv);
+        assert_eq!(pos.get_fen(), "o1o1o3/1xxx4/ox1xo3/1xxx4/o1o1o3/8/8/8 o");
+    }
+
+    #[test]
+    fn pass() {
+        // White has nowhere to go, but Black does
+        let mut pos = OthelloPosition::from_fen("xo6/8/8/8/8/8/8/8 o");
+        assert_eq!(pos.legal_moves(), vec![OthelloMove::Pass]);
+        assert!(!pos.is_gameover());
+        pos.makemove(&OthelloMove::Pass);
+        assert_eq!(
+            pos.legal_moves(),
+            vec![OthelloMove::Place(parse_square("c8").unwrap())]
+        );
+        assert_eq!(perft(&mut pos, 1), 1);
+
+        // Which ends the game
+        assert_eq!(perft(&mut pos, 2), 0);
+    }
+
+    #[test]
+    fn gameover() {
+        let pos = OthelloPosition::from_fen("xxxxxxxx/8/8/8/8/8/8/8 o");
+        assert!(pos.is_gameover());
+        assert!(pos.legal_moves().is_empty());
+        assert_eq!(pos.get_result(), Some(GameResult::Win(Side::Player1)));
+        // The empty squares go to the winner
+        assert_eq!(pos.final_score(), -64);
+
+        let pos = OthelloPosition::from_fen("xxxx4/8/8/8/8/8/8/4oooo x");
+        assert_eq!(pos.get_result(), Some(GameResult::Draw));
+        assert_eq!(pos.final_score(), 0);
+
+        assert_eq!(OthelloPosition::startpos().get_result(), None);
+    }
+
+    #[test]
+    fn solver_matches_minimax() {
+        for seed in 1..=20u64 {
+            let mut pos = random_position(seed.wrapping_mul(0x9e3779b97f4a7c15), 8);
+            if pos.is_gameover() {
+                continue;
+            }
+
+            let expected = minimax(&mut pos);
+            let mut stats = SearchStats::default();
+            let (mv, score) = solve_root(&mut pos, &|_| false, &mut stats).unwrap();
+            assert_eq!(score, expected, "{}", pos.get_fen());
+
+            pos.makemove(&mv);
+            assert_eq!(-minimax(&mut pos), expected, "{}", pos.get_fen());
+        }
+    }
+
+    #[test]
+    fn solver_stops() {
+        let mut pos = random_position(0xe50076937a9e5b1c, 14);
+        let mut stats = SearchStats::default();
+        assert_eq!(
+            solve_root(&mut pos, &|stats: &SearchStats| stats.nodes > 0, &mut stats),
+            None
+        );
+    }
+
+    #[test]
+    fn primary_endgame() {
+        // Taking the corner leaves White without a disc
+        let pos = OthelloPosition::from_fen("1ox5/8/8/8/8/8/8/8 x");
+        let mv = primary(pos, &GoSettings::from_depth(4), &empty_handler);
+        assert_eq!(mv, Some(OthelloMove::Place(parse_square("a8").unwrap())));
+
+        let pos = OthelloPosition::from_fen("xxxxxxxx/8/8/8/8/8/8/8 o");
+        assert_eq!(
+            primary(pos, &GoSettings::from_depth(4), &empty_handler),
+            None
+        );
+    }
+
+    #[test]
+    fn primary_startpos() {
+        let pos = OthelloPosition::startpos();
+        let mv = primary(pos.clone(), &GoSettings::from_depth(3), &empty_handler);
+        assert!(mv.is_some_and(|mv| pos.is_legal(&mv)));
+    }
+
+    #[test]
+    fn fewer_nodes() {
+        common::fewer_nodes(
+            ["8/8/8/3xo3/3ox3/8/8/8 x", "8/8/8/3xo3/3xx3/3x4/8/8 o"],
+            4,
+            |pos, settings, stats| primary_with_stats(pos, settings, &quiet, stats),
+            eval,
+            |_, mv| as_ugi(mv),
+        );
+    }
+}
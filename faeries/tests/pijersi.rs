@@ -1,9 +1,18 @@
+mod common;
+
 #[cfg(test)]
 mod pijersi {
+    use crate::common;
     use faeries::{
-        pijersi::{root::primary, ugi::as_ugi},
+        bench::quiet,
+        bound::Bound,
+        pijersi::{
+            root::{primary, primary_with_stats},
+            ugi::as_ugi,
+        },
         prng,
         search::{alphabeta::alphabeta, flatmc::flatmc, minimax::minimax, random::random},
+        searchstats::SearchStats,
     };
     use games::{
         gamerules::GameRules,
@@ -16,11 +25,13 @@ mod pijersi {
         _: Option<i32>,
         _: Option<i32>,
         _: Option<i32>,
+        _: Option<Bound>,
         _: Option<i32>,
         _: Option<u64>,
         _: Option<u128>,
         _: Option<i32>,
         _: &Vec<PijersiMove>,
+        _: Option<&SearchStats>,
     ) {
     }
 
@@ -37,26 +48,26 @@ mod pijersi {
         num_us - num_them
     }
 
+    const WIN_IN_ONE: [(&str, &str); 8] = [
+        // Get home
+        ("5r-/R-6/6/7/6/7/6 w 0 1", "f1g1"),
+        ("6/R-p-5/p-5/7/6/7/6 w 0 1", "f1g1"),
+        ("6/7/6/7/6/r-6/5R- b 0 1", "b1a1"),
+        ("6/7/6/7/P-5/r-P-5/6 b 0 1", "b1a1"),
+        // 20 ply rule
+        ("6/R-6/s-s-5/7/6/7/6 w 19 1", "f1g1"),
+        ("6/R-6/ssss5/7/6/7/6 w 19 1", "f1g1"),
+        // Win by material
+        ("6/7/s-5/R-6/6/7/6 w 0 1", "d1e1"),
+        ("6/7/6/R-6/s-5/7/6 w 0 1", "d1c1"),
+    ];
+
     #[test]
     fn win_in_one() {
-        let tests = [
-            // Get home
-            ("5r-/R-6/6/7/6/7/6 w 0 1", "f1g1"),
-            ("6/R-p-5/p-5/7/6/7/6 w 0 1", "f1g1"),
-            ("6/7/6/7/6/r-6/5R- b 0 1", "b1a1"),
-            ("6/7/6/7/P-5/r-P-5/6 b 0 1", "b1a1"),
-            // 20 ply rule
-            ("6/R-6/s-s-5/7/6/7/6 w 19 1", "f1g1"),
-            ("6/R-6/ssss5/7/6/7/6 w 19 1", "f1g1"),
-            // Win by material
-            ("6/7/s-5/R-6/6/7/6 w 0 1", "d1e1"),
-            ("6/7/6/R-6/s-5/7/6 w 0 1", "d1c1"),
-        ];
-
         let settings = GoSettings::from_depth(3);
         let mut prng = prng::XorshiftGenerator::new(0xe50076937a9e5b1c);
 
-        for (fen, movestr) in tests {
+        for (fen, movestr) in WIN_IN_ONE {
             let pos = PijersiPosition::from_fen(fen);
 
             // Primary
@@ -97,24 +108,24 @@ mod pijersi {
         }
     }
 
+    const EASY_MOVES: [(&str, &str); 6] = [
+        // Win soon
+        ("r-5/R-6/6/7/6/7/6 w 0 1", "f1f2"),
+        ("r-5/R-5W-/6/7/6/7/6 w 0 1", "f1f2"),
+        // No distractions
+        ("r-5/R-6/s-6/7/6/7/6 w 0 1", "f1f2"),
+        ("r-5/R-6/ss6/7/6/7/6 w 0 1", "f1f2"),
+        // 20 ply rule
+        ("r-5/R-6/s-4R-/7/6/7/6 w 19 1", "f1e1"),
+        ("r-5/R-6/ss4R-/7/6/7/6 w 19 1", "f1e1"),
+    ];
+
     #[test]
     fn easy_moves() {
-        let tests = [
-            // Win soon
-            ("r-5/R-6/6/7/6/7/6 w 0 1", "f1f2"),
-            ("r-5/R-5W-/6/7/6/7/6 w 0 1", "f1f2"),
-            // No distractions
-            ("r-5/R-6/s-6/7/6/7/6 w 0 1", "f1f2"),
-            ("r-5/R-6/ss6/7/6/7/6 w 0 1", "f1f2"),
-            // 20 ply rule
-            ("r-5/R-6/s-4R-/7/6/7/6 w 19 1", "f1e1"),
-            ("r-5/R-6/ss4R-/7/6/7/6 w 19 1", "f1e1"),
-        ];
-
         let settings = GoSettings::from_depth(3);
         let mut prng = prng::XorshiftGenerator::new(0xe50076937a9e5b1c);
 
-        for (fen, movestr) in tests {
+        for (fen, movestr) in EASY_MOVES {
             let pos = PijersiPosition::from_fen(fen);
 
             // Primary
@@ -142,4 +153,15 @@ mod pijersi {
             assert!(random(&pos, || prng.next()).is_some(), "{}", fen);
         }
     }
+
+    #[test]
+    fn fewer_nodes() {
+        common::fewer_nodes(
+            WIN_IN_ONE.into_iter().chain(EASY_MOVES).map(|(fen, _)| fen),
+            3,
+            |pos, settings, stats| primary_with_stats(pos, settings, &quiet, stats),
+            eval,
+            |_, mv| as_ugi(mv),
+        );
+    }
 }
@@ -0,0 +1,142 @@
+#[cfg(test)]
+mod record {
+    use faeries::{
+        chess::{san::as_san, ugi::as_ugi},
+        record::{Record, Recordable},
+    };
+    use games::{
+        ataxx::AtaxxPosition, chess::ChessPosition, gamerules::GameRules, tak::TakPosition,
+    };
+
+    #[must_use]
+    fn find<G: Recordable>(
+        pos: &G,
+        movestr: &str,
+        notation: impl Fn(&G, &G::MoveType) -> String,
+    ) -> G::MoveType {
+        pos.legal_moves()
+            .into_iter()
+            .find(|mv| notation(pos, mv) == movestr)
+            .unwrap_or_else(|| panic!("{} not legal in {}", movestr, pos.get_fen()))
+    }
+
+    #[test]
+    fn san() {
+        let tests = [
+            ("startpos", "e2e4", "e4"),
+            ("startpos", "g1f3", "Nf3"),
+            ("4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1", "e4d5", "exd5"),
+            ("7k/8/8/8/8/8/8/R4R1K w - - 0 1", "a1d1", "Rad1"),
+            ("7k/8/8/8/8/8/8/R4R1K w - - 0 1", "f1d1", "Rfd1"),
+            ("7k/8/8/R7/8/8/8/R6K w - - 0 1", "a1a3", "R1a3"),
+            ("7k/8/8/R7/8/8/8/R6K w - - 0 1", "a5a3", "R5a3"),
+            ("6k1/8/8/8/8/Q7/8/Q1Q4K w - - 0 1", "a1b2", "Qa1b2"),
+            ("4k3/8/8/8/8/8/8/R3K3 w - - 0 1", "a1a8", "Ra8+"),
+            ("4k3/P7/8/8/8/8/8/4K3 w - - 0 1", "a7a8q", "a8=Q+"),
+            ("4k3/P7/8/8/8/8/8/4K3 w - - 0 1", "a7a8n", "a8=N"),
+        ];
+
+        for (fen, movestr, expected) in tests {
+            let mut pos = ChessPosition::startpos();
+            pos.set_fen(fen);
+            let mv = find(&pos, movestr, as_ugi);
+            assert_eq!(as_san(&pos, &mv), expected, "{} {}", fen, movestr);
+        }
+    }
+
+    #[test]
+    fn castling() {
+        let pos = ChessPosition::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+        let moves = pos
+            .legal_moves()
+            .iter()
+            .map(|mv| as_san(&pos, mv))
+            .collect::<Vec<String>>();
+        assert!(moves.contains(&"O-O".to_owned()));
+        assert!(moves.contains(&"O-O-O".to_owned()));
+    }
+
+    #[test]
+    fn pgn() {
+        let mut pos = ChessPosition::startpos();
+        let mut history = vec![];
+        for movestr in ["f2f3", "e7e5", "g2g4", "d8h4"] {
+            let mv = find(&pos, movestr, as_ugi);
+            pos.makemove(&mv);
+            history.push(Some(mv));
+        }
+
+        let mut record = Record::replay::<ChessPosition>("startpos", &history);
+        record.set_players("Alice", "Bob");
+        assert_eq!(record.movetext(), "1. f3 e5 2. g4 Qh4# 0-1");
+
+        let text = record.to_string();
+        assert!(text.starts_with("[Event \"?\"]\n"));
+        assert!(text.contains("[White \"Alice\"]\n"));
+        assert!(text.contains("[Black \"Bob\"]\n"));
+        assert!(text.contains("[Result \"0-1\"]\n"));
+        assert!(!text.contains("[FEN"));
+        assert!(text.ends_with("\n\n1. f3 e5 2. g4 Qh4# 0-1\n"));
+    }
+
+    #[test]
+    fn pgn_setup() {
+        let pos = ChessPosition::from_fen("4k3/8/8/8/8/8/8/R3K3 b - - 0 12");
+        let mv = find(&pos, "Kd7", as_san);
+        let mut record = Record::new(&pos);
+        record.push(&pos, &mv);
+
+        assert_eq!(record.movetext(), "12... Kd7 *");
+        let text = record.to_string();
+        assert!(text.contains("[SetUp \"1\"]\n"));
+        assert!(text.contains(&format!("[FEN \"{}\"]\n", pos.get_fen())));
+    }
+
+    #[test]
+    fn ptn() {
+        let mut pos = TakPosition::<5>::startpos();
+        let mut record = Record::new(&pos);
+        for movestr in ["a1", "e5", "Cc3"] {
+            let mv = find(&pos, movestr, TakPosition::<5>::notation);
+            record.push(&pos, &mv);
+            pos.makemove(&mv);
+        }
+
+        assert_eq!(record.movetext(), "1. a1 e5 2. Cc3 *");
+        let text = record.to_string();
+        assert!(text.contains("[Size \"5\"]\n"));
+        assert!(text.contains("[Player1 \"?\"]\n"));
+        assert!(!text.contains("[TPS"));
+    }
+
+    #[test]
+    fn generic() {
+        let mut pos = AtaxxPosition::<7, 7>::startpos();
+        let mut record = Record::new(&pos);
+        for movestr in ["g2", "a1b3"] {
+            let mv = find(&pos, movestr, AtaxxPosition::<7, 7>::notation);
+            record.push(&pos, &mv);
+            pos.makemove(&mv);
+        }
+        record.push_null();
+
+        assert_eq!(record.movetext(), "1. g2 a1b3 2. 0000 *");
+        let text = record.to_string();
+        assert!(text.starts_with("[Game \"ataxx\"]\n"));
+        assert!(text.contains("[Result \"*\"]\n"));
+    }
+
+    #[test]
+    fn wrapping() {
+        let pos = AtaxxPosition::<7, 7>::startpos();
+        let mut record = Record::new(&pos);
+        for _ in 0..100 {
+            record.push_null();
+        }
+
+        let movetext = record.movetext();
+        assert!(movetext.lines().count() > 1);
+        assert!(movetext.lines().all(|line| line.len() < 80));
+        assert_eq!(movetext.split_ascii_whitespace().count(), 151);
+    }
+}
@@ -0,0 +1,242 @@
+#[cfg(test)]
+mod serve {
+    use faeries::serve::serve;
+    use protocols::json::{Value, parse_object};
+    use std::io::{Read, Write};
+    use std::net::{SocketAddr, TcpListener, TcpStream};
+
+    #[must_use]
+    fn start() -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || serve(listener));
+        addr
+    }
+
+    /// The status code and body of the reply, its chunks joined back together.
+    #[must_use]
+    fn request(addr: SocketAddr, method: &str, path: &str, body: &str) -> (u32, String) {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(
+            stream,
+            "{} {} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{}",
+            method,
+            path,
+            body.len(),
+            body
+        )
+        .unwrap();
+
+        let mut reply = String::new();
+        stream.read_to_string(&mut reply).unwrap();
+        let (head, mut rest) = reply.split_once("\r\n\r\n").unwrap();
+        let status = head.split(' ').nth(1).unwrap().parse::<u32>().unwrap();
+        if !head.contains("Transfer-Encoding: chunked") {
+            return (status, rest.to_owned());
+        }
+
+        let mut body = String::new();
+        loop {
+            let (size, after) = rest.split_once("\r\n").unwrap();
+            let size = usize::from_str_radix(size, 16).unwrap();
+            if size == 0 {
+                return (status, body);
+            }
+            body += &after[..size];
+            rest = &after[size + 2..];
+        }
+    }
+
+    #[must_use]
+    fn lines(body: &str) -> Vec<Vec<(String, Value)>> {
+        body.lines()
+            .map(|line| parse_object(line).unwrap())
+            .collect()
+    }
+
+    #[must_use]
+    fn get<'a>(fields: &'a [(String, Value)], name: &str) -> &'a Value {
+        &fields.iter().find(|(field, _)| field == name).unwrap().1
+    }
+
+    #[must_use]
+    fn strings(value: &Value) -> Vec<&str> {
+        value
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| v.as_str().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn about() {
+        let addr = start();
+        let (status, body) = request(addr, "GET", "/", "");
+        assert_eq!(status, 200);
+        let fields = parse_object(&body).unwrap();
+        assert_eq!(get(&fields, "type").as_str(), Some("about"));
+        assert!(strings(get(&fields, "games")).contains(&"chess"));
+    }
+
+    #[test]
+    fn moves() {
+        let tests = [
+            (r#"{"game": "chess"}"#, 20),
+            (
+                r#"{"game": "chess", "fen": "startpos", "moves": ["e2e4"]}"#,
+                20,
+            ),
+            (
+                r#"{"game": "chess", "fen": "4k3/8/8/8/8/8/8/4K2R w K - 0 1"}"#,
+                15,
+            ),
+            (r#"{"game": "connect4"}"#, 7),
+            (r#"{"game": "ataxx", "moves": []}"#, 16),
+            (r#"{"game": "othello", "moves": ["d3"]}"#, 3),
+        ];
+
+        let addr = start();
+        for (body, expected) in tests {
+            let (status, reply) = request(addr, "POST", "/moves", body);
+            assert_eq!(status, 200, "{}", body);
+            let fields = parse_object(&reply).unwrap();
+            assert_eq!(get(&fields, "type").as_str(), Some("moves"));
+            assert_eq!(strings(get(&fields, "moves")).len(), expected, "{}", body);
+        }
+    }
+
+    #[test]
+    fn play() {
+        let addr = start();
+        let body = r#"{"game": "chess", "moves": ["f2f3", "e7e5", "g2g4", "d8h4"]}"#;
+        let (status, reply) = request(addr, "POST", "/play", body);
+        assert_eq!(status, 200);
+        let fields = parse_object(&reply).unwrap();
+        assert_eq!(get(&fields, "type").as_str(), Some("position"));
+        assert_eq!(get(&fields, "turn").as_str(), Some("p1"));
+        assert_eq!(get(&fields, "result").as_str(), Some("p2"));
+        assert!(strings(get(&fields, "moves")).is_empty());
+
+        let (status, reply) = request(addr, "POST", "/play", r#"{"game": "connect4"}"#);
+        assert_eq!(status, 200);
+        let fields = parse_object(&reply).unwrap();
+        assert_eq!(get(&fields, "turn").as_str(), Some("p1"));
+        assert_eq!(get(&fields, "result"), &Value::Null);
+    }
+
+    #[test]
+    fn perft() {
+        let addr = start();
+        let (status, body) = request(addr, "POST", "/perft", r#"{"game": "chess", "depth": 3}"#);
+        assert_eq!(status, 200);
+
+        let lines = lines(&body);
+        let counts = lines
+            .iter()
+            .map(|fields| {
+                let kind = get(fields, "type").as_str().unwrap();
+                (kind, get(fields, "nodes").as_int().unwrap())
+            })
+            .collect::<Vec<(&str, i128)>>();
+        assert_eq!(
+            counts,
+            [
+                ("perft", 20),
+                ("perft", 400),
+                ("perft", 8902),
+                ("nodes", 8902)
+            ]
+        );
+    }
+
+    #[test]
+    fn analyse() {
+        let tests = [
+            r#"{"game": "chess", "depth": 3}"#,
+            r#"{"game": "connect4", "nodes": 1000}"#,
+            r#"{"game": "ataxx", "movetime": 50}"#,
+            r#"{"game": "shogi", "depth": 2}"#,
+            r#"{"game": "othello", "depth": 3}"#,
+        ];
+
+        let addr = start();
+        for body in tests {
+            let (status, reply) = request(addr, "POST", "/analyse", body);
+            assert_eq!(status, 200, "{}", body);
+
+            // Info as the search goes, then the move it settled on. Stats are
+            // an object inside an object, which requests never need, so they're
+            // left out
+            let without_stats = |line: &str| match line.split_once(",\"stats\":") {
+                Some((line, _)) => parse_object(&format!("{}}}", line)).unwrap(),
+                None => parse_object(line).unwrap(),
+            };
+            let replies = reply.lines().map(without_stats).collect::<Vec<_>>();
+            let (last, infos) = replies.split_last().unwrap();
+            assert_eq!(get(last, "type").as_str(), Some("bestmove"), "{}", body);
+            assert!(!infos.is_empty(), "{}", body);
+            for info in infos {
+                assert_eq!(get(info, "type").as_str(), Some("info"), "{}", body);
+            }
+
+            // Finished iterations say what the search counted
+            let counted = reply
+                .lines()
+                .filter(|line| line.starts_with("{\"type\":\"info\""))
+                .any(|line| line.contains(",\"stats\":{\"nodes\":"));
+            assert!(counted, "{}", body);
+
+            let mv = get(last, "move").as_str().unwrap();
+            let game = get(&parse_object(body).unwrap(), "game").clone();
+            let (_, legal) = request(addr, "POST", "/moves", &format!("{{\"game\":{}}}", game));
+            let legal = parse_object(&legal).unwrap();
+            assert!(strings(get(&legal, "moves")).contains(&mv), "{}", body);
+        }
+    }
+
+    #[test]
+    fn errors() {
+        let tests = [
+            ("GET", "/nowhere", "", 404),
+            ("GET", "/analyse", "", 405),
+            ("POST", "/moves", "", 400),
+            ("POST", "/moves", "{\"game\": ", 400),
+            ("POST", "/moves", r#"{}"#, 400),
+            ("POST", "/moves", r#"{"game": "nonsense"}"#, 400),
+            ("POST", "/moves", r#"{"game": "baduk"}"#, 400),
+            // A count of empty squares too big for any board
+            (
+                "POST",
+                "/moves",
+                r#"{"game": "connect4", "fen": "99999999999999999999999999 x"}"#,
+                400,
+            ),
+            (
+                "POST",
+                "/moves",
+                r#"{"game": "chess", "moves": ["e2e5"]}"#,
+                400,
+            ),
+            (
+                "POST",
+                "/moves",
+                r#"{"game": "chess", "moves": "e2e4"}"#,
+                400,
+            ),
+            ("POST", "/moves", r#"{"game": "chess", "fen": 4}"#, 400),
+            ("POST", "/perft", r#"{"game": "chess"}"#, 400),
+            ("POST", "/perft", r#"{"game": "chess", "depth": "3"}"#, 400),
+            ("POST", "/analyse", r#"{"game": "chess"}"#, 400),
+            ("POST", "/analyse", r#"{"game": "chess", "nodes": -1}"#, 400),
+        ];
+
+        let addr = start();
+        for (method, path, body, expected) in tests {
+            let (status, reply) = request(addr, method, path, body);
+            assert_eq!(status, expected, "{} {} {}", method, path, body);
+            let fields = parse_object(&reply).unwrap();
+            assert_eq!(get(&fields, "type").as_str(), Some("error"));
+        }
+    }
+}
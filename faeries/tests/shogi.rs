@@ -0,0 +1,298 @@
+mod common;
+
+#[cfg(test)]
+mod shogi {
+    use crate::common;
+    use faeries::bench::quiet;
+    use faeries::prng::XorshiftGenerator;
+    use faeries::searchstats::SearchStats;
+    use faeries::shogi::{
+        eval::eval,
+        position::{ShogiMove, ShogiPosition},
+        root::{primary, primary_with_stats},
+        usi::{info_handler, parse_move},
+    };
+    use games::{
+        gamerules::{GameResult, GameRules},
+        general::side::Side,
+    };
+    use protocols::GoSettings;
+
+    #[must_use]
+    fn perft(pos: &mut ShogiPosition, depth: i32) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+
+        let mut nodes = 0;
+        for mv in pos.legal_moves() {
+            pos.makemove(&mv);
+            nodes += perft(pos, depth - 1);
+            pos.undomove(&mv);
+        }
+        nodes
+    }
+
+    #[must_use]
+    fn moves(fen: &str) -> Vec<String> {
+        let pos = ShogiPosition::from_fen(fen);
+        pos.legal_moves().iter().map(|mv| mv.to_string()).collect()
+    }
+
+    fn play(pos: &mut ShogiPosition, movestrs: &[&str]) {
+        for movestr in movestrs {
+            let mv =
+                parse_move(pos, movestr).unwrap_or_else(|| panic!("{} {}", pos.get_fen(), movestr));
+            pos.makemove(&mv);
+        }
+    }
+
+    #[test]
+    fn perft_startpos() {
+        let mut pos = ShogiPosition::startpos();
+        let nodes = [1, 30, 900, 25470];
+        for (depth, expected) in nodes.iter().enumerate() {
+            assert_eq!(perft(&mut pos, depth as i32), *expected);
+        }
+        assert_eq!(pos.get_fen(), ShogiPosition::startpos().get_fen());
+    }
+
+    #[test]
+    fn perft_drops() {
+        // The most moves there can be in one position
+        let mut pos =
+            ShogiPosition::from_fen("R8/2K1S1SSk/4B4/9/9/9/9/9/1L1L1L3 b RBGSNLP3g3n17p 1");
+        assert_eq!(perft(&mut pos, 1), 593);
+    }
+
+    #[test]
+    fn fen() {
+        let fens = [
+            "lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1",
+            "lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL w - 2",
+            "8l/1l+R2P3/p2pBG1pp/kps1p4/Nn1P2G2/P1P1P2PP/1PS6/1KSG3+r1/LN2+p3L w Sbgn3p 124",
+            "R8/2K1S1SSk/4B4/9/9/9/9/9/1L1L1L3 b RBGSNLP3g3n17p 1",
+        ];
+        for fen in fens {
+            assert_eq!(ShogiPosition::from_fen(fen).get_fen(), fen);
+        }
+        assert_eq!(
+            ShogiPosition::from_fen("startpos").get_fen(),
+            ShogiPosition::startpos().get_fen()
+        );
+    }
+
+    #[test]
+    fn promotion() {
+        // Optional going into the far three ranks
+        let found = moves("k8/9/9/4P4/9/9/9/9/4K4 b - 1");
+        assert!(found.contains(&"5d5c+".to_owned()));
+        assert!(found.contains(&"5d5c".to_owned()));
+
+        // Forced where the piece could never move again
+        let found = moves("k8/4P4/9/9/9/9/9/9/4K4 b - 1");
+        assert!(found.contains(&"5b5a+".to_owned()));
+        assert!(!found.contains(&"5b5a".to_owned()));
+        let found = moves("k8/9/9/4N4/9/9/9/9/4K4 b - 1");
+        assert!(found.contains(&"5d4b+".to_owned()));
+        assert!(!found.contains(&"5d4b".to_owned()));
+
+        // White promotes at the other end
+        let found = moves("4k4/9/9/9/9/4p4/9/9/K8 w - 1");
+        assert!(found.contains(&"5f5g+".to_owned()));
+        assert!(found.contains(&"5f5g".to_owned()));
+    }
+
+    #[test]
+    fn captures() {
+        // A captured piece goes into the hand unpromoted
+        let fen = "4k4/9/9/9/9/9/9/1+r7/B3K4 b - 1";
+        let mut pos = ShogiPosition::from_fen(fen);
+        let mv = parse_move(&pos, "9i8h").unwrap();
+        pos.makemove(&mv);
+        assert_eq!(pos.get_fen(), "4k4/9/9/9/9/9/9/1B7/4K4 w R 2");
+        pos.undomove(&mv);
+        assert_eq!(pos.get_fen(), fen);
+
+        // And can be dropped back on the board
+        let fen = "4k4/9/9/9/9/9/9/9/4K4 b R 1";
+        let mut pos = ShogiPosition::from_fen(fen);
+        let mv = parse_move(&pos, "R*5e").unwrap();
+        assert!(!pos.is_check());
+        pos.makemove(&mv);
+        assert_eq!(pos.get_fen(), "4k4/9/9/9/4R4/9/9/9/4K4 w - 2");
+        assert!(pos.is_check());
+        pos.undomove(&mv);
+        assert_eq!(pos.get_fen(), fen);
+    }
+
+    #[test]
+    fn drops() {
+        // No pawn onto a file that already has one, nor anything onto a rank it
+        // could never leave
+        let found = moves("4k4/9/9/9/9/9/4P4/9/4K4 b PNL 1");
+        for movestr in &found {
+            if let Some(rest) = movestr.strip_prefix("P*") {
+                assert!(!rest.starts_with('5'), "{}", movestr);
+            }
+            if movestr.contains('*') {
+                assert!(!movestr.ends_with('a'), "{}", movestr);
+            }
+            if movestr.starts_with("N*") {
+                assert!(!movestr.ends_with('b'), "{}", movestr);
+            }
+        }
+        assert!(found.contains(&"P*4b".to_owned()));
+        assert!(found.contains(&"L*4b".to_owned()));
+        assert!(found.contains(&"N*4c".to_owned()));
+    }
+
+    #[test]
+    fn pawn_drop_mate() {
+        // Mate by dropping a pawn isn't allowed
+        assert!(!moves("8k/6G2/9/8L/9/9/9/9/4K4 b P 1").contains(&"P*1b".to_owned()));
+
+        // But it is when the king can get away
+        assert!(moves("8k/9/9/8L/9/9/9/9/4K4 b P 1").contains(&"P*1b".to_owned()));
+
+        // And mate by moving a pawn there is fine
+        let mut pos = ShogiPosition::from_fen("8k/6G2/8P/8L/9/9/9/9/4K4 b - 1");
+        play(&mut pos, &["1c1b"]);
+        assert_eq!(pos.get_result(), Some(GameResult::Win(Side::Player1)));
+    }
+
+    #[test]
+    fn check() {
+        // A pinned piece can only move along the pin
+        let mut found = moves("4k4/9/4g4/9/9/9/9/4R4/3K5 w - 1");
+        found.retain(|movestr| movestr.starts_with("5c"));
+        found.sort();
+        assert_eq!(found, vec!["5c5b", "5c5d"]);
+
+        // The king can't walk into check
+        let mut found = moves("4k4/9/9/9/9/9/9/9/3KR4 w - 1");
+        found.sort();
+        assert_eq!(found, vec!["5a4a", "5a4b", "5a6a", "5a6b"]);
+    }
+
+    fn walk_hashes(pos: &mut ShogiPosition, depth: i32) {
+        let fen = pos.get_fen();
+        assert_eq!(
+            pos.get_hash(),
+            ShogiPosition::from_fen(&fen).get_hash(),
+            "{}",
+            fen
+        );
+        if depth == 0 {
+            return;
+        }
+
+        for mv in pos.legal_moves() {
+            pos.makemove(&mv);
+            walk_hashes(pos, depth - 1);
+            pos.undomove(&mv);
+        }
+    }
+
+    #[test]
+    fn hash() {
+        // Captures, drops and promotions all change a hand or a piece
+        let fens = [
+            "lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1",
+            "lnsgkgsnl/1r5b1/pppp1pppp/9/4p4/4P4/PPPP1PPPP/1B5R1/LNSGKGSNL b - 1",
+            "4k4/9/4P4/9/9/9/9/9/4K4 b RBGSNLP3g3n17p 1",
+        ];
+
+        for fen in fens {
+            walk_hashes(&mut ShogiPosition::from_fen(fen), 2);
+        }
+    }
+
+    #[test]
+    fn repetition() {
+        let mut pos = ShogiPosition::from_fen("4k4/9/9/9/9/9/9/9/4K4 b - 1");
+        for _ in 0..3 {
+            assert_eq!(pos.get_result(), None);
+            play(&mut pos, &["5i5h", "5a5b", "5h5i", "5b5a"]);
+        }
+        assert_eq!(pos.get_result(), Some(GameResult::Draw));
+        assert!(pos.is_gameover());
+        assert!(pos.legal_moves().is_empty());
+    }
+
+    #[test]
+    fn perpetual_check() {
+        // Black checks every move, so Black loses
+        let mut pos = ShogiPosition::from_fen("4k4/9/9/9/5R3/9/9/9/4K4 b - 1");
+        for _ in 0..3 {
+            assert_eq!(pos.get_result(), None);
+            play(&mut pos, &["4e5e", "5a4a", "5e4e", "4a5a"]);
+        }
+        assert_eq!(pos.get_result(), Some(GameResult::Win(Side::Player2)));
+    }
+
+    #[test]
+    fn checkmate() {
+        let pos = ShogiPosition::from_fen("8k/6G1P/9/8L/9/9/9/9/4K4 w - 1");
+        assert!(pos.is_check());
+        assert_eq!(pos.get_result(), Some(GameResult::Win(Side::Player1)));
+    }
+
+    #[test]
+    fn eval_startpos() {
+        assert_eq!(eval(&ShogiPosition::startpos()), 0);
+        assert!(eval(&ShogiPosition::from_fen("4k4/9/9/9/9/9/9/9/4K4 b R 1")) > 0);
+        assert!(eval(&ShogiPosition::from_fen("4k4/9/9/9/9/9/9/9/4K4 w R 1")) < 0);
+    }
+
+    #[test]
+    fn primary_mate() {
+        let mut pos = ShogiPosition::from_fen("8k/6G2/8P/9/9/9/9/9/4K4 b G 1");
+        let mv = primary(pos.clone(), &GoSettings::from_depth(2), &info_handler).unwrap();
+        pos.makemove(&mv);
+        assert_eq!(pos.get_result(), Some(GameResult::Win(Side::Player1)));
+    }
+
+    #[test]
+    fn qnodes() {
+        // Checks at the horizon get looked past, and counted
+        let mut stats = SearchStats::default();
+        let pos = ShogiPosition::from_fen("8k/6G2/8P/9/9/9/9/9/4K4 b G 1");
+        primary_with_stats(pos, &GoSettings::from_depth(1), &quiet, &mut stats).unwrap();
+        assert!(stats.qnodes > 0);
+        assert!(stats.qnodes < stats.nodes);
+    }
+
+    #[test]
+    fn undo() {
+        let mut prng = XorshiftGenerator::new(0xe50076937a9e5b1c);
+        let mut pos = ShogiPosition::startpos();
+        let mut history: Vec<ShogiMove> = vec![];
+        let mut fens = vec![];
+
+        while !pos.is_gameover() && history.len() < 300 {
+            let moves = pos.legal_moves();
+            let mv = moves[prng.next() as usize % moves.len()];
+            fens.push(pos.get_fen());
+            history.push(mv);
+            pos.makemove(&mv);
+        }
+
+        while let Some(mv) = history.pop() {
+            pos.undomove(&mv);
+            assert_eq!(pos.get_fen(), fens.pop().unwrap());
+        }
+    }
+
+    #[test]
+    fn fewer_nodes() {
+        // The pawn in front of the rook can take it for nothing
+        common::fewer_nodes(
+            ["lnsgkgsnl/7b1/ppppppppp/9/9/7r1/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1"],
+            3,
+            |pos, settings, stats| primary_with_stats(pos, settings, &quiet, stats),
+            eval,
+            |_, mv| mv.to_string(),
+        );
+    }
+}
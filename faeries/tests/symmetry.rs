@@ -0,0 +1,160 @@
+#[cfg(test)]
+mod symmetry {
+    use faeries::connect4::position::Connect4Position;
+    use faeries::symmetry::{
+        Cells, DIHEDRAL, Symmetry, board_fen, canonical_fen, cells, dimensions, transform_fen,
+        transform_move,
+    };
+    use games::{ataxx::AtaxxPosition, tak::TakPosition};
+
+    #[test]
+    fn transform_ataxx() {
+        let tests = [
+            (Symmetry::Identity, "x6/7/7/7/7/7/o5- x 0 1"),
+            (Symmetry::Rotate90, "o5x/7/7/7/7/7/-6 x 0 1"),
+            (Symmetry::Rotate180, "-5o/7/7/7/7/7/6x x 0 1"),
+            (Symmetry::Rotate270, "6-/7/7/7/7/7/x5o x 0 1"),
+            (Symmetry::FlipFiles, "6x/7/7/7/7/7/-5o x 0 1"),
+            (Symmetry::FlipRanks, "o5-/7/7/7/7/7/x6 x 0 1"),
+            (Symmetry::FlipDiagonal, "-6/7/7/7/7/7/o5x x 0 1"),
+            (Symmetry::FlipAntidiagonal, "x5o/7/7/7/7/7/6- x 0 1"),
+        ];
+
+        for (symmetry, expected) in tests {
+            let result = transform_fen("x6/7/7/7/7/7/o5- x 0 1", symmetry, Cells::Chars);
+            assert_eq!(result.as_deref(), Some(expected), "{:?}", symmetry);
+        }
+    }
+
+    #[test]
+    fn transform_tak() {
+        let fen = "2,x3/x4/x4/1S,x2,12C 1 3";
+        let result = transform_fen(fen, Symmetry::FlipRanks, Cells::Commas);
+        assert_eq!(result.as_deref(), Some("1S,x2,12C/x4/x4/2,x3 1 3"));
+        let result = transform_fen(fen, Symmetry::Rotate90, Cells::Commas);
+        assert_eq!(result.as_deref(), Some("1S,x2,2/x4/x4/12C,x3 1 3"));
+    }
+
+    #[test]
+    fn inverse() {
+        let fen = "x2o3/1-5/7/3x3/7/5o1/o5x o 3 7";
+
+        for symmetry in DIHEDRAL {
+            let image = transform_fen(fen, symmetry, Cells::Chars).unwrap();
+            let back = transform_fen(&image, symmetry.inverse(), Cells::Chars).unwrap();
+            assert_eq!(back, fen, "{:?}", symmetry);
+        }
+    }
+
+    #[test]
+    fn rectangle() {
+        let fen = "x6/7/7/7/7/6o x";
+        assert_eq!(dimensions(fen, Cells::Chars), Some((7, 6)));
+
+        for symmetry in DIHEDRAL {
+            let image = transform_fen(fen, symmetry, Cells::Chars);
+            assert_eq!(image.is_none(), symmetry.is_transpose(), "{:?}", symmetry);
+        }
+    }
+
+    #[test]
+    fn boards() {
+        let tests = [
+            ("x6/7/7/7/7/6o x", Cells::Chars),
+            ("x2o3/1-5/7/3x3/7/5o1/o5x o 3 7", Cells::Chars),
+            ("2,x3/x4/x4/1S,x2,12C 1 3", Cells::Commas),
+        ];
+
+        for (fen, style) in tests {
+            let (width, _) = dimensions(fen, style).unwrap();
+            let board = board_fen(&cells(fen, style).unwrap(), width, style);
+            assert_eq!(Some(board.as_str()), fen.split(' ').next(), "{}", fen);
+        }
+    }
+
+    #[test]
+    fn moves() {
+        let tests = [
+            ("a1", Symmetry::Rotate90, "a7"),
+            ("a1", Symmetry::FlipFiles, "g1"),
+            ("b2d3", Symmetry::FlipDiagonal, "b2c4"),
+            ("0000", Symmetry::Rotate180, "0000"),
+            ("3c3>12*", Symmetry::Rotate90, "3c5-12*"),
+            ("Sa1", Symmetry::FlipRanks, "Sa7"),
+            ("c", Symmetry::FlipFiles, "e"),
+            ("pass", Symmetry::Rotate90, "pass"),
+        ];
+
+        for (movestr, symmetry, expected) in tests {
+            assert_eq!(
+                transform_move(movestr, symmetry, 7, 7).as_deref(),
+                Some(expected),
+                "{}",
+                movestr
+            );
+        }
+    }
+
+    #[test]
+    fn promotion() {
+        assert_eq!(
+            transform_move("e7e8q", Symmetry::FlipFiles, 8, 8).as_deref(),
+            Some("d7d8q")
+        );
+    }
+
+    #[test]
+    fn moves_off_board() {
+        let tests = ["h1", "a8", "a0", "a99999999999"];
+
+        for movestr in tests {
+            assert_eq!(
+                transform_move(movestr, Symmetry::Rotate90, 7, 7),
+                None,
+                "{}",
+                movestr
+            );
+        }
+    }
+
+    #[test]
+    fn canonical() {
+        let tests = [
+            "x5o/7/7/7/7/7/o5x x 0 1",
+            "7/7/7/7/7/7/x6 o 0 1",
+            "7/7/3-3/2x1o2/7/7/7 x 0 1",
+        ];
+
+        for fen in tests {
+            let (expected, _) = canonical_fen::<AtaxxPosition<7, 7>>(fen);
+
+            for symmetry in DIHEDRAL {
+                let image = transform_fen(fen, symmetry, Cells::Chars).unwrap();
+                let (canonical, found) = canonical_fen::<AtaxxPosition<7, 7>>(&image);
+                assert_eq!(canonical, expected, "{} {:?}", fen, symmetry);
+                assert_eq!(
+                    transform_fen(&image, found, Cells::Chars).unwrap(),
+                    canonical,
+                    "{} {:?}",
+                    fen,
+                    symmetry
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn canonical_restricted() {
+        // Connect 4 can only be mirrored
+        let (fen, _) = canonical_fen::<Connect4Position>("7/7/7/7/7/x6 o");
+        assert_eq!(fen, "7/7/7/7/7/6x o");
+        let (fen, symmetry) = canonical_fen::<Connect4Position>("7/7/7/7/7/6x o");
+        assert_eq!(fen, "7/7/7/7/7/6x o");
+        assert_eq!(symmetry, Symmetry::Identity);
+
+        // Tak can be turned
+        let (fen, _) = canonical_fen::<TakPosition<4>>("x4/x4/x4/x3,1 2 1");
+        let (other, _) = canonical_fen::<TakPosition<4>>("1,x3/x4/x4/x4 2 1");
+        assert_eq!(fen, other);
+    }
+}
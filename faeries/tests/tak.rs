@@ -0,0 +1,50 @@
+mod common;
+
+#[cfg(test)]
+mod tak {
+    use crate::common;
+    use faeries::{
+        bench::quiet,
+        tak::{
+            eval::eval,
+            root::{primary, primary_with_stats},
+            ugi::as_ugi,
+        },
+    };
+    use games::{
+        gamerules::{GameResult, GameRules},
+        general::side::Side,
+        tak::TakPosition,
+    };
+    use protocols::GoSettings;
+
+    /// A stone on f1 finishes a road along the first rank. A flat and a capstone
+    /// both do, so the win is checked for rather than the move.
+    const WIN_IN_ONE: [(&str, Side); 2] = [
+        ("x6/x6/x6/x6/2,2,2,2,2,x/1,1,1,1,1,x 1 6", Side::Player1),
+        ("x6/x6/x6/x6/1,1,1,1,1,x/2,2,2,2,2,x 2 6", Side::Player2),
+    ];
+
+    #[test]
+    fn win_in_one() {
+        let settings = GoSettings::from_depth(2);
+
+        for (fen, winner) in WIN_IN_ONE {
+            let mut pos = TakPosition::<6>::from_fen(fen);
+            let mv = primary(pos.clone(), &settings, &quiet).unwrap();
+            pos.makemove(&mv);
+            assert_eq!(pos.get_result(), Some(GameResult::Win(winner)), "{}", fen);
+        }
+    }
+
+    #[test]
+    fn fewer_nodes() {
+        common::fewer_nodes(
+            WIN_IN_ONE.map(|(fen, _)| fen),
+            2,
+            |pos: TakPosition<6>, settings, stats| primary_with_stats(pos, settings, &quiet, stats),
+            eval,
+            as_ugi,
+        );
+    }
+}
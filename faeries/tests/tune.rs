@@ -0,0 +1,137 @@
+#[cfg(test)]
+mod tune {
+    use faeries::checkers::{eval::eval, position::CheckersPosition};
+    use faeries::connect4::{eval, position::Connect4Position};
+    use faeries::othello::position::OthelloPosition;
+    use faeries::tune::{
+        Sample, Tunable, Tuner, as_rust, as_weights, error, fit_scale, parse_line, parse_result,
+        parse_weights, sigmoid,
+    };
+    use games::gamerules::GameRules;
+
+    #[test]
+    fn results() {
+        let tests = [
+            ("1-0", Some(1.0)),
+            ("0-1", Some(0.0)),
+            ("1/2-1/2", Some(0.5)),
+            ("[1.0]", Some(1.0)),
+            ("[0.5]", Some(0.5)),
+            ("0.25", Some(0.25)),
+            ("2.0", None),
+            ("x", None),
+        ];
+
+        for (word, expected) in tests {
+            assert_eq!(parse_result(word), expected, "{}", word);
+        }
+    }
+
+    #[test]
+    fn lines() {
+        assert_eq!(
+            parse_line("8/8/8/3xo3/3ox3/8/8/8 x [0.5]"),
+            Some(("8/8/8/3xo3/3ox3/8/8/8 x", 0.5))
+        );
+        assert_eq!(
+            parse_line("8/8/8/3xo3/3ox3/8/8/8 x; 1-0"),
+            Some(("8/8/8/3xo3/3ox3/8/8/8 x", 1.0))
+        );
+        assert_eq!(
+            parse_line("8/8/8/3xo3/3ox3/8/8/8 x | -35 | 0.0"),
+            Some(("8/8/8/3xo3/3ox3/8/8/8 x", 0.0))
+        );
+        assert_eq!(parse_line("8/8/8/3xo3/3ox3/8/8/8 x | 1.0"), None);
+        assert_eq!(parse_line("8/8/8/3xo3/3ox3/8/8/8 x"), None);
+        assert_eq!(parse_line("# comment 1-0"), None);
+        assert_eq!(parse_line(""), None);
+    }
+
+    #[test]
+    fn features_make_the_eval() {
+        let fens = [
+            "B:W18,24,27,28,K10,K15:B12,16,20,K22,K25,K29 0 1",
+            "W:W18,24,27,28,K10,K15:B12,16,20,K22,K25,K29 0 1",
+            "B:W21,22,23,24,25,26,27,28,29,30,31,32:B1,2,3,4,5,6,7,8,9,10,11,12 0 1",
+        ];
+
+        for fen in fens {
+            let pos = CheckersPosition::from_fen(fen);
+            let weights = CheckersPosition::WEIGHTS.iter().map(|w| f64::from(*w));
+            let sample = Sample::new(&pos, 0.5);
+            assert_eq!(
+                sample.eval(&weights.collect::<Vec<f64>>()),
+                eval(&pos) as f64
+            );
+        }
+    }
+
+    #[test]
+    fn connect4_features() {
+        // Seven lines of four go through d1, and they're all the other side's
+        let pos = Connect4Position::from_fen("7/7/7/7/7/3x3 o");
+        assert_eq!(pos.features(), vec![-7, 0, 0, 0, 0, 0]);
+        assert_eq!(eval::eval(&pos), -21);
+
+        for fen in ["7/7/7/3o3/3xo2/2xxo2 x", "7/7/2o4/2xo3/2xxo2/1xoxo2 o"] {
+            let pos = Connect4Position::from_fen(fen);
+            let sample = Sample::new(&pos, 0.5);
+            let weights = eval::WEIGHTS.map(f64::from);
+            assert_eq!(sample.eval(&weights), eval::eval(&pos) as f64, "{}", fen);
+        }
+    }
+
+    #[test]
+    fn results_are_for_the_side_to_move() {
+        let black = OthelloPosition::from_fen("8/8/8/3xo3/3ox3/8/8/8 x");
+        let white = OthelloPosition::from_fen("8/8/8/3xo3/3ox3/8/8/8 o");
+        assert_eq!(Sample::new(&black, 1.0).result, 1.0);
+        assert_eq!(Sample::new(&white, 1.0).result, 0.0);
+        assert_eq!(Sample::new(&white, 0.25).result, 0.75);
+    }
+
+    #[test]
+    fn weights_files() {
+        let names = ["man", "king"];
+        let text = as_weights(&names, &[105, 160]);
+        assert_eq!(text, "man 105\nking 160");
+        assert_eq!(
+            parse_weights(&names, &[100, 150], &text),
+            Ok(vec![105, 160])
+        );
+        assert_eq!(
+            parse_weights(&names, &[100, 150], "king 1"),
+            Ok(vec![100, 1])
+        );
+        assert!(parse_weights(&names, &[100, 150], "queen 900").is_err());
+        assert!(parse_weights(&names, &[100, 150], "man x").is_err());
+
+        assert_eq!(
+            as_rust(&names, &[105, 160]),
+            "pub const WEIGHTS: [i32; 2] = [\n    105, // man\n    160, // king\n];"
+        );
+    }
+
+    #[test]
+    fn finds_the_weight() {
+        // Results that a weight of 200 would predict exactly
+        let samples = (-5..=5)
+            .map(|n| Sample {
+                features: vec![f64::from(n)],
+                result: sigmoid(200.0 * f64::from(n), 1.0),
+            })
+            .collect::<Vec<Sample>>();
+
+        let mut tuner = Tuner::new(&[100], 1.0, 5.0);
+        let before = error(&samples, &tuner.weights, tuner.k);
+        for _ in 0..300 {
+            tuner.step(&samples);
+        }
+        assert!(error(&samples, &tuner.weights, tuner.k) < before);
+        assert!((tuner.rounded()[0] - 200).abs() <= 2, "{:?}", tuner.weights);
+
+        // The scale is found the other way round
+        let k = fit_scale(&samples, &[100.0]);
+        assert!((k - 2.0).abs() < 0.01, "{}", k);
+    }
+}
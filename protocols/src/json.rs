@@ -0,0 +1,309 @@
+use std::fmt::{self, Write};
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// A JSON value, enough of one for engine output to be read by machines.
+/// Objects keep their fields in the order given.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Int(i128),
+    Float(f64),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(&'static str, Value)>),
+}
+
+impl Value {
+    #[must_use]
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn as_int(&self) -> Option<i128> {
+        match self {
+            Value::Int(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Value::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+}
+
+pub trait ToJson {
+    #[must_use]
+    fn to_json(&self) -> Value;
+}
+
+fn write_string(f: &mut fmt::Formatter<'_>, text: &str) -> fmt::Result {
+    f.write_char('"')?;
+    for c in text.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if c < ' ' => write!(f, "\\u{:04x}", c as u32)?,
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_char('"')
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Null => write!(f, "null"),
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::Int(n) => write!(f, "{}", n),
+            // JSON has no infinities or NaN
+            Value::Float(x) if !x.is_finite() => write!(f, "null"),
+            Value::Float(x) => write!(f, "{}", x),
+            Value::String(text) => write_string(f, text),
+            Value::Array(values) => {
+                f.write_char('[')?;
+                for (idx, value) in values.iter().enumerate() {
+                    if idx > 0 {
+                        f.write_char(',')?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                f.write_char(']')
+            }
+            Value::Object(fields) => {
+                f.write_char('{')?;
+                for (idx, (name, value)) in fields.iter().enumerate() {
+                    if idx > 0 {
+                        f.write_char(',')?;
+                    }
+                    write_string(f, name)?;
+                    write!(f, ":{}", value)?;
+                }
+                f.write_char('}')
+            }
+        }
+    }
+}
+
+impl From<bool> for Value {
+    fn from(b: bool) -> Self {
+        Value::Bool(b)
+    }
+}
+
+impl From<i32> for Value {
+    fn from(n: i32) -> Self {
+        Value::Int(n.into())
+    }
+}
+
+impl From<u64> for Value {
+    fn from(n: u64) -> Self {
+        Value::Int(n.into())
+    }
+}
+
+impl From<u128> for Value {
+    fn from(n: u128) -> Self {
+        Value::Int(n.min(i128::MAX as u128) as i128)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(x: f64) -> Self {
+        Value::Float(x)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(text: &str) -> Self {
+        Value::String(text.to_owned())
+    }
+}
+
+impl From<String> for Value {
+    fn from(text: String) -> Self {
+        Value::String(text)
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Value::Null, Into::into)
+    }
+}
+
+impl<T: Into<Value>> From<Vec<T>> for Value {
+    fn from(values: Vec<T>) -> Self {
+        Value::Array(values.into_iter().map(Into::into).collect())
+    }
+}
+
+/// Read a JSON object, such as a request. Requests are flat, so its values can
+/// be anything but other objects.
+pub fn parse_object(text: &str) -> Result<Vec<(String, Value)>, &'static str> {
+    let mut chars = text.chars().peekable();
+    let mut fields = vec![];
+
+    skip_whitespace(&mut chars);
+    if chars.next() != Some('{') {
+        return Err("expected an object");
+    }
+    skip_whitespace(&mut chars);
+    if chars.next_if_eq(&'}').is_none() {
+        loop {
+            skip_whitespace(&mut chars);
+            if chars.next() != Some('"') {
+                return Err("expected a field name");
+            }
+            let name = parse_string(&mut chars)?;
+            skip_whitespace(&mut chars);
+            if chars.next() != Some(':') {
+                return Err("expected :");
+            }
+            fields.push((name, parse_value(&mut chars)?));
+            skip_whitespace(&mut chars);
+            match chars.next() {
+                Some(',') => {}
+                Some('}') => break,
+                _ => return Err("expected , or }"),
+            }
+        }
+    }
+    skip_whitespace(&mut chars);
+    if chars.next().is_some() {
+        return Err("expected the end of the object");
+    }
+
+    Ok(fields)
+}
+
+fn skip_whitespace(chars: &mut Peekable<Chars>) {
+    while chars.next_if(|c| c.is_ascii_whitespace()).is_some() {}
+}
+
+fn parse_value(chars: &mut Peekable<Chars>) -> Result<Value, &'static str> {
+    skip_whitespace(chars);
+    match chars.peek() {
+        Some('"') => {
+            chars.next();
+            parse_string(chars).map(Value::String)
+        }
+        Some('[') => {
+            chars.next();
+            let mut values = vec![];
+            skip_whitespace(chars);
+            if chars.next_if_eq(&']').is_some() {
+                return Ok(Value::Array(values));
+            }
+            loop {
+                values.push(parse_value(chars)?);
+                skip_whitespace(chars);
+                match chars.next() {
+                    Some(',') => {}
+                    Some(']') => return Ok(Value::Array(values)),
+                    _ => return Err("expected , or ]"),
+                }
+            }
+        }
+        Some('{') => Err("nested objects aren't supported"),
+        Some('t') => parse_word(chars, "true", Value::Bool(true)),
+        Some('f') => parse_word(chars, "false", Value::Bool(false)),
+        Some('n') => parse_word(chars, "null", Value::Null),
+        Some(c) if *c == '-' || c.is_ascii_digit() => parse_number(chars),
+        _ => Err("expected a value"),
+    }
+}
+
+fn parse_word(
+    chars: &mut Peekable<Chars>,
+    word: &str,
+    value: Value,
+) -> Result<Value, &'static str> {
+    for expected in word.chars() {
+        if chars.next() != Some(expected) {
+            return Err("expected a value");
+        }
+    }
+    Ok(value)
+}
+
+fn parse_number(chars: &mut Peekable<Chars>) -> Result<Value, &'static str> {
+    let mut text = String::new();
+    while let Some(c) =
+        chars.next_if(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'))
+    {
+        text.push(c);
+    }
+
+    if let Ok(n) = text.parse::<i128>() {
+        Ok(Value::Int(n))
+    } else {
+        text.parse::<f64>()
+            .map(Value::Float)
+            .map_err(|_| "bad number")
+    }
+}
+
+/// The rest of a string, its opening quote already read.
+fn parse_string(chars: &mut Peekable<Chars>) -> Result<String, &'static str> {
+    let mut text = String::new();
+    loop {
+        match chars.next() {
+            Some('"') => return Ok(text),
+            Some('\\') => match chars.next() {
+                Some('"') => text.push('"'),
+                Some('\\') => text.push('\\'),
+                Some('/') => text.push('/'),
+                Some('b') => text.push('\u{8}'),
+                Some('f') => text.push('\u{c}'),
+                Some('n') => text.push('\n'),
+                Some('r') => text.push('\r'),
+                Some('t') => text.push('\t'),
+                Some('u') => {
+                    let mut code = parse_hex(chars)?;
+                    // Anything past the first plane comes as a surrogate pair
+                    if (0xd800..0xdc00).contains(&code) {
+                        if chars.next() != Some('\\') || chars.next() != Some('u') {
+                            return Err("bad escape");
+                        }
+                        let low = parse_hex(chars)?;
+                        if !(0xdc00..0xe000).contains(&low) {
+                            return Err("bad escape");
+                        }
+                        code = 0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00);
+                    }
+                    text.push(char::from_u32(code).ok_or("bad escape")?);
+                }
+                _ => return Err("bad escape"),
+            },
+            Some(c) if c < ' ' => return Err("control character in string"),
+            Some(c) => text.push(c),
+            None => return Err("unterminated string"),
+        }
+    }
+}
+
+fn parse_hex(chars: &mut Peekable<Chars>) -> Result<u32, &'static str> {
+    let mut code = 0;
+    for _ in 0..4 {
+        let digit = chars
+            .next()
+            .and_then(|c| c.to_digit(16))
+            .ok_or("bad escape")?;
+        code = code * 16 + digit;
+    }
+    Ok(code)
+}
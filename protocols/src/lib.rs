@@ -1,7 +1,10 @@
 pub mod gtp;
+pub mod json;
 pub mod manual;
+pub mod output;
 pub mod tei;
 pub mod uci;
+pub mod usi;
 pub mod ugi {
     pub use ::ugi::GoSettings;
     pub use ::ugi::ugi::*;
@@ -27,6 +30,10 @@ pub struct GoSettings {
     pub nodes: Option<u64>,
     pub movetime: Option<i32>,
     pub movestogo: Option<i32>,
+    /// Time given for every move once the main time is used up, as in shogi
+    pub byoyomi: Option<i32>,
+    /// Whether the engine is in debug mode, and can say more about the search
+    pub debug: bool,
 }
 
 impl From<&ugi::GoSettings> for GoSettings {
@@ -41,6 +48,8 @@ impl From<&ugi::GoSettings> for GoSettings {
             nodes: settings.nodes,
             movetime: settings.movetime,
             movestogo: settings.movestogo,
+            byoyomi: None,
+            debug: false,
         }
     }
 }
@@ -63,6 +72,8 @@ impl GoSettings {
             nodes: None,
             movetime: None,
             movestogo: None,
+            byoyomi: None,
+            debug: false,
         }
     }
 
@@ -78,6 +89,8 @@ impl GoSettings {
             nodes: None,
             movetime: None,
             movestogo: None,
+            byoyomi: None,
+            debug: false,
         }
     }
 
@@ -93,6 +106,8 @@ impl GoSettings {
             nodes: None,
             movetime: Some(t),
             movestogo: None,
+            byoyomi: None,
+            debug: false,
         }
     }
 
@@ -108,6 +123,8 @@ impl GoSettings {
             nodes: Some(n),
             movetime: None,
             movestogo: None,
+            byoyomi: None,
+            debug: false,
         }
     }
 }
@@ -1,13 +1,59 @@
 use super::{Manual, ManualGameResult};
+use crate::GoSettings;
 use std::io::Write;
+use std::time::Duration;
+
+fn print_help() {
+    println!("<move>          play a move");
+    println!("pass            play a null move");
+    println!("hint [depth]    show what the engine would play");
+    println!("swap            swap sides with the engine");
+    println!("side p1|p2      choose which side to play");
+    println!("undo            take back the last move");
+    println!("new             start a new game");
+    println!("fen <fen>       start from a position");
+    println!("depth <n>       make the engine search to a depth");
+    println!("movetime <ms>   make the engine search for a time");
+    println!("search <name>   choose the engine's search");
+    println!("watch [delay]   let the engine play itself, delay in ms");
+    println!("history         show the moves played");
+    println!("save <path>     write the game to a file");
+    println!("print           show the board");
+    println!("quit            leave");
+}
+
+fn print_result(state: &dyn Manual) {
+    match state.get_result() {
+        Some(ManualGameResult::P1Win) => println!("Player 1 wins!"),
+        Some(ManualGameResult::P2Win) => println!("Player 2 wins!"),
+        Some(ManualGameResult::Draw) => println!("Draw!"),
+        None => println!("Game not finished"),
+    }
+}
+
+fn print_history(state: &dyn Manual) {
+    for (idx, pair) in state.history().chunks(2).enumerate() {
+        println!("{}. {}", idx + 1, pair.join(" "));
+    }
+}
 
 pub fn listen(state: &mut dyn Manual) -> std::io::Result<()> {
-    let depth = 3;
+    let mut settings = GoSettings::from_depth(3);
+    let mut human_p1 = true;
     let mut update = true;
 
-    while !state.is_gameover() {
+    loop {
+        // Computer move
+        if !state.is_gameover() && state.is_p1turn() != human_p1 {
+            state.play(&settings);
+            update = true;
+        }
+
         if update {
             state.print();
+            if state.is_gameover() {
+                print_result(state);
+            }
             update = false;
         }
 
@@ -23,46 +69,95 @@ pub fn listen(state: &mut dyn Manual) -> std::io::Result<()> {
         }
         println!();
 
-        // Manual move
         let mut stream = input.split_ascii_whitespace();
-        while let Some(word) = stream.next() {
-            match word {
-                "pass" => {
+        let Some(word) = stream.next() else {
+            continue;
+        };
+
+        match word {
+            "pass" => {
+                if !state.is_gameover() {
                     state.makenull();
-                    state.play(depth);
                     update = true;
                 }
-                "hint" => {
-                    let depth = stream.next().unwrap_or("3").parse::<i32>().unwrap_or(3);
-                    state.hint(depth);
-                    println!();
+            }
+            "hint" => {
+                let depth = stream.next().and_then(|d| d.parse::<i32>().ok());
+                match depth {
+                    Some(d) => state.hint(&GoSettings::from_depth(d)),
+                    None => state.hint(&settings),
                 }
-                "swap" => {
-                    state.play(depth);
+                println!();
+            }
+            "swap" => human_p1 = !human_p1,
+            "side" => match stream.next() {
+                Some("p1") => human_p1 = true,
+                Some("p2") => human_p1 = false,
+                _ => println!("Expected p1 or p2"),
+            },
+            "undo" => {
+                // Take back the engine's reply too
+                if state.undo() {
+                    while state.is_p1turn() != human_p1 && state.undo() {}
                     update = true;
+                } else {
+                    println!("No moves to undo");
                 }
-                "quit" => return Ok(()),
-                _ => {
-                    let success = state.makemove(word);
-
-                    // Computer move
-                    if success {
-                        state.play(depth);
-                        update = true;
+            }
+            "new" => {
+                state.newgame();
+                update = true;
+            }
+            "fen" => {
+                let fen = stream.collect::<Vec<&str>>().join(" ");
+                state.set_fen(&fen);
+                update = true;
+            }
+            "depth" => match stream.next().and_then(|d| d.parse::<i32>().ok()) {
+                Some(d) => settings = GoSettings::from_depth(d),
+                None => println!("Expected a depth"),
+            },
+            "movetime" => match stream.next().and_then(|t| t.parse::<i32>().ok()) {
+                Some(t) => settings = GoSettings::from_movetime(t),
+                None => println!("Expected a time in milliseconds"),
+            },
+            "search" => match stream.next() {
+                Some(name) if state.set_search(name) => {}
+                _ => println!("Unknown search"),
+            },
+            "watch" => {
+                let delay = stream.next().and_then(|d| d.parse::<u64>().ok());
+                state.autoplay(&settings, Duration::from_millis(delay.unwrap_or(500)));
+                println!();
+                print_result(state);
+            }
+            "history" => print_history(state),
+            "save" => match stream.next() {
+                Some(path) => {
+                    let (p1, p2) = if human_p1 {
+                        ("Human", "Faeries")
+                    } else {
+                        ("Faeries", "Human")
+                    };
+                    match std::fs::write(path, state.record(p1, p2)) {
+                        Ok(()) => println!("Saved to {}", path),
+                        Err(e) => println!("Couldn't save to {}: {}", path, e),
                     }
                 }
+                None => println!("Expected a path"),
+            },
+            "print" => update = true,
+            "help" => print_help(),
+            "quit" => return Ok(()),
+            _ => {
+                if state.is_gameover() || !state.makemove(word) {
+                    println!("Illegal move {}", word);
+                } else {
+                    update = true;
+                }
             }
         }
     }
 
-    state.print();
-
-    match state.get_result() {
-        Some(ManualGameResult::P1Win) => println!("Player 1 wins!"),
-        Some(ManualGameResult::P2Win) => println!("Player 2 wins!"),
-        Some(ManualGameResult::Draw) => println!("Draw!"),
-        None => println!("Game not finished"),
-    }
-
     Ok(())
 }
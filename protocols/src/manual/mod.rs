@@ -1,3 +1,6 @@
+use crate::GoSettings;
+use std::time::Duration;
+
 pub mod listen;
 
 pub enum ManualGameResult {
@@ -9,17 +12,58 @@ pub enum ManualGameResult {
 pub trait Manual {
     fn print(&self);
 
+    fn pprint(&self) {
+        self.print();
+    }
+
     #[must_use]
     fn is_gameover(&self) -> bool;
 
     #[must_use]
     fn get_result(&self) -> Option<ManualGameResult>;
 
+    #[must_use]
+    fn is_p1turn(&self) -> bool;
+
     fn makemove(&mut self, movestr: &str) -> bool;
 
     fn makenull(&mut self);
 
-    fn play(&mut self, depth: i32);
+    fn undo(&mut self) -> bool;
+
+    fn newgame(&mut self);
+
+    fn set_fen(&mut self, fen: &str);
+
+    fn set_search(&mut self, name: &str) -> bool;
+
+    #[must_use]
+    fn history(&self) -> Vec<String>;
+
+    /// The game so far, written out in the game's record format.
+    #[must_use]
+    fn record(&self, p1: &str, p2: &str) -> String;
+
+    fn play(&mut self, settings: &GoSettings);
+
+    fn hint(&mut self, settings: &GoSettings);
+
+    /// Let the engine play both sides until the game is over, showing the board
+    /// after every move.
+    fn autoplay(&mut self, settings: &GoSettings, delay: Duration) {
+        self.pprint();
+
+        while !self.is_gameover() {
+            std::thread::sleep(delay);
+
+            let played = self.history().len();
+            self.play(settings);
+            if self.history().len() == played {
+                break;
+            }
 
-    fn hint(&mut self, depth: i32);
+            println!();
+            self.pprint();
+        }
+    }
 }
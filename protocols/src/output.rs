@@ -0,0 +1,390 @@
+use crate::json::{ToJson, Value};
+use std::cell::RefCell;
+use std::fmt::{self, Display};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// How engines write search info, best moves, perft counts, options and
+/// errors. The rest of a protocol's replies are always text.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Format {
+    Text,
+    /// One JSON object to a line, its "type" saying which line it is
+    Json,
+}
+
+static JSON: AtomicBool = AtomicBool::new(false);
+
+type Sink = Box<dyn FnMut(&Value)>;
+
+thread_local! {
+    static SINK: RefCell<Option<Sink>> = const { RefCell::new(None) };
+}
+
+/// Set once at startup, for every listener to follow.
+pub fn set_format(format: Format) {
+    JSON.store(format == Format::Json, Ordering::Relaxed);
+}
+
+#[must_use]
+pub fn format() -> Format {
+    if JSON.load(Ordering::Relaxed) {
+        Format::Json
+    } else {
+        Format::Text
+    }
+}
+
+/// Print a line in the current format, unless this thread's lines are being
+/// captured.
+pub fn emit(line: &(impl Display + ToJson)) {
+    let captured = SINK.with_borrow_mut(|sink| match sink {
+        Some(sink) => {
+            sink(&line.to_json());
+            true
+        }
+        None => false,
+    });
+    if captured {
+        return;
+    }
+
+    match format() {
+        Format::Text => println!("{}", line),
+        Format::Json => println!("{}", line.to_json()),
+    }
+}
+
+/// Hand every line this thread emits while `f` runs to `sink` as JSON, rather
+/// than printing it. Other threads print as usual.
+pub fn capture<R>(sink: impl FnMut(&Value) + 'static, f: impl FnOnce() -> R) -> R {
+    let previous = SINK.replace(Some(Box::new(sink)));
+    let result = f();
+    SINK.set(previous);
+    result
+}
+
+/// A search's progress. Fields the search didn't give are left out of the
+/// text, and are null in JSON. The stats are only in JSON, and only given once
+/// an iteration's finished.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct Info {
+    pub depth: Option<i32>,
+    pub seldepth: Option<i32>,
+    pub score: Option<i32>,
+    pub bound: Option<String>,
+    pub mate: Option<i32>,
+    pub nodes: Option<u64>,
+    /// In milliseconds
+    pub elapsed: Option<u128>,
+    pub hashfull: Option<i32>,
+    pub pv: Vec<String>,
+    /// What the search had counted, as the engine's stats write it
+    pub stats: Option<Value>,
+}
+
+impl Info {
+    #[must_use]
+    pub fn nps(&self) -> Option<u128> {
+        match (self.elapsed, self.nodes) {
+            (Some(t), Some(n)) if t > 0 => Some((n as u128 * 1000) / t),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Info {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "info")?;
+        if let Some(depth) = self.depth {
+            write!(f, " depth {}", depth)?;
+        }
+        if let Some(seldepth) = self.seldepth {
+            write!(f, " seldepth {}", seldepth)?;
+        }
+        if let Some(score) = self.score {
+            write!(f, " score cp {}", score)?;
+        }
+        if let Some(bound) = &self.bound {
+            write!(f, " {}", bound)?;
+        }
+        if let Some(mate) = self.mate {
+            write!(f, " score mate {}", mate)?;
+        }
+        if let Some(nodes) = self.nodes {
+            write!(f, " nodes {}", nodes)?;
+        }
+        if let Some(elapsed) = self.elapsed {
+            write!(f, " time {}", elapsed)?;
+        }
+        if let Some(nps) = self.nps() {
+            write!(f, " nps {}", nps)?;
+        }
+        if let Some(hashfull) = self.hashfull {
+            write!(f, " hashfull {}", hashfull)?;
+        }
+        if !self.pv.is_empty() {
+            write!(f, " pv")?;
+            for mv in &self.pv {
+                write!(f, " {}", mv)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ToJson for Info {
+    fn to_json(&self) -> Value {
+        Value::Object(vec![
+            ("type", "info".into()),
+            ("depth", self.depth.into()),
+            ("seldepth", self.seldepth.into()),
+            ("score", self.score.into()),
+            ("bound", self.bound.clone().into()),
+            ("mate", self.mate.into()),
+            ("nodes", self.nodes.into()),
+            ("time", self.elapsed.into()),
+            ("nps", self.nps().into()),
+            ("hashfull", self.hashfull.into()),
+            ("pv", self.pv.clone().into()),
+            ("stats", self.stats.clone().into()),
+        ])
+    }
+}
+
+/// The move a search settled on, and what the search counted on its way.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Bestmove {
+    pub mv: String,
+    pub stats: Value,
+}
+
+impl Display for Bestmove {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "bestmove {}", self.mv)
+    }
+}
+
+impl ToJson for Bestmove {
+    fn to_json(&self) -> Value {
+        Value::Object(vec![
+            ("type", "bestmove".into()),
+            ("move", self.mv.as_str().into()),
+            ("stats", self.stats.clone()),
+        ])
+    }
+}
+
+/// A finished perft depth.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Perft {
+    pub depth: i32,
+    /// In seconds
+    pub elapsed: f32,
+    pub nodes: u64,
+}
+
+impl Perft {
+    #[must_use]
+    pub fn nps(&self) -> Option<u64> {
+        (self.elapsed > 0.0).then(|| (self.nodes as f32 / self.elapsed) as u64)
+    }
+}
+
+impl Display for Perft {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "info depth {} nodes {}", self.depth, self.nodes)?;
+        write!(f, " time {}", (self.elapsed * 1000.0) as u64)?;
+        if let Some(nps) = self.nps() {
+            write!(f, " nps {}", nps)?;
+        }
+        Ok(())
+    }
+}
+
+impl ToJson for Perft {
+    fn to_json(&self) -> Value {
+        Value::Object(vec![
+            ("type", "perft".into()),
+            ("depth", self.depth.into()),
+            ("nodes", self.nodes.into()),
+            ("time", ((self.elapsed * 1000.0) as u64).into()),
+            ("nps", self.nps().into()),
+        ])
+    }
+}
+
+/// The nodes under one root move, from a split perft.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Split {
+    pub mv: String,
+    pub nodes: u64,
+}
+
+impl Display for Split {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.mv, self.nodes)
+    }
+}
+
+impl ToJson for Split {
+    fn to_json(&self) -> Value {
+        Value::Object(vec![
+            ("type", "split".into()),
+            ("move", self.mv.as_str().into()),
+            ("nodes", self.nodes.into()),
+        ])
+    }
+}
+
+/// A perft's total.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Nodes(pub u64);
+
+impl Display for Nodes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "nodes {}", self.0)
+    }
+}
+
+impl ToJson for Nodes {
+    fn to_json(&self) -> Value {
+        Value::Object(vec![("type", "nodes".into()), ("nodes", self.0.into())])
+    }
+}
+
+/// Anything else worth saying, such as debugging.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Message(pub String);
+
+impl Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "info string {}", self.0)
+    }
+}
+
+impl ToJson for Message {
+    fn to_json(&self) -> Value {
+        Value::Object(vec![
+            ("type", "string".into()),
+            ("message", self.0.as_str().into()),
+        ])
+    }
+}
+
+/// Something that went wrong, such as a command that didn't parse.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Error(pub String);
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "info string {}", self.0)
+    }
+}
+
+impl ToJson for Error {
+    fn to_json(&self) -> Value {
+        Value::Object(vec![
+            ("type", "error".into()),
+            ("message", self.0.as_str().into()),
+        ])
+    }
+}
+
+/// An option taking any text, empty by default.
+#[derive(Clone, PartialEq, Debug)]
+pub struct StringOption {
+    pub name: String,
+}
+
+impl Display for StringOption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "option name {} type string default <empty>", self.name)
+    }
+}
+
+impl ToJson for StringOption {
+    fn to_json(&self) -> Value {
+        option(
+            &self.name,
+            "string",
+            "".into(),
+            Value::Null,
+            Value::Null,
+            vec![],
+        )
+    }
+}
+
+/// Every kind of option has the same fields, null where they don't apply.
+fn option(
+    name: &str,
+    kind: &str,
+    default: Value,
+    min: Value,
+    max: Value,
+    vars: Vec<String>,
+) -> Value {
+    Value::Object(vec![
+        ("type", "option".into()),
+        ("name", name.into()),
+        ("kind", kind.into()),
+        ("default", default),
+        ("min", min),
+        ("max", max),
+        ("vars", vars.into()),
+    ])
+}
+
+/// Each protocol has its own copy of the option types.
+macro_rules! options_to_json {
+    ($($options:ident)::+) => {
+        impl ToJson for $($options)::+::Check {
+            fn to_json(&self) -> Value {
+                let (min, max) = (Value::Null, Value::Null);
+                option(&self.name, "check", self.value.into(), min, max, vec![])
+            }
+        }
+
+        impl<T> ToJson for $($options)::+::Spin<T>
+        where
+            T: PartialOrd + PartialEq + Display + Copy + Into<Value>,
+        {
+            fn to_json(&self) -> Value {
+                let (min, max) = (self.min.into(), self.max.into());
+                option(&self.name, "spin", self.value.into(), min, max, vec![])
+            }
+        }
+
+        impl ToJson for $($options)::+::Combo {
+            fn to_json(&self) -> Value {
+                let (min, max) = (Value::Null, Value::Null);
+                let default = self.value.as_str().into();
+                option(&self.name, "combo", default, min, max, self.options.clone())
+            }
+        }
+    };
+}
+
+options_to_json!(crate::ugi::options);
+options_to_json!(crate::uci::options);
+options_to_json!(crate::usi::options);
+options_to_json!(crate::tei::options);
+
+/// Perft's per-depth callback.
+pub fn perft(depth: i32, elapsed: f32, nodes: u64) {
+    emit(&Perft {
+        depth,
+        elapsed,
+        nodes,
+    });
+}
+
+/// Perft's final callback.
+pub fn nodes(nodes: u64) {
+    emit(&Nodes(nodes));
+}
+
+/// Split perft's per-move callback, once the move's been written out.
+pub fn split(mv: String, nodes: u64) {
+    emit(&Split { mv, nodes });
+}
@@ -1,5 +1,6 @@
 use crate::GoKind;
 use crate::GoSettings;
+use crate::output::{self, Error, Message};
 use crate::tei::go::to_tei_string;
 
 use super::TEI;
@@ -30,20 +31,20 @@ pub fn listen(
             "setoption" => {
                 setoption::parse(&mut stream, |name, value| {
                     if state.is_debug() {
-                        println!("info string set option '{}' to '{}'", name, value);
+                        output::emit(&Message(format!("set option '{}' to '{}'", name, value)));
                     }
                     state.set_option(name, value);
                 });
             }
             "quit" => {
                 if state.is_debug() {
-                    println!("info string shutdown");
+                    output::emit(&Message("shutdown".to_owned()));
                 }
                 state.shutdown();
                 return Ok(());
             }
             "teinewgame" | "isready" | "print" | "pprint" | "options" | "stop" | "position"
-            | "moves" | "go" | "perft" | "split" | "movelist" => {
+            | "moves" | "go" | "perft" | "split" | "movelist" | "bench" => {
                 break;
             }
             _ => {}
@@ -53,11 +54,11 @@ pub fn listen(
     }
 
     if state.is_debug() {
-        println!("info string start init");
+        output::emit(&Message("start init".to_owned()));
     }
     state.init();
     if state.is_debug() {
-        println!("info string finish init");
+        output::emit(&Message("finish init".to_owned()));
     }
 
     // Post isready
@@ -110,35 +111,39 @@ pub fn listen(
                     };
 
                     if state.is_debug() {
-                        println!("info string new game size {} halfkomi {}", size, halfkomi);
+                        output::emit(&Message(format!(
+                            "new game size {} halfkomi {}",
+                            size, halfkomi
+                        )));
                     }
                     state.teinewgame(size, halfkomi);
                 }
                 "setoption" => setoption::parse(&mut stream, |name, value| {
                     if state.is_debug() {
-                        println!("info string set option '{}' to '{}'", name, value);
+                        output::emit(&Message(format!("set option '{}' to '{}'", name, value)));
                     }
                     state.set_option(name, value);
                 }),
                 "position" => match position::parse(&mut stream) {
                     Ok(tps) => {
                         if state.is_debug() {
-                            println!("info string set tps '{}'", tps);
+                            output::emit(&Message(format!("set tps '{}'", tps)));
                         }
                         state.position(&tps);
                     }
-                    Err(e) => println!("info string position error {}", e),
+                    Err(e) => output::emit(&Error(format!("position error {}", e))),
                 },
                 "moves" => moves::parse(&mut stream, |movestr| {
                     if state.is_debug() {
-                        println!("info string make move '{}'", movestr);
+                        output::emit(&Message(format!("make move '{}'", movestr)));
                     }
                     state.moves(movestr);
                 }),
                 "go" => match go::parse(&mut stream) {
-                    Ok(n) => {
+                    Ok(mut n) => {
+                        n.debug = state.is_debug();
                         if state.is_debug() {
-                            println!("info string {}", to_tei_string(&n));
+                            output::emit(&Message(to_tei_string(&n)));
                         }
                         match n.kind {
                             GoKind::Search => state.go(&n),
@@ -147,7 +152,7 @@ pub fn listen(
                             GoKind::SplitPerft => state.split(&n),
                         }
                     }
-                    Err(e) => println!("info string go error {}", e),
+                    Err(e) => output::emit(&Error(format!("go error {}", e))),
                 },
                 "perft" => {
                     if stream.peek() == Some(&"depth") {
@@ -172,6 +177,10 @@ pub fn listen(
                     }
                 }
                 "movelist" => state.movelist(),
+                "bench" => {
+                    let depth = stream.next().and_then(|word| word.parse::<i32>().ok());
+                    state.bench(depth);
+                }
                 _ => {}
             }
         }
@@ -185,7 +194,7 @@ pub fn listen(
     }
 
     if state.is_debug() {
-        println!("info string shutdown");
+        output::emit(&Message("shutdown".to_owned()));
     }
     state.shutdown();
 
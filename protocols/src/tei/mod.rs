@@ -46,6 +46,8 @@ pub trait TEI {
 
     fn movelist(&self) {}
 
+    fn bench(&mut self, _depth: Option<i32>) {}
+
     fn set_option(&mut self, name: &str, value: &str);
 
     #[must_use]
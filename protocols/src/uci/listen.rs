@@ -1,5 +1,6 @@
 use crate::GoKind;
 use crate::GoSettings;
+use crate::output::{self, Error, Message};
 use crate::uci::go::to_uci_string;
 
 use super::UCI;
@@ -29,20 +30,20 @@ pub fn listen(
             "setoption" => {
                 setoption::parse(&mut stream, |name, value| {
                     if state.is_debug() {
-                        println!("info string set option '{}' to '{}'", name, value);
+                        output::emit(&Message(format!("set option '{}' to '{}'", name, value)));
                     }
                     state.set_option(name, value);
                 });
             }
             "quit" => {
                 if state.is_debug() {
-                    println!("info string shutdown");
+                    output::emit(&Message("shutdown".to_owned()));
                 }
                 state.shutdown();
                 return Ok(());
             }
             "ucinewgame" | "isready" | "print" | "pprint" | "options" | "stop" | "position"
-            | "moves" | "go" | "perft" | "split" | "query" | "movelist" => {
+            | "moves" | "go" | "perft" | "split" | "query" | "movelist" | "bench" => {
                 break;
             }
             _ => {}
@@ -52,11 +53,11 @@ pub fn listen(
     }
 
     if state.is_debug() {
-        println!("info string start init");
+        output::emit(&Message("start init".to_owned()));
     }
     state.init();
     if state.is_debug() {
-        println!("info string finish init");
+        output::emit(&Message("finish init".to_owned()));
     }
 
     // Post isready
@@ -80,29 +81,30 @@ pub fn listen(
                 // Multiples
                 "setoption" => setoption::parse(&mut stream, |name, value| {
                     if state.is_debug() {
-                        println!("info string set option '{}' to '{}'", name, value);
+                        output::emit(&Message(format!("set option '{}' to '{}'", name, value)));
                     }
                     state.set_option(name, value);
                 }),
                 "position" => match position::parse(&mut stream) {
                     Ok(fen) => {
                         if state.is_debug() {
-                            println!("info string set fen '{}'", fen);
+                            output::emit(&Message(format!("set fen '{}'", fen)));
                         }
                         state.position(&fen);
                     }
-                    Err(e) => println!("info string position error {}", e),
+                    Err(e) => output::emit(&Error(format!("position error {}", e))),
                 },
                 "moves" => moves::parse(&mut stream, |movestr| {
                     if state.is_debug() {
-                        println!("info string make move '{}'", movestr);
+                        output::emit(&Message(format!("make move '{}'", movestr)));
                     }
                     state.moves(movestr);
                 }),
                 "go" => match go::parse(&mut stream) {
-                    Ok(n) => {
+                    Ok(mut n) => {
+                        n.debug = state.is_debug();
                         if state.is_debug() {
-                            println!("info string {}", to_uci_string(&n));
+                            output::emit(&Message(to_uci_string(&n)));
                         }
                         match n.kind {
                             GoKind::Search => state.go(&n),
@@ -111,7 +113,7 @@ pub fn listen(
                             GoKind::SplitPerft => state.split(&n),
                         }
                     }
-                    Err(e) => println!("info string go error {}", e),
+                    Err(e) => output::emit(&Error(format!("go error {}", e))),
                 },
                 "perft" => {
                     if stream.peek() == Some(&"depth") {
@@ -155,6 +157,10 @@ pub fn listen(
                     };
                 }
                 "movelist" => state.movelist(),
+                "bench" => {
+                    let depth = stream.next().and_then(|word| word.parse::<i32>().ok());
+                    state.bench(depth);
+                }
                 _ => {}
             }
         }
@@ -168,7 +174,7 @@ pub fn listen(
     }
 
     if state.is_debug() {
-        println!("info string shutdown");
+        output::emit(&Message("shutdown".to_owned()));
     }
     state.shutdown();
 
@@ -48,6 +48,8 @@ pub trait UCI {
 
     fn movelist(&self) {}
 
+    fn bench(&mut self, _depth: Option<i32>) {}
+
     fn set_option(&mut self, name: &str, value: &str);
 
     #[must_use]
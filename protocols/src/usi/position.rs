@@ -0,0 +1,29 @@
+use std::{iter::Peekable, str::SplitAsciiWhitespace};
+
+pub fn parse(stream: &mut Peekable<SplitAsciiWhitespace>) -> Result<String, &'static str> {
+    match stream.next() {
+        Some("startpos") => Ok("startpos".to_owned()),
+        Some("sfen") => {
+            let mut sfen = String::new();
+            while let Some(word) = stream.peek() {
+                if *word == "moves" {
+                    break;
+                }
+
+                if !sfen.is_empty() {
+                    sfen += " ";
+                }
+                sfen += word;
+
+                stream.next();
+            }
+
+            if sfen.is_empty() {
+                Err("Uh oh")
+            } else {
+                Ok(sfen)
+            }
+        }
+        _ => Err("Uh oh"),
+    }
+}
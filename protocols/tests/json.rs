@@ -0,0 +1,77 @@
+#[cfg(test)]
+mod json {
+    use protocols::json::{Value, parse_object};
+
+    #[test]
+    fn parse() {
+        let tests = [
+            ("{}", vec![]),
+            (" { } ", vec![]),
+            (
+                r#"{"game": "chess", "depth": 4, "moves": ["e2e4", "e7e5"]}"#,
+                vec![
+                    ("game", Value::from("chess")),
+                    ("depth", Value::Int(4)),
+                    ("moves", Value::from(vec!["e2e4", "e7e5"])),
+                ],
+            ),
+            (
+                r#"{"a":-1.5e2,"b":true,"c":false,"d":null,"e":[]}"#,
+                vec![
+                    ("a", Value::Float(-150.0)),
+                    ("b", Value::Bool(true)),
+                    ("c", Value::Bool(false)),
+                    ("d", Value::Null),
+                    ("e", Value::Array(vec![])),
+                ],
+            ),
+            (
+                r#"{"text": "a \"b\" \\ \/ \n \u00e9 \ud83d\ude00"}"#,
+                vec![("text", Value::from("a \"b\" \\ / \n \u{e9} \u{1f600}"))],
+            ),
+        ];
+
+        for (text, expected) in tests {
+            let fields = parse_object(text).unwrap();
+            let fields = fields
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.clone()))
+                .collect::<Vec<(&str, Value)>>();
+            assert_eq!(fields, expected, "{}", text);
+        }
+    }
+
+    #[test]
+    fn parse_errors() {
+        let tests = [
+            "",
+            "[]",
+            "{",
+            "{\"a\"}",
+            "{\"a\": }",
+            "{\"a\": 1,}",
+            "{\"a\": 1} 2",
+            "{\"a\": {\"b\": 1}}",
+            "{\"a\": [1, 2}",
+            "{\"a\": tru}",
+            "{\"a\": \"b}",
+            "{\"a\": \"\\x\"}",
+            "{\"a\": \"\\ud83d\"}",
+            "{\"a\": 1-}",
+        ];
+
+        for text in tests {
+            assert!(parse_object(text).is_err(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn roundtrip() {
+        let value = Value::from(vec!["quote \" backslash \\ tab \t bell \u{7}"]);
+        let text = format!("{{\"value\":{}}}", value);
+        assert_eq!(
+            parse_object(&text).unwrap(),
+            vec![("value".to_owned(), value)]
+        );
+    }
+}
@@ -1,2 +1,5 @@
+mod json;
+mod output;
 mod tei;
 mod uci;
+mod usi;
@@ -0,0 +1,178 @@
+#[cfg(test)]
+mod output {
+    use protocols::json::{ToJson, Value};
+    use protocols::output::{self, Bestmove, Error, Info, Nodes, Perft, Split};
+    use protocols::ugi::options::{Check, Combo, Spin};
+    use std::sync::mpsc;
+
+    #[test]
+    fn values() {
+        let tests = [
+            (Value::Null, "null"),
+            (Value::from(true), "true"),
+            (Value::from(-12), "-12"),
+            (Value::from(u64::MAX), "18446744073709551615"),
+            (Value::from(0.5), "0.5"),
+            (Value::from(f64::NAN), "null"),
+            (Value::from(None::<i32>), "null"),
+            (
+                Value::from("a \"b\" \\ c\n\u{1}"),
+                "\"a \\\"b\\\" \\\\ c\\n\\u0001\"",
+            ),
+            (Value::from(vec!["e2e4", "e7e5"]), "[\"e2e4\",\"e7e5\"]"),
+            (Value::Array(vec![]), "[]"),
+            (
+                Value::Object(vec![("a", 1.into()), ("b", Value::Object(vec![]))]),
+                "{\"a\":1,\"b\":{}}",
+            ),
+        ];
+
+        for (value, expected) in tests {
+            assert_eq!(value.to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn info() {
+        let info = Info {
+            depth: Some(3),
+            seldepth: Some(5),
+            score: Some(-20),
+            bound: Some("lowerbound".to_owned()),
+            nodes: Some(1500),
+            elapsed: Some(10),
+            pv: vec!["b1c3".to_owned(), "g8f6".to_owned()],
+            stats: Some(Value::Object(vec![("nodes", 1500u64.into())])),
+            ..Default::default()
+        };
+        assert_eq!(
+            info.to_string(),
+            "info depth 3 seldepth 5 score cp -20 lowerbound nodes 1500 time 10 nps 150000 pv b1c3 g8f6"
+        );
+        assert_eq!(
+            info.to_json().to_string(),
+            "{\"type\":\"info\",\"depth\":3,\"seldepth\":5,\"score\":-20,\"bound\":\"lowerbound\",\"mate\":null,\"nodes\":1500,\"time\":10,\"nps\":150000,\"hashfull\":null,\"pv\":[\"b1c3\",\"g8f6\"],\"stats\":{\"nodes\":1500}}"
+        );
+
+        // Every field's always there, whatever the search gave
+        let empty = Info::default().to_json();
+        let Value::Object(fields) = &empty else {
+            panic!("info isn't an object");
+        };
+        let Value::Object(full) = info.to_json() else {
+            panic!("info isn't an object");
+        };
+        let names = |fields: &[(&str, Value)]| {
+            fields
+                .iter()
+                .map(|(name, _)| name.to_string())
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(names(fields), names(&full));
+        assert_eq!(Info::default().to_string(), "info");
+    }
+
+    #[test]
+    fn lines() {
+        let bestmove = Bestmove {
+            mv: "e2e4".to_owned(),
+            stats: Value::Object(vec![("nodes", 100u64.into())]),
+        };
+        assert_eq!(bestmove.to_string(), "bestmove e2e4");
+        assert_eq!(
+            bestmove.to_json().to_string(),
+            "{\"type\":\"bestmove\",\"move\":\"e2e4\",\"stats\":{\"nodes\":100}}"
+        );
+
+        let perft = Perft {
+            depth: 4,
+            elapsed: 0.5,
+            nodes: 1000,
+        };
+        assert_eq!(
+            perft.to_string(),
+            "info depth 4 nodes 1000 time 500 nps 2000"
+        );
+        assert_eq!(
+            perft.to_json().to_string(),
+            "{\"type\":\"perft\",\"depth\":4,\"nodes\":1000,\"time\":500,\"nps\":2000}"
+        );
+
+        let split = Split {
+            mv: "a1a2".to_owned(),
+            nodes: 7,
+        };
+        assert_eq!(split.to_string(), "a1a2 7");
+        assert_eq!(
+            split.to_json().to_string(),
+            "{\"type\":\"split\",\"move\":\"a1a2\",\"nodes\":7}"
+        );
+
+        assert_eq!(Nodes(42).to_string(), "nodes 42");
+        assert_eq!(
+            Nodes(42).to_json().to_string(),
+            "{\"type\":\"nodes\",\"nodes\":42}"
+        );
+
+        let error = Error("go error Uh oh".to_owned());
+        assert_eq!(error.to_string(), "info string go error Uh oh");
+        assert_eq!(
+            error.to_json().to_string(),
+            "{\"type\":\"error\",\"message\":\"go error Uh oh\"}"
+        );
+    }
+
+    #[test]
+    fn options() {
+        let check = Check {
+            name: "debug".to_owned(),
+            value: false,
+        };
+        assert_eq!(
+            check.to_json().to_string(),
+            "{\"type\":\"option\",\"name\":\"debug\",\"kind\":\"check\",\"default\":false,\"min\":null,\"max\":null,\"vars\":[]}"
+        );
+
+        let spin = Spin {
+            name: "size".to_owned(),
+            min: 5,
+            max: 8,
+            value: 7,
+        };
+        assert_eq!(
+            spin.to_json().to_string(),
+            "{\"type\":\"option\",\"name\":\"size\",\"kind\":\"spin\",\"default\":7,\"min\":5,\"max\":8,\"vars\":[]}"
+        );
+
+        let combo = Combo {
+            name: "search".to_owned(),
+            value: "primary".to_owned(),
+            options: vec!["primary".to_owned(), "random".to_owned()],
+        };
+        assert_eq!(
+            combo.to_json().to_string(),
+            "{\"type\":\"option\",\"name\":\"search\",\"kind\":\"combo\",\"default\":\"primary\",\"min\":null,\"max\":null,\"vars\":[\"primary\",\"random\"]}"
+        );
+    }
+
+    #[test]
+    fn capture() {
+        let (tx, rx) = mpsc::channel();
+        let result = output::capture(
+            move |line| tx.send(line.to_string()).unwrap(),
+            || {
+                output::nodes(5);
+                output::emit(&Error("oops".to_owned()));
+                7
+            },
+        );
+        assert_eq!(result, 7);
+        assert_eq!(
+            rx.iter().collect::<Vec<String>>(),
+            [
+                "{\"type\":\"nodes\",\"nodes\":5}",
+                "{\"type\":\"error\",\"message\":\"oops\"}",
+            ]
+        );
+    }
+}
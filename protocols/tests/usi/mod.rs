@@ -0,0 +1,3 @@
+pub mod go;
+pub mod listen;
+pub mod position;
@@ -0,0 +1,68 @@
+#[cfg(test)]
+mod position {
+    use protocols::usi::position;
+
+    #[test]
+    fn test_input_nomoves() {
+        let inputs = [
+            ("startpos", "startpos"),
+            ("sfen test", "test"),
+            ("sfen 1 2", "1 2"),
+            ("sfen 1 2 3 4 5 6 7", "1 2 3 4 5 6 7"),
+            (
+                "sfen lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1",
+                "lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1",
+            ),
+        ];
+
+        for (input, expected) in inputs {
+            let mut stream = input.split_ascii_whitespace().peekable();
+            let parsed = position::parse(&mut stream);
+            assert!(parsed.is_ok(), "{}", input);
+            assert_eq!(parsed.unwrap(), expected, "{}", input);
+            assert_eq!(stream.next(), None, "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_input_moves() {
+        let inputs = [
+            ("startpos moves", "startpos"),
+            ("startpos moves a", "startpos"),
+            ("startpos moves a b c d e", "startpos"),
+            ("sfen test moves", "test"),
+            ("sfen test moves 1 2", "test"),
+            ("sfen 1 2 moves", "1 2"),
+            ("sfen 1 2 3 4 5 6 7 moves", "1 2 3 4 5 6 7"),
+            ("sfen 1 2 3 4 5 6 7 moves a", "1 2 3 4 5 6 7"),
+            ("sfen 1 2 3 4 5 6 7 moves a b c d e", "1 2 3 4 5 6 7"),
+        ];
+
+        for (input, expected) in inputs {
+            let mut stream = input.split_ascii_whitespace().peekable();
+            let parsed = position::parse(&mut stream);
+            assert!(parsed.is_ok(), "{}", input);
+            assert_eq!(parsed.unwrap(), expected, "{}", input);
+            assert_eq!(stream.next().unwrap(), "moves", "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_input_invalid() {
+        let inputs = [
+            "test",
+            "sfen",
+            "sfen moves",
+            "sfen moves a",
+            "sfen moves a b c d e",
+            // Chess style
+            "fen test",
+        ];
+
+        for input in inputs {
+            let mut stream = input.split_ascii_whitespace().peekable();
+            let parsed = position::parse(&mut stream);
+            assert!(parsed.is_err(), "{}", input);
+        }
+    }
+}
@@ -0,0 +1,272 @@
+use faeries::args::{Game, parse_game};
+use std::{
+    env,
+    fmt::Display,
+    num::{ParseFloatError, ParseIntError},
+};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Format {
+    /// The `WEIGHTS` constant, to paste over the one in the eval module
+    Rust,
+    /// One `name value` to a line, to pass back in with `--weights`
+    Weights,
+}
+
+pub struct Args {
+    pub data_path: String,
+    pub weights_path: Option<String>,
+    pub output_path: Option<String>,
+    pub game: Option<Game>,
+    pub epochs: usize,
+    /// Each kind of tuning has its own default
+    pub rate: Option<f64>,
+    /// Fitted to the starting weights when not given
+    pub k: Option<f64>,
+    pub format: Format,
+    /// Train an NNUE for the game rather than tune its evaluation's weights
+    pub nnue: bool,
+    pub verbose: bool,
+}
+
+#[derive(Debug)]
+pub struct ArgsError {
+    msg: String,
+}
+
+impl Display for ArgsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.msg)?;
+        Ok(())
+    }
+}
+
+impl Default for Args {
+    fn default() -> Self {
+        Self {
+            data_path: Default::default(),
+            weights_path: None,
+            output_path: None,
+            game: None,
+            epochs: 1_000,
+            rate: None,
+            k: None,
+            format: Format::Rust,
+            nnue: false,
+            verbose: false,
+        }
+    }
+}
+
+impl From<ArgsError> for std::io::Error {
+    fn from(e: ArgsError) -> Self {
+        std::io::Error::other(e.msg)
+    }
+}
+
+impl From<ParseIntError> for ArgsError {
+    fn from(value: ParseIntError) -> Self {
+        ArgsError {
+            msg: format!("Bad value: {}", value).to_string(),
+        }
+    }
+}
+
+impl From<ParseFloatError> for ArgsError {
+    fn from(value: ParseFloatError) -> Self {
+        ArgsError {
+            msg: format!("Bad value: {}", value).to_string(),
+        }
+    }
+}
+
+pub fn parse_commandline() -> Result<Args, ArgsError> {
+    let args: Vec<String> = env::args().collect();
+    parse_string(&args[1..].join(" "))
+}
+
+pub fn parse_string(line: &str) -> Result<Args, ArgsError> {
+    parse(&line.split(" ").collect::<Vec<&str>>())
+}
+
+pub fn parse(words: &[&str]) -> Result<Args, ArgsError> {
+    let mut parsed = Args::default();
+    let mut iter = words.iter().peekable();
+
+    while let Some(word) = iter.next() {
+        match (word, iter.peek()) {
+            // Data
+            (&"--data", Some(_)) => parsed.data_path = iter.next().unwrap().to_string(),
+            (&"--data", _) => {
+                return Err(ArgsError {
+                    msg: "Missing data path".to_string(),
+                });
+            }
+            // Weights
+            (&"--weights", Some(_)) => parsed.weights_path = Some(iter.next().unwrap().to_string()),
+            (&"--weights", _) => {
+                return Err(ArgsError {
+                    msg: "Missing weights path".to_string(),
+                });
+            }
+            // Output
+            (&"--output", Some(_)) => parsed.output_path = Some(iter.next().unwrap().to_string()),
+            (&"--output", _) => {
+                return Err(ArgsError {
+                    msg: "Missing output path".to_string(),
+                });
+            }
+            // Game
+            (&"--game", Some(name)) => {
+                parsed.game = match parse_game(name) {
+                    Some(game) => Some(game),
+                    None => {
+                        return Err(ArgsError {
+                            msg: "Unrecognised game name".to_string(),
+                        });
+                    }
+                };
+                iter.next();
+            }
+            // Epochs
+            (&"--epochs", Some(_)) => parsed.epochs = iter.next().unwrap().parse::<usize>()?,
+            (&"--epochs", _) => {
+                return Err(ArgsError {
+                    msg: "Missing number of epochs".to_string(),
+                });
+            }
+            // Learning rate
+            (&"--rate", Some(_)) => parsed.rate = Some(iter.next().unwrap().parse::<f64>()?),
+            (&"--rate", _) => {
+                return Err(ArgsError {
+                    msg: "Missing learning rate".to_string(),
+                });
+            }
+            // Scale
+            (&"--k", Some(_)) => parsed.k = Some(iter.next().unwrap().parse::<f64>()?),
+            (&"--k", _) => {
+                return Err(ArgsError {
+                    msg: "Missing scale".to_string(),
+                });
+            }
+            // Format
+            (&"--format", Some(&&"rust")) => {
+                parsed.format = Format::Rust;
+                iter.next();
+            }
+            (&"--format", Some(&&"weights")) => {
+                parsed.format = Format::Weights;
+                iter.next();
+            }
+            (&"--format", _) => {
+                return Err(ArgsError {
+                    msg: "Format must be 'rust' or 'weights'".to_string(),
+                });
+            }
+            // Flags
+            (&"--nnue", _) => parsed.nnue = true,
+            (&"--verbose", _) => parsed.verbose = true,
+            // Other
+            _ => {
+                return Err(ArgsError {
+                    msg: format!("Unrecognised word: '{}'", word),
+                });
+            }
+        }
+    }
+
+    if parsed.rate.is_some_and(|rate| rate <= 0.0) {
+        Err(ArgsError {
+            msg: "rate must be > 0".to_string(),
+        })
+    } else if parsed.k.is_some_and(|k| k <= 0.0) {
+        Err(ArgsError {
+            msg: "k must be > 0".to_string(),
+        })
+    } else if parsed.data_path.is_empty() {
+        Err(ArgsError {
+            msg: "data path must not be empty".to_string(),
+        })
+    } else if parsed.game.is_none() {
+        Err(ArgsError {
+            msg: "game must be specified".to_string(),
+        })
+    } else if parsed.nnue && parsed.output_path.is_none() {
+        Err(ArgsError {
+            msg: "a network needs an output path".to_string(),
+        })
+    } else {
+        Ok(parsed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default() {
+        assert_eq!(Args::default().epochs, 1_000);
+        assert_eq!(Args::default().format, Format::Rust);
+    }
+
+    #[test]
+    fn example() {
+        let args = parse_string(
+            "--data path --game checkers --epochs 50 --rate 0.5 --k 1.2 --format weights --weights start --output out",
+        )
+        .unwrap();
+        assert_eq!(args.data_path, "path");
+        assert_eq!(args.game, Some(Game::Checkers));
+        assert_eq!(args.epochs, 50);
+        assert_eq!(args.rate, Some(0.5));
+        assert_eq!(args.k, Some(1.2));
+        assert_eq!(args.format, Format::Weights);
+        assert_eq!(args.weights_path.as_deref(), Some("start"));
+        assert_eq!(args.output_path.as_deref(), Some("out"));
+    }
+
+    #[test]
+    fn success() {
+        let tests = [
+            "--data path --game ataxx",
+            "--data path --game othello",
+            "--data path --game shogi --verbose",
+            "--data path --game ataxx --epochs 0",
+            "--data path --game ataxx --rate 10",
+            "--data path --game ataxx --format rust",
+            "--data path --game ataxx --nnue --output net.bin",
+        ];
+
+        for line in tests {
+            let res = parse_string(line);
+            assert!(res.is_ok(), "{}", res.err().unwrap());
+        }
+    }
+
+    #[test]
+    fn errors() {
+        let tests = [
+            "",
+            "--data",
+            "--data path",
+            "--game ataxx",
+            "--data path --game unknown",
+            "--data path --game ataxx test",
+            "--data path --game ataxx --epochs",
+            "--data path --game ataxx --epochs -1",
+            "--data path --game ataxx --rate 0",
+            "--data path --game ataxx --rate test",
+            "--data path --game ataxx --k 0",
+            "--data path --game ataxx --format",
+            "--data path --game ataxx --format json",
+            "--data path --game ataxx --weights",
+            "--data path --game ataxx --output",
+            "--data path --game ataxx --nnue",
+        ];
+
+        for line in tests {
+            assert!(parse_string(line).is_err(), "{}", line);
+        }
+    }
+}
@@ -0,0 +1,135 @@
+mod args;
+mod nnue;
+
+use crate::args::{Args, Format};
+use faeries::args::Game;
+use faeries::checkers::position::CheckersPosition;
+use faeries::connect4::position::Connect4Position;
+use faeries::droptaxx::position::DroptaxxPosition;
+use faeries::gomoku::position::GomokuPosition;
+use faeries::othello::position::OthelloPosition;
+use faeries::shogi::position::ShogiPosition;
+use faeries::tune::{
+    Sample, Tunable, Tuner, as_rust, as_weights, error, fit_scale, parse_line, parse_weights,
+};
+use games::{ataxx::AtaxxPosition, gamerules::GameRules, pijersi::PijersiPosition};
+use std::fs::{read_to_string, write};
+use std::io::Error;
+
+const BATCH_SIZE: usize = 16_384;
+
+fn tune<G: Tunable>(args: &Args, from_fen: impl Fn(&str) -> G) -> Result<(), Error> {
+    let samples = read_to_string(&args.data_path)?
+        .lines()
+        .filter_map(parse_line)
+        .map(|(fen, result)| Sample::new(&from_fen(fen), result))
+        .collect::<Vec<Sample>>();
+
+    let start = match &args.weights_path {
+        Some(path) => {
+            parse_weights(G::NAMES, G::WEIGHTS, &read_to_string(path)?).map_err(Error::other)?
+        }
+        None => G::WEIGHTS.to_vec(),
+    };
+
+    let mut tuner = Tuner::new(&start, 1.0, args.rate.unwrap_or(1.0));
+    tuner.k = args
+        .k
+        .unwrap_or_else(|| fit_scale(&samples, &tuner.weights));
+
+    if args.verbose {
+        println!("samples: {}", samples.len());
+        println!("k:       {:.4}", tuner.k);
+        println!("error:   {:.6}", error(&samples, &tuner.weights, tuner.k));
+    }
+
+    for epoch in 1..=args.epochs {
+        tuner.step(&samples);
+        if args.verbose && epoch % 100 == 0 {
+            println!(
+                "epoch {} error {:.6}",
+                epoch,
+                error(&samples, &tuner.weights, tuner.k)
+            );
+        }
+    }
+
+    let weights = tuner.rounded();
+    let text = match args.format {
+        Format::Rust => as_rust(G::NAMES, &weights),
+        Format::Weights => as_weights(G::NAMES, &weights),
+    };
+
+    match &args.output_path {
+        Some(path) => write(path, text + "\n")?,
+        None => println!("{}", text),
+    }
+
+    Ok(())
+}
+
+/// Train a network for ataxx from scratch, a batch at a time.
+fn train(args: &Args) -> Result<(), Error> {
+    let output_path = args
+        .output_path
+        .as_ref()
+        .ok_or_else(|| Error::other("a network needs an output path"))?;
+    let samples = read_to_string(&args.data_path)?
+        .lines()
+        .filter_map(parse_line)
+        .map(|(fen, result)| nnue::Sample::new(&AtaxxPosition::from_fen(fen), result))
+        .collect::<Vec<nnue::Sample>>();
+
+    let mut trainer = nnue::Trainer::new(0xe50076937a9e5b1c, args.rate.unwrap_or(0.001) as f32);
+
+    if args.verbose {
+        println!("samples: {}", samples.len());
+        println!("error:   {:.6}", trainer.error(&samples));
+    }
+
+    for epoch in 1..=args.epochs {
+        for batch in samples.chunks(BATCH_SIZE) {
+            trainer.train(batch);
+        }
+        if args.verbose {
+            println!("epoch {} error {:.6}", epoch, trainer.error(&samples));
+        }
+    }
+
+    write(output_path, trainer.quantise().to_bytes())
+}
+
+fn main() -> Result<(), Error> {
+    let args = args::parse_commandline()?;
+
+    if args.verbose {
+        println!("Verbose enabled");
+        println!("game:   {:?}", args.game.unwrap());
+        println!("data:   {}", args.data_path);
+        println!("epochs: {}", args.epochs);
+    }
+
+    if args.nnue {
+        return match args.game.unwrap() {
+            Game::Ataxx => train(&args),
+            game => Err(Error::other(format!("{:?} has no NNUE", game))),
+        };
+    }
+
+    match args.game.unwrap() {
+        Game::Ataxx => tune(&args, AtaxxPosition::<7, 7>::from_fen),
+        Game::Checkers => tune(&args, CheckersPosition::from_fen),
+        Game::Droptaxx => tune(&args, DroptaxxPosition::from_fen),
+        Game::Gomoku => tune(&args, GomokuPosition::from_fen),
+        Game::Othello => tune(&args, OthelloPosition::from_fen),
+        Game::Pijersi => tune(&args, PijersiPosition::from_fen),
+        Game::Shogi => tune(&args, ShogiPosition::from_fen),
+        Game::Connect4 => tune(&args, Connect4Position::from_fen),
+        // Baduk's search has no evaluation. Chess, Isolation and Tak score every
+        // position 0, and with no features to weigh there's nothing to tune, so
+        // none of them are `Tunable`
+        game @ (Game::Baduk | Game::Chess | Game::Isolation | Game::Tak) => Err(Error::other(
+            format!("{:?} has no tunable evaluation", game),
+        )),
+    }
+}
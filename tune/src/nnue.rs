@@ -0,0 +1,236 @@
+use faeries::ataxx::nnue::{HIDDEN, INPUTS, Network, QA, QB, features};
+use faeries::prng::XorshiftGenerator;
+use games::{ataxx::AtaxxPosition, gamerules::GameRules, general::side::Side};
+
+/// Where each part of the network starts in the parameters, which are in the
+/// same order as a network file.
+const FEATURE_BIAS: usize = INPUTS * HIDDEN;
+const OUTPUT_WEIGHTS: usize = FEATURE_BIAS + HIDDEN;
+const OUTPUT_BIAS: usize = OUTPUT_WEIGHTS + 2 * HIDDEN;
+const PARAMS: usize = OUTPUT_BIAS + 1;
+
+/// Weights and biases are kept small enough that the quantised accumulators
+/// can't overflow.
+const CLIP: f32 = 1.98;
+
+const BETA1: f32 = 0.9;
+const BETA2: f32 = 0.999;
+const EPSILON: f32 = 1e-8;
+
+/// A position's inputs from the side to move's view and the other side's, and
+/// how its game went for the side to move.
+pub struct Sample {
+    us: Vec<usize>,
+    them: Vec<usize>,
+    result: f32,
+}
+
+impl Sample {
+    #[must_use]
+    pub fn new(pos: &AtaxxPosition<7, 7>, result: f64) -> Self {
+        let (black, white) = features(pos);
+        match pos.get_turn() {
+            Side::Player1 => Sample {
+                us: black,
+                them: white,
+                result: result as f32,
+            },
+            Side::Player2 => Sample {
+                us: white,
+                them: black,
+                result: 1.0 - result as f32,
+            },
+        }
+    }
+}
+
+/// The network in floating point while it's trained, with an output of 1.0
+/// meaning what `SCALE` centidiscs do to the engine.
+pub struct Trainer {
+    params: Vec<f32>,
+    momentum: Vec<f32>,
+    velocity: Vec<f32>,
+    step: i32,
+    pub rate: f32,
+}
+
+#[must_use]
+fn sigmoid(x: f32) -> f32 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+impl Trainer {
+    #[must_use]
+    pub fn new(seed: u64, rate: f32) -> Self {
+        let mut prng = XorshiftGenerator::new(seed);
+        let mut random = || (prng.next() % 2001) as f32 / 1000.0 - 1.0;
+
+        let params = (0..PARAMS)
+            .map(|i| match i {
+                i if i < FEATURE_BIAS => 0.1 * random(),
+                i if i < OUTPUT_WEIGHTS => 0.0,
+                i if i < OUTPUT_BIAS => 0.5 * random(),
+                _ => 0.0,
+            })
+            .collect();
+
+        Trainer {
+            params,
+            momentum: vec![0.0; PARAMS],
+            velocity: vec![0.0; PARAMS],
+            step: 0,
+            rate,
+        }
+    }
+
+    #[must_use]
+    fn accumulate(&self, inputs: &[usize]) -> [f32; HIDDEN] {
+        let mut acc = [0.0; HIDDEN];
+        acc.copy_from_slice(&self.params[FEATURE_BIAS..OUTPUT_WEIGHTS]);
+        for input in inputs {
+            for (h, value) in acc.iter_mut().enumerate() {
+                *value += self.params[input * HIDDEN + h];
+            }
+        }
+        acc
+    }
+
+    /// The output before it's made into a chance of winning.
+    #[must_use]
+    pub fn output(&self, sample: &Sample) -> f32 {
+        let us = self.accumulate(&sample.us);
+        let them = self.accumulate(&sample.them);
+        let weights = &self.params[OUTPUT_WEIGHTS..OUTPUT_BIAS];
+        us.iter()
+            .chain(&them)
+            .zip(weights)
+            .map(|(value, weight)| value.clamp(0.0, 1.0) * weight)
+            .sum::<f32>()
+            + self.params[OUTPUT_BIAS]
+    }
+
+    /// The mean squared error between the predictions and the results.
+    #[must_use]
+    pub fn error(&self, samples: &[Sample]) -> f32 {
+        let total: f32 = samples
+            .iter()
+            .map(|sample| (sigmoid(self.output(sample)) - sample.result).powi(2))
+            .sum();
+        total / samples.len().max(1) as f32
+    }
+
+    /// Add the gradient of one sample's error to `gradient`.
+    fn backward(&self, sample: &Sample, gradient: &mut [f32]) {
+        let us = self.accumulate(&sample.us);
+        let them = self.accumulate(&sample.them);
+        let prediction = sigmoid(self.output(sample));
+        let delta = 2.0 * (prediction - sample.result) * prediction * (1.0 - prediction);
+
+        gradient[OUTPUT_BIAS] += delta;
+        for (side, (acc, inputs)) in [(&us, &sample.us), (&them, &sample.them)]
+            .into_iter()
+            .enumerate()
+        {
+            for h in 0..HIDDEN {
+                let weight = OUTPUT_WEIGHTS + side * HIDDEN + h;
+                gradient[weight] += delta * acc[h].clamp(0.0, 1.0);
+
+                // Nothing gets through where the activation's clipped
+                if acc[h] <= 0.0 || acc[h] >= 1.0 {
+                    continue;
+                }
+                let hidden = delta * self.params[weight];
+                gradient[FEATURE_BIAS + h] += hidden;
+                for input in inputs {
+                    gradient[input * HIDDEN + h] += hidden;
+                }
+            }
+        }
+    }
+
+    /// One step of Adam over a batch of samples.
+    pub fn train(&mut self, batch: &[Sample]) {
+        let mut gradient = vec![0.0; PARAMS];
+        for sample in batch {
+            self.backward(sample, &mut gradient);
+        }
+
+        self.step += 1;
+        let n = batch.len().max(1) as f32;
+        for (i, g) in gradient.iter().enumerate() {
+            let g = g / n;
+            self.momentum[i] = BETA1 * self.momentum[i] + (1.0 - BETA1) * g;
+            self.velocity[i] = BETA2 * self.velocity[i] + (1.0 - BETA2) * g * g;
+            let m = self.momentum[i] / (1.0 - BETA1.powi(self.step));
+            let v = self.velocity[i] / (1.0 - BETA2.powi(self.step));
+            self.params[i] -= self.rate * m / (v.sqrt() + EPSILON);
+            if i < OUTPUT_BIAS {
+                self.params[i] = self.params[i].clamp(-CLIP, CLIP);
+            }
+        }
+    }
+
+    /// The network as the engine uses it.
+    #[must_use]
+    pub fn quantise(&self) -> Network {
+        let quantise = |value: f32, scale: i32| {
+            (value * scale as f32)
+                .round()
+                .clamp(i16::MIN as f32, i16::MAX as f32) as i16
+        };
+
+        Network {
+            feature_weights: (0..INPUTS)
+                .map(|input| std::array::from_fn(|h| quantise(self.params[input * HIDDEN + h], QA)))
+                .collect(),
+            feature_bias: std::array::from_fn(|h| quantise(self.params[FEATURE_BIAS + h], QA)),
+            output_weights: std::array::from_fn(|i| quantise(self.params[OUTPUT_WEIGHTS + i], QB)),
+            output_bias: quantise(self.params[OUTPUT_BIAS], QB),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use faeries::ataxx::nnue::SCALE;
+
+    #[test]
+    fn learns_material() {
+        // More discs win, whoever's to move
+        let fens = [
+            ("x5o/7/7/7/7/7/o5x x 0 1", 0.5),
+            ("xx4o/7/7/7/7/7/o5x x 0 1", 1.0),
+            ("xx4o/7/7/7/7/7/o5x o 0 1", 1.0),
+            ("x5o/7/7/7/7/7/oo4x x 0 1", 0.0),
+            ("x5o/7/7/7/7/7/oo4x o 0 1", 0.0),
+            ("xxx3o/7/7/7/7/7/o5x o 0 1", 1.0),
+        ];
+        let samples = fens
+            .iter()
+            .map(|(fen, result)| Sample::new(&AtaxxPosition::from_fen(fen), *result))
+            .collect::<Vec<Sample>>();
+
+        let mut trainer = Trainer::new(0x1234_5678_9abc_def0, 0.01);
+        let before = trainer.error(&samples);
+        for _ in 0..500 {
+            trainer.train(&samples);
+        }
+        assert!(trainer.error(&samples) < before / 4.0);
+
+        // The quantised network agrees, and survives the trip through a file
+        let network = trainer.quantise();
+        assert_eq!(Network::from_bytes(&network.to_bytes()).unwrap(), network);
+        for (sample, (fen, _)) in samples.iter().zip(fens) {
+            let pos = AtaxxPosition::<7, 7>::from_fen(fen);
+            let exact = trainer.output(sample) * SCALE as f32;
+            let quantised = network.evaluate(&network.accumulate(&pos), pos.get_turn());
+            assert!(
+                (exact - quantised as f32).abs() < 10.0,
+                "{} {}",
+                exact,
+                quantised
+            );
+        }
+    }
+}